@@ -6,7 +6,14 @@
 //!
 
 use automaton::{Automaton, FiniteStateAutomaton};
+use snapshot::Snapshot;
+use stateless_mapper::StatelessMapper;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::hash::Hash;
 use std::marker::PhantomData;
+#[cfg(feature = "const_generics")]
+use std::{array, mem};
 
 
 pub struct MachineSeries<'k, M, N> where 
@@ -49,6 +56,29 @@ impl<'k, M, N> MachineSeries<'k, M, N> where
     }
 }
 
+impl<'k, I, C, N> MachineSeries<'k, StatelessMapper<'k, I, N::Input, C>, N> where 
+    C: Fn(&I) -> N::Input + 'k,
+    N: Automaton<'k>,
+    I: 'k
+{
+    /// Create a new machine series which maps its input through the closure 
+    /// before passing it to the machine. 
+    pub fn with_input_map(map: C, machine: N) -> Self {
+        MachineSeries::new(StatelessMapper::new(map), machine)
+    }
+}
+
+impl<'k, M, A, C> MachineSeries<'k, M, StatelessMapper<'k, M::Action, A, C>> where 
+    M: Automaton<'k>,
+    C: Fn(&M::Action) -> A + 'k
+{
+    /// Create a new machine series which maps the actions of the machine 
+    /// through the closure. 
+    pub fn with_output_map(machine: M, map: C) -> Self {
+        MachineSeries::new(machine, StatelessMapper::new(map))
+    }
+}
+
 impl<'k, M, N> Automaton<'k> for MachineSeries<'k, M, N> where 
     M: Automaton<'k>,
     N: Automaton<'k, Input=M::Action>
@@ -67,6 +97,69 @@ impl<'k, M, N> FiniteStateAutomaton<'k> for MachineSeries<'k, M, N> where
     N: Automaton<'k, Input=M::Action> + Copy
 {}
 
+/// Series of two machines, where the second machine is given references to 
+/// both the input of the first machine and the action the first machine 
+/// returned for it. The second machine takes inputs of any lifetime, so it 
+/// implements Automaton for all lifetimes, and returns the same action type 
+/// for each. 
+pub struct MachineSeriesWith<'k, M, N, B> where 
+    M: Automaton<'k>,
+    N: for<'t> Automaton<'t, Input=(&'t M::Input, &'t M::Action), Action=B>
+{
+    before: M,
+    after: N,
+    _bounds: PhantomData<&'k (M, N, B)>
+}
+
+impl<'k, M, N, B> Clone for MachineSeriesWith<'k, M, N, B> where
+    M: Automaton<'k> + Clone,
+    N: for<'t> Automaton<'t, Input=(&'t M::Input, &'t M::Action), Action=B> + Clone
+{
+    fn clone(&self) -> Self {
+        MachineSeriesWith {
+            before: self.before.clone(),
+            after: self.after.clone(),
+            _bounds: PhantomData
+        }
+    }
+}
+
+impl<'k, M, N, B> Copy for MachineSeriesWith<'k, M, N, B> where
+    M: Automaton<'k> + Copy,
+    N: for<'t> Automaton<'t, Input=(&'t M::Input, &'t M::Action), Action=B> + Copy
+{}
+
+impl<'k, M, N, B> MachineSeriesWith<'k, M, N, B> where 
+    M: Automaton<'k>,
+    N: for<'t> Automaton<'t, Input=(&'t M::Input, &'t M::Action), Action=B>
+{
+    pub fn new(before: M, after: N) -> Self {
+        MachineSeriesWith {
+            before: before,
+            after: after,
+            _bounds: PhantomData
+        }
+    }
+}
+
+impl<'k, M, N, B> Automaton<'k> for MachineSeriesWith<'k, M, N, B> where 
+    M: Automaton<'k>,
+    N: for<'t> Automaton<'t, Input=(&'t M::Input, &'t M::Action), Action=B>
+{
+    type Input = M::Input;
+    type Action = B;
+
+    fn transition(&mut self, input: &M::Input) -> B {
+        let intermediate = self.before.transition(input);
+        self.after.transition(&(input, &intermediate))
+    }
+}
+
+impl<'k, M, N, B> FiniteStateAutomaton<'k> for MachineSeriesWith<'k, M, N, B> where 
+    M: Automaton<'k> + Copy,
+    N: for<'t> Automaton<'t, Input=(&'t M::Input, &'t M::Action), Action=B> + Copy
+{}
+
 #[derive(Copy, Clone, PartialEq, Debug)]
 pub enum LazyConstructedInner<'k, M, C> where
     M: Automaton<'k>,
@@ -206,6 +299,70 @@ impl<'k, M, N> FiniteStateAutomaton<'k> for MachineTee<'k, M, N> where
     N: Automaton<'k, Input=M::Action> + Copy
 {}
 
+/// Tee of two machines, where the second machine is given references to both 
+/// the input of the first machine and the action the first machine returned 
+/// for it, and the actions of both machines are returned. As with 
+/// MachineSeriesWith, the second machine implements Automaton for all 
+/// lifetimes. 
+pub struct MachineTeeWith<'k, M, N, B> where 
+    M: Automaton<'k>,
+    N: for<'t> Automaton<'t, Input=(&'t M::Input, &'t M::Action), Action=B>
+{
+    before: M,
+    after: N,
+    _bounds: PhantomData<&'k (M, N, B)>
+}
+
+impl<'k, M, N, B> Clone for MachineTeeWith<'k, M, N, B> where
+    M: Automaton<'k> + Clone,
+    N: for<'t> Automaton<'t, Input=(&'t M::Input, &'t M::Action), Action=B> + Clone
+{
+    fn clone(&self) -> Self {
+        MachineTeeWith {
+            before: self.before.clone(),
+            after: self.after.clone(),
+            _bounds: PhantomData
+        }
+    }
+}
+
+impl<'k, M, N, B> Copy for MachineTeeWith<'k, M, N, B> where
+    M: Automaton<'k> + Copy,
+    N: for<'t> Automaton<'t, Input=(&'t M::Input, &'t M::Action), Action=B> + Copy
+{}
+
+impl<'k, M, N, B> MachineTeeWith<'k, M, N, B> where 
+    M: Automaton<'k>,
+    N: for<'t> Automaton<'t, Input=(&'t M::Input, &'t M::Action), Action=B>
+{
+    pub fn new(before: M, after: N) -> Self {
+        MachineTeeWith {
+            before: before,
+            after: after,
+            _bounds: PhantomData
+        }
+    }
+}
+
+impl<'k, M, N, B> Automaton<'k> for MachineTeeWith<'k, M, N, B> where 
+    M: Automaton<'k>,
+    N: for<'t> Automaton<'t, Input=(&'t M::Input, &'t M::Action), Action=B>
+{
+    type Input = M::Input;
+    type Action = (M::Action, B);
+
+    fn transition(&mut self, input: &M::Input) -> (M::Action, B) {
+        let intermediate = self.before.transition(input);
+        let reaction = self.after.transition(&(input, &intermediate));
+        (intermediate, reaction)
+    }
+}
+
+impl<'k, M, N, B> FiniteStateAutomaton<'k> for MachineTeeWith<'k, M, N, B> where 
+    M: Automaton<'k> + Copy,
+    N: for<'t> Automaton<'t, Input=(&'t M::Input, &'t M::Action), Action=B> + Copy
+{}
+
 #[derive(PartialEq, Debug)]
 pub struct ParallelMachines<'k, M, N> where 
     M: Automaton<'k>,
@@ -264,51 +421,1138 @@ impl<'k, M, N> FiniteStateAutomaton<'k> for ParallelMachines<'k, M, N> where
     N: Automaton<'k, Input=M::Input> + Copy
 {}
 
-#[cfg(test)]
-mod tests {
-    use internal_state_machine::{InternalTransition, 
-        InternalStateMachine};
-    use automaton::Automaton;
+/// Wrapper for a machine, which maps each input to an input of the machine 
+/// through a fallible transform. When the transform returns None, the 
+/// machine is not transitioned, and the last action it returned, or the 
+/// initial action if it has not been transitioned yet, is repeated. 
+pub struct FilteredInputMachine<'k, M, F, I> where
+    M: Automaton<'k>,
+    M::Action: Clone,
+    F: Fn(&I) -> Option<M::Input>,
+    I: 'k
+{
+    machine: M,
+    transform: F,
+    last: M::Action,
+    _bounds: PhantomData<&'k I>
+}
 
-    #[derive(Copy, Clone)]
-    struct Echoer;
+impl<'k, M, F, I> Clone for FilteredInputMachine<'k, M, F, I> where
+    M: Automaton<'k> + Clone,
+    M::Action: Clone,
+    F: Fn(&I) -> Option<M::Input> + Clone,
+    I: 'k
+{
+    fn clone(&self) -> Self {
+        FilteredInputMachine {
+            machine: self.machine.clone(),
+            transform: self.transform.clone(),
+            last: self.last.clone(),
+            _bounds: PhantomData
+        }
+    }
+}
 
-    impl InternalTransition for Echoer {
-        type Input = i64;
-        type Internal = ();
-        type Action = i64;
+impl<'k, M, F, I> Copy for FilteredInputMachine<'k, M, F, I> where
+    M: Automaton<'k> + Copy,
+    M::Action: Copy,
+    F: Fn(&I) -> Option<M::Input> + Copy,
+    I: 'k
+{}
 
-        fn step(&self, input: &i64, state: &mut ()) -> i64 {
-            *input
+impl<'k, M, F, I> FilteredInputMachine<'k, M, F, I> where
+    M: Automaton<'k>,
+    M::Action: Clone,
+    F: Fn(&I) -> Option<M::Input>,
+    I: 'k
+{
+    /// Create a new filtered input machine, which returns the given initial 
+    /// action for inputs filtered out before the machine is first 
+    /// transitioned. 
+    pub fn new(transform: F, machine: M, initial: M::Action) -> Self {
+        FilteredInputMachine {
+            machine: machine,
+            transform: transform,
+            last: initial,
+            _bounds: PhantomData
         }
     }
 
-    #[test]
-    fn input_map_test() {
-        use stateless_mapper::StatelessMapper;
-        use automata_combinators::MachineSeries;
-        let base_node = InternalStateMachine::new(Echoer, ());
-        let mut wrapped_machine = MachineSeries::new(
-            StatelessMapper::new(|input: &i64| -input), base_node);
-        assert_eq!(wrapped_machine.transition(&-5), 5);
-        assert_eq!(wrapped_machine.transition(&4), -4);
-        assert_eq!(wrapped_machine.transition(&-7), 7);
-        assert_eq!(wrapped_machine.transition(&12), -12);
+    /// Return the action which is repeated for filtered out inputs. 
+    pub fn last_action(&self) -> &M::Action {
+        &self.last
     }
+}
 
-    #[test]
-    fn output_map_test() {
-        use stateless_mapper::StatelessMapper;
-        use automata_combinators::MachineSeries;
-        let base_node = InternalStateMachine::new(Echoer, ());
-        let mut wrapped_machine = MachineSeries::new(
-            base_node,
-            StatelessMapper::new(|&val| val+1)
-        );
-        assert_eq!(wrapped_machine.transition(&-5), -4);
-        assert_eq!(wrapped_machine.transition(&4), 5);
-        assert_eq!(wrapped_machine.transition(&-7), -6);
-        assert_eq!(wrapped_machine.transition(&12), 13);
+impl<'k, M, F, I> Automaton<'k> for FilteredInputMachine<'k, M, F, I> where
+    M: Automaton<'k>,
+    M::Action: Clone,
+    F: Fn(&I) -> Option<M::Input>,
+    I: 'k
+{
+    type Input = I;
+    type Action = M::Action;
+
+    #[inline]
+    fn transition(&mut self, input: &I) -> M::Action {
+        if let Option::Some(mapped) = (self.transform)(input) {
+            self.last = self.machine.transition(&mapped);
+        }
+        self.last.clone()
+    }
+}
+
+impl<'k, M, F, I> FiniteStateAutomaton<'k> for FilteredInputMachine<'k, M, F, I> where
+    M: Automaton<'k> + Copy,
+    M::Action: Copy,
+    F: Fn(&I) -> Option<M::Input> + Copy,
+    I: 'k
+{}
+
+/// Wrapper for a machine, which only transitions the machine on inputs the 
+/// trigger fires on, and otherwise repeats the last action it returned, or 
+/// the initial action if it has not been transitioned yet. 
+pub struct Latch<'k, M, P> where
+    M: Automaton<'k>,
+    M::Action: Clone,
+    P: Fn(&M::Input) -> bool
+{
+    machine: M,
+    trigger: P,
+    last: M::Action,
+    _bounds: PhantomData<&'k M>
+}
+
+impl<'k, M, P> Clone for Latch<'k, M, P> where
+    M: Automaton<'k> + Clone,
+    M::Action: Clone,
+    P: Fn(&M::Input) -> bool + Clone
+{
+    fn clone(&self) -> Self {
+        Latch {
+            machine: self.machine.clone(),
+            trigger: self.trigger.clone(),
+            last: self.last.clone(),
+            _bounds: PhantomData
+        }
+    }
+}
+
+impl<'k, M, P> Copy for Latch<'k, M, P> where
+    M: Automaton<'k> + Copy,
+    M::Action: Copy,
+    P: Fn(&M::Input) -> bool + Copy
+{}
+
+impl<'k, M, P> Latch<'k, M, P> where
+    M: Automaton<'k>,
+    M::Action: Clone,
+    P: Fn(&M::Input) -> bool
+{
+    /// Create a new latch, which returns the given initial action until the 
+    /// trigger first fires. 
+    pub fn new(trigger: P, machine: M, initial: M::Action) -> Self {
+        Latch {
+            machine: machine,
+            trigger: trigger,
+            last: initial,
+            _bounds: PhantomData
+        }
+    }
+
+    /// Return the action which is held until the trigger fires. 
+    pub fn last_action(&self) -> &M::Action {
+        &self.last
+    }
+}
+
+impl<'k, M, P> Automaton<'k> for Latch<'k, M, P> where
+    M: Automaton<'k>,
+    M::Action: Clone,
+    P: Fn(&M::Input) -> bool
+{
+    type Input = M::Input;
+    type Action = M::Action;
+
+    #[inline]
+    fn transition(&mut self, input: &M::Input) -> M::Action {
+        if (self.trigger)(input) {
+            self.last = self.machine.transition(input);
+        }
+        self.last.clone()
+    }
+}
+
+impl<'k, M, P> FiniteStateAutomaton<'k> for Latch<'k, M, P> where
+    M: FiniteStateAutomaton<'k>,
+    M::Action: Copy,
+    P: Fn(&M::Input) -> bool + Copy
+{}
+
+/// Wrapper for a machine, which only transitions the machine once every 
+/// given number of transitions, and otherwise repeats the last action it 
+/// returned, or the initial action if it has not been transitioned yet. 
+pub struct RateDivider<'k, M> where
+    M: Automaton<'k>,
+    M::Action: Clone
+{
+    machine: M,
+    period: usize,
+    countdown: usize,
+    last: M::Action,
+    _bounds: PhantomData<&'k M>
+}
+
+impl<'k, M> Clone for RateDivider<'k, M> where
+    M: Automaton<'k> + Clone,
+    M::Action: Clone
+{
+    fn clone(&self) -> Self {
+        RateDivider {
+            machine: self.machine.clone(),
+            period: self.period,
+            countdown: self.countdown,
+            last: self.last.clone(),
+            _bounds: PhantomData
+        }
+    }
+}
+
+impl<'k, M> Copy for RateDivider<'k, M> where
+    M: Automaton<'k> + Copy,
+    M::Action: Copy
+{}
+
+impl<'k, M> RateDivider<'k, M> where
+    M: Automaton<'k>,
+    M::Action: Clone
+{
+    /// Create a new rate divider, which transitions the machine on its first 
+    /// transition, and once every period after that. 
+    pub fn new(period: usize, machine: M, initial: M::Action) -> Self {
+        RateDivider::with_phase(period, 0, machine, initial)
+    }
+
+    /// Create a new rate divider, which first transitions the machine after 
+    /// the given number of transitions, and once every period after that, 
+    /// so that several dividers with the same period can be spread out over 
+    /// different transitions. 
+    pub fn with_phase(period: usize, phase: usize, machine: M, initial: M::Action) -> Self {
+        assert!(period > 0, "Rate divider period must be positive");
+        RateDivider {
+            machine: machine,
+            period: period,
+            countdown: phase % period,
+            last: initial,
+            _bounds: PhantomData
+        }
+    }
+
+    /// Return the action which is repeated until the machine is next 
+    /// transitioned. 
+    pub fn last_action(&self) -> &M::Action {
+        &self.last
+    }
+}
+
+impl<'k, M> Automaton<'k> for RateDivider<'k, M> where
+    M: Automaton<'k>,
+    M::Action: Clone
+{
+    type Input = M::Input;
+    type Action = M::Action;
+
+    #[inline]
+    fn transition(&mut self, input: &M::Input) -> M::Action {
+        if self.countdown == 0 {
+            self.last = self.machine.transition(input);
+            self.countdown = self.period - 1;
+        } else {
+            self.countdown -= 1;
+        }
+        self.last.clone()
+    }
+}
+
+impl<'k, M> FiniteStateAutomaton<'k> for RateDivider<'k, M> where
+    M: FiniteStateAutomaton<'k>,
+    M::Action: Copy
+{}
+
+/// Wrapper for a machine, which maps each input to an input of the machine. 
+pub struct InputMappedMachine<'k, M, F, I> where
+    M: Automaton<'k>,
+    F: Fn(&I) -> M::Input,
+    I: 'k
+{
+    machine: M,
+    mapper: F,
+    _bounds: PhantomData<&'k I>
+}
+
+impl<'k, M, F, I> Clone for InputMappedMachine<'k, M, F, I> where
+    M: Automaton<'k> + Clone,
+    F: Fn(&I) -> M::Input + Clone,
+    I: 'k
+{
+    fn clone(&self) -> Self {
+        InputMappedMachine {
+            machine: self.machine.clone(),
+            mapper: self.mapper.clone(),
+            _bounds: PhantomData
+        }
+    }
+}
+
+impl<'k, M, F, I> Copy for InputMappedMachine<'k, M, F, I> where
+    M: Automaton<'k> + Copy,
+    F: Fn(&I) -> M::Input + Copy,
+    I: 'k
+{}
+
+impl<'k, M, F, I> InputMappedMachine<'k, M, F, I> where
+    M: Automaton<'k>,
+    F: Fn(&I) -> M::Input,
+    I: 'k
+{
+    /// Create a new input mapped machine. 
+    pub fn new(mapper: F, machine: M) -> Self {
+        InputMappedMachine {
+            machine: machine,
+            mapper: mapper,
+            _bounds: PhantomData
+        }
+    }
+}
+
+impl<'k, M, F, I> Automaton<'k> for InputMappedMachine<'k, M, F, I> where
+    M: Automaton<'k>,
+    F: Fn(&I) -> M::Input,
+    I: 'k
+{
+    type Input = I;
+    type Action = M::Action;
+
+    #[inline]
+    fn transition(&mut self, input: &I) -> M::Action {
+        self.machine.transition(&(self.mapper)(input))
+    }
+}
+
+impl<'k, M, F, I> FiniteStateAutomaton<'k> for InputMappedMachine<'k, M, F, I> where
+    M: FiniteStateAutomaton<'k>,
+    F: Fn(&I) -> M::Input + Copy,
+    I: 'k
+{}
+
+/// Wrapper for a machine, which maps each action the machine returns. 
+pub struct OutputMappedMachine<'k, M, F, A> where
+    M: Automaton<'k>,
+    F: Fn(M::Action) -> A
+{
+    machine: M,
+    mapper: F,
+    _bounds: PhantomData<&'k A>
+}
+
+impl<'k, M, F, A> Clone for OutputMappedMachine<'k, M, F, A> where
+    M: Automaton<'k> + Clone,
+    F: Fn(M::Action) -> A + Clone
+{
+    fn clone(&self) -> Self {
+        OutputMappedMachine {
+            machine: self.machine.clone(),
+            mapper: self.mapper.clone(),
+            _bounds: PhantomData
+        }
+    }
+}
+
+impl<'k, M, F, A> Copy for OutputMappedMachine<'k, M, F, A> where
+    M: Automaton<'k> + Copy,
+    F: Fn(M::Action) -> A + Copy
+{}
+
+impl<'k, M, F, A> OutputMappedMachine<'k, M, F, A> where
+    M: Automaton<'k>,
+    F: Fn(M::Action) -> A
+{
+    /// Create a new output mapped machine. 
+    pub fn new(machine: M, mapper: F) -> Self {
+        OutputMappedMachine {
+            machine: machine,
+            mapper: mapper,
+            _bounds: PhantomData
+        }
+    }
+}
+
+impl<'k, M, F, A> Automaton<'k> for OutputMappedMachine<'k, M, F, A> where
+    M: Automaton<'k>,
+    F: Fn(M::Action) -> A
+{
+    type Input = M::Input;
+    type Action = A;
+
+    #[inline]
+    fn transition(&mut self, input: &M::Input) -> A {
+        (self.mapper)(self.machine.transition(input))
+    }
+}
+
+impl<'k, M, F, A> FiniteStateAutomaton<'k> for OutputMappedMachine<'k, M, F, A> where
+    M: FiniteStateAutomaton<'k>,
+    F: Fn(M::Action) -> A + Copy
+{}
+
+/// Wrapper for a machine, which passes each input and the action the 
+/// machine returned for it to an inspector, such as for logging or 
+/// collecting statistics, without changing either. 
+pub struct InspectedMachine<'k, M, F> where
+    M: Automaton<'k>,
+    F: FnMut(&M::Input, &M::Action)
+{
+    machine: M,
+    inspector: F,
+    _bounds: PhantomData<&'k M>
+}
+
+impl<'k, M, F> Clone for InspectedMachine<'k, M, F> where
+    M: Automaton<'k> + Clone,
+    F: FnMut(&M::Input, &M::Action) + Clone
+{
+    fn clone(&self) -> Self {
+        InspectedMachine {
+            machine: self.machine.clone(),
+            inspector: self.inspector.clone(),
+            _bounds: PhantomData
+        }
+    }
+}
+
+impl<'k, M, F> Copy for InspectedMachine<'k, M, F> where
+    M: Automaton<'k> + Copy,
+    F: FnMut(&M::Input, &M::Action) + Copy
+{}
+
+impl<'k, M, F> InspectedMachine<'k, M, F> where
+    M: Automaton<'k>,
+    F: FnMut(&M::Input, &M::Action)
+{
+    /// Create a new inspected machine. 
+    pub fn new(machine: M, inspector: F) -> Self {
+        InspectedMachine {
+            machine: machine,
+            inspector: inspector,
+            _bounds: PhantomData
+        }
+    }
+}
+
+impl<'k, M, F> Automaton<'k> for InspectedMachine<'k, M, F> where
+    M: Automaton<'k>,
+    F: FnMut(&M::Input, &M::Action)
+{
+    type Input = M::Input;
+    type Action = M::Action;
+
+    #[inline]
+    fn transition(&mut self, input: &M::Input) -> M::Action {
+        let action = self.machine.transition(input);
+        (self.inspector)(input, &action);
+        action
+    }
+}
+
+impl<'k, M, F> FiniteStateAutomaton<'k> for InspectedMachine<'k, M, F> where
+    M: FiniteStateAutomaton<'k>,
+    F: FnMut(&M::Input, &M::Action) + Copy
+{}
+
+/// Combinator routing each input to one of several machines, selected by 
+/// the index the selector returns for the input. The other machines are 
+/// not transitioned. 
+pub struct Switch<'k, M, F> where
+    M: Automaton<'k>,
+    F: Fn(&M::Input) -> usize
+{
+    machines: Vec<M>,
+    selector: F,
+    _bounds: PhantomData<&'k M>
+}
+
+impl<'k, M, F> Clone for Switch<'k, M, F> where
+    M: Automaton<'k> + Clone,
+    F: Fn(&M::Input) -> usize + Clone
+{
+    fn clone(&self) -> Self {
+        Switch {
+            machines: self.machines.clone(),
+            selector: self.selector.clone(),
+            _bounds: PhantomData
+        }
+    }
+}
+
+impl<'k, M, F> Switch<'k, M, F> where
+    M: Automaton<'k>,
+    F: Fn(&M::Input) -> usize
+{
+    /// Create a new switch over the given machines. 
+    pub fn new(selector: F, machines: Vec<M>) -> Self {
+        Switch {
+            machines: machines,
+            selector: selector,
+            _bounds: PhantomData
+        }
+    }
+
+    /// Return the machines of the switch. 
+    pub fn machines(&self) -> &[M] {
+        &self.machines
+    }
+}
+
+impl<'k, M, F> Automaton<'k> for Switch<'k, M, F> where
+    M: Automaton<'k>,
+    F: Fn(&M::Input) -> usize
+{
+    type Input = M::Input;
+    type Action = M::Action;
+
+    #[inline]
+    fn transition(&mut self, input: &M::Input) -> M::Action {
+        let index = (self.selector)(input);
+        let count = self.machines.len();
+        match self.machines.get_mut(index) {
+            Option::Some(machine) => machine.transition(input),
+            Option::None => panic!("Switch selected machine {} of {}", index, count)
+        }
+    }
+}
+
+/// Combinator holding a machine for each key the key function returns for 
+/// its inputs, such as a target or a channel, and routing each input to the 
+/// machine of its key. Machines are created by the constructor the first 
+/// time their key comes up. 
+pub struct Demux<'k, M, K, F, C> where
+    M: Automaton<'k>,
+    K: Eq + Hash,
+    F: Fn(&M::Input) -> K,
+    C: Fn(&K) -> M
+{
+    machines: HashMap<K, M>,
+    key: F,
+    constructor: C,
+    _bounds: PhantomData<&'k M>
+}
+
+impl<'k, M, K, F, C> Clone for Demux<'k, M, K, F, C> where
+    M: Automaton<'k> + Clone,
+    K: Eq + Hash + Clone,
+    F: Fn(&M::Input) -> K + Clone,
+    C: Fn(&K) -> M + Clone
+{
+    fn clone(&self) -> Self {
+        Demux {
+            machines: self.machines.clone(),
+            key: self.key.clone(),
+            constructor: self.constructor.clone(),
+            _bounds: PhantomData
+        }
+    }
+}
+
+impl<'k, M, K, F, C> Demux<'k, M, K, F, C> where
+    M: Automaton<'k>,
+    K: Eq + Hash,
+    F: Fn(&M::Input) -> K,
+    C: Fn(&K) -> M
+{
+    /// Create a new demultiplexer without any machines. 
+    pub fn new(key: F, constructor: C) -> Self {
+        Demux {
+            machines: HashMap::new(),
+            key: key,
+            constructor: constructor,
+            _bounds: PhantomData
+        }
+    }
+
+    /// Return the machine of the given key, if it has been created. 
+    pub fn machine(&self, key: &K) -> Option<&M> {
+        self.machines.get(key)
+    }
+
+    /// Remove the machine of the given key, which is created anew if the 
+    /// key comes up again. 
+    pub fn remove(&mut self, key: &K) -> Option<M> {
+        self.machines.remove(key)
+    }
+
+    /// Return the number of machines created so far and not removed. 
+    pub fn len(&self) -> usize {
+        self.machines.len()
+    }
+
+    /// Return whether no machines have been created, or all of them have 
+    /// been removed. 
+    pub fn is_empty(&self) -> bool {
+        self.machines.is_empty()
+    }
+}
+
+impl<'k, M, K, F, C> Automaton<'k> for Demux<'k, M, K, F, C> where
+    M: Automaton<'k>,
+    K: Eq + Hash,
+    F: Fn(&M::Input) -> K,
+    C: Fn(&K) -> M
+{
+    type Input = M::Input;
+    type Action = M::Action;
+
+    #[inline]
+    fn transition(&mut self, input: &M::Input) -> M::Action {
+        let key = (self.key)(input);
+        let constructor = &self.constructor;
+        match self.machines.entry(key) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let machine = constructor(entry.key());
+                entry.insert(machine)
+            }
+        }.transition(input)
+    }
+}
+
+/// One of two machines of different types with the same input and action 
+/// types, so that a function can return either of them without boxing. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum EitherMachine<A, B> {
+    /// The first machine. 
+    Left(A),
+    /// The second machine. 
+    Right(B)
+}
+
+impl<'k, A, B> Automaton<'k> for EitherMachine<A, B> where
+    A: Automaton<'k>,
+    B: Automaton<'k, Input=A::Input, Action=A::Action>
+{
+    type Input = A::Input;
+    type Action = A::Action;
+
+    #[inline]
+    fn transition(&mut self, input: &A::Input) -> A::Action {
+        match *self {
+            EitherMachine::Left(ref mut machine) => machine.transition(input),
+            EitherMachine::Right(ref mut machine) => machine.transition(input)
+        }
+    }
+}
+
+impl<'k, A, B> FiniteStateAutomaton<'k> for EitherMachine<A, B> where
+    A: FiniteStateAutomaton<'k>,
+    B: FiniteStateAutomaton<'k, Input=A::Input, Action=A::Action>
+{}
+
+/// Wrapper for a machine, which returns the action the machine returned K 
+/// transitions ago, or the default action for the first K transitions. 
+#[cfg(feature = "const_generics")]
+pub struct Delay<'k, M, const K: usize> where
+    M: Automaton<'k>,
+    M::Action: Default
+{
+    machine: M,
+    pending: [M::Action; K],
+    position: usize,
+    _bounds: PhantomData<&'k M>
+}
+
+#[cfg(feature = "const_generics")]
+impl<'k, M, const K: usize> Clone for Delay<'k, M, K> where
+    M: Automaton<'k> + Clone,
+    M::Action: Default + Clone
+{
+    fn clone(&self) -> Self {
+        Delay {
+            machine: self.machine.clone(),
+            pending: self.pending.clone(),
+            position: self.position,
+            _bounds: PhantomData
+        }
+    }
+}
+
+#[cfg(feature = "const_generics")]
+impl<'k, M, const K: usize> Copy for Delay<'k, M, K> where
+    M: Automaton<'k> + Copy,
+    M::Action: Default + Copy
+{}
+
+#[cfg(feature = "const_generics")]
+impl<'k, M, const K: usize> Delay<'k, M, K> where
+    M: Automaton<'k>,
+    M::Action: Default
+{
+    /// Create a new delayed machine. 
+    pub fn new(machine: M) -> Self {
+        Delay {
+            machine: machine,
+            pending: array::from_fn(|_| M::Action::default()),
+            position: 0,
+            _bounds: PhantomData
+        }
+    }
+}
+
+#[cfg(feature = "const_generics")]
+impl<'k, M, const K: usize> Automaton<'k> for Delay<'k, M, K> where
+    M: Automaton<'k>,
+    M::Action: Default
+{
+    type Input = M::Input;
+    type Action = M::Action;
+
+    #[inline]
+    fn transition(&mut self, input: &M::Input) -> M::Action {
+        let action = self.machine.transition(input);
+        if K == 0 {
+            return action;
+        }
+        let delayed = mem::replace(&mut self.pending[self.position], action);
+        self.position = (self.position + 1) % K;
+        delayed
+    }
+}
+
+#[cfg(feature = "const_generics")]
+impl<'k, M, const K: usize> FiniteStateAutomaton<'k> for Delay<'k, M, K> where
+    M: FiniteStateAutomaton<'k>,
+    M::Action: Default + Copy
+{}
+
+/// Wrapper for a machine, which returns the last K actions of the machine, 
+/// from oldest to newest, with the default action in place of those from 
+/// before the machine was first transitioned. 
+#[cfg(feature = "const_generics")]
+pub struct History<'k, M, const K: usize> where
+    M: Automaton<'k>,
+    M::Action: Default + Clone
+{
+    machine: M,
+    recent: [M::Action; K],
+    position: usize,
+    _bounds: PhantomData<&'k M>
+}
+
+#[cfg(feature = "const_generics")]
+impl<'k, M, const K: usize> Clone for History<'k, M, K> where
+    M: Automaton<'k> + Clone,
+    M::Action: Default + Clone
+{
+    fn clone(&self) -> Self {
+        History {
+            machine: self.machine.clone(),
+            recent: self.recent.clone(),
+            position: self.position,
+            _bounds: PhantomData
+        }
+    }
+}
+
+#[cfg(feature = "const_generics")]
+impl<'k, M, const K: usize> Copy for History<'k, M, K> where
+    M: Automaton<'k> + Copy,
+    M::Action: Default + Copy
+{}
+
+#[cfg(feature = "const_generics")]
+impl<'k, M, const K: usize> History<'k, M, K> where
+    M: Automaton<'k>,
+    M::Action: Default + Clone
+{
+    /// Create a new machine history. 
+    pub fn new(machine: M) -> Self {
+        History {
+            machine: machine,
+            recent: array::from_fn(|_| M::Action::default()),
+            position: 0,
+            _bounds: PhantomData
+        }
+    }
+}
+
+#[cfg(feature = "const_generics")]
+impl<'k, M, const K: usize> Automaton<'k> for History<'k, M, K> where
+    M: Automaton<'k>,
+    M::Action: Default + Clone
+{
+    type Input = M::Input;
+    type Action = [M::Action; K];
+
+    #[inline]
+    fn transition(&mut self, input: &M::Input) -> [M::Action; K] {
+        let action = self.machine.transition(input);
+        if K > 0 {
+            self.recent[self.position] = action;
+            self.position = (self.position + 1) % K;
+        }
+        let oldest = self.position;
+        array::from_fn(|offset| self.recent[(oldest + offset) % K].clone())
+    }
+}
+
+#[cfg(feature = "const_generics")]
+impl<'k, M, const K: usize> FiniteStateAutomaton<'k> for History<'k, M, K> where
+    M: FiniteStateAutomaton<'k>,
+    M::Action: Default + Copy
+{}
+
+impl<'k, M, N> Snapshot for MachineSeries<'k, M, N> where 
+    M: Automaton<'k> + Snapshot,
+    N: Automaton<'k, Input=M::Action> + Snapshot
+{
+    type State = (M::State, N::State);
+
+    fn snapshot(&self) -> (M::State, N::State) {
+        (self.before.snapshot(), self.after.snapshot())
+    }
+
+    fn restore(state: (M::State, N::State)) -> Self {
+        MachineSeries::new(M::restore(state.0), N::restore(state.1))
+    }
+}
+
+impl<'k, M, N, B> Snapshot for MachineSeriesWith<'k, M, N, B> where 
+    M: Automaton<'k> + Snapshot,
+    N: for<'t> Automaton<'t, Input=(&'t M::Input, &'t M::Action), Action=B> + Snapshot
+{
+    type State = (M::State, N::State);
+
+    fn snapshot(&self) -> (M::State, N::State) {
+        (self.before.snapshot(), self.after.snapshot())
+    }
+
+    fn restore(state: (M::State, N::State)) -> Self {
+        MachineSeriesWith::new(M::restore(state.0), N::restore(state.1))
+    }
+}
+
+impl<'k, M, N> Snapshot for MachineTee<'k, M, N> where 
+    M: Automaton<'k> + Snapshot,
+    N: Automaton<'k, Input=M::Action> + Snapshot
+{
+    type State = (M::State, N::State);
+
+    fn snapshot(&self) -> (M::State, N::State) {
+        (self.before.snapshot(), self.after.snapshot())
+    }
+
+    fn restore(state: (M::State, N::State)) -> Self {
+        MachineTee::new(M::restore(state.0), N::restore(state.1))
+    }
+}
+
+impl<'k, M, N, B> Snapshot for MachineTeeWith<'k, M, N, B> where 
+    M: Automaton<'k> + Snapshot,
+    N: for<'t> Automaton<'t, Input=(&'t M::Input, &'t M::Action), Action=B> + Snapshot
+{
+    type State = (M::State, N::State);
+
+    fn snapshot(&self) -> (M::State, N::State) {
+        (self.before.snapshot(), self.after.snapshot())
+    }
+
+    fn restore(state: (M::State, N::State)) -> Self {
+        MachineTeeWith::new(M::restore(state.0), N::restore(state.1))
+    }
+}
+
+impl<'k, M, N> Snapshot for ParallelMachines<'k, M, N> where 
+    M: Automaton<'k> + Snapshot,
+    N: Automaton<'k, Input=M::Input> + Snapshot
+{
+    type State = (M::State, N::State);
+
+    fn snapshot(&self) -> (M::State, N::State) {
+        (self.first.snapshot(), self.second.snapshot())
+    }
+
+    fn restore(state: (M::State, N::State)) -> Self {
+        ParallelMachines::new(M::restore(state.0), N::restore(state.1))
+    }
+}
+
+impl<A, B> Snapshot for EitherMachine<A, B> where 
+    A: Snapshot,
+    B: Snapshot
+{
+    type State = EitherMachine<A::State, B::State>;
+
+    fn snapshot(&self) -> EitherMachine<A::State, B::State> {
+        match *self {
+            EitherMachine::Left(ref machine) => EitherMachine::Left(machine.snapshot()),
+            EitherMachine::Right(ref machine) => EitherMachine::Right(machine.snapshot())
+        }
+    }
+
+    fn restore(state: EitherMachine<A::State, B::State>) -> Self {
+        match state {
+            EitherMachine::Left(state) => EitherMachine::Left(A::restore(state)),
+            EitherMachine::Right(state) => EitherMachine::Right(B::restore(state))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use internal_state_machine::{InternalTransition, 
+        InternalStateMachine};
+    use automaton::Automaton;
+
+    #[derive(Copy, Clone)]
+    struct Echoer;
+
+    impl InternalTransition for Echoer {
+        type Input = i64;
+        type Internal = ();
+        type Action = i64;
+
+        fn step(&self, input: &i64, _state: &mut ()) -> i64 {
+            *input
+        }
+    }
+
+    #[test]
+    fn input_map_test() {
+        use stateless_mapper::StatelessMapper;
+        use automata_combinators::MachineSeries;
+        let base_node = InternalStateMachine::new(Echoer, ());
+        let mut wrapped_machine = MachineSeries::new(
+            StatelessMapper::new(|input: &i64| -input), base_node);
+        assert_eq!(wrapped_machine.transition(&-5), 5);
+        assert_eq!(wrapped_machine.transition(&4), -4);
+        assert_eq!(wrapped_machine.transition(&-7), 7);
+        assert_eq!(wrapped_machine.transition(&12), -12);
+    }
+
+    #[test]
+    fn closure_map_test() {
+        use automata_combinators::MachineSeries;
+        let mut input_mapped = MachineSeries::with_input_map(|input: &(i64, i64)| 
+            input.0 * input.1, InternalStateMachine::new(Echoer, ()));
+        assert_eq!(input_mapped.transition(&(3, -2)), -6);
+        let mut output_mapped = MachineSeries::with_output_map(
+            InternalStateMachine::new(Echoer, ()), |action: &i64| *action > 0);
+        assert!(output_mapped.transition(&2));
+        assert!(!output_mapped.transition(&-2));
+    }
+
+    // Sums up the inputs and actions it is given references to
+    struct Observer(i64);
+
+    impl<'t> Automaton<'t> for Observer {
+        type Input = (&'t i64, &'t i64);
+        type Action = i64;
+
+        fn transition(&mut self, pair: &(&'t i64, &'t i64)) -> i64 {
+            self.0 += pair.0 + pair.1;
+            self.0
+        }
+    }
+
+    // Subtracts the input from the action it is given references to
+    struct Difference;
+
+    impl<'t> Automaton<'t> for Difference {
+        type Input = (&'t i64, &'t i64);
+        type Action = i64;
+
+        fn transition(&mut self, pair: &(&'t i64, &'t i64)) -> i64 {
+            pair.1 - pair.0
+        }
+    }
+
+    #[test]
+    fn series_with_test() {
+        use stateless_mapper::StatelessMapper;
+        use automata_combinators::MachineSeriesWith;
+        let mut machine = MachineSeriesWith::new(
+            InternalStateMachine::new(Echoer, ()).then(
+                StatelessMapper::new(|val: &i64| val * 2)),
+            Difference
+        );
+        assert_eq!(machine.transition(&3), 3);
+        assert_eq!(machine.transition(&-4), -4);
+    }
+
+    #[test]
+    fn tee_with_test() {
+        let mut machine = InternalStateMachine::new(Echoer, ()).tee_with(Observer(0));
+        assert_eq!(machine.transition(&3), (3, 6));
+        assert_eq!(machine.transition(&-1), (-1, 4));
+    }
+
+    #[test]
+    fn output_map_test() {
+        use stateless_mapper::StatelessMapper;
+        use automata_combinators::MachineSeries;
+        let base_node = InternalStateMachine::new(Echoer, ());
+        let mut wrapped_machine = MachineSeries::new(
+            base_node,
+            StatelessMapper::new(|&val| val+1)
+        );
+        assert_eq!(wrapped_machine.transition(&-5), -4);
+        assert_eq!(wrapped_machine.transition(&4), 5);
+        assert_eq!(wrapped_machine.transition(&-7), -6);
+        assert_eq!(wrapped_machine.transition(&12), 13);
+    }
+
+    #[test]
+    fn filtered_input_test() {
+        use automata_combinators::FilteredInputMachine;
+        let base_node = InternalStateMachine::with(|input: &i64, sum: &mut i64| {
+            *sum += input;
+            *sum
+        }, 0);
+        let mut wrapped_machine = FilteredInputMachine::new(|input: &Option<i64>| *input, 
+            base_node, -1);
+        assert_eq!(wrapped_machine.transition(&Option::None), -1);
+        assert_eq!(wrapped_machine.transition(&Option::Some(3)), 3);
+        assert_eq!(wrapped_machine.transition(&Option::None), 3);
+        assert_eq!(wrapped_machine.transition(&Option::Some(4)), 7);
+        assert_eq!(*wrapped_machine.last_action(), 7);
+    }
+
+    #[test]
+    fn latch_test() {
+        use automata_combinators::Latch;
+        let mut latch = Latch::new(|input: &i64| *input % 2 == 0, 
+            InternalStateMachine::new(Echoer, ()), -1);
+        assert_eq!(latch.transition(&1), -1);
+        assert_eq!(latch.transition(&2), 2);
+        assert_eq!(latch.transition(&3), 2);
+        assert_eq!(latch.transition(&5), 2);
+        assert_eq!(latch.transition(&6), 6);
+        assert_eq!(*latch.last_action(), 6);
+    }
+
+    #[test]
+    fn rate_divider_test() {
+        use automata_combinators::RateDivider;
+        let mut divided = RateDivider::new(3, InternalStateMachine::new(Echoer, ()), -1);
+        let actions = (1..8).map(|input| divided.transition(&input)).collect::<Vec<_>>();
+        assert_eq!(actions, vec![1, 1, 1, 4, 4, 4, 7]);
+        let mut shifted = RateDivider::with_phase(3, 2, InternalStateMachine::new(Echoer, ()), 
+            -1);
+        let actions = (1..8).map(|input| shifted.transition(&input)).collect::<Vec<_>>();
+        assert_eq!(actions, vec![-1, -1, 3, 3, 3, 6, 6]);
+        assert_eq!(*shifted.last_action(), 6);
+    }
+
+    #[test]
+    #[should_panic]
+    fn rate_divider_zero_test() {
+        use automata_combinators::RateDivider;
+        RateDivider::new(0, InternalStateMachine::new(Echoer, ()), -1);
+    }
+
+    #[test]
+    fn mapped_and_inspected_test() {
+        let mut seen = Vec::new();
+        {
+            let mut wrapped_machine = InternalStateMachine::new(Echoer, ())
+                .map_input(|input: &(i64, i64)| input.0 * input.1)
+                .map_output(|action: i64| action > 0)
+                .inspect(|input: &(i64, i64), action: &bool| seen.push((input.0, *action)));
+            assert!(wrapped_machine.transition(&(2, 3)));
+            assert!(!wrapped_machine.transition(&(-2, 3)));
+            assert!(wrapped_machine.transition(&(-1, -1)));
+        }
+        assert_eq!(seen, vec![(2, true), (-2, false), (-1, true)]);
+    }
+
+    #[test]
+    fn switch_test() {
+        use automata_combinators::Switch;
+        let mut switch = Switch::new(|input: &i64| if *input < 0 { 0 } else { 1 }, vec![
+            InternalStateMachine::new(IndefinitePlayback, -1),
+            InternalStateMachine::new(IndefinitePlayback, 1)
+        ]);
+        assert_eq!(switch.transition(&-5), -1);
+        assert_eq!(switch.transition(&5), 1);
+        assert_eq!(switch.machines().len(), 2);
+    }
+
+    #[test]
+    fn either_machine_test() {
+        use automata_combinators::EitherMachine;
+        use snapshot::Snapshot;
+        let pick = |echo: bool| if echo {
+            EitherMachine::Left(InternalStateMachine::new(Echoer, ()))
+        } else {
+            EitherMachine::Right(InternalStateMachine::new(IndefinitePlayback, 7))
+        };
+        let mut left = pick(true);
+        let mut right = pick(false);
+        assert_eq!(left.transition(&3), 3);
+        assert_eq!(right.transition(&3), 7);
+        let mut playback = EitherMachine::<InternalStateMachine<IndefinitePlayback>, 
+            InternalStateMachine<IndefinitePlayback>>::Left(InternalStateMachine::new(
+            IndefinitePlayback, 4));
+        let mut restored = EitherMachine::<InternalStateMachine<IndefinitePlayback>, 
+            InternalStateMachine<IndefinitePlayback>>::restore(playback.snapshot());
+        assert_eq!(playback.transition(&0), 4);
+        assert_eq!(restored.transition(&0), 4);
+    }
+
+    #[cfg(feature = "const_generics")]
+    #[test]
+    fn delay_test() {
+        use automata_combinators::Delay;
+        let mut delayed = Delay::<_, 2>::new(InternalStateMachine::new(Echoer, ()));
+        assert_eq!(delayed.transition(&1), 0);
+        assert_eq!(delayed.transition(&2), 0);
+        assert_eq!(delayed.transition(&3), 1);
+        assert_eq!(delayed.transition(&4), 2);
+        assert_eq!(delayed.transition(&5), 3);
+        let mut undelayed = Delay::<_, 0>::new(InternalStateMachine::new(Echoer, ()));
+        assert_eq!(undelayed.transition(&1), 1);
+    }
+
+    #[cfg(feature = "const_generics")]
+    #[test]
+    fn history_test() {
+        use automata_combinators::History;
+        let mut history = History::<_, 3>::new(InternalStateMachine::new(Echoer, ()));
+        assert_eq!(history.transition(&1), [0, 0, 1]);
+        assert_eq!(history.transition(&2), [0, 1, 2]);
+        assert_eq!(history.transition(&3), [1, 2, 3]);
+        assert_eq!(history.transition(&4), [2, 3, 4]);
+        let mut empty = History::<_, 0>::new(InternalStateMachine::new(Echoer, ()));
+        assert_eq!(empty.transition(&1), []);
+    }
+
+    #[test]
+    fn demux_test() {
+        use automata_combinators::Demux;
+        let mut demux = Demux::new(|input: &(char, i64)| input.0, |key: &char| {
+            let channel = *key;
+            InternalStateMachine::with(move |input: &(char, i64), total: &mut i64| {
+                *total += input.1;
+                (channel, *total)
+            }, 0)
+        });
+        assert!(demux.is_empty());
+        assert_eq!(demux.transition(&('a', 1)), ('a', 1));
+        assert_eq!(demux.transition(&('b', 10)), ('b', 10));
+        assert_eq!(demux.transition(&('a', 2)), ('a', 3));
+        assert_eq!(demux.len(), 2);
+        assert!(demux.remove(&'a').is_some());
+        assert!(demux.machine(&'a').is_none());
+        assert_eq!(demux.transition(&('a', 4)), ('a', 4));
+        assert_eq!(demux.transition(&('b', 5)), ('b', 15));
     }
 
     #[derive(Copy, Clone, Default)]
@@ -319,7 +1563,7 @@ mod tests {
         type Internal = i64;
         type Action = i64;
 
-        fn step(&self, input: &i64, state: &mut i64) -> i64{
+        fn step(&self, _input: &i64, state: &mut i64) -> i64{
             *state
         }
     }
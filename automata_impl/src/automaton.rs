@@ -1,6 +1,18 @@
 use std::ops::FnMut;
 use std::iter::Iterator;
-use automata_combinators::{MachineSeries, MachineTee, ParallelMachines};
+use std::fmt;
+use std::collections::HashMap;
+use std::hash::Hash;
+#[cfg(feature = "const_generics")]
+use std::array;
+use automata_combinators::{MachineSeries, MachineSeriesWith, MachineTee, 
+    MachineTeeWith, ParallelMachines, InputMappedMachine, OutputMappedMachine, 
+    InspectedMachine};
+use snapshot::Snapshot;
+
+/// Boxed FnMut made from an automaton, as returned by as_fnmut and 
+/// into_fnmut. 
+pub type BoxedFnMut<'t, I, A> = Box<dyn FnMut(&I) -> A + 't>;
 
 /// The automaton trait is used to represent agents which, at a regular rate, 
 /// take input, process it, and return an action. Most of them also change 
@@ -34,10 +46,10 @@ pub trait Automaton<'k> {
     fn transition(&mut self, input: &Self::Input) -> Self::Action;
 
     /// Temporarily use the automaton as an FnMut. 
-    fn as_fnmut<'t>(&'t mut self) -> Box<FnMut(&Self::Input)->Self::Action+'t> where 
+    fn as_fnmut<'t>(&'t mut self) -> BoxedFnMut<'t, Self::Input, Self::Action> where 
         'k: 't
     {
-        let mut this = self;
+        let this = self;
         Box::new(move |input: &Self::Input| {
             this.transition(input)
         })
@@ -45,7 +57,7 @@ pub trait Automaton<'k> {
 
     #[cfg(not(feature = "unsized_locals"))]
     /// Turn a automaton value into an FnMut. 
-    fn into_fnmut(self) -> Box<FnMut(&Self::Input) -> Self::Action + 'k> where 
+    fn into_fnmut(self) -> BoxedFnMut<'k, Self::Input, Self::Action> where 
         Self: Sized + 'k
     {
         let mut this = self;
@@ -56,7 +68,7 @@ pub trait Automaton<'k> {
 
     #[cfg(feature = "unsized_locals")]
     /// Turn a automaton value into an FnMut. 
-    fn into_fnmut(self) -> Box<FnMut(&Self::Input) -> Self::Action + 'k> where 
+    fn into_fnmut(self) -> BoxedFnMut<'k, Self::Input, Self::Action> where 
         Self: 'k
     {
         let mut this = self;
@@ -66,8 +78,8 @@ pub trait Automaton<'k> {
     }
 
     /// Turn the boxed automaton into an fnmut. 
-    fn boxed_into_fnmut(self: Box<Self>) -> Box<FnMut(&Self::Input) -> 
-        Self::Action + 'k> where 
+    fn boxed_into_fnmut(self: Box<Self>) -> BoxedFnMut<'k, Self::Input, 
+        Self::Action> where 
         Self: 'k
     {
         let mut this = self;
@@ -76,6 +88,33 @@ pub trait Automaton<'k> {
         })
     }
 
+    /// Transition the automaton the given number of times with the same 
+    /// input, returning the actions it took. 
+    fn step_n(&mut self, input: &Self::Input, count: usize) -> Vec<Self::Action> {
+        (0..count).map(|_| self.transition(input)).collect()
+    }
+
+    /// Transition the automaton with each of the given inputs until it 
+    /// returns an action satisfying the predicate, returning that action 
+    /// along with the number of transitions it took, or None if the inputs 
+    /// run out first. 
+    fn run_until<J, P>(&mut self, inputs: J, done: P) -> Option<(Self::Action, usize)> 
+        where 
+        J: IntoIterator<Item=Self::Input>,
+        P: Fn(&Self::Action) -> bool,
+        Self: Sized
+    {
+        let mut steps = 0;
+        for input in inputs {
+            steps += 1;
+            let action = self.transition(&input);
+            if done(&action) {
+                return Option::Some((action, steps));
+            }
+        }
+        Option::None
+    }
+
     fn then<N>(self, after: N) -> MachineSeries<'k, Self, N> where
         N: Automaton<'k, Input=Self::Action>,
         Self: Sized + 'k
@@ -83,6 +122,15 @@ pub trait Automaton<'k> {
         MachineSeries::new(self, after)
     }
 
+    /// Chain another automaton after this one, which is given references to 
+    /// both the input and the action of this automaton. 
+    fn then_with<N, B>(self, after: N) -> MachineSeriesWith<'k, Self, N, B> where
+        N: for<'t> Automaton<'t, Input=(&'t Self::Input, &'t Self::Action), Action=B>,
+        Self: Sized + 'k
+    {
+        MachineSeriesWith::new(self, after)
+    }
+
     fn tee<N>(self, after: N) -> MachineTee<'k, Self, N> where
         N: Automaton<'k, Input=Self::Action>,
         Self: Sized + 'k
@@ -90,12 +138,48 @@ pub trait Automaton<'k> {
         MachineTee::new(self, after)
     }
 
+    /// Tee another automaton off this one, which is given references to both 
+    /// the input and the action of this automaton. 
+    fn tee_with<N, B>(self, after: N) -> MachineTeeWith<'k, Self, N, B> where
+        N: for<'t> Automaton<'t, Input=(&'t Self::Input, &'t Self::Action), Action=B>,
+        Self: Sized + 'k
+    {
+        MachineTeeWith::new(self, after)
+    }
+
     fn alongside<N>(self, other: N) -> ParallelMachines<'k, Self, N> where 
         N: Automaton<'k, Input=Self::Input>,
         Self: Sized + 'k
     {
         ParallelMachines::new(self, other)
     }
+
+    /// Map each input through the mapper before passing it to this 
+    /// automaton. 
+    fn map_input<F, I>(self, mapper: F) -> InputMappedMachine<'k, Self, F, I> where 
+        F: Fn(&I) -> Self::Input,
+        I: 'k,
+        Self: Sized + 'k
+    {
+        InputMappedMachine::new(mapper, self)
+    }
+
+    /// Map each action of this automaton through the mapper. 
+    fn map_output<F, A>(self, mapper: F) -> OutputMappedMachine<'k, Self, F, A> where 
+        F: Fn(Self::Action) -> A,
+        Self: Sized + 'k
+    {
+        OutputMappedMachine::new(self, mapper)
+    }
+
+    /// Pass each input and the action this automaton returned for it to the 
+    /// inspector. 
+    fn inspect<F>(self, inspector: F) -> InspectedMachine<'k, Self, F> where 
+        F: FnMut(&Self::Input, &Self::Action),
+        Self: Sized + 'k
+    {
+        InspectedMachine::new(self, inspector)
+    }
 }
 
 impl<'k, P> Automaton<'k> for Box<P> where 
@@ -110,6 +194,8 @@ impl<'k, P> Automaton<'k> for Box<P> where
     }
 }
 
+// Collecting the actions into a new boxed slice allocates every transition, 
+// which the BufferedAutomaton implementation avoids
 impl<'k, M> Automaton<'k> for [M] where 
     M: Automaton<'k>
 {
@@ -136,12 +222,383 @@ impl<'k, I, A> Automaton<'k> for [&'k mut dyn Automaton<'k, Input=I, Action=A>]
     }
 }
 
+impl<'k, M> Automaton<'k> for Vec<M> where 
+    M: Automaton<'k>
+{
+    type Input = M::Input;
+    type Action = Box<[M::Action]>;
+
+    fn transition(&mut self, input: &M::Input) -> Self::Action {
+        self.as_mut_slice().transition(input)
+    }
+}
+
+#[cfg(feature = "const_generics")]
+impl<'k, M, const N: usize> Automaton<'k> for [M; N] where 
+    M: Automaton<'k>
+{
+    type Input = M::Input;
+    type Action = [M::Action; N];
+
+    fn transition(&mut self, input: &M::Input) -> [M::Action; N] {
+        array::from_fn(|index| self[index].transition(input))
+    }
+}
+
+// An absent machine does nothing, which it reports with the default action
+impl<'k, M> Automaton<'k> for Option<M> where 
+    M: Automaton<'k>,
+    M::Action: Default
+{
+    type Input = M::Input;
+    type Action = M::Action;
+
+    fn transition(&mut self, input: &M::Input) -> M::Action {
+        match *self {
+            Option::Some(ref mut machine) => machine.transition(input),
+            Option::None => M::Action::default()
+        }
+    }
+}
+
+impl<'k, K, M> Automaton<'k> for HashMap<K, M> where 
+    K: Eq + Hash + Clone,
+    M: Automaton<'k>
+{
+    type Input = M::Input;
+    type Action = HashMap<K, M::Action>;
+
+    fn transition(&mut self, input: &M::Input) -> HashMap<K, M::Action> {
+        self.iter_mut()
+            .map(|(key, mach)| (key.clone(), mach.transition(input)))
+            .collect()
+    }
+}
+
+/// Trait for collections of automata which all transition on the same 
+/// input, writing their actions into a buffer owned by the caller instead of 
+/// returning them. Reusing the buffer between transitions avoids allocating 
+/// once it has grown to hold all of the actions, unlike the automaton 
+/// implementation of slices of automata, which allocates a new boxed slice 
+/// of actions every transition. 
+/// 
+/// # Example
+/// ```
+/// use stackbt_automata_impl::automaton::BufferedAutomaton;
+/// use stackbt_automata_impl::stateless_mapper::StatelessMapper;
+/// let positive = |input: &i64| *input > 0;
+/// let mut machines = vec![
+///     StatelessMapper::new(positive), 
+///     StatelessMapper::new(positive)
+/// ];
+/// let mut actions = Vec::new();
+/// machines.transition_into(&1, &mut actions);
+/// assert_eq!(actions, vec![true, true]);
+/// machines.transition_into(&-1, &mut actions);
+/// assert_eq!(actions, vec![false, false]);
+/// ```
+pub trait BufferedAutomaton<'k> {
+    /// The input type taken by the automata. 
+    type Input: 'k;
+    /// The action type returned by each of the automata. 
+    type Action;
+
+    /// Take an input by reference, transition each of the automata with it, 
+    /// and replace the contents of the buffer with their actions, in order. 
+    fn transition_into(&mut self, input: &Self::Input, actions: &mut Vec<Self::Action>);
+}
+
+impl<'k, M> BufferedAutomaton<'k> for [M] where 
+    M: Automaton<'k>
+{
+    type Input = M::Input;
+    type Action = M::Action;
+
+    fn transition_into(&mut self, input: &M::Input, actions: &mut Vec<M::Action>) {
+        actions.clear();
+        actions.extend(self.iter_mut().map(|mach| mach.transition(input)));
+    }
+}
+
+impl<'k, I, A> BufferedAutomaton<'k> for [&'k mut dyn Automaton<'k, Input=I, Action=A>] {
+    type Input = I;
+    type Action = A;
+
+    fn transition_into(&mut self, input: &I, actions: &mut Vec<A>) {
+        actions.clear();
+        actions.extend(self.iter_mut().map(|mach| mach.transition(input)));
+    }
+}
+
+impl<'k, P> BufferedAutomaton<'k> for Box<P> where 
+    P: BufferedAutomaton<'k> + ?Sized
+{
+    type Input = P::Input;
+    type Action = P::Action;
+
+    fn transition_into(&mut self, input: &P::Input, actions: &mut Vec<P::Action>) {
+        self.as_mut().transition_into(input, actions);
+    }
+}
+
+impl<'k, M> BufferedAutomaton<'k> for Vec<M> where 
+    M: Automaton<'k>
+{
+    type Input = M::Input;
+    type Action = M::Action;
+
+    fn transition_into(&mut self, input: &M::Input, actions: &mut Vec<M::Action>) {
+        self.as_mut_slice().transition_into(input, actions);
+    }
+}
+
+/// Adapter for automata which return boxed slices of actions, such as 
+/// hand-written collections of automata of differing types, so that they 
+/// can be used where buffered automata are expected. The boxed slice is 
+/// still allocated by the adapted automaton every transition. 
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct BoxedActions<M> {
+    machine: M
+}
+
+impl<M> BoxedActions<M> {
+    /// Adapt an automaton returning boxed slices of actions. 
+    pub fn new(machine: M) -> BoxedActions<M> {
+        BoxedActions {
+            machine: machine
+        }
+    }
+}
+
+impl<'k, M, A> BufferedAutomaton<'k> for BoxedActions<M> where 
+    M: Automaton<'k, Action=Box<[A]>>
+{
+    type Input = M::Input;
+    type Action = A;
+
+    fn transition_into(&mut self, input: &M::Input, actions: &mut Vec<A>) {
+        actions.clear();
+        actions.extend(self.machine.transition(input).into_vec());
+    }
+}
+
+impl<M> Snapshot for BoxedActions<M> where 
+    M: Snapshot
+{
+    type State = M::State;
+
+    fn snapshot(&self) -> M::State {
+        self.machine.snapshot()
+    }
+
+    fn restore(state: M::State) -> BoxedActions<M> {
+        BoxedActions {
+            machine: M::restore(state)
+        }
+    }
+}
+
 /// Marker trait for Finite State Automata, which are a restricted class of 
 /// automata that are quite well behaved. In particular, they occupy fixed 
 /// memory, and thus do not need extra allocation to operate, and instances 
 /// with known type can be copied around freely. 
 pub trait FiniteStateAutomaton<'k>: Automaton<'k> + Copy {}
 
+#[cfg(feature = "const_generics")]
+impl<'k, M, const N: usize> FiniteStateAutomaton<'k> for [M; N] where 
+    M: FiniteStateAutomaton<'k>
+{}
+
+impl<'k, M> FiniteStateAutomaton<'k> for Option<M> where 
+    M: FiniteStateAutomaton<'k>,
+    M::Action: Default
+{}
+
+macro_rules! machine_tuple_impl {
+    ( $( $name:ident : $index:tt ),* ) => {
+        impl<'k, X, $( $name ),*> Automaton<'k> for ( $( $name , )* ) where 
+            X: 'k,
+            $( $name: Automaton<'k, Input=X> ),*
+        {
+            type Input = X;
+            type Action = ( $( $name::Action , )* );
+
+            #[inline]
+            fn transition(&mut self, input: &X) -> Self::Action {
+                ( $( self.$index.transition(input) , )* )
+            }
+        }
+
+        impl<'k, X, $( $name ),*> FiniteStateAutomaton<'k> for ( $( $name , )* ) where 
+            X: 'k,
+            $( $name: FiniteStateAutomaton<'k, Input=X> ),*
+        {}
+    }
+}
+
+// Tuples of up to twelve automata with a common input type run in parallel, 
+// returning a flat tuple of their actions
+machine_tuple_impl!(A: 0, B: 1);
+machine_tuple_impl!(A: 0, B: 1, C: 2);
+machine_tuple_impl!(A: 0, B: 1, C: 2, D: 3);
+machine_tuple_impl!(A: 0, B: 1, C: 2, D: 3, E: 4);
+machine_tuple_impl!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5);
+machine_tuple_impl!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6);
+machine_tuple_impl!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7);
+machine_tuple_impl!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7, I: 8);
+machine_tuple_impl!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7, I: 8, J: 9);
+machine_tuple_impl!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7, I: 8, J: 9, K: 10);
+machine_tuple_impl!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7, I: 8, J: 9, K: 10, L: 11);
+
+/// Error for automata which panicked partway through an earlier transition, 
+/// leaving them without a state to transition from. 
+#[derive(Clone, PartialEq, Debug)]
+pub struct Poisoned {
+    /// The kind of automaton which was poisoned. 
+    pub kind: &'static str,
+    /// The name given to the automaton, if it was given one. 
+    pub name: Option<&'static str>,
+    /// The Debug representation of the input of the transition which 
+    /// panicked, if the automaton was set up to record it. 
+    pub input: Option<String>
+}
+
+impl fmt::Display for Poisoned {
+    fn fmt(&self, fmter: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmter, "{}", self.kind)?;
+        if let Option::Some(name) = self.name {
+            write!(fmter, " '{}'", name)?;
+        }
+        write!(fmter, " was poisoned")?;
+        if let Option::Some(ref input) = self.input {
+            write!(fmter, " (last input: {})", input)?;
+        }
+        Result::Ok(())
+    }
+}
+
+impl ::std::error::Error for Poisoned {}
+
+/// Capacity in bytes of the Debug representations of inputs kept by input 
+/// formatters. Longer representations are cut short. 
+pub const INPUT_DEBUG_CAPACITY: usize = 96;
+
+/// Formatter which records the Debug representation of the inputs of an 
+/// automaton in the Poisoned errors it returns. Each input is formatted 
+/// before the automaton transitions on it, so that the input reported is the 
+/// one of the transition which panicked. The representation is kept inline, 
+/// so that automata which record it stay Copy. 
+pub struct InputDebug<I> {
+    format: fn(&I, &mut dyn fmt::Write) -> fmt::Result,
+    last: InlineStr
+}
+
+impl<I> InputDebug<I> where I: fmt::Debug {
+    /// Create a new input formatter. 
+    pub fn new() -> InputDebug<I> {
+        InputDebug {
+            format: |input, out| write!(out, "{:?}", input),
+            last: InlineStr::new()
+        }
+    }
+}
+
+impl<I> InputDebug<I> {
+    /// Record the Debug representation of the input, replacing the one 
+    /// recorded before. 
+    pub fn record(&mut self, input: &I) {
+        self.last = InlineStr::new();
+        // Writing to an inline string cuts the representation short rather 
+        // than failing, so only the Debug impl itself can fail here
+        let _ = (self.format)(input, &mut self.last);
+    }
+
+    /// Return the Debug representation of the input recorded last, ending 
+    /// with an ellipsis if it was cut short. 
+    pub fn last(&self) -> String {
+        let mut described = self.last.as_str().to_owned();
+        if self.last.truncated {
+            described.push_str("...");
+        }
+        described
+    }
+}
+
+impl<I> Default for InputDebug<I> where I: fmt::Debug {
+    fn default() -> InputDebug<I> {
+        InputDebug::new()
+    }
+}
+
+impl<I> Copy for InputDebug<I> {}
+
+impl<I> Clone for InputDebug<I> {
+    fn clone(&self) -> InputDebug<I> {
+        *self
+    }
+}
+
+// All input formatters for the same input type format it the same way, so 
+// only the recorded representations are compared
+impl<I> PartialEq for InputDebug<I> {
+    fn eq(&self, other: &InputDebug<I>) -> bool {
+        self.last == other.last
+    }
+}
+
+impl<I> fmt::Debug for InputDebug<I> {
+    fn fmt(&self, fmter: &mut fmt::Formatter) -> fmt::Result {
+        fmter.debug_struct("InputDebug")
+            .field("last", &self.last())
+            .finish()
+    }
+}
+
+// Fixed capacity string, which keeps the longest prefix of what is written 
+// to it that fits, cut at a character boundary
+#[derive(Copy, Clone)]
+struct InlineStr {
+    bytes: [u8; INPUT_DEBUG_CAPACITY],
+    len: usize,
+    truncated: bool
+}
+
+impl InlineStr {
+    fn new() -> InlineStr {
+        InlineStr {
+            bytes: [0; INPUT_DEBUG_CAPACITY],
+            len: 0,
+            truncated: false
+        }
+    }
+
+    fn as_str(&self) -> &str {
+        ::std::str::from_utf8(&self.bytes[..self.len])
+            .expect("Inline string was cut inside a character")
+    }
+}
+
+impl PartialEq for InlineStr {
+    fn eq(&self, other: &InlineStr) -> bool {
+        self.as_str() == other.as_str() && self.truncated == other.truncated
+    }
+}
+
+impl fmt::Write for InlineStr {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            let width = c.len_utf8();
+            if self.truncated || self.len + width > INPUT_DEBUG_CAPACITY {
+                self.truncated = true;
+                break;
+            }
+            c.encode_utf8(&mut self.bytes[self.len..self.len + width]);
+            self.len += width;
+        }
+        Result::Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use internal_state_machine::InternalTransition;
@@ -223,4 +680,104 @@ mod tests {
         assert_eq!(scanner.next().unwrap(), 21);
         assert!(scanner.next().is_none());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn machine_tuple_test() {
+        use internal_state_machine::InternalStateMachine;
+        use stateless_mapper::StatelessMapper;
+        use automaton::{Automaton, FiniteStateAutomaton};
+        fn is_finite<'k, M: FiniteStateAutomaton<'k>>(_machine: &M) {}
+        let mut machines = (
+            InternalStateMachine::new(ThingMachine, 0),
+            StatelessMapper::new(|input: &i64| *input > 0),
+            InternalStateMachine::new(ThingMachine, 10)
+        );
+        is_finite(&machines);
+        assert_eq!(machines.transition(&2), (0, true, 10));
+        assert_eq!(machines.transition(&-1), (2, false, 12));
+        assert_eq!(machines.transition(&0), (1, false, 11));
+    }
+
+    #[test]
+    fn owned_collections_test() {
+        use internal_state_machine::InternalStateMachine;
+        use automaton::Automaton;
+        use std::collections::HashMap;
+        let mut machines = vec![
+            InternalStateMachine::new(ThingMachine, 0),
+            InternalStateMachine::new(ThingMachine, 10)
+        ];
+        assert_eq!(&*machines.transition(&2), &[0, 10]);
+        assert_eq!(&*machines.transition(&2), &[2, 12]);
+        let mut present = Option::Some(InternalStateMachine::new(ThingMachine, 5));
+        let mut absent: Option<InternalStateMachine<ThingMachine>> = Option::None;
+        assert_eq!(present.transition(&1), 5);
+        assert_eq!(present.transition(&1), 6);
+        assert_eq!(absent.transition(&1), 0);
+        let mut keyed = HashMap::new();
+        keyed.insert('a', InternalStateMachine::new(ThingMachine, 0));
+        keyed.insert('b', InternalStateMachine::new(ThingMachine, 100));
+        keyed.transition(&3);
+        let actions = keyed.transition(&3);
+        assert_eq!(actions.len(), 2);
+        assert_eq!(actions[&'a'], 3);
+        assert_eq!(actions[&'b'], 103);
+    }
+
+    #[cfg(feature = "const_generics")]
+    #[test]
+    fn array_test() {
+        use internal_state_machine::InternalStateMachine;
+        use automaton::{Automaton, FiniteStateAutomaton};
+        fn is_finite<'k, M: FiniteStateAutomaton<'k>>(_machine: &M) {}
+        let mut machines = [
+            InternalStateMachine::new(ThingMachine, 0),
+            InternalStateMachine::new(ThingMachine, 1),
+            InternalStateMachine::new(ThingMachine, 2)
+        ];
+        is_finite(&machines);
+        assert_eq!(machines.transition(&4), [0, 1, 2]);
+        assert_eq!(machines.transition(&4), [4, 5, 6]);
+    }
+
+    #[test]
+    fn step_n_test() {
+        use internal_state_machine::InternalStateMachine;
+        use automaton::Automaton;
+        let mut machine = InternalStateMachine::new(ThingMachine, 0);
+        assert_eq!(machine.step_n(&2, 4), vec![0, 2, 4, 6]);
+        assert_eq!(machine.step_n(&1, 0), vec![]);
+        assert_eq!(machine.transition(&0), 8);
+    }
+
+    #[test]
+    fn run_until_test() {
+        use internal_state_machine::InternalStateMachine;
+        use automaton::Automaton;
+        let mut machine = InternalStateMachine::new(ThingMachine, 0);
+        assert_eq!(machine.run_until(1..8, |acc: &i64| *acc > 5), Option::Some((6, 4)));
+        assert_eq!(machine.run_until(vec![-1, -1], |acc: &i64| *acc > 100), Option::None);
+        assert_eq!(machine.transition(&0), 8);
+    }
+    #[test]
+    fn buffered_automaton_test() {
+        use internal_state_machine::InternalStateMachine;
+        use automaton::{Automaton, BufferedAutomaton, BoxedActions};
+        let mut machines = vec![
+            InternalStateMachine::new(ThingMachine, 0), 
+            InternalStateMachine::new(ThingMachine, 10)
+        ];
+        let mut actions = Vec::with_capacity(2);
+        let buffer = actions.as_ptr();
+        machines.transition_into(&1, &mut actions);
+        assert_eq!(actions, vec![0, 10]);
+        machines.transition_into(&2, &mut actions);
+        assert_eq!(actions, vec![1, 11]);
+        assert_eq!(actions.as_ptr(), buffer);
+        let mut adapted = BoxedActions::new(machines.into_boxed_slice());
+        adapted.transition_into(&0, &mut actions);
+        assert_eq!(actions, vec![3, 13]);
+        let mut boxed = vec![InternalStateMachine::new(ThingMachine, 5)].into_boxed_slice();
+        assert_eq!(boxed.transition(&1), vec![5].into_boxed_slice());
+    }
+}
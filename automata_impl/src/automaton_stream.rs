@@ -0,0 +1,143 @@
+//! Adapter driving an automaton from an asynchronous stream of inputs. 
+//!
+//! Inputs arriving asynchronously, such as network messages or events from 
+//! another task, come as a stream. An automaton stream transitions its 
+//! automaton on each input the input stream yields, and yields the 
+//! resulting actions as a stream of its own, ending when the input stream 
+//! ends. 
+
+use automaton::Automaton;
+use futures_core::Stream;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// Stream of the actions of an automaton, transitioned on each input of an 
+/// input stream. Both the automaton and the input stream have to be Unpin, 
+/// so input streams which are not have to be pinned in a box first. 
+#[derive(Clone, PartialEq, Debug)]
+pub struct AutomatonStream<'k, M, S> where
+    M: Automaton<'k>,
+    S: Stream<Item=M::Input>
+{
+    machine: M,
+    inputs: S,
+    _bounds: PhantomData<&'k M>
+}
+
+impl<'k, M, S> AutomatonStream<'k, M, S> where
+    M: Automaton<'k>,
+    S: Stream<Item=M::Input>
+{
+    /// Create a new automaton stream. 
+    pub fn new(machine: M, inputs: S) -> AutomatonStream<'k, M, S> {
+        AutomatonStream {
+            machine: machine,
+            inputs: inputs,
+            _bounds: PhantomData
+        }
+    }
+
+    /// Return the automaton and the input stream. 
+    pub fn into_inner(self) -> (M, S) {
+        (self.machine, self.inputs)
+    }
+}
+
+impl<'k, M, S> Stream for AutomatonStream<'k, M, S> where
+    M: Automaton<'k> + Unpin,
+    S: Stream<Item=M::Input> + Unpin
+{
+    type Item = M::Action;
+
+    fn poll_next(self: Pin<&mut Self>, context: &mut Context) -> Poll<Option<M::Action>> {
+        let this = self.get_mut();
+        match Pin::new(&mut this.inputs).poll_next(context) {
+            Poll::Ready(Option::Some(input)) => Poll::Ready(Option::Some(
+                this.machine.transition(&input))),
+            Poll::Ready(Option::None) => Poll::Ready(Option::None),
+            Poll::Pending => Poll::Pending
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inputs.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use automaton_stream::AutomatonStream;
+    use futures_core::Stream;
+    use internal_state_machine::{InternalTransition, InternalStateMachine};
+    use std::collections::VecDeque;
+    use std::pin::Pin;
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Wake, Waker};
+
+    struct Noop;
+
+    impl Wake for Noop {
+        fn wake(self: Arc<Noop>) {}
+    }
+
+    // Stream which is pending before each of its inputs
+    struct Trickle {
+        inputs: VecDeque<i64>,
+        ready: bool
+    }
+
+    impl Stream for Trickle {
+        type Item = i64;
+
+        fn poll_next(self: Pin<&mut Trickle>, context: &mut Context) -> Poll<Option<i64>> {
+            let this = self.get_mut();
+            if this.ready || this.inputs.is_empty() {
+                this.ready = false;
+                Poll::Ready(this.inputs.pop_front())
+            } else {
+                this.ready = true;
+                context.waker().wake_by_ref();
+                Poll::Pending
+            }
+        }
+    }
+
+    #[derive(Copy, Clone)]
+    struct Sum;
+
+    impl InternalTransition for Sum {
+        type Input = i64;
+        type Internal = i64;
+        type Action = i64;
+
+        fn step(&self, input: &i64, total: &mut i64) -> i64 {
+            *total += *input;
+            *total
+        }
+    }
+
+    #[test]
+    fn automaton_stream_test() {
+        let inputs = Trickle {
+            inputs: vec![1, 2, 3].into_iter().collect(),
+            ready: false
+        };
+        let mut stream = AutomatonStream::new(InternalStateMachine::new(Sum, 0), inputs);
+        let waker = Waker::from(Arc::new(Noop));
+        let mut context = Context::from_waker(&waker);
+        let mut polls = Vec::new();
+        for _ in 0..7 {
+            polls.push(Pin::new(&mut stream).poll_next(&mut context));
+        }
+        assert_eq!(polls, vec![
+            Poll::Pending,
+            Poll::Ready(Option::Some(1)),
+            Poll::Pending,
+            Poll::Ready(Option::Some(3)),
+            Poll::Pending,
+            Poll::Ready(Option::Some(6)),
+            Poll::Ready(Option::None)
+        ]);
+    }
+}
@@ -1,4 +1,6 @@
-use automaton::{Automaton, FiniteStateAutomaton};
+use automaton::{Automaton, FiniteStateAutomaton, InputDebug, Poisoned};
+use snapshot::Snapshot;
+use std::fmt::Debug;
 use std::marker::PhantomData;
 
 /// Transition trait for DualStateMachine. 
@@ -15,6 +17,10 @@ pub trait DualTransition {
     fn step(self, &Self::Input, &mut Self::Internal) -> (Self::Action, Self);
 }
 
+// Spelled out through an alias so that derives do not require the input 
+// type to implement the derived traits
+type InputFormatter<C> = InputDebug<<C as DualTransition>::Input>;
+
 /// State machine implementation which combines the changing functions of 
 /// RefStateMachine with the internal mutable state of InternalStateMachine. 
 /// This is the most general state machine form in this crate, but the other 
@@ -60,6 +66,8 @@ pub struct DualStateMachine<'k, C> where
 {
     state_fn: Option<C>, 
     internal: C::Internal,
+    name: Option<&'static str>,
+    input_debug: Option<InputFormatter<C>>,
     _lifetime_check: PhantomData<&'k C>
 }
 
@@ -71,9 +79,81 @@ impl<'k, C> DualStateMachine<'k, C> where
         DualStateMachine {
             state_fn: Option::Some(calling_fn),
             internal: init_state,
+            name: Option::None,
+            input_debug: Option::None,
             _lifetime_check: PhantomData
         }
     }
+
+    /// Give the state machine a name, which is reported if it is poisoned. 
+    pub fn with_name(mut self, name: &'static str) -> DualStateMachine<'k, C> {
+        self.name = Option::Some(name);
+        self
+    }
+
+    /// Report the input of the transition which panicked if the state 
+    /// machine is poisoned. 
+    pub fn with_input_debug(mut self) -> DualStateMachine<'k, C> where C::Input: Debug {
+        self.input_debug = Option::Some(InputDebug::new());
+        self
+    }
+
+    /// Return the name of the state machine, if it was given one. 
+    pub fn name(&self) -> Option<&'static str> {
+        self.name
+    }
+
+    /// Return the internal state of the state machine. 
+    pub fn state(&self) -> &C::Internal {
+        &self.internal
+    }
+
+    /// Return the internal state of the state machine mutably. 
+    pub fn state_mut(&mut self) -> &mut C::Internal {
+        &mut self.internal
+    }
+
+    /// Consume the state machine, returning its current transition value, 
+    /// which is None if the state machine was poisoned, along with its 
+    /// internal state. 
+    pub fn into_inner(self) -> (Option<C>, C::Internal) {
+        (self.state_fn, self.internal)
+    }
+
+    /// Transition the state machine, returning an error instead of panicking 
+    /// if a panic in an earlier transition poisoned it. 
+    pub fn try_transition(&mut self, input: &C::Input) -> Result<C::Action, Poisoned> {
+        match self.state_fn.take() {
+            Option::Some(state_fn) => {
+                if let Option::Some(ref mut recorder) = self.input_debug {
+                    recorder.record(input);
+                }
+                let (action, new_fn) = state_fn.step(input, &mut self.internal);
+                self.state_fn = Option::Some(new_fn);
+                Result::Ok(action)
+            },
+            Option::None => Result::Err(Poisoned {
+                kind: "State machine",
+                name: self.name,
+                input: self.input_debug.as_ref().map(InputDebug::last)
+            })
+        }
+    }
+
+    /// Recover the state machine if it was poisoned, reinstalling the 
+    /// transition value returned by the given function, which may also 
+    /// reset the internal state left behind by the panic. Returns whether 
+    /// the state machine was poisoned. 
+    pub fn recover_with<F>(&mut self, fresh: F) -> bool where 
+        F: FnOnce(&mut C::Internal) -> C
+    {
+        if self.state_fn.is_some() {
+            false
+        } else {
+            self.state_fn = Option::Some(fresh(&mut self.internal));
+            true
+        }
+    }
 }
 
 impl<'k, C> Default for DualStateMachine<'k, C> where
@@ -93,12 +173,10 @@ impl<'k, C> Automaton<'k> for DualStateMachine<'k, C> where
     
     #[inline]
     fn transition(&mut self, input: &C::Input) -> C::Action {
-        let (action, new_fn) = self.state_fn
-            .take()
-            .expect("State machine was poisoned")
-            .step(input, &mut self.internal);
-        self.state_fn = Option::Some(new_fn);
-        action
+        match self.try_transition(input) {
+            Result::Ok(action) => action,
+            Result::Err(poisoned) => panic!("{}", poisoned)
+        }
     }
 }
 
@@ -107,6 +185,33 @@ impl<'k, C> FiniteStateAutomaton<'k> for DualStateMachine<'k, C> where
     C::Internal: Copy
 {}
 
+impl<'k, C> Snapshot for DualStateMachine<'k, C> where 
+    C: DualTransition + Clone + 'k,
+    C::Internal: Clone
+{
+    type State = (C, C::Internal);
+
+    /// Capture the current state. 
+    /// 
+    /// # Panics 
+    /// 
+    /// Panics if the state machine was poisoned. 
+    fn snapshot(&self) -> (C, C::Internal) {
+        match self.state_fn {
+            Option::Some(ref state_fn) => (state_fn.clone(), self.internal.clone()),
+            Option::None => panic!("{}", Poisoned {
+                kind: "State machine",
+                name: self.name,
+                input: self.input_debug.as_ref().map(InputDebug::last)
+            })
+        }
+    }
+
+    fn restore(state: (C, C::Internal)) -> DualStateMachine<'k, C> {
+        DualStateMachine::new(state.0, state.1)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use dual_state_machine::DualTransition;
@@ -154,4 +259,49 @@ mod tests {
         assert_eq!(x.transition(&0), -2);
         assert_eq!(x.transition(&10), 8);
     }
+
+    #[test]
+    fn state_access_test() {
+        use dual_state_machine::DualStateMachine;
+        use automaton::Automaton;
+        let mut x = DualStateMachine::new(ThingMachine::Add, 0);
+        x.transition(&2);
+        assert_eq!(*x.state(), 2);
+        *x.state_mut() = 7;
+        assert_eq!(x.transition(&1), 8);
+        let (state_fn, internal) = x.into_inner();
+        assert!(state_fn.is_some());
+        assert_eq!(internal, 8);
+    }
+
+    #[derive(Copy, Clone)]
+    struct Fragile;
+
+    impl DualTransition for Fragile {
+        type Internal = i64;
+        type Input = i64;
+        type Action = i64;
+        fn step(self, input: &i64, total: &mut i64) -> (i64, Fragile) {
+            *total += *input;
+            assert!(*total >= 0, "Total went negative");
+            (*total, Fragile)
+        }
+    }
+
+    #[test]
+    fn recover_test() {
+        use dual_state_machine::DualStateMachine;
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+        let mut x = DualStateMachine::new(Fragile, 0);
+        assert_eq!(x.try_transition(&3), Result::Ok(3));
+        assert!(catch_unwind(AssertUnwindSafe(|| x.try_transition(&-5))).is_err());
+        assert!(x.try_transition(&1).is_err());
+        assert_eq!(*x.state(), -2);
+        assert!(x.recover_with(|total| {
+            *total = 0;
+            Fragile
+        }));
+        assert!(!x.recover_with(|_| Fragile));
+        assert_eq!(x.try_transition(&1), Result::Ok(1));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,274 @@
+//! Hierarchical state machines, whose states contain machines of their own. 
+//!
+//! Flat state machines grow a transition for every pair of states which 
+//! can follow each other, and an agent which can flee from combat, patrol 
+//! or idling alike needs that transition written out in all three. A 
+//! hierarchical state machine gives each of its states a child machine of 
+//! its own, and lets a parent machine see each input first, so that events 
+//! shared by every state are handled once by the parent, and everything 
+//! else is passed down to the child of the current state. 
+
+use automaton::Automaton;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::mem;
+
+/// Transition of the parent machine of a hierarchical state machine. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum HierarchicalTransition<A, S> {
+    /// Pass the input down to the child machine of the current state, 
+    /// returning its action. 
+    Delegate,
+    /// Handle the input without passing it down, staying in the current 
+    /// state. 
+    Handle(A),
+    /// Leave the current state for the given state, whose child machine is 
+    /// created anew. 
+    Enter(S, A),
+    /// Leave the current state for the given state, resuming its child 
+    /// machine where it was left off, or creating it anew if the state has 
+    /// not been left before. 
+    Resume(S, A)
+}
+
+/// Hierarchical state machine, made of a parent machine which decides the 
+/// current state and a child machine for each state. Each input is given to 
+/// the parent first, which either handles it, changes the current state, or 
+/// passes it down to the child of the current state. 
+/// 
+/// The children of states which are left are kept, so that they can be 
+/// resumed later. All children are of the same type, which can be an enum 
+/// or an EitherMachine for states with different kinds of machines, or 
+/// another hierarchical state machine for deeper hierarchies. 
+/// 
+/// # Example
+/// ```
+/// use stackbt_automata_impl::automaton::Automaton;
+/// use stackbt_automata_impl::hierarchical::{HierarchicalMachine,
+///     HierarchicalTransition};
+/// use stackbt_automata_impl::internal_state_machine::InternalStateMachine;
+/// use stackbt_automata_impl::stateless_mapper::StatelessMapper;
+/// 
+/// let parent = StatelessMapper::new(|alarm: &bool| if *alarm {
+///     HierarchicalTransition::Enter("alert", 0)
+/// } else {
+///     HierarchicalTransition::Delegate
+/// });
+/// let mut machine = HierarchicalMachine::new(parent, "calm", |_: &&str| {
+///     InternalStateMachine::with(|_: &bool, steps: &mut i64| {
+///         *steps += 1;
+///         *steps
+///     }, 0)
+/// });
+/// assert_eq!(machine.transition(&false), 1);
+/// assert_eq!(machine.transition(&false), 2);
+/// assert_eq!(machine.transition(&true), 0);
+/// assert_eq!(*machine.state(), "alert");
+/// assert_eq!(machine.transition(&false), 1);
+/// ```
+pub struct HierarchicalMachine<'k, P, S, C, F> where
+    P: Automaton<'k, Input=C::Input, Action=HierarchicalTransition<C::Action, S>>,
+    S: Eq + Hash,
+    C: Automaton<'k>,
+    F: Fn(&S) -> C
+{
+    parent: P,
+    state: S,
+    child: C,
+    suspended: HashMap<S, C>,
+    constructor: F,
+    _bounds: PhantomData<&'k C>
+}
+
+impl<'k, P, S, C, F> Clone for HierarchicalMachine<'k, P, S, C, F> where
+    P: Automaton<'k, Input=C::Input, Action=HierarchicalTransition<C::Action, S>> + Clone,
+    S: Eq + Hash + Clone,
+    C: Automaton<'k> + Clone,
+    F: Fn(&S) -> C + Clone
+{
+    fn clone(&self) -> Self {
+        HierarchicalMachine {
+            parent: self.parent.clone(),
+            state: self.state.clone(),
+            child: self.child.clone(),
+            suspended: self.suspended.clone(),
+            constructor: self.constructor.clone(),
+            _bounds: PhantomData
+        }
+    }
+}
+
+impl<'k, P, S, C, F> HierarchicalMachine<'k, P, S, C, F> where
+    P: Automaton<'k, Input=C::Input, Action=HierarchicalTransition<C::Action, S>>,
+    S: Eq + Hash,
+    C: Automaton<'k>,
+    F: Fn(&S) -> C
+{
+    /// Create a new hierarchical state machine in the given state, whose 
+    /// child machines are created by the constructor. 
+    pub fn new(parent: P, initial: S, constructor: F) -> Self {
+        let child = constructor(&initial);
+        HierarchicalMachine {
+            parent: parent,
+            state: initial,
+            child: child,
+            suspended: HashMap::new(),
+            constructor: constructor,
+            _bounds: PhantomData
+        }
+    }
+
+    /// Return the current state. 
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+
+    /// Return the child machine of the current state. 
+    pub fn child(&self) -> &C {
+        &self.child
+    }
+
+    /// Return the child machine kept for a state which was left, if there 
+    /// is one. 
+    pub fn suspended(&self, state: &S) -> Option<&C> {
+        self.suspended.get(state)
+    }
+
+    // Make the given state current with the given child, keeping the child
+    // of the state left behind
+    fn switch(&mut self, state: S, child: C) {
+        let old_child = mem::replace(&mut self.child, child);
+        let old_state = mem::replace(&mut self.state, state);
+        if old_state != self.state {
+            self.suspended.insert(old_state, old_child);
+        }
+    }
+}
+
+impl<'k, P, S, C, F> Automaton<'k> for HierarchicalMachine<'k, P, S, C, F> where
+    P: Automaton<'k, Input=C::Input, Action=HierarchicalTransition<C::Action, S>>,
+    S: Eq + Hash,
+    C: Automaton<'k>,
+    F: Fn(&S) -> C
+{
+    type Input = C::Input;
+    type Action = C::Action;
+
+    #[inline]
+    fn transition(&mut self, input: &C::Input) -> C::Action {
+        match self.parent.transition(input) {
+            HierarchicalTransition::Delegate => self.child.transition(input),
+            HierarchicalTransition::Handle(action) => action,
+            HierarchicalTransition::Enter(state, action) => {
+                self.suspended.remove(&state);
+                let child = (self.constructor)(&state);
+                self.switch(state, child);
+                action
+            },
+            HierarchicalTransition::Resume(state, action) => {
+                if state != self.state {
+                    let child = match self.suspended.remove(&state) {
+                        Option::Some(child) => child,
+                        Option::None => (self.constructor)(&state)
+                    };
+                    self.switch(state, child);
+                }
+                action
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use automaton::Automaton;
+    use hierarchical::{HierarchicalMachine, HierarchicalTransition};
+    use internal_state_machine::{InternalStateMachine, InternalTransition};
+    use stateless_mapper::StatelessMapper;
+
+    #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+    enum Mode {
+        Patrol,
+        Combat
+    }
+
+    #[derive(Copy, Clone)]
+    struct Counter;
+
+    impl InternalTransition for Counter {
+        type Input = i64;
+        type Internal = i64;
+        type Action = i64;
+
+        fn step(&self, _input: &i64, count: &mut i64) -> i64 {
+            *count += 1;
+            *count
+        }
+    }
+
+    fn alert(input: &i64) -> HierarchicalTransition<i64, Mode> {
+        match *input {
+            1 => HierarchicalTransition::Enter(Mode::Combat, -1),
+            2 => HierarchicalTransition::Resume(Mode::Patrol, -2),
+            3 => HierarchicalTransition::Handle(-3),
+            4 => HierarchicalTransition::Resume(Mode::Combat, -4),
+            _ => HierarchicalTransition::Delegate
+        }
+    }
+
+    fn counter(mode: &Mode) -> InternalStateMachine<'static, Counter> {
+        match *mode {
+            Mode::Patrol => InternalStateMachine::new(Counter, 0),
+            Mode::Combat => InternalStateMachine::new(Counter, 100)
+        }
+    }
+
+    #[test]
+    fn history_test() {
+        let mut machine = HierarchicalMachine::new(StatelessMapper::new(alert),
+            Mode::Patrol, counter);
+        assert_eq!(machine.transition(&0), 1);
+        assert_eq!(machine.transition(&0), 2);
+        assert_eq!(machine.transition(&1), -1);
+        assert_eq!(*machine.state(), Mode::Combat);
+        assert!(machine.suspended(&Mode::Patrol).is_some());
+        assert_eq!(machine.transition(&0), 101);
+        assert_eq!(machine.transition(&3), -3);
+        assert_eq!(machine.transition(&0), 102);
+        assert_eq!(machine.transition(&2), -2);
+        assert_eq!(machine.transition(&0), 3);
+        assert_eq!(machine.transition(&4), -4);
+        assert_eq!(machine.transition(&0), 103);
+        assert_eq!(machine.transition(&1), -1);
+        assert_eq!(machine.transition(&0), 101);
+    }
+
+    #[test]
+    fn resume_fresh_test() {
+        let mut machine = HierarchicalMachine::new(StatelessMapper::new(alert),
+            Mode::Patrol, counter);
+        assert_eq!(machine.transition(&4), -4);
+        assert_eq!(machine.transition(&0), 101);
+        assert_eq!(machine.transition(&4), -4);
+        assert_eq!(machine.transition(&0), 102);
+        assert!(machine.suspended(&Mode::Combat).is_none());
+    }
+
+    #[test]
+    fn nested_test() {
+        let inner = |_: &Mode| HierarchicalMachine::new(StatelessMapper::new(alert),
+            Mode::Patrol, counter);
+        let outer = StatelessMapper::new(|input: &i64| if *input == 5 {
+            HierarchicalTransition::Enter(Mode::Patrol, -5)
+        } else {
+            HierarchicalTransition::Delegate
+        });
+        let mut machine = HierarchicalMachine::new(outer, Mode::Patrol, inner);
+        assert_eq!(machine.transition(&1), -1);
+        assert_eq!(machine.transition(&0), 101);
+        assert_eq!(machine.transition(&5), -5);
+        assert_eq!(*machine.child().state(), Mode::Patrol);
+        assert_eq!(machine.transition(&0), 1);
+    }
+}
\ No newline at end of file
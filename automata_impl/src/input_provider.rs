@@ -0,0 +1,300 @@
+//!
+//! Before a behavior tree or state machine can make a decision, it needs 
+//! its input, which is generally gathered from several sources in the world 
+//! each tick. This module provides the InputProvider trait for things which 
+//! gather such information, and a compositor which assembles the outputs 
+//! of several providers into the input type of an automaton. 
+//!
+
+use automaton::Automaton;
+use std::marker::PhantomData;
+
+/// Trait for sources of information, which given a reference to some 
+/// shared source (such as a world snapshot), produce a piece of the input 
+/// for an automaton. 
+pub trait InputProvider<'k> {
+    /// The type of the source to query. 
+    type Source: 'k;
+    /// The type of the information provided. 
+    type Output;
+
+    /// Query the source for information. 
+    fn provide(&mut self, source: &Self::Source) -> Self::Output;
+}
+
+/// Input provider which wraps a closure. 
+#[derive(PartialEq, Debug)]
+pub struct ProviderFn<'k, S, O, C> where 
+    C: FnMut(&S) -> O + 'k,
+    S: 'k
+{
+    closure: C,
+    _junk: PhantomData<&'k (S, O)>
+}
+
+impl<'k, S, O, C> Clone for ProviderFn<'k, S, O, C> where 
+    C: FnMut(&S) -> O + 'k + Clone,
+    S: 'k
+{
+    fn clone(&self) -> Self {
+        ProviderFn {
+            closure: self.closure.clone(),
+            _junk: PhantomData
+        }
+    }
+}
+
+impl<'k, S, O, C> Copy for ProviderFn<'k, S, O, C> where 
+    C: FnMut(&S) -> O + 'k + Copy,
+    S: 'k
+{}
+
+impl<'k, S, O, C> ProviderFn<'k, S, O, C> where 
+    C: FnMut(&S) -> O + 'k,
+    S: 'k
+{
+    /// Create a new input provider from a closure. 
+    pub fn new(closure: C) -> ProviderFn<'k, S, O, C> {
+        ProviderFn {
+            closure: closure,
+            _junk: PhantomData
+        }
+    }
+}
+
+impl<'k, S, O, C> InputProvider<'k> for ProviderFn<'k, S, O, C> where 
+    C: FnMut(&S) -> O + 'k,
+    S: 'k
+{
+    type Source = S;
+    type Output = O;
+
+    #[inline]
+    fn provide(&mut self, source: &S) -> O {
+        (self.closure)(source)
+    }
+}
+
+/// Input provider which caches the output of an expensive provider, only 
+/// querying it again when the cached value expires, either after a fixed 
+/// number of queries, or when a key derived from the source changes. 
+#[derive(Clone, PartialEq, Debug)]
+pub struct CachedProvider<'k, P, K, F> where 
+    P: InputProvider<'k>,
+    P::Output: Clone,
+    K: PartialEq,
+    F: Fn(&P::Source) -> K
+{
+    provider: P,
+    key_fn: F,
+    lifetime: usize,
+    cached: Option<(K, usize, P::Output)>
+}
+
+impl<'k, P> CachedProvider<'k, P, (), fn(&P::Source)> where 
+    P: InputProvider<'k>,
+    P::Output: Clone
+{
+    /// Create a new cached provider which refreshes the cached value after 
+    /// it has been used for the given number of queries. 
+    pub fn every(lifetime: usize, provider: P) -> Self {
+        fn no_key<S>(_s: &S) {}
+        CachedProvider::new(no_key::<P::Source>, lifetime, provider)
+    }
+}
+
+impl<'k, P, K, F> CachedProvider<'k, P, K, F> where 
+    P: InputProvider<'k>,
+    P::Output: Clone,
+    K: PartialEq,
+    F: Fn(&P::Source) -> K
+{
+    /// Create a new cached provider, which refreshes the cached value 
+    /// whenever the key function returns a different key, or after the 
+    /// cached value has been used for the given number of queries. 
+    pub fn new(key_fn: F, lifetime: usize, provider: P) -> Self {
+        assert!(lifetime > 0, "Cache lifetime must be nonzero");
+        CachedProvider {
+            provider: provider,
+            key_fn: key_fn,
+            lifetime: lifetime,
+            cached: Option::None
+        }
+    }
+
+    /// Discard the cached value, forcing the next query to refresh it. 
+    pub fn invalidate(&mut self) {
+        self.cached = Option::None;
+    }
+}
+
+impl<'k, P, K, F> InputProvider<'k> for CachedProvider<'k, P, K, F> where 
+    P: InputProvider<'k>,
+    P::Output: Clone,
+    K: PartialEq,
+    F: Fn(&P::Source) -> K
+{
+    type Source = P::Source;
+    type Output = P::Output;
+
+    #[inline]
+    fn provide(&mut self, source: &P::Source) -> P::Output {
+        let key = (self.key_fn)(source);
+        match self.cached {
+            Option::Some((ref old_key, ref mut uses, ref val)) if 
+                *old_key == key && *uses < self.lifetime => 
+            {
+                *uses += 1;
+                return val.clone();
+            },
+            _ => ()
+        };
+        let val = self.provider.provide(source);
+        self.cached = Option::Some((key, 1, val.clone()));
+        val
+    }
+}
+
+macro_rules! provider_tuple_impl {
+    ( $( $name:ident : $index:tt ),* ) => {
+        impl<'k, S, $( $name ),*> InputProvider<'k> for ( $( $name , )* ) where 
+            S: 'k,
+            $( $name: InputProvider<'k, Source=S> ),*
+        {
+            type Source = S;
+            type Output = ( $( $name::Output , )* );
+
+            #[inline]
+            fn provide(&mut self, source: &S) -> Self::Output {
+                ( $( self.$index.provide(source) , )* )
+            }
+        }
+    }
+}
+
+provider_tuple_impl!(A: 0);
+provider_tuple_impl!(A: 0, B: 1);
+provider_tuple_impl!(A: 0, B: 1, C: 2);
+provider_tuple_impl!(A: 0, B: 1, C: 2, D: 3);
+provider_tuple_impl!(A: 0, B: 1, C: 2, D: 3, E: 4);
+provider_tuple_impl!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5);
+provider_tuple_impl!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6);
+provider_tuple_impl!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7);
+
+/// Automaton which, each step, queries a collection of input providers with 
+/// the given source, and assembles their outputs into a single value using 
+/// a combining function. Tuples of up to eight providers with a common 
+/// source type are themselves providers, whose outputs are tuples of the 
+/// outputs of their members. 
+/// 
+/// # Example
+/// ```
+/// use stackbt_automata_impl::automaton::Automaton;
+/// use stackbt_automata_impl::input_provider::{ProviderFn, InputCompositor};
+/// 
+/// struct World {
+///     positions: Vec<i64>
+/// }
+/// 
+/// let mut compositor = InputCompositor::new(
+///     |(count, max): (usize, i64)| count as i64 + max,
+///     (
+///         ProviderFn::new(|w: &World| w.positions.len()),
+///         ProviderFn::new(|w: &World| *w.positions.iter().max().unwrap())
+///     )
+/// );
+/// let world = World { positions: vec![1, 5, 3] };
+/// assert_eq!(compositor.transition(&world), 8);
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct InputCompositor<'k, P, C, I> where 
+    P: InputProvider<'k>,
+    C: Fn(P::Output) -> I
+{
+    providers: P,
+    combiner: C,
+    _junk: PhantomData<&'k I>
+}
+
+impl<'k, P, C, I> InputCompositor<'k, P, C, I> where 
+    P: InputProvider<'k>,
+    C: Fn(P::Output) -> I
+{
+    /// Create a new input compositor. 
+    pub fn new(combiner: C, providers: P) -> InputCompositor<'k, P, C, I> {
+        InputCompositor {
+            providers: providers,
+            combiner: combiner,
+            _junk: PhantomData
+        }
+    }
+}
+
+impl<'k, P, C, I> Automaton<'k> for InputCompositor<'k, P, C, I> where 
+    P: InputProvider<'k>,
+    C: Fn(P::Output) -> I
+{
+    type Input = P::Source;
+    type Action = I;
+
+    #[inline]
+    fn transition(&mut self, input: &P::Source) -> I {
+        (self.combiner)(self.providers.provide(input))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use automaton::Automaton;
+    use input_provider::{InputProvider, ProviderFn};
+    use std::cell::Cell;
+
+    #[test]
+    fn cached_provider_test() {
+        use input_provider::CachedProvider;
+        let calls = Cell::new(0);
+        let mut provider = CachedProvider::every(3, ProviderFn::new(|s: &i64| {
+            calls.set(calls.get() + 1);
+            *s * 2
+        }));
+        assert_eq!(provider.provide(&1), 2);
+        assert_eq!(provider.provide(&2), 2);
+        assert_eq!(provider.provide(&3), 2);
+        assert_eq!(provider.provide(&4), 8);
+        assert_eq!(calls.get(), 2);
+        provider.invalidate();
+        assert_eq!(provider.provide(&5), 10);
+        assert_eq!(calls.get(), 3);
+    }
+
+    #[test]
+    fn keyed_cache_test() {
+        use input_provider::CachedProvider;
+        let calls = Cell::new(0);
+        let mut provider = CachedProvider::new(|s: &(i64, i64)| s.0, 100, 
+            ProviderFn::new(|s: &(i64, i64)| {
+                calls.set(calls.get() + 1);
+                s.0 + s.1
+            })
+        );
+        assert_eq!(provider.provide(&(1, 1)), 2);
+        assert_eq!(provider.provide(&(1, 5)), 2);
+        assert_eq!(provider.provide(&(2, 5)), 7);
+        assert_eq!(calls.get(), 2);
+    }
+
+    #[test]
+    fn compositor_test() {
+        use input_provider::InputCompositor;
+        let mut compositor = InputCompositor::new(
+            |(a, b, c): (i64, i64, bool)| if c { a } else { b },
+            (
+                ProviderFn::new(|s: &i64| *s + 1),
+                ProviderFn::new(|s: &i64| *s - 1),
+                ProviderFn::new(|s: &i64| *s > 0)
+            )
+        );
+        assert_eq!(compositor.transition(&4), 5);
+        assert_eq!(compositor.transition(&-4), -5);
+    }
+}
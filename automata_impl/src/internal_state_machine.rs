@@ -1,4 +1,5 @@
 use automaton::{Automaton, FiniteStateAutomaton};
+use snapshot::Snapshot;
 use std::marker::PhantomData;
 
 /// Transition trait for InternalStateMachine. 
@@ -42,7 +43,8 @@ impl<I, N, A, C> Copy for InternalTransClosure<I, N, A, C> where
 impl<I, N, A, C> InternalTransClosure<I, N, A, C> where 
     C: Fn(&I, &mut N) -> A
 {
-    fn new(closure: C) -> InternalTransClosure<I, N, A, C> {
+    /// Create a new internal transition from a closure. 
+    pub fn from_fn(closure: C) -> InternalTransClosure<I, N, A, C> {
         InternalTransClosure {
             closure: closure,
             _junk: PhantomData
@@ -114,6 +116,21 @@ impl<'k, C> InternalStateMachine<'k, C> where
             _lifetime_check: PhantomData
         }
     }
+
+    /// Return the internal state of the state machine. 
+    pub fn state(&self) -> &C::Internal {
+        &self.internal
+    }
+
+    /// Return the internal state of the state machine mutably. 
+    pub fn state_mut(&mut self) -> &mut C::Internal {
+        &mut self.internal
+    }
+
+    /// Consume the state machine, returning its internal state. 
+    pub fn into_inner(self) -> C::Internal {
+        self.internal
+    }
 } 
 
 impl<'k, I, N, A, C> InternalStateMachine<'k, InternalTransClosure<I, N, A, C>> where 
@@ -124,7 +141,7 @@ impl<'k, I, N, A, C> InternalStateMachine<'k, InternalTransClosure<I, N, A, C>>
         InternalTransClosure<I, N, A, C>> 
     {
         InternalStateMachine::new(
-            InternalTransClosure::new(init),
+            InternalTransClosure::from_fn(init),
             init_state
         )
     }
@@ -150,7 +167,7 @@ impl<'k, C> Automaton<'k> for InternalStateMachine<'k, C> where
     type Action = C::Action;
     #[inline]
     fn transition(&mut self, input: &C::Input) -> C::Action {
-        self.stepper.step(&input, &mut self.internal)
+        self.stepper.step(input, &mut self.internal)
     }
 }
 
@@ -159,6 +176,21 @@ impl<'k, C> FiniteStateAutomaton<'k> for InternalStateMachine<'k, C> where
     C::Internal: Copy
 {}
 
+impl<'k, C> Snapshot for InternalStateMachine<'k, C> where 
+    C: InternalTransition + Default + 'k,
+    C::Internal: Clone
+{
+    type State = C::Internal;
+
+    fn snapshot(&self) -> C::Internal {
+        self.internal.clone()
+    }
+
+    fn restore(state: C::Internal) -> InternalStateMachine<'k, C> {
+        InternalStateMachine::new(C::default(), state)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use internal_state_machine::InternalTransition;
@@ -196,4 +228,33 @@ mod tests {
         assert_eq!(x.transition(&3), 3);
         assert_eq!(x.transition(&6), 6);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn closure_adapter_test() {
+        use internal_state_machine::{InternalStateMachine, InternalTransClosure};
+        use automaton::Automaton;
+        let adapter = InternalTransClosure::from_fn(|input: &i64, max: &mut i64| {
+            if *input > *max {
+                *max = *input;
+            }
+            *max
+        });
+        assert_eq!(adapter.step(&4, &mut 2), 4);
+        let mut x = InternalStateMachine::new(adapter, 0);
+        assert_eq!(x.transition(&3), 3);
+        assert_eq!(x.transition(&1), 3);
+        assert_eq!(x.transition(&5), 5);
+    }
+
+    #[test]
+    fn state_access_test() {
+        use internal_state_machine::InternalStateMachine;
+        use automaton::Automaton;
+        let mut x = InternalStateMachine::new(ThingMachine, 0);
+        x.transition(&3);
+        assert_eq!(*x.state(), 3);
+        *x.state_mut() = 10;
+        assert_eq!(x.transition(&1), 10);
+        assert_eq!(x.into_inner(), 11);
+    }
+}
@@ -1,5 +1,16 @@
 #![cfg_attr(feature = "unsized_locals", feature(unsized_locals))]
 
+#[cfg(feature = "ncollide")]
+extern crate nalgebra;
+#[cfg(feature = "ncollide")]
+extern crate ncollide2d;
+#[cfg(feature = "futures")]
+extern crate futures_core;
+#[cfg(feature = "rayon")]
+extern crate rayon;
+#[cfg(feature = "smallvec")]
+extern crate smallvec;
+
 /// The Automaton trait and the FiniteStateAutomaton trait. 
 pub mod automaton;
 /// The RefStateMachine finite state machine implementation. 
@@ -12,5 +23,32 @@ pub mod dual_state_machine;
 pub mod stateless_mapper;
 /// A pushdown automaton implementation based on finite state machines. 
 pub mod pushdown_automaton;
+/// A queue automaton implementation based on finite state machines. 
+pub mod queue_automaton;
+/// A two-stack automaton implementation based on finite state machines. 
+pub mod two_stack_automaton;
+/// Hierarchical state machines whose states contain machines of their own. 
+pub mod hierarchical;
+/// Statecharts with entry and exit actions and guarded transitions. 
+pub mod statechart;
 /// Combinators for automata. 
-pub mod automata_combinators;
\ No newline at end of file
+pub mod automata_combinators;
+/// Numeric signal filtering automata. 
+pub mod signal_filters;
+/// Steering behaviors for autonomous agents. 
+pub mod steering;
+/// Input providers and the input compositor. 
+pub mod input_provider;
+/// Needs and drives modelling agent motivation. 
+pub mod needs;
+/// The Snapshot trait for capturing and restoring state. 
+pub mod snapshot;
+/// Input providers running spatial queries against ncollide2d worlds. 
+#[cfg(feature = "ncollide")]
+pub mod spatial_query;
+/// Adapter driving automata from asynchronous input streams. 
+#[cfg(feature = "futures")]
+pub mod automaton_stream;
+/// Stepping of independent automata across threads. 
+#[cfg(feature = "rayon")]
+pub mod parallel_automata;
\ No newline at end of file
@@ -0,0 +1,173 @@
+//!
+//! A simple way to give agents motivation is to model their needs, such as 
+//! hunger, fear or aggression, as levels which drift up or down on their 
+//! own each step, and which stimuli from the world push up or down. The 
+//! urgency of each need is read off its level through a response curve, 
+//! and can be used directly as the score of the behavior which satisfies 
+//! the need, such as by a utility node. 
+//!
+
+use automaton::{Automaton, FiniteStateAutomaton};
+use snapshot::Snapshot;
+
+/// Curve mapping the level of a need, between 0 and 1, to its urgency, 
+/// which is also clamped to between 0 and 1. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum ResponseCurve {
+    /// Urgency proportional to the level, scaled by the slope and shifted 
+    /// by the offset. 
+    Linear {
+        /// The slope of the line. 
+        slope: f64,
+        /// The urgency at a level of zero. 
+        offset: f64
+    },
+    /// Urgency equal to the level raised to the exponent. Exponents greater 
+    /// than 1 keep the urgency low until the level gets high. 
+    Power(f64),
+    /// Urgency following a logistic curve, rising most steeply around the 
+    /// midpoint. 
+    Logistic {
+        /// How steeply the urgency rises around the midpoint. 
+        steepness: f64,
+        /// The level at which the urgency is one half. 
+        midpoint: f64
+    },
+    /// Urgency of 1 at or above the threshold level, and 0 below it. 
+    Step(f64)
+}
+
+impl ResponseCurve {
+    /// Return the urgency for the level. 
+    pub fn evaluate(&self, level: f64) -> f64 {
+        let urgency = match *self {
+            ResponseCurve::Linear { slope, offset } => slope * level + offset,
+            ResponseCurve::Power(exponent) => level.powf(exponent),
+            ResponseCurve::Logistic { steepness, midpoint } => {
+                1.0 / (1.0 + (-steepness * (level - midpoint)).exp())
+            },
+            ResponseCurve::Step(threshold) => if level >= threshold { 1.0 } else { 0.0 }
+        };
+        clamp_unit(urgency)
+    }
+}
+
+// NaN, as from a response curve evaluated at a bad parameter, counts as 0
+// rather than poisoning the level of the need for good
+fn clamp_unit(value: f64) -> f64 {
+    if value.is_nan() {
+        0.0
+    } else {
+        value.clamp(0.0, 1.0)
+    }
+}
+
+/// Automaton modelling a single need. Each step, the level of the need 
+/// drifts by the drift rate, and is then pushed by the stimulus given as 
+/// input, staying between 0 and 1. The urgency of the need is returned. 
+///
+/// # Example 
+/// ```
+/// use stackbt_automata_impl::automaton::Automaton;
+/// use stackbt_automata_impl::needs::{Need, ResponseCurve};
+///
+/// // Hunger grows on its own, and eating satisfies it
+/// let mut hunger = Need::new(0.5, 0.25, ResponseCurve::Step(0.75));
+/// assert_eq!(hunger.transition(&0.0), 1.0);
+/// assert_eq!(hunger.transition(&-0.75), 0.0);
+/// assert_eq!(hunger.level(), 0.25);
+/// ```
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Need {
+    level: f64,
+    drift: f64,
+    curve: ResponseCurve
+}
+
+impl Need {
+    /// Create a new need starting at the given level, which drifts by the 
+    /// given rate each step. Positive rates make the need accumulate, as 
+    /// with hunger, while negative rates make it decay, as with fear. 
+    pub fn new(level: f64, drift: f64, curve: ResponseCurve) -> Need {
+        Need {
+            level: clamp_unit(level),
+            drift: drift,
+            curve: curve
+        }
+    }
+
+    /// Return the level of the need. 
+    pub fn level(&self) -> f64 {
+        self.level
+    }
+
+    /// Return the urgency of the need at its current level. 
+    pub fn urgency(&self) -> f64 {
+        self.curve.evaluate(self.level)
+    }
+}
+
+impl<'k> Automaton<'k> for Need {
+    type Input = f64;
+    type Action = f64;
+
+    #[inline]
+    fn transition(&mut self, stimulus: &f64) -> f64 {
+        self.level = clamp_unit(self.level + self.drift + *stimulus);
+        self.urgency()
+    }
+}
+
+impl<'k> FiniteStateAutomaton<'k> for Need {}
+
+impl Snapshot for Need {
+    type State = Need;
+
+    fn snapshot(&self) -> Need {
+        *self
+    }
+
+    fn restore(state: Need) -> Need {
+        state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use automaton::Automaton;
+    use needs::{Need, ResponseCurve};
+
+    #[test]
+    fn response_curve_test() {
+        let linear = ResponseCurve::Linear { slope: 2.0, offset: -0.5 };
+        assert_eq!(linear.evaluate(0.5), 0.5);
+        assert_eq!(linear.evaluate(0.1), 0.0);
+        assert_eq!(linear.evaluate(0.9), 1.0);
+        assert_eq!(ResponseCurve::Power(2.0).evaluate(0.5), 0.25);
+        let logistic = ResponseCurve::Logistic { steepness: 10.0, midpoint: 0.5 };
+        assert_eq!(logistic.evaluate(0.5), 0.5);
+        assert!(logistic.evaluate(0.9) > 0.95);
+        assert_eq!(ResponseCurve::Step(0.5).evaluate(0.4), 0.0);
+    }
+
+    #[test]
+    fn nan_test() {
+        assert_eq!(ResponseCurve::Power(f64::NAN).evaluate(0.5), 0.0);
+        let mut need = Need::new(f64::NAN, 0.25, ResponseCurve::Power(1.0));
+        assert_eq!(need.level(), 0.0);
+        assert_eq!(need.transition(&f64::NAN), 0.0);
+        assert_eq!(need.transition(&0.0), 0.25);
+    }
+
+    #[test]
+    fn need_test() {
+        let mut fear = Need::new(0.0, -0.25, ResponseCurve::Power(1.0));
+        assert_eq!(fear.transition(&1.0), 0.75);
+        assert_eq!(fear.transition(&0.0), 0.5);
+        assert_eq!(fear.transition(&0.0), 0.25);
+        assert_eq!(fear.transition(&0.0), 0.0);
+        assert_eq!(fear.transition(&0.0), 0.0);
+        let mut drives = (Need::new(0.5, 0.25, ResponseCurve::Power(1.0)), fear);
+        assert_eq!(drives.transition(&0.5), (1.0, 0.25));
+    }
+}
@@ -0,0 +1,104 @@
+//! Stepping of independent automata across threads. 
+//!
+//! Crowd simulations, such as flocks of boids, transition thousands of 
+//! automata on the same input every tick, each of them independently of the 
+//! others. Collections of automata which can be sent between threads can be 
+//! transitioned on rayon's thread pool instead of one at a time, writing 
+//! their actions into a buffer owned by the caller like buffered automata 
+//! do. 
+
+use automaton::Automaton;
+use rayon::prelude::*;
+
+/// Trait for collections of automata which can all be transitioned on the 
+/// same input in parallel. 
+///
+/// # Example
+/// ```
+/// use stackbt_automata_impl::parallel_automata::ParTransition;
+/// use stackbt_automata_impl::stateless_mapper::StatelessMapper;
+/// let positive = |input: &i64| *input > 0;
+/// let mut machines = vec![StatelessMapper::new(positive); 64];
+/// let mut actions = Vec::new();
+/// machines.par_transition(&1, &mut actions);
+/// assert_eq!(actions, vec![true; 64]);
+/// ```
+pub trait ParTransition<'k> {
+    /// The input type taken by the automata. 
+    type Input: 'k;
+    /// The action type returned by each of the automata. 
+    type Action;
+
+    /// Take an input by reference, transition each of the automata with it 
+    /// in parallel, and replace the contents of the buffer with their 
+    /// actions, in order. 
+    fn par_transition(&mut self, input: &Self::Input, actions: &mut Vec<Self::Action>);
+}
+
+impl<'k, M> ParTransition<'k> for [M] where
+    M: Automaton<'k> + Send,
+    M::Input: Sync,
+    M::Action: Send
+{
+    type Input = M::Input;
+    type Action = M::Action;
+
+    fn par_transition(&mut self, input: &M::Input, actions: &mut Vec<M::Action>) {
+        self.par_iter_mut()
+            .map(|mach| mach.transition(input))
+            .collect_into_vec(actions);
+    }
+}
+
+impl<'k, M> ParTransition<'k> for Vec<M> where
+    M: Automaton<'k> + Send,
+    M::Input: Sync,
+    M::Action: Send
+{
+    type Input = M::Input;
+    type Action = M::Action;
+
+    fn par_transition(&mut self, input: &M::Input, actions: &mut Vec<M::Action>) {
+        self.as_mut_slice().par_transition(input, actions);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use automaton::BufferedAutomaton;
+    use internal_state_machine::{InternalTransition, InternalStateMachine};
+    use parallel_automata::ParTransition;
+
+    #[derive(Copy, Clone)]
+    struct Sum;
+
+    impl InternalTransition for Sum {
+        type Input = i64;
+        type Internal = i64;
+        type Action = i64;
+
+        fn step(&self, input: &i64, total: &mut i64) -> i64 {
+            *total += *input;
+            *total
+        }
+    }
+
+    #[test]
+    fn par_transition_test() {
+        let mut machines = (0..1000)
+            .map(|start| InternalStateMachine::new(Sum, start))
+            .collect::<Vec<_>>();
+        let mut serial = machines.clone();
+        let mut actions = Vec::new();
+        let mut expected = Vec::new();
+        for input in 1..4 {
+            machines.par_transition(&input, &mut actions);
+            serial.transition_into(&input, &mut expected);
+            assert_eq!(actions, expected);
+        }
+        assert_eq!(actions[0], 6);
+        assert_eq!(actions[999], 1005);
+        machines[..0].par_transition(&1, &mut actions);
+        assert!(actions.is_empty());
+    }
+}
\ No newline at end of file
@@ -1,5 +1,9 @@
-use automaton::{Automaton, FiniteStateAutomaton};
+use automaton::{Automaton, FiniteStateAutomaton, Poisoned};
+use snapshot::Snapshot;
 use std::marker::PhantomData;
+use std::slice::Iter;
+#[cfg(feature = "smallvec")]
+use smallvec::SmallVec;
 
 /// Nonterminal pushdown transition for the pushdown automaton. 
 #[derive(Copy, Clone, PartialEq, Debug)]
@@ -9,7 +13,16 @@ pub enum PushdownTransition<A, N> {
     /// Keep the frames on the stack as is. 
     Stay(A),
     /// Remove the topmost frame from the stack. 
-    Pop(A)
+    Pop(A),
+    /// Remove the given number of topmost frames from the stack, counting 
+    /// the frame being transitioned, or all of them if there are not that 
+    /// many. Removing no frames keeps the frames on the stack as is. 
+    PopN(A, usize),
+    /// Replace the topmost frame with a new frame. 
+    Replace(A, N),
+    /// Remove all frames from the stack, returning control to the bottom 
+    /// machine. 
+    Clear(A)
 }
 
 /// Terminal pushdown transition for the pushdown automaton. 
@@ -18,13 +31,56 @@ pub enum TerminalTransition<A, N> {
     /// Push a new frame onto the pushdown stack. 
     Push(A, N),
     /// Keep the frames on the stack as is. 
-    Stay(A)
+    Stay(A),
+    /// Signal that the pushdown automaton as a whole has completed. 
+    Finish(A)
+}
+
+/// Policy for frames pushed onto the stack of a depth-limited pushdown 
+/// automaton whose stack is already at its depth limit. 
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum OverflowPolicy {
+    /// Discard the pushed frame, keeping the frames on the stack as is. 
+    Reject,
+    /// Remove the topmost frame from the stack, and push the new frame in 
+    /// its place. 
+    Replace,
+    /// Panic. 
+    Panic
+}
+
+// Storage for the frames of the stack, which keeps shallow stacks inline 
+// instead of on the heap when the smallvec feature is enabled
+#[cfg(feature = "smallvec")]
+type FrameStack<N> = SmallVec<[N; 8]>;
+#[cfg(not(feature = "smallvec"))]
+type FrameStack<N> = Vec<N>;
+
+/// Action of a pushdown automaton along with whether it has completed, as 
+/// returned by transition_status. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum PushdownStatus<A> {
+    /// The pushdown automaton is still running. 
+    Running(A),
+    /// The bottom machine finished the pushdown automaton. 
+    Finished(A)
+}
+
+impl<A> PushdownStatus<A> {
+    /// Discard the completion status, returning just the action. 
+    pub fn into_action(self) -> A {
+        match self {
+            PushdownStatus::Running(a) => a,
+            PushdownStatus::Finished(a) => a
+        }
+    }
 }
 
 /// Implementation of a pushdown automaton which builds upon existing state 
 /// machines. Somewhat more powerful than state machines, but in return, 
 /// requires some allocable space and some extra tolerance for amortized 
-/// runtime costs. 
+/// runtime costs. With the smallvec feature enabled, the first few frames 
+/// of the stack are kept inline, so that shallow stacks do not allocate. 
 #[derive(Clone, PartialEq, Debug)]
 pub struct PushdownAutomaton <'k, I, A, N, T> where 
     I: 'k,
@@ -32,7 +88,9 @@ pub struct PushdownAutomaton <'k, I, A, N, T> where
     T: FiniteStateAutomaton<'k, Input=I, Action=TerminalTransition<A, N>> + 'k,
 {
     bottom: Option<T>,
-    stack: Vec<N>,
+    stack: FrameStack<N>,
+    depth_limit: Option<usize>,
+    overflow: OverflowPolicy,
     _i_exists: PhantomData<&'k I>,
     _a_exists: PhantomData<A>
 }
@@ -46,7 +104,9 @@ impl<'k, I, A, N, T> PushdownAutomaton<'k, I, A, N, T> where
     pub fn new(terminal: T) -> PushdownAutomaton<'k, I, A, N, T> {
         PushdownAutomaton {
             bottom: Option::Some(terminal),
-            stack: Vec::new(),
+            stack: FrameStack::new(),
+            depth_limit: Option::None,
+            overflow: OverflowPolicy::Panic,
             _i_exists: PhantomData,
             _a_exists: PhantomData
         }
@@ -72,52 +132,226 @@ impl<'k, I, A, N, T> PushdownAutomaton<'k, I, A, N, T> where
         PushdownAutomaton {
             bottom: Option::Some(terminal),
             stack: to_use_vec,
+            depth_limit: Option::None,
+            overflow: OverflowPolicy::Panic,
             _i_exists: PhantomData,
             _a_exists: PhantomData,
         }
     }
-}
 
-impl<'k, I, A, N, T> Automaton<'k> for PushdownAutomaton<'k, I, A, N, T> where 
-    I: 'k,
-    N: FiniteStateAutomaton<'k, Input=I, Action=PushdownTransition<A, N>> + 'k,
-    T: FiniteStateAutomaton<'k, Input=I, Action=TerminalTransition<A, N>> + 'k,
-{
-    type Input = I;
-    type Action = A;
-    #[inline]
-    fn transition(&mut self, input: &I) -> A {
-        match self.stack.pop() {
+    /// Create a new pushdown automaton which holds at most the given number 
+    /// of frames above the bottom machine, handling pushes beyond that 
+    /// depth by the given policy. The depth limit is not part of snapshots 
+    /// of the pushdown automaton. 
+    /// 
+    /// # Panics 
+    /// Panics if the depth limit is zero. 
+    pub fn with_depth_limit(terminal: T, limit: usize, overflow: OverflowPolicy) 
+    -> PushdownAutomaton<'k, I, A, N, T> {
+        assert!(limit > 0, "Pushdown automaton depth limit must be positive");
+        let mut automaton = PushdownAutomaton::new(terminal);
+        automaton.depth_limit = Option::Some(limit);
+        automaton.overflow = overflow;
+        automaton
+    }
+
+    /// Return the depth limit of the pushdown automaton, if it has one. 
+    pub fn depth_limit(&self) -> Option<usize> {
+        self.depth_limit
+    }
+
+    // Push a frame onto the stack, unless the depth limit forbids it
+    fn push_frame(&mut self, frame: N) {
+        if let Option::Some(limit) = self.depth_limit {
+            if self.stack.len() >= limit {
+                match self.overflow {
+                    OverflowPolicy::Reject => return,
+                    OverflowPolicy::Replace => {
+                        self.stack.pop();
+                    },
+                    OverflowPolicy::Panic => panic!(
+                        "Pushdown automaton exceeded its depth limit of {}", limit)
+                }
+            }
+        }
+        self.stack.push(frame);
+    }
+
+    /// Return the number of frames pushed above the bottom machine. 
+    pub fn stack_depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// Return the topmost frame, which is transitioned next, or None if no 
+    /// frames are pushed above the bottom machine. 
+    pub fn peek(&self) -> Option<&N> {
+        self.stack.last()
+    }
+
+    /// Return the bottom machine, or None if the pushdown automaton was 
+    /// poisoned by a panic in an earlier transition. 
+    pub fn bottom(&self) -> Option<&T> {
+        self.bottom.as_ref()
+    }
+
+    /// Return the topmost frame mutably, or None if no frames are pushed 
+    /// above the bottom machine. 
+    pub fn peek_mut(&mut self) -> Option<&mut N> {
+        self.stack.last_mut()
+    }
+
+    /// Return the bottom machine mutably, or None if the pushdown automaton 
+    /// was poisoned by a panic in an earlier transition. 
+    pub fn bottom_mut(&mut self) -> Option<&mut T> {
+        self.bottom.as_mut()
+    }
+
+    /// Return an iterator over the frames pushed above the bottom machine, 
+    /// from the bottom of the stack to the top. 
+    pub fn frames<'t>(&'t self) -> Iter<'t, N> {
+        self.stack.iter()
+    }
+
+    /// Break the pushdown automaton into its bottom machine, which is None 
+    /// if it was poisoned, and its frames, from the bottom of the stack to 
+    /// the top. 
+    pub fn into_parts(self) -> (Option<T>, Vec<N>) {
+        (self.bottom, self.stack.into_iter().collect())
+    }
+
+    /// Recover the pushdown automaton if it was poisoned, reinstalling the 
+    /// bottom machine returned by the given function. Returns whether the 
+    /// pushdown automaton was poisoned. 
+    pub fn recover_with<F>(&mut self, fresh: F) -> bool where 
+        F: FnOnce() -> T
+    {
+        if self.bottom.is_some() {
+            false
+        } else {
+            self.bottom = Option::Some(fresh());
+            true
+        }
+    }
+
+    /// Transition the pushdown automaton, returning the action along with 
+    /// whether the bottom machine finished the pushdown automaton. Once 
+    /// finished, the pushdown automaton may still be transitioned, in which 
+    /// case it continues from the bottom machine as it was left. 
+    pub fn transition_status(&mut self, input: &I) -> PushdownStatus<A> {
+        match self.try_transition_status(input) {
+            Result::Ok(status) => status,
+            Result::Err(poisoned) => panic!("{}", poisoned)
+        }
+    }
+
+    /// Transition the pushdown automaton, returning the action, or an error 
+    /// instead of panicking if a panic in an earlier transition poisoned it. 
+    pub fn try_transition(&mut self, input: &I) -> Result<A, Poisoned> {
+        self.try_transition_status(input).map(PushdownStatus::into_action)
+    }
+
+    /// Transition the pushdown automaton as with transition_status, returning 
+    /// an error instead of panicking if a panic in an earlier transition 
+    /// poisoned it. 
+    pub fn try_transition_status(&mut self, input: &I) 
+    -> Result<PushdownStatus<A>, Poisoned> {
+        let status = match self.stack.pop() {
             Option::Some(mut val) => {
                 match val.transition(input) {
                     PushdownTransition::Push(act, new) => {
                         self.stack.push(val);
-                        self.stack.push(new);
-                        act
+                        self.push_frame(new);
+                        PushdownStatus::Running(act)
                     },
                     PushdownTransition::Stay(act) => {
                         self.stack.push(val);
-                        act
+                        PushdownStatus::Running(act)
                     },
-                    PushdownTransition::Pop(act) => act
+                    PushdownTransition::Pop(act) => PushdownStatus::Running(act),
+                    PushdownTransition::PopN(act, count) => {
+                        if count == 0 {
+                            self.stack.push(val);
+                        } else {
+                            let keep = self.stack.len().saturating_sub(count - 1);
+                            self.stack.truncate(keep);
+                        }
+                        PushdownStatus::Running(act)
+                    },
+                    PushdownTransition::Replace(act, new) => {
+                        self.stack.push(new);
+                        PushdownStatus::Running(act)
+                    },
+                    PushdownTransition::Clear(act) => {
+                        self.stack.clear();
+                        PushdownStatus::Running(act)
+                    }
                 }
             },
             Option::None => {
-                let mut tmp_some = self.bottom
-                    .take()
-                    .expect("Pushdown automaton was poisoned");
+                let mut tmp_some = match self.bottom.take() {
+                    Option::Some(bottom) => bottom,
+                    Option::None => return Result::Err(Poisoned {
+                        kind: "Pushdown automaton",
+                        name: Option::None,
+                        input: Option::None
+                    })
+                };
                 match tmp_some.transition(input) {
                     TerminalTransition::Push(act, new) => {
-                        self.stack.push(new);
                         self.bottom = Option::Some(tmp_some);
-                        act
+                        self.push_frame(new);
+                        PushdownStatus::Running(act)
                     },
                     TerminalTransition::Stay(act) => {
                         self.bottom = Option::Some(tmp_some);
-                        act
+                        PushdownStatus::Running(act)
+                    },
+                    TerminalTransition::Finish(act) => {
+                        self.bottom = Option::Some(tmp_some);
+                        PushdownStatus::Finished(act)
                     }
                 }
             }
+        };
+        Result::Ok(status)
+    }
+}
+
+impl<'k, I, A, N, T> Automaton<'k> for PushdownAutomaton<'k, I, A, N, T> where 
+    I: 'k,
+    N: FiniteStateAutomaton<'k, Input=I, Action=PushdownTransition<A, N>> + 'k,
+    T: FiniteStateAutomaton<'k, Input=I, Action=TerminalTransition<A, N>> + 'k,
+{
+    type Input = I;
+    type Action = A;
+    #[inline]
+    fn transition(&mut self, input: &I) -> A {
+        self.transition_status(input).into_action()
+    }
+}
+
+impl<'k, I, A, N, T> Snapshot for PushdownAutomaton<'k, I, A, N, T> where 
+    I: 'k,
+    N: FiniteStateAutomaton<'k, Input=I, Action=PushdownTransition<A, N>> + Snapshot + 'k,
+    T: FiniteStateAutomaton<'k, Input=I, Action=TerminalTransition<A, N>> + Snapshot + 'k,
+{
+    type State = (Option<T::State>, Vec<N::State>);
+
+    fn snapshot(&self) -> Self::State {
+        (
+            self.bottom.as_ref().map(|bottom| bottom.snapshot()),
+            self.stack.iter().map(|machine| machine.snapshot()).collect()
+        )
+    }
+
+    fn restore(state: Self::State) -> Self {
+        PushdownAutomaton {
+            bottom: state.0.map(T::restore),
+            stack: state.1.into_iter().map(N::restore).collect(),
+            depth_limit: Option::None,
+            overflow: OverflowPolicy::Panic,
+            _i_exists: PhantomData,
+            _a_exists: PhantomData
         }
     }
 }
@@ -127,7 +361,7 @@ mod test {
     use automaton::Automaton;
     use internal_state_machine::{InternalTransition, InternalStateMachine};
     use pushdown_automaton::{
-            PushdownAutomaton, PushdownTransition, TerminalTransition};
+            PushdownAutomaton, PushdownTransition, TerminalTransition, PushdownStatus};
 
     #[derive(Copy, Clone)]
     struct TerminalFunction;
@@ -145,6 +379,8 @@ mod test {
                     NonterminalFunction, 
                     0
                 ))
+            } else if *new < 0 {
+                TerminalTransition::Finish(*internal)
             } else {
                 let orig_internal = *internal;
                 *internal = *new;
@@ -201,4 +437,178 @@ mod test {
         assert_eq!(test_pushdown.transition(&2), 4);
     }
 
+    #[test]
+    fn check_finish() {
+        let mut test_pushdown = PushdownAutomaton::from_iterable(
+            InternalStateMachine::new(TerminalFunction, 0),
+            Vec::<InternalStateMachine<NonterminalFunction>>::new()
+        );
+        assert_eq!(test_pushdown.transition_status(&3), PushdownStatus::Running(0));
+        assert_eq!(test_pushdown.transition_status(&0), PushdownStatus::Running(3));
+        // A pop within the stack does not finish the automaton
+        assert_eq!(test_pushdown.transition_status(&-1), PushdownStatus::Running(0));
+        assert_eq!(test_pushdown.transition_status(&-1), PushdownStatus::Finished(3));
+        assert_eq!(test_pushdown.transition(&4), 3);
+    }
+
+    #[test]
+    fn introspection_test() {
+        let mut test_pushdown = PushdownAutomaton::from_iterable(
+            InternalStateMachine::new(TerminalFunction, 0),
+            Vec::<InternalStateMachine<NonterminalFunction>>::new()
+        );
+        assert_eq!(test_pushdown.stack_depth(), 0);
+        assert!(test_pushdown.peek().is_none());
+        test_pushdown.transition(&0);
+        test_pushdown.transition(&7);
+        test_pushdown.transition(&0);
+        assert_eq!(test_pushdown.stack_depth(), 2);
+        assert_eq!(test_pushdown.frames().count(), 2);
+        // The topmost frame returns its previous input
+        let mut top = *test_pushdown.peek().unwrap();
+        match top.transition(&1) {
+            PushdownTransition::Stay(act) => assert_eq!(act, 0),
+            _ => unreachable!("Expected the frame to stay")
+        };
+        let mut below = *test_pushdown.frames().next().unwrap();
+        match below.transition(&1) {
+            PushdownTransition::Stay(act) => assert_eq!(act, 7),
+            _ => unreachable!("Expected the frame to stay")
+        };
+        // Transitioning the topmost frame in place is seen by the next transition
+        test_pushdown.peek_mut().unwrap().transition(&8);
+        assert_eq!(test_pushdown.transition(&1), 8);
+        let (bottom, frames) = test_pushdown.into_parts();
+        assert!(bottom.is_some());
+        assert_eq!(frames.len(), 2);
+    }
+
+    #[test]
+    fn depth_limit_test() {
+        use pushdown_automaton::OverflowPolicy;
+        let mut test_pushdown = PushdownAutomaton::with_depth_limit(
+            InternalStateMachine::new(TerminalFunction, 0), 2, OverflowPolicy::Reject);
+        assert_eq!(test_pushdown.depth_limit(), Option::Some(2));
+        test_pushdown.transition(&0);
+        test_pushdown.transition(&4);
+        test_pushdown.transition(&0);
+        assert_eq!(test_pushdown.stack_depth(), 2);
+        // The push is rejected, but the frame still acts
+        assert_eq!(test_pushdown.transition(&0), 0);
+        assert_eq!(test_pushdown.stack_depth(), 2);
+        assert_eq!(test_pushdown.transition(&-1), 0);
+        assert_eq!(test_pushdown.transition(&-1), 4);
+        assert_eq!(test_pushdown.stack_depth(), 0);
+    }
+
+    #[test]
+    fn overflow_replace_test() {
+        use pushdown_automaton::OverflowPolicy;
+        let mut test_pushdown = PushdownAutomaton::with_depth_limit(
+            InternalStateMachine::new(TerminalFunction, 0), 1, OverflowPolicy::Replace);
+        test_pushdown.transition(&0);
+        assert_eq!(test_pushdown.transition(&6), 0);
+        // The pushing frame is replaced by the frame it pushed
+        assert_eq!(test_pushdown.transition(&0), 6);
+        assert_eq!(test_pushdown.stack_depth(), 1);
+        assert_eq!(test_pushdown.transition(&3), 0);
+        assert_eq!(test_pushdown.transition(&-1), 3);
+        assert_eq!(test_pushdown.stack_depth(), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn overflow_panic_test() {
+        use pushdown_automaton::OverflowPolicy;
+        let mut test_pushdown = PushdownAutomaton::with_depth_limit(
+            InternalStateMachine::new(TerminalFunction, 0), 1, OverflowPolicy::Panic);
+        test_pushdown.transition(&0);
+        test_pushdown.transition(&0);
+    }
+
+    #[derive(Copy, Clone)]
+    struct UnwindTerminal;
+    #[derive(Copy, Clone)]
+    struct UnwindFunction;
+
+    type UnwindFrame = InternalStateMachine<'static, UnwindFunction>;
+
+    impl InternalTransition for UnwindTerminal {
+        type Internal = ();
+        type Input = i64;
+        type Action = TerminalTransition<i64, UnwindFrame>;
+        fn step (&self, new: &i64, _internal: &mut ()) -> Self::Action {
+            if *new == 0 {
+                TerminalTransition::Push(0, InternalStateMachine::new(UnwindFunction, 1))
+            } else {
+                TerminalTransition::Stay(0)
+            }
+        }
+    }
+
+    // Each frame returns its own number, pushing the next number on 0, 
+    // popping n frames on 10 + n, replacing itself on 5 and clearing the 
+    // stack on -1
+    impl InternalTransition for UnwindFunction {
+        type Internal = i64;
+        type Input = i64;
+        type Action = PushdownTransition<i64, UnwindFrame>;
+        fn step (&self, new: &i64, internal: &mut i64) -> Self::Action {
+            match *new {
+                0 => PushdownTransition::Push(*internal, 
+                    InternalStateMachine::new(UnwindFunction, *internal + 1)),
+                5 => PushdownTransition::Replace(*internal, 
+                    InternalStateMachine::new(UnwindFunction, 100)),
+                -1 => PushdownTransition::Clear(*internal),
+                n if n >= 10 => PushdownTransition::PopN(*internal, (n - 10) as usize),
+                _ => PushdownTransition::Stay(*internal)
+            }
+        }
+    }
+
+    #[test]
+    fn unwind_test() {
+        let mut test_pushdown = PushdownAutomaton::new(
+            InternalStateMachine::new(UnwindTerminal, ()));
+        for expected in 0..4 {
+            assert_eq!(test_pushdown.transition(&0), expected);
+        }
+        assert_eq!(test_pushdown.stack_depth(), 4);
+        assert_eq!(test_pushdown.transition(&10), 4);
+        assert_eq!(test_pushdown.stack_depth(), 4);
+        assert_eq!(test_pushdown.transition(&12), 4);
+        assert_eq!(test_pushdown.stack_depth(), 2);
+        assert_eq!(test_pushdown.transition(&5), 2);
+        assert_eq!(test_pushdown.stack_depth(), 2);
+        assert_eq!(test_pushdown.transition(&7), 100);
+        assert_eq!(test_pushdown.transition(&19), 100);
+        assert_eq!(test_pushdown.stack_depth(), 0);
+        assert_eq!(test_pushdown.transition(&0), 0);
+        assert_eq!(test_pushdown.transition(&0), 1);
+        assert_eq!(test_pushdown.transition(&-1), 2);
+        assert_eq!(test_pushdown.stack_depth(), 0);
+        assert_eq!(test_pushdown.transition(&7), 0);
+    }
+
+    fn careful_terminal(new: &i64) -> TerminalTransition<i64, UnwindFrame> {
+        assert!(*new >= 0, "Negative input");
+        TerminalTransition::Stay(*new)
+    }
+
+    #[test]
+    fn recover_test() {
+        use stateless_mapper::StatelessMapper;
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+        let mut test_pushdown = PushdownAutomaton::new(
+            StatelessMapper::new(careful_terminal));
+        assert!(!test_pushdown.recover_with(|| StatelessMapper::new(careful_terminal)));
+        assert_eq!(test_pushdown.try_transition(&1), Result::Ok(1));
+        assert!(catch_unwind(AssertUnwindSafe(|| test_pushdown.transition(&-1))).is_err());
+        let poisoned = test_pushdown.try_transition(&1).unwrap_err();
+        assert_eq!(poisoned.to_string(), "Pushdown automaton was poisoned");
+        assert!(test_pushdown.bottom().is_none());
+        assert!(test_pushdown.recover_with(|| StatelessMapper::new(careful_terminal)));
+        assert_eq!(test_pushdown.try_transition_status(&2), 
+            Result::Ok(PushdownStatus::Running(2)));
+    }
 }
\ No newline at end of file
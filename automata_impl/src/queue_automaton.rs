@@ -0,0 +1,267 @@
+use automaton::{Automaton, FiniteStateAutomaton};
+use snapshot::Snapshot;
+use std::collections::VecDeque;
+use std::collections::vec_deque::Iter;
+use std::marker::PhantomData;
+
+/// Transition of the frame at the front of the queue automaton. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum QueueTransition<A, N> {
+    /// Add a new frame to the back of the queue. 
+    Enqueue(A, N),
+    /// Keep the frames in the queue as is. 
+    Stay(A),
+    /// Remove the frame at the front of the queue, moving on to the next. 
+    Dequeue(A)
+}
+
+/// Transition of the idle machine of the queue automaton, which is 
+/// transitioned while the queue is empty. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum IdleTransition<A, N> {
+    /// Add a new frame to the back of the queue. 
+    Enqueue(A, N),
+    /// Keep the queue empty. 
+    Stay(A)
+}
+
+/// Implementation of a queue automaton which builds upon existing state 
+/// machines. Frames are worked through in the order they were added, each 
+/// until it removes itself from the queue, while an idle machine fills in 
+/// whenever the queue is empty. Useful for command queues, where orders 
+/// given to an agent are carried out one after another. 
+/// 
+/// # Example
+/// ```
+/// use stackbt_automata_impl::automaton::Automaton;
+/// use stackbt_automata_impl::internal_state_machine::{InternalStateMachine, 
+///     InternalTransition};
+/// use stackbt_automata_impl::queue_automaton::{IdleTransition, QueueAutomaton, 
+///     QueueTransition};
+/// use stackbt_automata_impl::stateless_mapper::StatelessMapper;
+/// 
+/// #[derive(Copy, Clone)]
+/// struct Deliver;
+/// 
+/// type Order = InternalStateMachine<'static, Deliver>;
+/// 
+/// impl InternalTransition for Deliver {
+///     type Input = ();
+///     type Internal = i64;
+///     type Action = QueueTransition<i64, Order>;
+///     fn step(&self, _: &(), parcel: &mut i64) -> QueueTransition<i64, Order> {
+///         QueueTransition::Dequeue(*parcel)
+///     }
+/// }
+/// 
+/// let mut courier = QueueAutomaton::new(StatelessMapper::new(|_: &()| {
+///     IdleTransition::<i64, Order>::Stay(0)
+/// }));
+/// courier.enqueue(InternalStateMachine::new(Deliver, 1));
+/// courier.enqueue(InternalStateMachine::new(Deliver, 2));
+/// assert_eq!(courier.transition(&()), 1);
+/// assert_eq!(courier.transition(&()), 2);
+/// assert_eq!(courier.transition(&()), 0);
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct QueueAutomaton<'k, I, A, N, T> where
+    I: 'k,
+    N: FiniteStateAutomaton<'k, Input=I, Action=QueueTransition<A, N>> + 'k,
+    T: FiniteStateAutomaton<'k, Input=I, Action=IdleTransition<A, N>> + 'k
+{
+    idle: T,
+    queue: VecDeque<N>,
+    _i_exists: PhantomData<&'k I>,
+    _a_exists: PhantomData<A>
+}
+
+impl<'k, I, A, N, T> QueueAutomaton<'k, I, A, N, T> where
+    I: 'k,
+    N: FiniteStateAutomaton<'k, Input=I, Action=QueueTransition<A, N>> + 'k,
+    T: FiniteStateAutomaton<'k, Input=I, Action=IdleTransition<A, N>> + 'k
+{
+    /// Create a new queue automaton with an empty queue. 
+    pub fn new(idle: T) -> QueueAutomaton<'k, I, A, N, T> {
+        QueueAutomaton {
+            idle: idle,
+            queue: VecDeque::new(),
+            _i_exists: PhantomData,
+            _a_exists: PhantomData
+        }
+    }
+
+    /// Create a new queue automaton from an iterator supplying finite state 
+    /// machines, with the first frame at the front of the queue. 
+    pub fn from_iter<K>(idle: T, queued: K) -> QueueAutomaton<'k, I, A, N, T> where
+        K: Iterator<Item = N>
+    {
+        QueueAutomaton {
+            idle: idle,
+            queue: queued.collect(),
+            _i_exists: PhantomData,
+            _a_exists: PhantomData
+        }
+    }
+
+    /// Add a frame to the back of the queue from outside the automaton. 
+    pub fn enqueue(&mut self, frame: N) {
+        self.queue.push_back(frame);
+    }
+
+    /// Return the number of frames in the queue. 
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Return whether the queue is empty, so that the idle machine is 
+    /// transitioned next. 
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// Return the frame at the front of the queue, which is transitioned 
+    /// next, or None if the queue is empty. 
+    pub fn front(&self) -> Option<&N> {
+        self.queue.front()
+    }
+
+    /// Return an iterator over the frames in the queue, from the front to 
+    /// the back. 
+    pub fn frames<'t>(&'t self) -> Iter<'t, N> {
+        self.queue.iter()
+    }
+}
+
+impl<'k, I, A, N, T> Automaton<'k> for QueueAutomaton<'k, I, A, N, T> where
+    I: 'k,
+    N: FiniteStateAutomaton<'k, Input=I, Action=QueueTransition<A, N>> + 'k,
+    T: FiniteStateAutomaton<'k, Input=I, Action=IdleTransition<A, N>> + 'k
+{
+    type Input = I;
+    type Action = A;
+
+    fn transition(&mut self, input: &I) -> A {
+        let front = match self.queue.front_mut() {
+            Option::Some(frame) => frame.transition(input),
+            Option::None => return match self.idle.transition(input) {
+                IdleTransition::Enqueue(act, new) => {
+                    self.queue.push_back(new);
+                    act
+                },
+                IdleTransition::Stay(act) => act
+            }
+        };
+        match front {
+            QueueTransition::Enqueue(act, new) => {
+                self.queue.push_back(new);
+                act
+            },
+            QueueTransition::Stay(act) => act,
+            QueueTransition::Dequeue(act) => {
+                self.queue.pop_front();
+                act
+            }
+        }
+    }
+}
+
+impl<'k, I, A, N, T> Snapshot for QueueAutomaton<'k, I, A, N, T> where
+    I: 'k,
+    N: FiniteStateAutomaton<'k, Input=I, Action=QueueTransition<A, N>> + Snapshot + 'k,
+    T: FiniteStateAutomaton<'k, Input=I, Action=IdleTransition<A, N>> + Snapshot + 'k
+{
+    type State = (T::State, Vec<N::State>);
+
+    fn snapshot(&self) -> Self::State {
+        (
+            self.idle.snapshot(),
+            self.queue.iter().map(|machine| machine.snapshot()).collect()
+        )
+    }
+
+    fn restore(state: Self::State) -> Self {
+        QueueAutomaton {
+            idle: T::restore(state.0),
+            queue: state.1.into_iter().map(N::restore).collect(),
+            _i_exists: PhantomData,
+            _a_exists: PhantomData
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use automaton::Automaton;
+    use internal_state_machine::{InternalTransition, InternalStateMachine};
+    use queue_automaton::{IdleTransition, QueueAutomaton, QueueTransition};
+
+    #[derive(Copy, Clone)]
+    struct IdleFunction;
+    #[derive(Copy, Clone)]
+    struct WalkFunction;
+
+    type Walk = InternalStateMachine<'static, WalkFunction>;
+
+    // Positive inputs order a walk of that many steps
+    impl InternalTransition for IdleFunction {
+        type Internal = ();
+        type Input = i64;
+        type Action = IdleTransition<i64, Walk>;
+        fn step (&self, new: &i64, _internal: &mut ()) -> Self::Action {
+            if *new > 0 {
+                IdleTransition::Enqueue(0, InternalStateMachine::new(WalkFunction, *new))
+            } else {
+                IdleTransition::Stay(0)
+            }
+        }
+    }
+
+    // Walks return the steps left, and also take orders for further walks
+    impl InternalTransition for WalkFunction {
+        type Internal = i64;
+        type Input = i64;
+        type Action = QueueTransition<i64, Walk>;
+        fn step (&self, new: &i64, left: &mut i64) -> Self::Action {
+            *left -= 1;
+            if *new > 0 {
+                QueueTransition::Enqueue(*left, InternalStateMachine::new(WalkFunction, *new))
+            } else if *left == 0 {
+                QueueTransition::Dequeue(*left)
+            } else {
+                QueueTransition::Stay(*left)
+            }
+        }
+    }
+
+    #[test]
+    fn check_def() {
+        let mut test_queue = QueueAutomaton::new(
+            InternalStateMachine::new(IdleFunction, ()));
+        assert_eq!(test_queue.transition(&0), 0);
+        assert_eq!(test_queue.transition(&2), 0);
+        assert_eq!(test_queue.len(), 1);
+        assert_eq!(test_queue.transition(&3), 1);
+        assert_eq!(test_queue.len(), 2);
+        assert_eq!(test_queue.transition(&0), 0);
+        assert_eq!(test_queue.len(), 1);
+        assert_eq!(test_queue.transition(&0), 2);
+        assert_eq!(test_queue.transition(&0), 1);
+        assert_eq!(test_queue.transition(&0), 0);
+        assert!(test_queue.is_empty());
+        assert_eq!(test_queue.transition(&0), 0);
+    }
+
+    #[test]
+    fn enqueue_test() {
+        let mut test_queue = QueueAutomaton::from_iter(
+            InternalStateMachine::new(IdleFunction, ()),
+            vec![InternalStateMachine::new(WalkFunction, 1)].into_iter());
+        test_queue.enqueue(InternalStateMachine::new(WalkFunction, 2));
+        assert_eq!(test_queue.frames().count(), 2);
+        assert!(test_queue.front().is_some());
+        assert_eq!(test_queue.transition(&0), 0);
+        assert_eq!(test_queue.transition(&0), 1);
+        assert_eq!(test_queue.transition(&0), 0);
+        assert!(test_queue.front().is_none());
+    }
+}
\ No newline at end of file
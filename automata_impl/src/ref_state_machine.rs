@@ -1,7 +1,10 @@
-use automaton::{Automaton, FiniteStateAutomaton};
+use automaton::{Automaton, FiniteStateAutomaton, InputDebug, Poisoned};
+use snapshot::Snapshot;
+use std::fmt::Debug;
 use std::marker::PhantomData;
 
-/// Transition trait for RefStateMachine. 
+/// Transition trait for RefStateMachine. Enums of states can implement it 
+/// from a transition table with the state_machine! macro of stackbt. 
 pub trait ReferenceTransition {
     /// The input type taken by the state machine. 
     type Input;
@@ -13,6 +16,10 @@ pub trait ReferenceTransition {
     fn step(self, &Self::Input) -> (Self::Action, Self);
 }
 
+// Spelled out through an alias so that derives do not require the input 
+// type to implement the derived traits
+type InputFormatter<C> = InputDebug<<C as ReferenceTransition>::Input>;
+
 /// State machine implemented through a self-contained callable type. Each 
 /// step, the currently referenced callable is called, returning an action 
 /// and the new value to call for the next step. 
@@ -56,6 +63,8 @@ pub struct RefStateMachine<'k, C> where
     C: ReferenceTransition + 'k
 {
     current_state: Option<C>,
+    name: Option<&'static str>,
+    input_debug: Option<InputFormatter<C>>,
     _lifetime_check: PhantomData<&'k C>
 }
 
@@ -66,9 +75,81 @@ impl <'k, C> RefStateMachine<'k, C> where
     pub fn new(init_state: C) -> RefStateMachine<'k, C> {
         RefStateMachine {
             current_state: Option::Some(init_state),
+            name: Option::None,
+            input_debug: Option::None,
             _lifetime_check: PhantomData
         }
     }
+
+    /// Give the state machine a name, which is reported if it is poisoned. 
+    pub fn with_name(mut self, name: &'static str) -> RefStateMachine<'k, C> {
+        self.name = Option::Some(name);
+        self
+    }
+
+    /// Report the input of the transition which panicked if the state 
+    /// machine is poisoned. 
+    pub fn with_input_debug(mut self) -> RefStateMachine<'k, C> where C::Input: Debug {
+        self.input_debug = Option::Some(InputDebug::new());
+        self
+    }
+
+    /// Return the name of the state machine, if it was given one. 
+    pub fn name(&self) -> Option<&'static str> {
+        self.name
+    }
+
+    /// Return the current state of the state machine, or None if it was 
+    /// poisoned. 
+    pub fn state(&self) -> Option<&C> {
+        self.current_state.as_ref()
+    }
+
+    /// Return the current state of the state machine mutably, or None if 
+    /// it was poisoned. 
+    pub fn state_mut(&mut self) -> Option<&mut C> {
+        self.current_state.as_mut()
+    }
+
+    /// Consume the state machine, returning its current state, or None if 
+    /// it was poisoned. 
+    pub fn into_inner(self) -> Option<C> {
+        self.current_state
+    }
+
+    /// Transition the state machine, returning an error instead of panicking 
+    /// if a panic in an earlier transition poisoned it. 
+    pub fn try_transition(&mut self, input: &C::Input) -> Result<C::Action, Poisoned> {
+        match self.current_state.take() {
+            Option::Some(state) => {
+                if let Option::Some(ref mut recorder) = self.input_debug {
+                    recorder.record(input);
+                }
+                let (action, new_fn) = state.step(input);
+                self.current_state = Option::Some(new_fn);
+                Result::Ok(action)
+            },
+            Option::None => Result::Err(Poisoned {
+                kind: "State machine",
+                name: self.name,
+                input: self.input_debug.as_ref().map(InputDebug::last)
+            })
+        }
+    }
+
+    /// Recover the state machine if it was poisoned, reinstalling the state 
+    /// returned by the given function. Returns whether the state machine 
+    /// was poisoned. 
+    pub fn recover_with<F>(&mut self, fresh: F) -> bool where 
+        F: FnOnce() -> C
+    {
+        if self.current_state.is_some() {
+            false
+        } else {
+            self.current_state = Option::Some(fresh());
+            true
+        }
+    }
 }
 
 impl <'k, C> Default for RefStateMachine<'k, C> where 
@@ -86,12 +167,10 @@ impl <'k, C> Automaton<'k> for RefStateMachine<'k, C> where
     type Action = C::Action;
     #[inline]
     fn transition(&mut self, input: &C::Input) -> C::Action {
-        let (action, new_fn) = self.current_state
-            .take()
-            .expect("State machine was poisoned")
-            .step(&input);
-        self.current_state = Option::Some(new_fn);
-        action
+        match self.try_transition(input) {
+            Result::Ok(action) => action,
+            Result::Err(poisoned) => panic!("{}", poisoned)
+        }
     }
 }
 
@@ -99,11 +178,37 @@ impl <'k, C> FiniteStateAutomaton<'k> for RefStateMachine<'k, C> where
     C: ReferenceTransition + Copy + 'k
 {}
 
+impl <'k, C> Snapshot for RefStateMachine<'k, C> where 
+    C: ReferenceTransition + Clone + 'k
+{
+    type State = C;
+
+    /// Capture the current state. 
+    /// 
+    /// # Panics 
+    /// 
+    /// Panics if the state machine was poisoned. 
+    fn snapshot(&self) -> C {
+        match self.current_state {
+            Option::Some(ref state) => state.clone(),
+            Option::None => panic!("{}", Poisoned {
+                kind: "State machine",
+                name: self.name,
+                input: self.input_debug.as_ref().map(InputDebug::last)
+            })
+        }
+    }
+
+    fn restore(state: C) -> RefStateMachine<'k, C> {
+        RefStateMachine::new(state)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use ref_state_machine::ReferenceTransition;
 
-    #[derive(Copy, Clone)]
+    #[derive(Copy, Clone, PartialEq, Debug)]
     enum ThingBob {
         XorSwap0,
         XorSwap1
@@ -144,4 +249,85 @@ mod tests {
         assert!(!x.transition(&false));
         assert!(!x.transition(&true));
     }
+
+    struct Fragile;
+
+    impl ReferenceTransition for Fragile {
+        type Input = bool;
+        type Action = ();
+
+        fn step(self, input: &bool) -> ((), Fragile) {
+            assert!(*input, "Fragile machine broke");
+            ((), Fragile)
+        }
+    }
+
+    #[test]
+    fn poisoned_test() {
+        use ref_state_machine::RefStateMachine;
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+        let mut x = RefStateMachine::new(Fragile).with_name("fragile");
+        assert_eq!(x.try_transition(&true), Result::Ok(()));
+        assert!(catch_unwind(AssertUnwindSafe(|| x.try_transition(&false))).is_err());
+        let poisoned = x.try_transition(&true).unwrap_err();
+        assert_eq!(poisoned.input, Option::None);
+        assert_eq!(poisoned.to_string(), "State machine 'fragile' was poisoned");
+    }
+
+    struct Picky;
+
+    impl ReferenceTransition for Picky {
+        type Input = String;
+        type Action = ();
+
+        fn step(self, input: &String) -> ((), Picky) {
+            assert!(input.len() < 8, "Picky machine broke");
+            ((), Picky)
+        }
+    }
+
+    #[test]
+    fn poisoned_input_test() {
+        use ref_state_machine::RefStateMachine;
+        use automaton::INPUT_DEBUG_CAPACITY;
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+        let mut x = RefStateMachine::new(Picky).with_input_debug();
+        assert_eq!(x.try_transition(&"short".to_owned()), Result::Ok(()));
+        let long = "\u{e9}".repeat(INPUT_DEBUG_CAPACITY);
+        assert!(catch_unwind(AssertUnwindSafe(|| x.try_transition(&long))).is_err());
+        let poisoned = x.try_transition(&"short".to_owned()).unwrap_err();
+        let reported = poisoned.input.unwrap();
+        assert!(reported.starts_with("\"\u{e9}\u{e9}"));
+        assert!(reported.ends_with("\u{e9}..."));
+        assert!(reported.len() <= INPUT_DEBUG_CAPACITY + 3);
+    }
+
+    #[test]
+    fn state_access_test() {
+        use ref_state_machine::RefStateMachine;
+        use automaton::Automaton;
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+        let mut x = RefStateMachine::new(ThingBob::XorSwap0);
+        x.transition(&true);
+        assert_eq!(x.state(), Option::Some(&ThingBob::XorSwap1));
+        *x.state_mut().unwrap() = ThingBob::XorSwap0;
+        assert!(!x.transition(&false));
+        assert_eq!(x.into_inner(), Option::Some(ThingBob::XorSwap0));
+        let mut y = RefStateMachine::new(Fragile);
+        assert!(catch_unwind(AssertUnwindSafe(|| y.transition(&false))).is_err());
+        assert!(y.state().is_none());
+        assert!(y.into_inner().is_none());
+    }
+
+    #[test]
+    fn recover_test() {
+        use ref_state_machine::RefStateMachine;
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+        let mut x = RefStateMachine::new(Fragile);
+        assert!(!x.recover_with(|| Fragile));
+        assert!(catch_unwind(AssertUnwindSafe(|| x.try_transition(&false))).is_err());
+        assert!(x.try_transition(&true).is_err());
+        assert!(x.recover_with(|| Fragile));
+        assert_eq!(x.try_transition(&true), Result::Ok(()));
+    }
 }
\ No newline at end of file
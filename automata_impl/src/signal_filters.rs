@@ -0,0 +1,416 @@
+//!
+//! Inputs fed to decision logic are often noisy, and are generally filtered 
+//! before they are used. This module contains a few automata which perform 
+//! common filtering tasks on numeric inputs, which can then be composed with 
+//! other automata using the usual combinators. 
+//!
+
+use automaton::{Automaton, FiniteStateAutomaton};
+use snapshot::Snapshot;
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+use std::ops::{Add, Sub, Mul, Div};
+
+/// Automaton which outputs the mean of the most recent inputs it has 
+/// received, up to a fixed window size. Before the window fills up, the 
+/// mean of all inputs received so far is returned. 
+/// 
+/// The window is kept on the heap, so this is not a finite state automaton. 
+/// With the const_generics feature, FixedMovingAverage keeps its window 
+/// inline instead. 
+/// 
+/// # Example
+/// ```
+/// use stackbt_automata_impl::automaton::Automaton;
+/// use stackbt_automata_impl::signal_filters::MovingAverage;
+/// 
+/// let mut average = MovingAverage::new(2);
+/// assert_eq!(average.transition(&2.0_f64), 2.0);
+/// assert_eq!(average.transition(&4.0_f64), 3.0);
+/// assert_eq!(average.transition(&8.0_f64), 6.0);
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct MovingAverage<'k, T> where 
+    T: Copy + Add<Output=T> + Div<Output=T> + From<u16> + 'k
+{
+    window: VecDeque<T>,
+    capacity: u16,
+    _lifetime_check: PhantomData<&'k T>
+}
+
+impl<'k, T> MovingAverage<'k, T> where 
+    T: Copy + Add<Output=T> + Div<Output=T> + From<u16> + 'k
+{
+    /// Create a new moving average filter with the given window size. 
+    pub fn new(capacity: u16) -> MovingAverage<'k, T> {
+        assert!(capacity > 0, "Window capacity must be nonzero");
+        MovingAverage {
+            window: VecDeque::with_capacity(capacity as usize),
+            capacity: capacity,
+            _lifetime_check: PhantomData
+        }
+    }
+}
+
+impl<'k, T> Automaton<'k> for MovingAverage<'k, T> where 
+    T: Copy + Add<Output=T> + Div<Output=T> + From<u16> + 'k
+{
+    type Input = T;
+    type Action = T;
+
+    #[inline]
+    fn transition(&mut self, input: &T) -> T {
+        if self.window.len() >= self.capacity as usize {
+            self.window.pop_front();
+        }
+        self.window.push_back(*input);
+        let mut sum = *input;
+        for val in self.window.iter().rev().skip(1) {
+            sum = sum + *val;
+        }
+        sum / T::from(self.window.len() as u16)
+    }
+}
+
+/// Moving average filter whose window size is fixed at compile time, and 
+/// kept inline, so that it is a finite state automaton. Behaves the same as 
+/// a MovingAverage of the same window size. 
+/// 
+/// # Example
+/// ```
+/// use stackbt_automata_impl::automaton::Automaton;
+/// use stackbt_automata_impl::signal_filters::FixedMovingAverage;
+/// 
+/// let mut average = FixedMovingAverage::<f64, 2>::new();
+/// assert_eq!(average.transition(&2.0_f64), 2.0);
+/// assert_eq!(average.transition(&4.0_f64), 3.0);
+/// assert_eq!(average.transition(&8.0_f64), 6.0);
+/// ```
+#[cfg(feature = "const_generics")]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct FixedMovingAverage<'k, T, const K: usize> where 
+    T: Copy + Add<Output=T> + Div<Output=T> + From<u16> + 'k
+{
+    window: [T; K],
+    filled: usize,
+    next: usize,
+    _lifetime_check: PhantomData<&'k T>
+}
+
+#[cfg(feature = "const_generics")]
+impl<'k, T, const K: usize> FixedMovingAverage<'k, T, K> where 
+    T: Copy + Add<Output=T> + Div<Output=T> + From<u16> + 'k
+{
+    /// Create a new moving average filter. 
+    pub fn new() -> FixedMovingAverage<'k, T, K> {
+        assert!(K > 0 && K <= u16::MAX as usize,
+            "Window capacity must be nonzero and fit in a u16");
+        FixedMovingAverage {
+            window: [T::from(0); K],
+            filled: 0,
+            next: 0,
+            _lifetime_check: PhantomData
+        }
+    }
+}
+
+#[cfg(feature = "const_generics")]
+impl<'k, T, const K: usize> Default for FixedMovingAverage<'k, T, K> where 
+    T: Copy + Add<Output=T> + Div<Output=T> + From<u16> + 'k
+{
+    fn default() -> FixedMovingAverage<'k, T, K> {
+        FixedMovingAverage::new()
+    }
+}
+
+#[cfg(feature = "const_generics")]
+impl<'k, T, const K: usize> Automaton<'k> for FixedMovingAverage<'k, T, K> where 
+    T: Copy + Add<Output=T> + Div<Output=T> + From<u16> + 'k
+{
+    type Input = T;
+    type Action = T;
+
+    #[inline]
+    fn transition(&mut self, input: &T) -> T {
+        self.window[self.next] = *input;
+        self.next = (self.next + 1) % K;
+        if self.filled < K {
+            self.filled += 1;
+        }
+        // The window fills up from the front, so its first value is always set
+        let sum = self.window[1..self.filled].iter().fold(self.window[0], |acc, val| {
+            acc + *val
+        });
+        sum / T::from(self.filled as u16)
+    }
+}
+
+#[cfg(feature = "const_generics")]
+impl<'k, T, const K: usize> FiniteStateAutomaton<'k> for FixedMovingAverage<'k, T, K> where 
+    T: Copy + Add<Output=T> + Div<Output=T> + From<u16> + 'k
+{}
+
+/// Automaton which performs exponential smoothing on its inputs. Each step, 
+/// the output moves towards the input by the given fraction of the 
+/// difference between them. The first input is passed through as is. 
+/// 
+/// # Example
+/// ```
+/// use stackbt_automata_impl::automaton::Automaton;
+/// use stackbt_automata_impl::signal_filters::ExponentialSmoother;
+/// 
+/// let mut smoother = ExponentialSmoother::new(0.5_f64);
+/// assert_eq!(smoother.transition(&4.0), 4.0);
+/// assert_eq!(smoother.transition(&8.0), 6.0);
+/// assert_eq!(smoother.transition(&8.0), 7.0);
+/// ```
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct ExponentialSmoother<'k, T> where 
+    T: Copy + Add<Output=T> + Sub<Output=T> + Mul<Output=T> + 'k
+{
+    factor: T,
+    current: Option<T>,
+    _lifetime_check: PhantomData<&'k T>
+}
+
+impl<'k, T> ExponentialSmoother<'k, T> where 
+    T: Copy + Add<Output=T> + Sub<Output=T> + Mul<Output=T> + 'k
+{
+    /// Create a new exponential smoother with the given smoothing factor, 
+    /// which should be between 0 and 1. Larger factors follow the input 
+    /// more closely. 
+    pub fn new(factor: T) -> ExponentialSmoother<'k, T> {
+        ExponentialSmoother {
+            factor: factor,
+            current: Option::None,
+            _lifetime_check: PhantomData
+        }
+    }
+
+    /// Create a new exponential smoother which starts at the given value. 
+    pub fn starting_at(factor: T, init: T) -> ExponentialSmoother<'k, T> {
+        ExponentialSmoother {
+            factor: factor,
+            current: Option::Some(init),
+            _lifetime_check: PhantomData
+        }
+    }
+}
+
+impl<'k, T> Automaton<'k> for ExponentialSmoother<'k, T> where 
+    T: Copy + Add<Output=T> + Sub<Output=T> + Mul<Output=T> + 'k
+{
+    type Input = T;
+    type Action = T;
+
+    #[inline]
+    fn transition(&mut self, input: &T) -> T {
+        let new_val = match self.current {
+            Option::Some(val) => val + self.factor * (*input - val),
+            Option::None => *input
+        };
+        self.current = Option::Some(new_val);
+        new_val
+    }
+}
+
+impl<'k, T> FiniteStateAutomaton<'k> for ExponentialSmoother<'k, T> where 
+    T: Copy + Add<Output=T> + Sub<Output=T> + Mul<Output=T> + 'k
+{}
+
+/// Automaton which implements a Schmitt trigger. The output switches on 
+/// when the input rises above the high threshold, and only switches off 
+/// again when the input falls below the low threshold, so that inputs 
+/// hovering around a single threshold don't cause rapid flipping. 
+/// 
+/// # Example
+/// ```
+/// use stackbt_automata_impl::automaton::Automaton;
+/// use stackbt_automata_impl::signal_filters::SchmittTrigger;
+/// 
+/// let mut trigger = SchmittTrigger::new(1.0_f64, 3.0_f64);
+/// assert!(!trigger.transition(&2.0));
+/// assert!(trigger.transition(&4.0));
+/// assert!(trigger.transition(&2.0));
+/// assert!(!trigger.transition(&0.0));
+/// ```
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct SchmittTrigger<'k, T> where 
+    T: Copy + PartialOrd + 'k
+{
+    low: T,
+    high: T,
+    active: bool,
+    _lifetime_check: PhantomData<&'k T>
+}
+
+impl<'k, T> SchmittTrigger<'k, T> where 
+    T: Copy + PartialOrd + 'k
+{
+    /// Create a new Schmitt trigger with the given low and high thresholds, 
+    /// which starts off inactive. 
+    pub fn new(low: T, high: T) -> SchmittTrigger<'k, T> {
+        SchmittTrigger::with_state(low, high, false)
+    }
+
+    /// Create a new Schmitt trigger with the given thresholds and initial 
+    /// state. 
+    pub fn with_state(low: T, high: T, active: bool) -> SchmittTrigger<'k, T> {
+        assert!(low <= high, "Low threshold must not exceed high threshold");
+        SchmittTrigger {
+            low: low,
+            high: high,
+            active: active,
+            _lifetime_check: PhantomData
+        }
+    }
+}
+
+impl<'k, T> Automaton<'k> for SchmittTrigger<'k, T> where 
+    T: Copy + PartialOrd + 'k
+{
+    type Input = T;
+    type Action = bool;
+
+    #[inline]
+    fn transition(&mut self, input: &T) -> bool {
+        if self.active {
+            if *input < self.low {
+                self.active = false;
+            }
+        } else if *input > self.high {
+            self.active = true;
+        }
+        self.active
+    }
+}
+
+impl<'k, T> FiniteStateAutomaton<'k> for SchmittTrigger<'k, T> where 
+    T: Copy + PartialOrd + 'k
+{}
+
+impl<'k, T> Snapshot for MovingAverage<'k, T> where 
+    T: Copy + Add<Output=T> + Div<Output=T> + From<u16> + 'k
+{
+    type State = MovingAverage<'k, T>;
+
+    fn snapshot(&self) -> MovingAverage<'k, T> {
+        self.clone()
+    }
+
+    fn restore(state: MovingAverage<'k, T>) -> MovingAverage<'k, T> {
+        state
+    }
+}
+
+#[cfg(feature = "const_generics")]
+impl<'k, T, const K: usize> Snapshot for FixedMovingAverage<'k, T, K> where 
+    T: Copy + Add<Output=T> + Div<Output=T> + From<u16> + 'k
+{
+    type State = FixedMovingAverage<'k, T, K>;
+
+    fn snapshot(&self) -> FixedMovingAverage<'k, T, K> {
+        *self
+    }
+
+    fn restore(state: FixedMovingAverage<'k, T, K>) -> FixedMovingAverage<'k, T, K> {
+        state
+    }
+}
+
+impl<'k, T> Snapshot for ExponentialSmoother<'k, T> where 
+    T: Copy + Add<Output=T> + Sub<Output=T> + Mul<Output=T> + 'k
+{
+    type State = ExponentialSmoother<'k, T>;
+
+    fn snapshot(&self) -> ExponentialSmoother<'k, T> {
+        *self
+    }
+
+    fn restore(state: ExponentialSmoother<'k, T>) -> ExponentialSmoother<'k, T> {
+        state
+    }
+}
+
+impl<'k, T> Snapshot for SchmittTrigger<'k, T> where 
+    T: Copy + PartialOrd + 'k
+{
+    type State = SchmittTrigger<'k, T>;
+
+    fn snapshot(&self) -> SchmittTrigger<'k, T> {
+        *self
+    }
+
+    fn restore(state: SchmittTrigger<'k, T>) -> SchmittTrigger<'k, T> {
+        state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use automaton::Automaton;
+
+    #[test]
+    fn moving_average_test() {
+        use signal_filters::MovingAverage;
+        let mut average = MovingAverage::new(3);
+        assert_eq!(average.transition(&3.0_f32), 3.0);
+        assert_eq!(average.transition(&6.0_f32), 4.5);
+        assert_eq!(average.transition(&9.0_f32), 6.0);
+        assert_eq!(average.transition(&0.0_f32), 5.0);
+        assert_eq!(average.transition(&0.0_f32), 3.0);
+        assert_eq!(average.transition(&0.0_f32), 0.0);
+    }
+
+    #[cfg(feature = "const_generics")]
+    #[test]
+    fn fixed_moving_average_test() {
+        use automaton::FiniteStateAutomaton;
+        use signal_filters::FixedMovingAverage;
+        fn check_finite<'k, M: FiniteStateAutomaton<'k>>(_machine: &M) {}
+        let mut average = FixedMovingAverage::<f32, 3>::new();
+        check_finite(&average);
+        assert_eq!(average.transition(&3.0), 3.0);
+        assert_eq!(average.transition(&6.0), 4.5);
+        let mut copied = average;
+        assert_eq!(average.transition(&9.0), 6.0);
+        assert_eq!(average.transition(&0.0), 5.0);
+        assert_eq!(average.transition(&0.0), 3.0);
+        assert_eq!(average.transition(&0.0), 0.0);
+        assert_eq!(copied.transition(&0.0), 3.0);
+    }
+
+    #[test]
+    fn exponential_smoother_test() {
+        use signal_filters::ExponentialSmoother;
+        let mut smoother = ExponentialSmoother::starting_at(0.25_f64, 0.0);
+        assert_eq!(smoother.transition(&8.0), 2.0);
+        assert_eq!(smoother.transition(&8.0), 3.5);
+        assert_eq!(smoother.transition(&-2.5), 2.0);
+    }
+
+    #[test]
+    fn schmitt_trigger_test() {
+        use signal_filters::SchmittTrigger;
+        let mut trigger = SchmittTrigger::new(-1, 1);
+        assert!(!trigger.transition(&0));
+        assert!(!trigger.transition(&1));
+        assert!(trigger.transition(&2));
+        assert!(trigger.transition(&-1));
+        assert!(trigger.transition(&0));
+        assert!(!trigger.transition(&-2));
+        assert!(!trigger.transition(&1));
+    }
+
+    #[test]
+    fn filter_composition_test() {
+        use signal_filters::{ExponentialSmoother, SchmittTrigger};
+        let mut filtered = ExponentialSmoother::new(0.5_f64)
+            .then(SchmittTrigger::new(2.0, 5.0));
+        assert!(!filtered.transition(&0.0));
+        assert!(!filtered.transition(&8.0));
+        assert!(filtered.transition(&8.0));
+        assert!(filtered.transition(&0.0));
+        assert!(!filtered.transition(&0.0));
+    }
+}
@@ -0,0 +1,144 @@
+//!
+//! Saving a game, or rolling it back to resimulate it with late network 
+//! input, needs the state of every automaton to be captured and put back 
+//! later. The Snapshot trait does this without depending on any 
+//! serialization framework. Only the state that changes as an automaton runs 
+//! is captured, while its transition behavior is rebuilt from its type on 
+//! restore, so automata holding closures cannot be restored, and automata 
+//! made of other automata compose the snapshots of their parts. 
+//!
+
+/// Trait for automata and nodes whose state can be captured and restored. 
+///
+/// # Example 
+/// ```
+/// use stackbt_automata_impl::automaton::Automaton;
+/// use stackbt_automata_impl::internal_state_machine::{InternalTransition,
+///     InternalStateMachine};
+/// use stackbt_automata_impl::snapshot::Snapshot;
+///
+/// #[derive(Copy, Clone, Default)]
+/// struct Counter;
+///
+/// impl InternalTransition for Counter {
+///     type Input = i64;
+///     type Internal = i64;
+///     type Action = i64;
+///     fn step(&self, input: &i64, count: &mut i64) -> i64 {
+///         *count += *input;
+///         *count
+///     }
+/// }
+///
+/// let mut counter = InternalStateMachine::new(Counter, 0);
+/// counter.transition(&3);
+/// let saved = counter.snapshot();
+/// counter.transition(&4);
+/// let mut restored = InternalStateMachine::<Counter>::restore(saved);
+/// assert_eq!(restored.transition(&1), 4);
+/// ```
+pub trait Snapshot: Sized {
+    /// The type of the captured state. 
+    type State;
+
+    /// Capture the current state. 
+    fn snapshot(&self) -> Self::State;
+
+    /// Rebuild a value from a captured state. 
+    fn restore(state: Self::State) -> Self;
+}
+
+macro_rules! snapshot_tuple_impl {
+    ( $( $name:ident : $index:tt ),* ) => {
+        impl<$( $name ),*> Snapshot for ( $( $name , )* ) where
+            $( $name: Snapshot ),*
+        {
+            type State = ( $( $name::State , )* );
+
+            fn snapshot(&self) -> Self::State {
+                ( $( self.$index.snapshot() , )* )
+            }
+
+            fn restore(state: Self::State) -> Self {
+                ( $( $name::restore(state.$index) , )* )
+            }
+        }
+    }
+}
+
+// Tuples of automata, as run in parallel, snapshot to tuples of their states
+snapshot_tuple_impl!(A: 0, B: 1);
+snapshot_tuple_impl!(A: 0, B: 1, C: 2);
+snapshot_tuple_impl!(A: 0, B: 1, C: 2, D: 3);
+snapshot_tuple_impl!(A: 0, B: 1, C: 2, D: 3, E: 4);
+snapshot_tuple_impl!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5);
+snapshot_tuple_impl!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6);
+snapshot_tuple_impl!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7);
+snapshot_tuple_impl!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7, I: 8);
+snapshot_tuple_impl!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7, I: 8, J: 9);
+snapshot_tuple_impl!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7, I: 8, J: 9, K: 10);
+snapshot_tuple_impl!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7, I: 8, J: 9, K: 10, L: 11);
+
+#[cfg(test)]
+mod tests {
+    use automaton::Automaton;
+    use automata_combinators::MachineSeries;
+    use dual_state_machine::{DualTransition, DualStateMachine};
+    use ref_state_machine::{ReferenceTransition, RefStateMachine};
+    use snapshot::Snapshot;
+
+    #[derive(Copy, Clone, PartialEq, Debug)]
+    enum Toggle {
+        Off,
+        On
+    }
+
+    impl ReferenceTransition for Toggle {
+        type Input = bool;
+        type Action = bool;
+
+        fn step(self, input: &bool) -> (bool, Toggle) {
+            match (self, *input) {
+                (Toggle::Off, true) => (true, Toggle::On),
+                (Toggle::On, true) => (false, Toggle::Off),
+                (state, false) => (state == Toggle::On, state)
+            }
+        }
+    }
+
+    impl DualTransition for Toggle {
+        type Input = bool;
+        type Internal = u64;
+        type Action = u64;
+
+        fn step(self, input: &bool, flips: &mut u64) -> (u64, Toggle) {
+            let (_, next) = ReferenceTransition::step(self, input);
+            if next != self {
+                *flips += 1;
+            }
+            (*flips, next)
+        }
+    }
+
+    #[test]
+    fn snapshot_test() {
+        let mut pair = (
+            RefStateMachine::new(Toggle::Off),
+            DualStateMachine::new(Toggle::Off, 0)
+        );
+        assert_eq!(pair.transition(&true), (true, 1));
+        let saved = pair.snapshot();
+        assert_eq!(saved, (Toggle::On, (Toggle::On, 1)));
+        assert_eq!(pair.transition(&true), (false, 2));
+        let mut restored = <(RefStateMachine<Toggle>,
+            DualStateMachine<Toggle>)>::restore(saved);
+        assert_eq!(restored.transition(&false), (true, 1));
+
+        let mut series = MachineSeries::new(RefStateMachine::new(Toggle::Off),
+            DualStateMachine::new(Toggle::Off, 0));
+        assert_eq!(series.transition(&true), 1);
+        let mut restored = MachineSeries::<RefStateMachine<Toggle>,
+            DualStateMachine<Toggle>>::restore(series.snapshot());
+        assert_eq!(restored.transition(&false), 2);
+    }
+}
@@ -0,0 +1,241 @@
+//!
+//! Most agents start each tick by looking around: finding the other objects 
+//! near them, and checking what lies along the direction they are headed. 
+//! This module provides input providers which run these queries against an 
+//! ncollide2d collision world, packaging the data of the objects found 
+//! along with where they are relative to the querying agent. 
+//!
+//! The providers take the world and the position of the agent from the 
+//! source through closures, so that they can be used with whatever snapshot 
+//! of the world the input of an automaton is gathered from. 
+//!
+
+use input_provider::InputProvider;
+use nalgebra::{Point2, Vector2};
+use ncollide2d::bounding_volume::AABB;
+use ncollide2d::query::Ray;
+use ncollide2d::pipeline::{CollisionGroups, CollisionWorld};
+use std::f32;
+use std::marker::PhantomData;
+
+/// Object found by a neighborhood query. 
+#[derive(Clone, PartialEq, Debug)]
+pub struct Neighbor<T> {
+    /// The data attached to the collision object. 
+    pub data: T,
+    /// The position of the object relative to the querying agent. 
+    pub offset: Vector2<f32>,
+    /// The distance from the querying agent to the object. 
+    pub distance: f32
+}
+
+/// Input provider which finds the collision objects whose positions are 
+/// within a radius of the agent, nearest first. 
+pub struct NeighborhoodProvider<'k, S, T, W, P> where
+    S: 'k,
+    T: Clone + 'k,
+    W: Fn(&S) -> &CollisionWorld<f32, T> + 'k,
+    P: Fn(&S) -> Point2<f32> + 'k
+{
+    world: W,
+    origin: P,
+    radius: f32,
+    groups: CollisionGroups,
+    _junk: PhantomData<&'k (S, T)>
+}
+
+impl<'k, S, T, W, P> NeighborhoodProvider<'k, S, T, W, P> where
+    S: 'k,
+    T: Clone + 'k,
+    W: Fn(&S) -> &CollisionWorld<f32, T> + 'k,
+    P: Fn(&S) -> Point2<f32> + 'k
+{
+    /// Create a new neighborhood provider, which queries the objects in the 
+    /// collision groups. 
+    pub fn new(world: W, origin: P, radius: f32, groups: CollisionGroups) ->
+        NeighborhoodProvider<'k, S, T, W, P>
+    {
+        NeighborhoodProvider {
+            world: world,
+            origin: origin,
+            radius: radius,
+            groups: groups,
+            _junk: PhantomData
+        }
+    }
+}
+
+impl<'k, S, T, W, P> InputProvider<'k> for NeighborhoodProvider<'k, S, T, W, P> where
+    S: 'k,
+    T: Clone + 'k,
+    W: Fn(&S) -> &CollisionWorld<f32, T> + 'k,
+    P: Fn(&S) -> Point2<f32> + 'k
+{
+    type Source = S;
+    type Output = Box<[Neighbor<T>]>;
+
+    fn provide(&mut self, source: &S) -> Box<[Neighbor<T>]> {
+        let world = (self.world)(source);
+        let origin = (self.origin)(source);
+        let reach = Vector2::new(self.radius, self.radius);
+        let bounds = AABB::new(origin - reach, origin + reach);
+        let mut found = world.interferences_with_aabb(&bounds, &self.groups)
+            .filter_map(|(_, object)| {
+                let offset = object.position().translation.vector - origin.coords;
+                let distance = offset.norm();
+                if distance <= self.radius {
+                    Option::Some(Neighbor {
+                        data: object.data().clone(),
+                        offset: offset,
+                        distance: distance
+                    })
+                } else {
+                    Option::None
+                }
+            })
+            .collect::<Vec<_>>();
+        found.sort_by(|a, b| a.distance.partial_cmp(&b.distance)
+            .expect("Distances are never NaN"));
+        found.into_boxed_slice()
+    }
+}
+
+/// Object hit by a raycast query. 
+#[derive(Clone, PartialEq, Debug)]
+pub struct RayHit<T> {
+    /// The data attached to the collision object. 
+    pub data: T,
+    /// The distance along the ray to the hit, in multiples of the length of 
+    /// the ray direction. 
+    pub toi: f32
+}
+
+/// Input provider which casts a ray from the agent, finding the nearest 
+/// collision object the ray hits, if any. 
+pub struct RaycastProvider<'k, S, T, W, R> where
+    S: 'k,
+    T: Clone + 'k,
+    W: Fn(&S) -> &CollisionWorld<f32, T> + 'k,
+    R: Fn(&S) -> Ray<f32> + 'k
+{
+    world: W,
+    ray: R,
+    groups: CollisionGroups,
+    _junk: PhantomData<&'k (S, T)>
+}
+
+impl<'k, S, T, W, R> RaycastProvider<'k, S, T, W, R> where
+    S: 'k,
+    T: Clone + 'k,
+    W: Fn(&S) -> &CollisionWorld<f32, T> + 'k,
+    R: Fn(&S) -> Ray<f32> + 'k
+{
+    /// Create a new raycast provider, which casts the ray against the 
+    /// objects in the collision groups. 
+    pub fn new(world: W, ray: R, groups: CollisionGroups) ->
+        RaycastProvider<'k, S, T, W, R>
+    {
+        RaycastProvider {
+            world: world,
+            ray: ray,
+            groups: groups,
+            _junk: PhantomData
+        }
+    }
+}
+
+impl<'k, S, T, W, R> InputProvider<'k> for RaycastProvider<'k, S, T, W, R> where
+    S: 'k,
+    T: Clone + 'k,
+    W: Fn(&S) -> &CollisionWorld<f32, T> + 'k,
+    R: Fn(&S) -> Ray<f32> + 'k
+{
+    type Source = S;
+    type Output = Option<RayHit<T>>;
+
+    fn provide(&mut self, source: &S) -> Option<RayHit<T>> {
+        let world = (self.world)(source);
+        let ray = (self.ray)(source);
+        let mut nearest: Option<RayHit<T>> = Option::None;
+        let hits = world.interferences_with_ray(&ray, f32::MAX, &self.groups);
+        for (_, object, intersection) in hits {
+            let closer = match nearest {
+                Option::Some(ref hit) => intersection.toi < hit.toi,
+                Option::None => true
+            };
+            if closer {
+                nearest = Option::Some(RayHit {
+                    data: object.data().clone(),
+                    toi: intersection.toi
+                });
+            }
+        }
+        nearest
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use input_provider::InputProvider;
+    use nalgebra::{Isometry2, Point2, Vector2};
+    use ncollide2d::pipeline::{CollisionGroups, CollisionWorld, GeometricQueryType};
+    use ncollide2d::query::Ray;
+    use ncollide2d::shape::{Ball, ShapeHandle};
+
+    fn world_of(objects: &[(f32, f32, &'static str)]) -> CollisionWorld<f32, &'static str> {
+        let mut world = CollisionWorld::new(0.02);
+        for &(x, y, name) in objects {
+            world.add(
+                Isometry2::translation(x, y),
+                ShapeHandle::new(Ball::new(0.5)),
+                CollisionGroups::new(),
+                GeometricQueryType::Contacts(0.0, 0.0),
+                name
+            );
+        }
+        world.update();
+        world
+    }
+
+    #[test]
+    fn neighborhood_test() {
+        use spatial_query::NeighborhoodProvider;
+        let world = world_of(&[(3.0, 0.0, "near"), (0.0, -4.0, "far"), 
+            (9.0, 9.0, "away")]);
+        let mut provider = NeighborhoodProvider::new(
+            |w: &CollisionWorld<f32, &'static str>| w, 
+            |_w: &CollisionWorld<f32, &'static str>| Point2::new(0.0, 0.0), 
+            5.0, 
+            CollisionGroups::new()
+        );
+        let found = provider.provide(&world);
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].data, "near");
+        assert_eq!(found[0].offset, Vector2::new(3.0, 0.0));
+        assert_eq!(found[1].data, "far");
+        assert_eq!(found[1].distance, 4.0);
+    }
+
+    #[test]
+    fn raycast_test() {
+        use spatial_query::RaycastProvider;
+        let world = world_of(&[(4.0, 0.0, "behind"), (2.0, 0.0, "ahead"), 
+            (0.0, 3.0, "aside")]);
+        let mut provider = RaycastProvider::new(
+            |w: &CollisionWorld<f32, &'static str>| w, 
+            |_w: &CollisionWorld<f32, &'static str>| Ray::new(Point2::new(0.0, 0.0), 
+                Vector2::new(1.0, 0.0)), 
+            CollisionGroups::new()
+        );
+        let hit = provider.provide(&world).unwrap();
+        assert_eq!(hit.data, "ahead");
+        assert!((hit.toi - 1.5).abs() < 1e-5);
+        let mut blind = RaycastProvider::new(
+            |w: &CollisionWorld<f32, &'static str>| w, 
+            |_w: &CollisionWorld<f32, &'static str>| Ray::new(Point2::new(0.0, 0.0), 
+                Vector2::new(0.0, -1.0)), 
+            CollisionGroups::new()
+        );
+        assert_eq!(blind.provide(&world), Option::None);
+    }
+}
@@ -0,0 +1,263 @@
+//! Statecharts, with entry and exit actions and guarded transitions. 
+//!
+//! Setting up and tearing down after a state, such as starting an animation 
+//! or releasing a claimed resource, ends up repeated in every transition 
+//! into or out of the state when written into the transition function of a 
+//! plain state machine. A statechart lists its transitions as a table of 
+//! guarded transitions between states instead, and runs the exit action of 
+//! the state left and the entry action of the state entered whenever one of 
+//! them is taken, so each state sets itself up and tears itself down in one 
+//! place. 
+
+use automaton::{Automaton, FiniteStateAutomaton};
+use std::marker::PhantomData;
+
+/// Transition between two states of a statechart, which is only taken if 
+/// its guard returns true for the input and internal state. 
+pub struct GuardedTransition<S, I, N> {
+    /// The state the transition is taken from. 
+    pub from: S,
+    /// The state the transition leads to. 
+    pub to: S,
+    /// The guard of the transition. 
+    pub guard: fn(&I, &N) -> bool
+}
+
+impl<S, I, N> Clone for GuardedTransition<S, I, N> where
+    S: Clone
+{
+    fn clone(&self) -> Self {
+        GuardedTransition {
+            from: self.from.clone(),
+            to: self.to.clone(),
+            guard: self.guard
+        }
+    }
+}
+
+impl<S, I, N> Copy for GuardedTransition<S, I, N> where
+    S: Copy
+{}
+
+/// Transition trait for Statechart. 
+pub trait StatechartTransition {
+    /// The input type taken by the statechart. 
+    type Input;
+    /// The type of the states of the statechart. 
+    type State: Clone + PartialEq;
+    /// The type of the internal state shared by all states. 
+    type Internal;
+    /// The action type returned by the statechart. 
+    type Action;
+
+    /// Return the guarded transitions of the statechart. Of the transitions 
+    /// from the current state, the first one whose guard passes is taken. 
+    fn transitions(&self) -> &[GuardedTransition<Self::State, Self::Input,
+        Self::Internal>];
+
+    /// Run the entry action of the given state. Does nothing by default. 
+    fn on_enter(&self, _state: &Self::State, _internal: &mut Self::Internal) {}
+
+    /// Run the exit action of the given state. Does nothing by default. 
+    fn on_exit(&self, _state: &Self::State, _internal: &mut Self::Internal) {}
+
+    /// Given the current state and references to the input and internal 
+    /// state, return the action to return. 
+    fn step(&self, state: &Self::State, input: &Self::Input,
+        internal: &mut Self::Internal) -> Self::Action;
+}
+
+/// State machine which takes guarded transitions between its states, 
+/// running exit and entry actions as it does so. Each step, the first 
+/// transition from the current state whose guard passes is taken, running 
+/// the exit action of the current state and then the entry action of the 
+/// next one, even if they are the same state. The statechart then steps in 
+/// whichever state it ended up in. 
+/// 
+/// # Example
+/// ```
+/// use stackbt_automata_impl::automaton::Automaton;
+/// use stackbt_automata_impl::statechart::{GuardedTransition, Statechart,
+///     StatechartTransition};
+/// 
+/// #[derive(Copy, Clone, PartialEq, Debug)]
+/// enum Door {
+///     Closed,
+///     Open
+/// }
+/// 
+/// static DOOR_TABLE: [GuardedTransition<Door, bool, i64>; 2] = [
+///     GuardedTransition { from: Door::Closed, to: Door::Open, guard: |push, _| *push },
+///     GuardedTransition { from: Door::Open, to: Door::Closed, guard: |push, _| !*push }
+/// ];
+/// 
+/// struct DoorChart;
+/// 
+/// impl StatechartTransition for DoorChart {
+///     type Input = bool;
+///     type State = Door;
+///     type Internal = i64;
+///     type Action = i64;
+/// 
+///     fn transitions(&self) -> &[GuardedTransition<Door, bool, i64>] {
+///         &DOOR_TABLE
+///     }
+/// 
+///     fn on_enter(&self, state: &Door, swings: &mut i64) {
+///         if *state == Door::Open {
+///             *swings += 1;
+///         }
+///     }
+/// 
+///     fn step(&self, _state: &Door, _push: &bool, swings: &mut i64) -> i64 {
+///         *swings
+///     }
+/// }
+/// 
+/// let mut door = Statechart::new(DoorChart, Door::Closed, 0);
+/// assert_eq!(door.transition(&true), 1);
+/// assert_eq!(door.transition(&true), 1);
+/// assert_eq!(door.transition(&false), 1);
+/// assert_eq!(*door.state(), Door::Closed);
+/// assert_eq!(door.transition(&true), 2);
+/// ```
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Statechart<'k, C> where
+    C: StatechartTransition + 'k
+{
+    chart: C,
+    state: C::State,
+    internal: C::Internal,
+    _lifetime_check: PhantomData<&'k C>
+}
+
+impl<'k, C> Statechart<'k, C> where
+    C: StatechartTransition + 'k
+{
+    /// Create a new statechart in the given state, running its entry 
+    /// action. 
+    pub fn new(chart: C, init_state: C::State, internal: C::Internal) -> Statechart<'k, C> {
+        let mut internal = internal;
+        chart.on_enter(&init_state, &mut internal);
+        Statechart {
+            chart: chart,
+            state: init_state,
+            internal: internal,
+            _lifetime_check: PhantomData
+        }
+    }
+
+    /// Return the current state of the statechart. 
+    pub fn state(&self) -> &C::State {
+        &self.state
+    }
+
+    /// Return the internal state shared by all states. 
+    pub fn internal(&self) -> &C::Internal {
+        &self.internal
+    }
+}
+
+impl<'k, C> Automaton<'k> for Statechart<'k, C> where
+    C: StatechartTransition + 'k
+{
+    type Input = C::Input;
+    type Action = C::Action;
+
+    #[inline]
+    fn transition(&mut self, input: &C::Input) -> C::Action {
+        let next = self.chart.transitions()
+            .iter()
+            .find(|trans| trans.from == self.state && (trans.guard)(input, &self.internal))
+            .map(|trans| trans.to.clone());
+        if let Option::Some(next) = next {
+            self.chart.on_exit(&self.state, &mut self.internal);
+            self.state = next;
+            self.chart.on_enter(&self.state, &mut self.internal);
+        }
+        self.chart.step(&self.state, input, &mut self.internal)
+    }
+}
+
+impl<'k, C> FiniteStateAutomaton<'k> for Statechart<'k, C> where
+    C: StatechartTransition + Copy + 'k,
+    C::State: Copy,
+    C::Internal: Copy
+{}
+
+#[cfg(test)]
+mod tests {
+    use automaton::Automaton;
+    use statechart::{GuardedTransition, Statechart, StatechartTransition};
+
+    #[derive(Copy, Clone, PartialEq, Debug)]
+    enum Guard {
+        Idle,
+        Chase,
+        Search
+    }
+
+    // Inputs are the distance to the intruder, or a negative number if it
+    // is out of sight
+    static GUARD_TABLE: [GuardedTransition<Guard, i64, Vec<&'static str>>; 4] = [
+        GuardedTransition { from: Guard::Idle, to: Guard::Chase, guard: |dist, _| *dist >= 0 },
+        GuardedTransition { from: Guard::Chase, to: Guard::Search, guard: |dist, _| *dist < 0 },
+        GuardedTransition { from: Guard::Search, to: Guard::Chase, guard: |dist, _| *dist >= 0 },
+        GuardedTransition { from: Guard::Search, to: Guard::Idle, guard: |_, log| log.len() > 6 }
+    ];
+
+    struct GuardChart;
+
+    impl StatechartTransition for GuardChart {
+        type Input = i64;
+        type State = Guard;
+        type Internal = Vec<&'static str>;
+        type Action = Guard;
+
+        fn transitions(&self) -> &[GuardedTransition<Guard, i64, Vec<&'static str>>] {
+            &GUARD_TABLE
+        }
+
+        fn on_enter(&self, state: &Guard, log: &mut Vec<&'static str>) {
+            if *state == Guard::Chase {
+                log.push("shout");
+            }
+        }
+
+        fn on_exit(&self, state: &Guard, log: &mut Vec<&'static str>) {
+            if *state == Guard::Chase {
+                log.push("pant");
+            }
+        }
+
+        fn step(&self, state: &Guard, _dist: &i64, _log: &mut Vec<&'static str>) -> Guard {
+            *state
+        }
+    }
+
+    #[test]
+    fn entry_exit_test() {
+        let mut chart = Statechart::new(GuardChart, Guard::Idle, Vec::new());
+        assert_eq!(chart.transition(&-1), Guard::Idle);
+        assert_eq!(chart.transition(&5), Guard::Chase);
+        assert_eq!(chart.transition(&3), Guard::Chase);
+        assert_eq!(chart.transition(&-1), Guard::Search);
+        assert_eq!(*chart.internal(), vec!["shout", "pant"]);
+        assert_eq!(chart.transition(&2), Guard::Chase);
+        assert_eq!(chart.transition(&-1), Guard::Search);
+        assert_eq!(chart.internal().len(), 4);
+    }
+
+    #[test]
+    fn guard_internal_test() {
+        let mut chart = Statechart::new(GuardChart, Guard::Chase, Vec::new());
+        assert_eq!(*chart.internal(), vec!["shout"]);
+        for _ in 0..3 {
+            assert_eq!(chart.transition(&-1), Guard::Search);
+            assert_eq!(chart.transition(&1), Guard::Chase);
+        }
+        assert_eq!(chart.transition(&-1), Guard::Search);
+        assert_eq!(chart.transition(&-1), Guard::Idle);
+        assert_eq!(chart.transition(&1), Guard::Chase);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,466 @@
+//!
+//! Steering behaviors are a classic means of producing lifelike movement for 
+//! autonomous agents. Each behavior here is an automaton which takes a 
+//! simple description of the agent's kinematic state (and that of its 
+//! target or neighbors) and returns a steering vector, which is the change 
+//! in velocity the agent desires. These can then be composed, such as by 
+//! running several alongside each other and summing their steering vectors 
+//! with a StatelessMapper, to produce flocking and other compound movement. 
+//!
+
+use automaton::{Automaton, FiniteStateAutomaton};
+use std::ops::{Add, Sub, Mul, Neg};
+
+/// A simple two-dimensional vector. 
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct Vector2 {
+    /// The horizontal component. 
+    pub x: f32,
+    /// The vertical component. 
+    pub y: f32
+}
+
+impl Vector2 {
+    /// Create a new vector from its components. 
+    pub fn new(x: f32, y: f32) -> Vector2 {
+        Vector2 {
+            x: x,
+            y: y
+        }
+    }
+
+    /// Create a unit vector pointing at the given angle, in radians. 
+    pub fn from_angle(angle: f32) -> Vector2 {
+        let (sin, cos) = angle.sin_cos();
+        Vector2::new(cos, sin)
+    }
+
+    /// Get the length of the vector. 
+    pub fn length(self) -> f32 {
+        (self.x * self.x + self.y * self.y).sqrt()
+    }
+
+    /// Get a vector pointing in the same direction with unit length, or 
+    /// the zero vector if this vector is the zero vector. 
+    pub fn normalize(self) -> Vector2 {
+        let len = self.length();
+        if len > 0.0 {
+            self * (1.0 / len)
+        } else {
+            Vector2::default()
+        }
+    }
+
+    /// Get a vector pointing in the same direction whose length is at most 
+    /// the given length. 
+    pub fn truncate(self, max_length: f32) -> Vector2 {
+        if self.length() > max_length {
+            self.normalize() * max_length
+        } else {
+            self
+        }
+    }
+}
+
+impl Add for Vector2 {
+    type Output = Vector2;
+    fn add(self, other: Vector2) -> Vector2 {
+        Vector2::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub for Vector2 {
+    type Output = Vector2;
+    fn sub(self, other: Vector2) -> Vector2 {
+        Vector2::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Mul<f32> for Vector2 {
+    type Output = Vector2;
+    fn mul(self, scale: f32) -> Vector2 {
+        Vector2::new(self.x * scale, self.y * scale)
+    }
+}
+
+impl Neg for Vector2 {
+    type Output = Vector2;
+    fn neg(self) -> Vector2 {
+        Vector2::new(-self.x, -self.y)
+    }
+}
+
+/// The kinematic state of an agent. 
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct Kinematic {
+    /// The position of the agent. 
+    pub position: Vector2,
+    /// The velocity of the agent. 
+    pub velocity: Vector2
+}
+
+/// Input for steering behaviors which steer an agent relative to a target 
+/// point. 
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct TargetInput {
+    /// The agent being steered. 
+    pub agent: Kinematic,
+    /// The target point. 
+    pub target: Vector2
+}
+
+/// Input for steering behaviors which steer an agent relative to its 
+/// neighbors. 
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct NeighborInput {
+    /// The agent being steered. 
+    pub agent: Kinematic,
+    /// The neighbors of the agent. 
+    pub neighbors: Vec<Kinematic>
+}
+
+/// Steering behavior which heads straight for the target at full speed. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Seek {
+    max_speed: f32
+}
+
+impl Seek {
+    /// Create a new seek behavior with the given maximum speed. 
+    pub fn new(max_speed: f32) -> Seek {
+        Seek {
+            max_speed: max_speed
+        }
+    }
+}
+
+impl<'k> Automaton<'k> for Seek {
+    type Input = TargetInput;
+    type Action = Vector2;
+
+    #[inline]
+    fn transition(&mut self, input: &TargetInput) -> Vector2 {
+        let desired = (input.target - input.agent.position).normalize() 
+            * self.max_speed;
+        desired - input.agent.velocity
+    }
+}
+
+impl<'k> FiniteStateAutomaton<'k> for Seek {}
+
+/// Steering behavior which heads straight away from the target at full 
+/// speed. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Flee {
+    max_speed: f32
+}
+
+impl Flee {
+    /// Create a new flee behavior with the given maximum speed. 
+    pub fn new(max_speed: f32) -> Flee {
+        Flee {
+            max_speed: max_speed
+        }
+    }
+}
+
+impl<'k> Automaton<'k> for Flee {
+    type Input = TargetInput;
+    type Action = Vector2;
+
+    #[inline]
+    fn transition(&mut self, input: &TargetInput) -> Vector2 {
+        let desired = (input.agent.position - input.target).normalize() 
+            * self.max_speed;
+        desired - input.agent.velocity
+    }
+}
+
+impl<'k> FiniteStateAutomaton<'k> for Flee {}
+
+/// Steering behavior which heads for the target, slowing down once within 
+/// the slowing radius so as to come to a stop at the target. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Arrive {
+    max_speed: f32,
+    slowing_radius: f32
+}
+
+impl Arrive {
+    /// Create a new arrive behavior with the given maximum speed and 
+    /// slowing radius. 
+    pub fn new(max_speed: f32, slowing_radius: f32) -> Arrive {
+        Arrive {
+            max_speed: max_speed,
+            slowing_radius: slowing_radius
+        }
+    }
+}
+
+impl<'k> Automaton<'k> for Arrive {
+    type Input = TargetInput;
+    type Action = Vector2;
+
+    #[inline]
+    fn transition(&mut self, input: &TargetInput) -> Vector2 {
+        let offset = input.target - input.agent.position;
+        let distance = offset.length();
+        let speed = if distance < self.slowing_radius {
+            self.max_speed * distance / self.slowing_radius
+        } else {
+            self.max_speed
+        };
+        offset.normalize() * speed - input.agent.velocity
+    }
+}
+
+impl<'k> FiniteStateAutomaton<'k> for Arrive {}
+
+/// Steering behavior which meanders about, by steering towards a point on 
+/// a circle projected in front of the agent, which is jittered a little 
+/// each step. The jitter is generated from an internal pseudorandom number
+/// generator, so that wandering is deterministic for a given seed. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Wander {
+    distance: f32,
+    radius: f32,
+    jitter: f32,
+    angle: f32,
+    seed: u32
+}
+
+impl Wander {
+    /// Create a new wander behavior, which projects a circle with the given 
+    /// radius the given distance in front of the agent, and perturbs the 
+    /// steering angle on that circle by at most the given jitter, in 
+    /// radians, each step. 
+    pub fn new(distance: f32, radius: f32, jitter: f32, seed: u32) -> Wander {
+        Wander {
+            distance: distance,
+            radius: radius,
+            jitter: jitter,
+            angle: 0.0,
+            // Xorshift gets stuck at zero
+            seed: if seed == 0 { 0x9E37_79B9 } else { seed }
+        }
+    }
+
+    fn next_unit(&mut self) -> f32 {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 17;
+        self.seed ^= self.seed << 5;
+        (self.seed as f32 / u32::MAX as f32) * 2.0 - 1.0
+    }
+}
+
+impl<'k> Automaton<'k> for Wander {
+    type Input = Kinematic;
+    type Action = Vector2;
+
+    #[inline]
+    fn transition(&mut self, input: &Kinematic) -> Vector2 {
+        self.angle += self.next_unit() * self.jitter;
+        let heading = input.velocity.normalize();
+        heading * self.distance + Vector2::from_angle(self.angle) * self.radius
+    }
+}
+
+impl<'k> FiniteStateAutomaton<'k> for Wander {}
+
+/// Steering behavior which steers away from neighbors within the given 
+/// radius, more strongly the closer they are. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Separation {
+    radius: f32
+}
+
+impl Separation {
+    /// Create a new separation behavior with the given radius. 
+    pub fn new(radius: f32) -> Separation {
+        Separation {
+            radius: radius
+        }
+    }
+}
+
+impl<'k> Automaton<'k> for Separation {
+    type Input = NeighborInput;
+    type Action = Vector2;
+
+    #[inline]
+    fn transition(&mut self, input: &NeighborInput) -> Vector2 {
+        input.neighbors.iter().fold(Vector2::default(), |acc, other| {
+            let offset = input.agent.position - other.position;
+            let distance = offset.length();
+            if distance > 0.0 && distance < self.radius {
+                acc + offset.normalize() * ((self.radius - distance) / self.radius)
+            } else {
+                acc
+            }
+        })
+    }
+}
+
+impl<'k> FiniteStateAutomaton<'k> for Separation {}
+
+/// Steering behavior which steers towards the center of mass of the 
+/// neighbors. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Cohesion {
+    max_speed: f32
+}
+
+impl Cohesion {
+    /// Create a new cohesion behavior with the given maximum speed. 
+    pub fn new(max_speed: f32) -> Cohesion {
+        Cohesion {
+            max_speed: max_speed
+        }
+    }
+}
+
+impl<'k> Automaton<'k> for Cohesion {
+    type Input = NeighborInput;
+    type Action = Vector2;
+
+    #[inline]
+    fn transition(&mut self, input: &NeighborInput) -> Vector2 {
+        if input.neighbors.is_empty() {
+            return Vector2::default();
+        }
+        let sum = input.neighbors.iter().fold(Vector2::default(), |acc, other| {
+            acc + other.position
+        });
+        let center = sum * (1.0 / input.neighbors.len() as f32);
+        Seek::new(self.max_speed).transition(&TargetInput {
+            agent: input.agent,
+            target: center
+        })
+    }
+}
+
+impl<'k> FiniteStateAutomaton<'k> for Cohesion {}
+
+/// Steering behavior which steers towards the average velocity of the 
+/// neighbors. 
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct Alignment;
+
+impl Alignment {
+    /// Create a new alignment behavior. 
+    pub fn new() -> Alignment {
+        Alignment
+    }
+}
+
+impl<'k> Automaton<'k> for Alignment {
+    type Input = NeighborInput;
+    type Action = Vector2;
+
+    #[inline]
+    fn transition(&mut self, input: &NeighborInput) -> Vector2 {
+        if input.neighbors.is_empty() {
+            return Vector2::default();
+        }
+        let sum = input.neighbors.iter().fold(Vector2::default(), |acc, other| {
+            acc + other.velocity
+        });
+        sum * (1.0 / input.neighbors.len() as f32) - input.agent.velocity
+    }
+}
+
+impl<'k> FiniteStateAutomaton<'k> for Alignment {}
+
+#[cfg(test)]
+mod tests {
+    use automaton::Automaton;
+    use steering::{Kinematic, NeighborInput, TargetInput, Vector2};
+
+    fn at(x: f32, y: f32) -> Kinematic {
+        Kinematic {
+            position: Vector2::new(x, y),
+            velocity: Vector2::default()
+        }
+    }
+
+    #[test]
+    fn seek_flee_test() {
+        use steering::{Seek, Flee};
+        let input = TargetInput {
+            agent: at(0.0, 0.0),
+            target: Vector2::new(10.0, 0.0)
+        };
+        assert_eq!(Seek::new(2.0).transition(&input), Vector2::new(2.0, 0.0));
+        assert_eq!(Flee::new(2.0).transition(&input), Vector2::new(-2.0, 0.0));
+    }
+
+    #[test]
+    fn arrive_test() {
+        use steering::Arrive;
+        let mut arrive = Arrive::new(4.0, 8.0);
+        let far = TargetInput {
+            agent: at(0.0, 0.0),
+            target: Vector2::new(0.0, 20.0)
+        };
+        assert_eq!(arrive.transition(&far), Vector2::new(0.0, 4.0));
+        let near = TargetInput {
+            agent: at(0.0, 0.0),
+            target: Vector2::new(0.0, 2.0)
+        };
+        assert_eq!(arrive.transition(&near), Vector2::new(0.0, 1.0));
+    }
+
+    #[test]
+    fn wander_test() {
+        use steering::Wander;
+        let agent = Kinematic {
+            position: Vector2::default(),
+            velocity: Vector2::new(1.0, 0.0)
+        };
+        let mut wander = Wander::new(2.0, 1.0, 0.5, 7);
+        let mut replay = wander;
+        for _ in 0..16 {
+            let steer = wander.transition(&agent);
+            assert_eq!(steer, replay.transition(&agent));
+            assert!((steer - Vector2::new(2.0, 0.0)).length() <= 1.0001);
+        }
+    }
+
+    #[test]
+    fn flocking_test() {
+        use steering::{Separation, Cohesion, Alignment};
+        let input = NeighborInput {
+            agent: at(0.0, 0.0),
+            neighbors: vec![
+                Kinematic {
+                    position: Vector2::new(1.0, 0.0),
+                    velocity: Vector2::new(0.0, 2.0)
+                },
+                Kinematic {
+                    position: Vector2::new(3.0, 0.0),
+                    velocity: Vector2::new(0.0, 4.0)
+                }
+            ]
+        };
+        let separate = Separation::new(2.0).transition(&input);
+        assert_eq!(separate, Vector2::new(-0.5, 0.0));
+        let cohere = Cohesion::new(1.0).transition(&input);
+        assert_eq!(cohere, Vector2::new(1.0, 0.0));
+        let align = Alignment::new().transition(&input);
+        assert_eq!(align, Vector2::new(0.0, 3.0));
+    }
+
+    #[test]
+    fn combined_steering_test() {
+        use steering::{Seek, Flee};
+        use stateless_mapper::StatelessMapper;
+        let mut combined = Seek::new(1.0)
+            .alongside(Flee::new(0.5))
+            .then(StatelessMapper::new(|&(a, b): &(Vector2, Vector2)| a + b));
+        let input = TargetInput {
+            agent: at(0.0, 0.0),
+            target: Vector2::new(0.0, -3.0)
+        };
+        assert_eq!(combined.transition(&input), Vector2::new(0.0, -0.5));
+    }
+}
@@ -0,0 +1,306 @@
+use automaton::{Automaton, FiniteStateAutomaton};
+use pushdown_automaton::PushdownStatus;
+use snapshot::Snapshot;
+use std::marker::PhantomData;
+
+/// Nonterminal transition for the two-stack automaton. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum TwoStackTransition<A, N> {
+    /// Push a new frame onto the active stack. 
+    Push(A, N),
+    /// Keep the frames on both stacks as is. 
+    Stay(A),
+    /// Remove the topmost frame from the active stack. 
+    Pop(A),
+    /// Move the topmost frame of the active stack onto the shelved stack, 
+    /// handing control to the frame below it. 
+    Shelve(A),
+    /// Move the topmost frame of the shelved stack onto the active stack, 
+    /// handing control to it. Keeps the frames as is if the shelved stack 
+    /// is empty. 
+    Unshelve(A)
+}
+
+/// Terminal transition for the two-stack automaton. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum TwoStackTerminal<A, N> {
+    /// Push a new frame onto the active stack. 
+    Push(A, N),
+    /// Keep the frames on both stacks as is. 
+    Stay(A),
+    /// Move the topmost frame of the shelved stack onto the active stack, 
+    /// handing control to it. Keeps the frames as is if the shelved stack 
+    /// is empty. 
+    Unshelve(A),
+    /// Signal that the two-stack automaton as a whole has completed. 
+    Finish(A)
+}
+
+/// Implementation of a two-stack automaton which builds upon existing state 
+/// machines. Like the pushdown automaton, the topmost frame of the active 
+/// stack is transitioned, with a bottom machine beneath it, but frames can 
+/// also be set aside onto a second, shelved stack, and taken back from it 
+/// later. Useful for agents which interrupt what they are doing, and 
+/// later pick up the interrupted tasks again in the reverse order. 
+/// 
+/// # Example
+/// ```
+/// use stackbt_automata_impl::automaton::Automaton;
+/// use stackbt_automata_impl::internal_state_machine::{InternalStateMachine,
+///     InternalTransition};
+/// use stackbt_automata_impl::stateless_mapper::StatelessMapper;
+/// use stackbt_automata_impl::two_stack_automaton::{TwoStackAutomaton,
+///     TwoStackTerminal, TwoStackTransition};
+/// 
+/// #[derive(Copy, Clone)]
+/// struct Task;
+/// 
+/// type TaskFrame = InternalStateMachine<'static, Task>;
+/// 
+/// impl InternalTransition for Task {
+///     type Input = bool;
+///     type Internal = i64;
+///     type Action = TwoStackTransition<i64, TaskFrame>;
+///     fn step(&self, interrupt: &bool, id: &mut i64) -> TwoStackTransition<i64, TaskFrame> {
+///         if *interrupt {
+///             TwoStackTransition::Shelve(*id)
+///         } else {
+///             TwoStackTransition::Stay(*id)
+///         }
+///     }
+/// }
+/// 
+/// let mut agent = TwoStackAutomaton::new(StatelessMapper::new(|_: &bool| {
+///     TwoStackTerminal::<i64, TaskFrame>::Unshelve(0)
+/// }));
+/// agent.push(InternalStateMachine::new(Task, 1));
+/// assert_eq!(agent.transition(&false), 1);
+/// assert_eq!(agent.transition(&true), 1);
+/// assert_eq!(agent.shelved().len(), 1);
+/// assert_eq!(agent.transition(&false), 0);
+/// assert_eq!(agent.transition(&false), 1);
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct TwoStackAutomaton<'k, I, A, N, T> where
+    I: 'k,
+    N: FiniteStateAutomaton<'k, Input=I, Action=TwoStackTransition<A, N>> + 'k,
+    T: FiniteStateAutomaton<'k, Input=I, Action=TwoStackTerminal<A, N>> + 'k
+{
+    bottom: T,
+    active: Vec<N>,
+    shelved: Vec<N>,
+    _i_exists: PhantomData<&'k I>,
+    _a_exists: PhantomData<A>
+}
+
+impl<'k, I, A, N, T> TwoStackAutomaton<'k, I, A, N, T> where
+    I: 'k,
+    N: FiniteStateAutomaton<'k, Input=I, Action=TwoStackTransition<A, N>> + 'k,
+    T: FiniteStateAutomaton<'k, Input=I, Action=TwoStackTerminal<A, N>> + 'k
+{
+    /// Create a new two-stack automaton with both stacks empty. 
+    pub fn new(terminal: T) -> TwoStackAutomaton<'k, I, A, N, T> {
+        TwoStackAutomaton {
+            bottom: terminal,
+            active: Vec::new(),
+            shelved: Vec::new(),
+            _i_exists: PhantomData,
+            _a_exists: PhantomData
+        }
+    }
+
+    /// Push a frame onto the active stack from outside the automaton. 
+    pub fn push(&mut self, frame: N) {
+        self.active.push(frame);
+    }
+
+    /// Return the frames of the active stack, from the bottom to the top. 
+    pub fn active(&self) -> &[N] {
+        &self.active
+    }
+
+    /// Return the frames of the shelved stack, from the bottom to the top. 
+    pub fn shelved(&self) -> &[N] {
+        &self.shelved
+    }
+
+    // Move the topmost shelved frame, if there is one, onto the active stack
+    fn unshelve(&mut self) {
+        if let Option::Some(frame) = self.shelved.pop() {
+            self.active.push(frame);
+        }
+    }
+
+    /// Transition the two-stack automaton, returning the action along with 
+    /// whether the bottom machine finished the two-stack automaton. Once 
+    /// finished, the two-stack automaton may still be transitioned, in which 
+    /// case it continues from the bottom machine as it was left. 
+    pub fn transition_status(&mut self, input: &I) -> PushdownStatus<A> {
+        match self.active.pop() {
+            Option::Some(mut val) => {
+                match val.transition(input) {
+                    TwoStackTransition::Push(act, new) => {
+                        self.active.push(val);
+                        self.active.push(new);
+                        PushdownStatus::Running(act)
+                    },
+                    TwoStackTransition::Stay(act) => {
+                        self.active.push(val);
+                        PushdownStatus::Running(act)
+                    },
+                    TwoStackTransition::Pop(act) => PushdownStatus::Running(act),
+                    TwoStackTransition::Shelve(act) => {
+                        self.shelved.push(val);
+                        PushdownStatus::Running(act)
+                    },
+                    TwoStackTransition::Unshelve(act) => {
+                        self.active.push(val);
+                        self.unshelve();
+                        PushdownStatus::Running(act)
+                    }
+                }
+            },
+            Option::None => {
+                match self.bottom.transition(input) {
+                    TwoStackTerminal::Push(act, new) => {
+                        self.active.push(new);
+                        PushdownStatus::Running(act)
+                    },
+                    TwoStackTerminal::Stay(act) => PushdownStatus::Running(act),
+                    TwoStackTerminal::Unshelve(act) => {
+                        self.unshelve();
+                        PushdownStatus::Running(act)
+                    },
+                    TwoStackTerminal::Finish(act) => PushdownStatus::Finished(act)
+                }
+            }
+        }
+    }
+}
+
+impl<'k, I, A, N, T> Automaton<'k> for TwoStackAutomaton<'k, I, A, N, T> where
+    I: 'k,
+    N: FiniteStateAutomaton<'k, Input=I, Action=TwoStackTransition<A, N>> + 'k,
+    T: FiniteStateAutomaton<'k, Input=I, Action=TwoStackTerminal<A, N>> + 'k
+{
+    type Input = I;
+    type Action = A;
+    #[inline]
+    fn transition(&mut self, input: &I) -> A {
+        self.transition_status(input).into_action()
+    }
+}
+
+impl<'k, I, A, N, T> Snapshot for TwoStackAutomaton<'k, I, A, N, T> where
+    I: 'k,
+    N: FiniteStateAutomaton<'k, Input=I, Action=TwoStackTransition<A, N>> + Snapshot + 'k,
+    T: FiniteStateAutomaton<'k, Input=I, Action=TwoStackTerminal<A, N>> + Snapshot + 'k
+{
+    type State = (T::State, Vec<N::State>, Vec<N::State>);
+
+    fn snapshot(&self) -> Self::State {
+        (
+            self.bottom.snapshot(),
+            self.active.iter().map(|machine| machine.snapshot()).collect(),
+            self.shelved.iter().map(|machine| machine.snapshot()).collect()
+        )
+    }
+
+    fn restore(state: Self::State) -> Self {
+        TwoStackAutomaton {
+            bottom: T::restore(state.0),
+            active: state.1.into_iter().map(N::restore).collect(),
+            shelved: state.2.into_iter().map(N::restore).collect(),
+            _i_exists: PhantomData,
+            _a_exists: PhantomData
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use automaton::Automaton;
+    use internal_state_machine::{InternalTransition, InternalStateMachine};
+    use pushdown_automaton::PushdownStatus;
+    use two_stack_automaton::{TwoStackAutomaton, TwoStackTerminal, TwoStackTransition};
+
+    #[derive(Copy, Clone)]
+    struct TerminalFunction;
+    #[derive(Copy, Clone)]
+    struct NonterminalFunction;
+
+    type Frame = InternalStateMachine<'static, NonterminalFunction>;
+
+    impl InternalTransition for TerminalFunction {
+        type Internal = ();
+        type Input = i64;
+        type Action = TwoStackTerminal<i64, Frame>;
+        fn step (&self, new: &i64, _internal: &mut ()) -> Self::Action {
+            match *new {
+                n if n > 0 => TwoStackTerminal::Push(0,
+                    InternalStateMachine::new(NonterminalFunction, n)),
+                -2 => TwoStackTerminal::Unshelve(0),
+                -3 => TwoStackTerminal::Finish(0),
+                _ => TwoStackTerminal::Stay(0)
+            }
+        }
+    }
+
+    // Frames return their own number, pushing frames on positive inputs,
+    // popping on -1, unshelving on -2, and shelving themselves on -3
+    impl InternalTransition for NonterminalFunction {
+        type Internal = i64;
+        type Input = i64;
+        type Action = TwoStackTransition<i64, Frame>;
+        fn step (&self, new: &i64, internal: &mut i64) -> Self::Action {
+            match *new {
+                n if n > 0 => TwoStackTransition::Push(*internal,
+                    InternalStateMachine::new(NonterminalFunction, n)),
+                -1 => TwoStackTransition::Pop(*internal),
+                -2 => TwoStackTransition::Unshelve(*internal),
+                -3 => TwoStackTransition::Shelve(*internal),
+                _ => TwoStackTransition::Stay(*internal)
+            }
+        }
+    }
+
+    #[test]
+    fn check_def() {
+        let mut test_stacks = TwoStackAutomaton::new(
+            InternalStateMachine::new(TerminalFunction, ()));
+        assert_eq!(test_stacks.transition(&1), 0);
+        assert_eq!(test_stacks.transition(&2), 1);
+        assert_eq!(test_stacks.transition(&3), 2);
+        // 1, 2, 3 |
+        assert_eq!(test_stacks.transition(&-3), 3);
+        assert_eq!(test_stacks.transition(&-3), 2);
+        // 1 | 3, 2
+        assert_eq!(test_stacks.active().len(), 1);
+        assert_eq!(test_stacks.shelved().len(), 2);
+        assert_eq!(test_stacks.transition(&-2), 1);
+        // 1, 2 | 3
+        assert_eq!(test_stacks.transition(&0), 2);
+        assert_eq!(test_stacks.transition(&-1), 2);
+        assert_eq!(test_stacks.transition(&-1), 1);
+        // | 3
+        assert_eq!(test_stacks.transition(&0), 0);
+        assert_eq!(test_stacks.transition(&-2), 0);
+        // 3 |
+        assert_eq!(test_stacks.transition(&0), 3);
+        assert!(test_stacks.shelved().is_empty());
+    }
+
+    #[test]
+    fn check_finish() {
+        let mut test_stacks = TwoStackAutomaton::new(
+            InternalStateMachine::new(TerminalFunction, ()));
+        assert_eq!(test_stacks.transition_status(&4), PushdownStatus::Running(0));
+        assert_eq!(test_stacks.transition_status(&-3), PushdownStatus::Running(4));
+        // A shelved frame does not keep the automaton from finishing
+        assert_eq!(test_stacks.transition_status(&-3), PushdownStatus::Finished(0));
+        assert_eq!(test_stacks.transition_status(&-2), PushdownStatus::Running(0));
+        // Unshelving with nothing shelved keeps the frames as is
+        assert_eq!(test_stacks.transition_status(&-2), PushdownStatus::Running(4));
+        assert_eq!(test_stacks.active().len(), 1);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,344 @@
+//! Behavior tree nodes whose steps are futures. 
+//!
+//! Some leaf actions, such as pathfinding requests or network calls, take 
+//! longer than a single tick of a game loop, and blocking on them stalls 
+//! everything else. An asynchronous behavior tree node returns a future 
+//! from each step instead, which resolves to the result of the step once 
+//! the action completes. The futures do not borrow the input, so nodes 
+//! copy out of it whatever they need before returning. 
+//!
+//! Synchronous nodes can be used as asynchronous ones through SyncNode, and 
+//! asynchronous nodes as synchronous ones through BlockingNode, which 
+//! blocks the thread on each step. The asynchronous node runner can either 
+//! be awaited from asynchronous code, or polled once per tick by a game 
+//! loop, without blocking it. 
+
+use base_nodes::NoopWaker;
+use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+use std::future::{self, Future, Ready};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+
+/// The asynchronous counterpart of the behavior tree node trait. 
+pub trait AsyncBehaviorTreeNode: Sized {
+    /// Type of the input to take. 
+    type Input;
+    /// Type of the nonterminal statepoints returned. 
+    type Nonterminal;
+    /// Type of the terminal statepoints returned. 
+    type Terminal;
+    /// Type of the future of a step, which resolves to its result. 
+    type Step: Future<Output = NodeResult<Self::Nonterminal, Self::Terminal, Self>>;
+
+    /// Start a step of the node with the given input. 
+    fn step(self, input: &Self::Input) -> Self::Step;
+
+    /// Halt the node before it terminates, given the input it was halted on. 
+    fn halt(self, _input: &Self::Input) {}
+}
+
+/// Adapter using a synchronous node as an asynchronous one, whose steps 
+/// resolve as soon as they are polled. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct SyncNode<N>(N);
+
+impl<N> SyncNode<N> where N: BehaviorTreeNode {
+    /// Wrap a synchronous node. 
+    pub fn new(node: N) -> SyncNode<N> {
+        SyncNode(node)
+    }
+}
+
+impl<N> AsyncBehaviorTreeNode for SyncNode<N> where N: BehaviorTreeNode {
+    type Input = N::Input;
+    type Nonterminal = N::Nonterminal;
+    type Terminal = N::Terminal;
+    type Step = Ready<NodeResult<N::Nonterminal, N::Terminal, SyncNode<N>>>;
+
+    fn step(self, input: &N::Input) -> Self::Step {
+        future::ready(match self.0.step(input) {
+            NodeResult::Nonterminal(n, next) => NodeResult::Nonterminal(n, SyncNode(next)),
+            NodeResult::Terminal(t) => NodeResult::Terminal(t)
+        })
+    }
+
+    fn halt(self, input: &N::Input) {
+        self.0.halt(input);
+    }
+}
+
+/// Adapter using an asynchronous node as a synchronous one, which blocks 
+/// the calling thread until each step resolves. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct BlockingNode<A>(A);
+
+impl<A> BlockingNode<A> where A: AsyncBehaviorTreeNode {
+    /// Wrap an asynchronous node. 
+    pub fn new(node: A) -> BlockingNode<A> {
+        BlockingNode(node)
+    }
+}
+
+impl<A> BehaviorTreeNode for BlockingNode<A> where A: AsyncBehaviorTreeNode {
+    type Input = A::Input;
+    type Nonterminal = A::Nonterminal;
+    type Terminal = A::Terminal;
+
+    fn step(self, input: &A::Input) -> NodeResult<A::Nonterminal, A::Terminal,
+        BlockingNode<A>>
+    {
+        match block_on(self.0.step(input)) {
+            NodeResult::Nonterminal(n, next) => NodeResult::Nonterminal(n, BlockingNode(next)),
+            NodeResult::Terminal(t) => NodeResult::Terminal(t)
+        }
+    }
+
+    fn halt(self, input: &A::Input) {
+        self.0.halt(input);
+    }
+}
+
+// Waker unparking the thread blocked on a future
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<ThreadWaker>) {
+        self.0.unpark();
+    }
+}
+
+fn block_on<F>(future: F) -> F::Output where F: Future {
+    let mut future = Box::pin(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut context = Context::from_waker(&waker);
+    loop {
+        match future.as_mut().poll(&mut context) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park()
+        }
+    }
+}
+
+/// Runner of an asynchronous node, which restarts the node when it 
+/// terminates, like the node runner does for synchronous nodes. 
+///
+/// A step started by the runner stays in progress until its future 
+/// resolves, and the input given while it is in progress is ignored, since 
+/// the node already copied what it needed when the step started. 
+pub struct AsyncNodeRunner<N, C> where
+    N: AsyncBehaviorTreeNode,
+    C: Fn() -> N
+{
+    constructor: C,
+    node: Option<N>,
+    pending: Option<Pin<Box<N::Step>>>
+}
+
+impl<N, C> AsyncNodeRunner<N, C> where
+    N: AsyncBehaviorTreeNode,
+    C: Fn() -> N
+{
+    /// Create a new asynchronous node runner from a node constructor. 
+    pub fn new(constructor: C) -> AsyncNodeRunner<N, C> {
+        let new_node = constructor();
+        AsyncNodeRunner {
+            constructor: constructor,
+            node: Option::Some(new_node),
+            pending: Option::None
+        }
+    }
+
+    /// Return whether a step is in progress. 
+    pub fn is_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    fn start(&mut self, input: &N::Input) {
+        if self.pending.is_none() {
+            let node = self.node.take().expect("Asynchronous node runner has no node");
+            self.pending = Option::Some(Box::pin(node.step(input)));
+        }
+    }
+
+    fn poll_pending(&mut self, context: &mut Context) -> Poll<Statepoint<N::Nonterminal,
+        N::Terminal>>
+    {
+        let result = match self.pending {
+            Option::Some(ref mut step) => match step.as_mut().poll(context) {
+                Poll::Ready(result) => result,
+                Poll::Pending => return Poll::Pending
+            },
+            Option::None => unreachable!("No step in progress")
+        };
+        self.pending = Option::None;
+        Poll::Ready(match result {
+            NodeResult::Nonterminal(n, next) => {
+                self.node = Option::Some(next);
+                Statepoint::Nonterminal(n)
+            },
+            NodeResult::Terminal(t) => {
+                self.node = Option::Some((self.constructor)());
+                Statepoint::Terminal(t)
+            }
+        })
+    }
+
+    /// Start a step with the given input if none is in progress, and poll 
+    /// it once without blocking, returning the statepoint the node reached 
+    /// if the step resolved. 
+    pub fn poll_step(&mut self, input: &N::Input) -> Option<Statepoint<N::Nonterminal,
+        N::Terminal>>
+    {
+        self.start(input);
+        let waker = Waker::from(Arc::new(NoopWaker));
+        match self.poll_pending(&mut Context::from_waker(&waker)) {
+            Poll::Ready(point) => Option::Some(point),
+            Poll::Pending => Option::None
+        }
+    }
+
+    /// Start a step with the given input if none is in progress, and return 
+    /// a future resolving to the statepoint the node reaches. 
+    pub fn step<'k>(&'k mut self, input: &N::Input) -> RunnerStep<'k, N, C> {
+        self.start(input);
+        RunnerStep {
+            runner: self
+        }
+    }
+
+    /// Halt the node of the runner and replace it with a new one, dropping 
+    /// any step in progress. 
+    pub fn reset(&mut self, input: &N::Input) {
+        self.pending = Option::None;
+        if let Option::Some(node) = self.node.take() {
+            node.halt(input);
+        }
+        self.node = Option::Some((self.constructor)());
+    }
+}
+
+/// Future of a step of an asynchronous node runner. 
+pub struct RunnerStep<'k, N, C> where
+    N: AsyncBehaviorTreeNode + 'k,
+    C: Fn() -> N + 'k
+{
+    runner: &'k mut AsyncNodeRunner<N, C>
+}
+
+impl<'k, N, C> Future for RunnerStep<'k, N, C> where
+    N: AsyncBehaviorTreeNode + 'k,
+    C: Fn() -> N + 'k
+{
+    type Output = Statepoint<N::Nonterminal, N::Terminal>;
+
+    fn poll(self: Pin<&mut Self>, context: &mut Context) -> Poll<Self::Output> {
+        self.get_mut().runner.poll_pending(context)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_node::{block_on, AsyncBehaviorTreeNode, AsyncNodeRunner, BlockingNode,
+        SyncNode};
+use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    // Request taking a number of polls to resolve
+    struct Request {
+        polls: u8,
+        distance: i64,
+        traveled: i64
+    }
+
+    impl Future for Request {
+        type Output = NodeResult<i64, i64, Walker>;
+
+        fn poll(self: Pin<&mut Request>, context: &mut Context) -> Poll<Self::Output> {
+            let request = self.get_mut();
+            if request.polls > 0 {
+                request.polls -= 1;
+                context.waker().wake_by_ref();
+                Poll::Pending
+            } else if request.traveled + request.distance < 10 {
+                let traveled = request.traveled + request.distance;
+                Poll::Ready(NodeResult::Nonterminal(traveled, Walker(traveled)))
+            } else {
+                Poll::Ready(NodeResult::Terminal(request.traveled + request.distance))
+            }
+        }
+    }
+
+    struct Walker(i64);
+
+    impl AsyncBehaviorTreeNode for Walker {
+        type Input = i64;
+        type Nonterminal = i64;
+        type Terminal = i64;
+        type Step = Request;
+
+        fn step(self, input: &i64) -> Request {
+            Request {
+                polls: 2,
+                distance: *input,
+                traveled: self.0
+            }
+        }
+    }
+
+    struct Tally(u8);
+
+    impl BehaviorTreeNode for Tally {
+        type Input = ();
+        type Nonterminal = u8;
+        type Terminal = u8;
+
+        fn step(self, _input: &()) -> NodeResult<u8, u8, Tally> {
+            match self.0 {
+                0 => NodeResult::Nonterminal(1, Tally(1)),
+                n => NodeResult::Terminal(n + 1)
+            }
+        }
+    }
+
+    #[test]
+    fn poll_step_test() {
+        let mut runner = AsyncNodeRunner::new(|| Walker(0));
+        assert_eq!(runner.poll_step(&6), Option::None);
+        assert!(runner.is_pending());
+        assert_eq!(runner.poll_step(&100), Option::None);
+        assert_eq!(runner.poll_step(&100), Option::Some(Statepoint::Nonterminal(6)));
+        assert!(!runner.is_pending());
+        assert_eq!(runner.poll_step(&6), Option::None);
+        assert_eq!(runner.poll_step(&0), Option::None);
+        assert_eq!(runner.poll_step(&0), Option::Some(Statepoint::Terminal(12)));
+        assert_eq!(runner.poll_step(&3), Option::None);
+        runner.reset(&3);
+        assert!(!runner.is_pending());
+    }
+
+    #[test]
+    fn runner_step_test() {
+        let mut runner = AsyncNodeRunner::new(|| Walker(0));
+        assert_eq!(block_on(runner.step(&4)), Statepoint::Nonterminal(4));
+        assert_eq!(block_on(runner.step(&4)), Statepoint::Nonterminal(8));
+        assert_eq!(block_on(runner.step(&4)), Statepoint::Terminal(12));
+        assert_eq!(block_on(runner.step(&4)), Statepoint::Nonterminal(4));
+    }
+
+    #[test]
+    fn adapter_test() {
+        let node = BlockingNode::new(Walker(0));
+        let node = assert_steps!(node;
+            5 => Nonterminal(5),
+            3 => Nonterminal(8)
+        );
+        assert_steps!(node; 3 => Terminal(11));
+        let node = BlockingNode::new(SyncNode::new(Tally(0)));
+        let node = assert_steps!(node; () => Nonterminal(1));
+        assert_steps!(node; () => Terminal(2));
+    }
+}
@@ -1,6 +1,43 @@
 use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
 use std::marker::PhantomData;
-use stackbt_automata_impl::automaton::Automaton;
+use stackbt_automata_impl::automaton::{Automaton, FiniteStateAutomaton};
+use stackbt_automata_impl::snapshot::Snapshot;
+use stackbt_automata_impl::pushdown_automaton::{PushdownAutomaton, PushdownTransition, 
+    TerminalTransition, PushdownStatus};
+#[cfg(any(feature = "futures", feature = "async"))]
+use std::sync::Arc;
+#[cfg(any(feature = "futures", feature = "async"))]
+use std::task::Wake;
+#[cfg(feature = "futures")]
+use std::future::Future;
+#[cfg(feature = "futures")]
+use std::pin::Pin;
+#[cfg(feature = "futures")]
+use std::task::{Context, Poll, Waker};
+
+/// Trait for zero-sized types standing in for the function of a predicate 
+/// wait node, which decides from the input whether the node terminates. 
+pub trait WaitCondition {
+    /// The input type taken by the condition. 
+    type Input;
+    /// The type of the nonterminal states of the condition. 
+    type Nonterminal;
+    /// The type of the terminal states of the condition. 
+    type Terminal;
+    /// Given a reference to the input, return the state to stop at. 
+    fn do_end(input: &Self::Input) -> Statepoint<Self::Nonterminal, Self::Terminal>;
+}
+
+/// Trait for zero-sized types standing in for the function called by an 
+/// evaluation or call loop node. 
+pub trait CallWrapper {
+    /// The input type taken by the function. 
+    type Input;
+    /// The output type returned by the function. 
+    type Output;
+    /// Given a reference to the input, return the output. 
+    fn call(input: &Self::Input) -> Self::Output;
+}
 
 /// Node whose function is to stall within itself until a function of its 
 /// input return a terminal state, then terminates at that state. 
@@ -68,6 +105,15 @@ impl<I, N, T, C> PredicateWait<I, N, T, C> where
     }
 }
 
+impl<I, N, T> PredicateWait<I, N, T, fn(&I) -> Statepoint<N, T>> {
+    /// Create a new predicate wait node from a wait condition. 
+    pub fn with<W>(_condition: W) -> Self where 
+        W: WaitCondition<Input=I, Nonterminal=N, Terminal=T>
+    {
+        PredicateWait::new(W::do_end)
+    }
+}
+
 impl<I, N, T, C> BehaviorTreeNode for PredicateWait<I, N, T, C> where 
     C: Fn(&I) -> Statepoint<N, T>
 {
@@ -143,6 +189,15 @@ impl<I, O, C> Evaluation<I, O, C> where
     }
 }
 
+impl<I, O> Evaluation<I, O, fn(&I) -> O> {
+    /// Create a new evaluation node from a call wrapper. 
+    pub fn with<W>(_wrapper: W) -> Self where 
+        W: CallWrapper<Input=I, Output=O>
+    {
+        Evaluation::new(W::call)
+    }
+}
+
 impl<I, O, C> BehaviorTreeNode for Evaluation<I, O, C> where 
     C: Fn(&I) -> O
 {
@@ -191,6 +246,15 @@ impl<I, O, C> CallLoop<I, O, C> where
     }
 }
 
+impl<I, O> CallLoop<I, O, fn(&I) -> O> {
+    /// Create a new call loop node from a call wrapper. 
+    pub fn with<W>(_wrapper: W) -> Self where 
+        W: CallWrapper<Input=I, Output=O>
+    {
+        CallLoop::new(W::call)
+    }
+}
+
 impl<I, O, C> BehaviorTreeNode for CallLoop<I, O, C> where 
     C: Fn(&I) -> O
 {
@@ -241,6 +305,21 @@ impl<M, N, T> MachineWrapper<M, N, T> where
             _exists_tuple: PhantomData
         }
     }
+
+    /// Return the wrapped machine, which holds the state of the node. 
+    pub fn state(&self) -> &M {
+        &self.machine
+    }
+
+    /// Return the wrapped machine mutably. 
+    pub fn state_mut(&mut self) -> &mut M {
+        &mut self.machine
+    }
+
+    /// Consume the node, returning the wrapped machine. 
+    pub fn into_inner(self) -> M {
+        self.machine
+    }
 }
 
 impl<M, N, T> BehaviorTreeNode for MachineWrapper<M, N, T> where 
@@ -264,6 +343,20 @@ impl<M, N, T> BehaviorTreeNode for MachineWrapper<M, N, T> where
     }
 }
 
+impl<M, N, T> Snapshot for MachineWrapper<M, N, T> where 
+    M: Automaton<'static, Action=Statepoint<N, T>> + 'static + Snapshot
+{
+    type State = M::State;
+
+    fn snapshot(&self) -> M::State {
+        self.machine.snapshot()
+    }
+
+    fn restore(state: M::State) -> MachineWrapper<M, N, T> {
+        MachineWrapper::new(M::restore(state))
+    }
+}
+
 /// Node wrapper for an automaton. 
 #[derive(PartialEq, Debug)]
 pub struct MachineLoop<M> where 
@@ -314,14 +407,252 @@ impl<M> BehaviorTreeNode for MachineLoop<M> where
     }
 }
 
+impl<M> Snapshot for MachineLoop<M> where 
+    M: Automaton<'static> + 'static + Snapshot
+{
+    type State = M::State;
+
+    fn snapshot(&self) -> M::State {
+        self.machine.snapshot()
+    }
+
+    fn restore(state: M::State) -> MachineLoop<M> {
+        MachineLoop::new(M::restore(state))
+    }
+}
+
+/// Leaf node wrapping an automaton along with a predicate on its actions. 
+/// Each step, the automaton is transitioned, and its action is returned as 
+/// a terminal if the predicate holds for it, or as a nonterminal otherwise. 
+/// Unlike the machine wrapper, the automaton does not need to return 
+/// statepoints itself. 
+pub struct LeafNode<M, P> where 
+    M: Automaton<'static> + 'static,
+    P: Fn(&M::Action) -> bool
+{
+    machine: M,
+    predicate: P,
+    _m_bound: PhantomData<&'static M>
+}
+
+impl<M, P> Clone for LeafNode<M, P> where 
+    M: Automaton<'static> + 'static + Clone,
+    P: Fn(&M::Action) -> bool + Clone
+{
+    fn clone(&self) -> Self {
+        LeafNode {
+            machine: self.machine.clone(),
+            predicate: self.predicate.clone(),
+            _m_bound: PhantomData
+        }
+    }
+}
+
+impl<M, P> Copy for LeafNode<M, P> where 
+    M: Automaton<'static> + 'static + Copy,
+    P: Fn(&M::Action) -> bool + Copy
+{}
+
+impl<M, P> LeafNode<M, P> where 
+    M: Automaton<'static> + 'static,
+    P: Fn(&M::Action) -> bool
+{
+    /// Create a new leaf node, which terminates with the first action of 
+    /// the automaton the predicate holds for. 
+    pub fn new(machine: M, predicate: P) -> LeafNode<M, P> {
+        LeafNode {
+            machine: machine,
+            predicate: predicate,
+            _m_bound: PhantomData
+        }
+    }
+}
+
+impl<M, P> BehaviorTreeNode for LeafNode<M, P> where 
+    M: Automaton<'static> + 'static,
+    P: Fn(&M::Action) -> bool
+{
+    type Input = M::Input;
+    type Nonterminal = M::Action;
+    type Terminal = M::Action;
+
+    #[inline]
+    fn step(self, input: &M::Input) -> NodeResult<M::Action, M::Action, Self> {
+        let mut mut_self = self;
+        let action = mut_self.machine.transition(input);
+        if (mut_self.predicate)(&action) {
+            NodeResult::Terminal(action)
+        } else {
+            NodeResult::Nonterminal(action, mut_self)
+        }
+    }
+}
+
+/// Node wrapper for a pushdown automaton, which terminates when the bottom 
+/// machine of the pushdown automaton finishes. 
+#[derive(Clone, PartialEq, Debug)]
+pub struct PushdownWrapper<I, A, N, T> where 
+    I: 'static,
+    A: 'static,
+    N: FiniteStateAutomaton<'static, Input=I, Action=PushdownTransition<A, N>> + 'static,
+    T: FiniteStateAutomaton<'static, Input=I, Action=TerminalTransition<A, N>> + 'static
+{
+    machine: PushdownAutomaton<'static, I, A, N, T>
+}
+
+impl<I, A, N, T> PushdownWrapper<I, A, N, T> where 
+    I: 'static,
+    A: 'static,
+    N: FiniteStateAutomaton<'static, Input=I, Action=PushdownTransition<A, N>> + 'static,
+    T: FiniteStateAutomaton<'static, Input=I, Action=TerminalTransition<A, N>> + 'static
+{
+    /// Create a new pushdown automaton wrapping node. 
+    pub fn new(machine: PushdownAutomaton<'static, I, A, N, T>) -> 
+        PushdownWrapper<I, A, N, T> 
+    {
+        PushdownWrapper { 
+            machine: machine
+        }
+    }
+}
+
+impl<I, A, N, T> BehaviorTreeNode for PushdownWrapper<I, A, N, T> where 
+    I: 'static,
+    A: 'static,
+    N: FiniteStateAutomaton<'static, Input=I, Action=PushdownTransition<A, N>> + 'static,
+    T: FiniteStateAutomaton<'static, Input=I, Action=TerminalTransition<A, N>> + 'static
+{
+    type Input = I;
+    type Nonterminal = A;
+    type Terminal = A;
+
+    #[inline]
+    fn step(self, input: &I) -> NodeResult<A, A, Self> {
+        let mut mach = self;
+        match mach.machine.transition_status(input) {
+            PushdownStatus::Running(thing) => NodeResult::Nonterminal(thing, mach),
+            PushdownStatus::Finished(thing) => NodeResult::Terminal(thing)
+        }
+    }
+}
+
+impl<I, A, N, T> Snapshot for PushdownWrapper<I, A, N, T> where 
+    I: 'static,
+    A: 'static,
+    N: FiniteStateAutomaton<'static, Input=I, Action=PushdownTransition<A, N>> + 'static 
+        + Snapshot,
+    T: FiniteStateAutomaton<'static, Input=I, Action=TerminalTransition<A, N>> + 'static 
+        + Snapshot
+{
+    type State = <PushdownAutomaton<'static, I, A, N, T> as Snapshot>::State;
+
+    fn snapshot(&self) -> Self::State {
+        self.machine.snapshot()
+    }
+
+    fn restore(state: Self::State) -> PushdownWrapper<I, A, N, T> {
+        PushdownWrapper::new(PushdownAutomaton::restore(state))
+    }
+}
+
+// Waker for futures polled once each step, which are polled again on the 
+// next step regardless of wakeups
+#[cfg(any(feature = "futures", feature = "async"))]
+pub(crate) struct NoopWaker;
+
+#[cfg(any(feature = "futures", feature = "async"))]
+impl Wake for NoopWaker {
+    fn wake(self: Arc<NoopWaker>) {}
+}
+
+/// Leaf node polling a future once each step, which is nonterminal while 
+/// the future is pending, and terminates with the output of the future once 
+/// it is ready. The input is ignored, so the future has to get whatever it 
+/// needs from elsewhere. 
+#[cfg(feature = "futures")]
+pub struct FutureLeaf<I, F> where 
+    F: Future
+{
+    future: Pin<Box<F>>,
+    _junk: PhantomData<I>
+}
+
+#[cfg(feature = "futures")]
+impl<I, F> FutureLeaf<I, F> where 
+    F: Future
+{
+    /// Create a new future polling node. 
+    pub fn new(future: F) -> FutureLeaf<I, F> {
+        FutureLeaf {
+            future: Box::pin(future),
+            _junk: PhantomData
+        }
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<I, F> BehaviorTreeNode for FutureLeaf<I, F> where 
+    F: Future
+{
+    type Input = I;
+    type Nonterminal = ();
+    type Terminal = F::Output;
+
+    #[inline]
+    fn step(self, _input: &I) -> NodeResult<(), F::Output, Self> {
+        let mut mut_self = self;
+        let waker = Waker::from(Arc::new(NoopWaker));
+        match mut_self.future.as_mut().poll(&mut Context::from_waker(&waker)) {
+            Poll::Ready(output) => NodeResult::Terminal(output),
+            Poll::Pending => NodeResult::Nonterminal((), mut_self)
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use base_nodes::{CallWrapper, WaitCondition};
     use behavior_tree_node::Statepoint;
-    use stackbt_automata_impl::internal_state_machine::InternalTransition;
+    use stackbt_automata_impl::internal_state_machine::{InternalTransition, 
+        InternalStateMachine};
+    use stackbt_automata_impl::pushdown_automaton::{PushdownTransition, 
+        TerminalTransition};
+
+    #[cfg(feature = "futures")]
+    #[test]
+    fn future_leaf_test() {
+        use base_nodes::FutureLeaf;
+        use std::future::Future;
+        use std::pin::Pin;
+        use std::task::{Context, Poll};
+
+        struct Delay(u8);
+
+        impl Future for Delay {
+            type Output = &'static str;
+
+            fn poll(self: Pin<&mut Delay>, context: &mut Context) -> Poll<&'static str> {
+                let this = self.get_mut();
+                if this.0 == 0 {
+                    Poll::Ready("arrived")
+                } else {
+                    this.0 -= 1;
+                    context.waker().wake_by_ref();
+                    Poll::Pending
+                }
+            }
+        }
+
+        let node = FutureLeaf::<(), _>::new(Delay(2));
+        let node = assert_steps!(node;
+            () => Nonterminal(()),
+            () => Nonterminal(())
+        );
+        assert_steps!(node; () => Terminal("arrived"));
+    }
 
     #[test]
     fn pred_wait_test() {
-        use behavior_tree_node::{BehaviorTreeNode, NodeResult};
         use base_nodes::PredicateWait;
         let thing = PredicateWait::new(|i: &i64| {
             if *i == 0 {
@@ -330,25 +661,52 @@ mod tests {
                 Statepoint::Nonterminal(())
             }
         });
-        let thing_1 = match thing.step(&4) {
-            NodeResult::Nonterminal(_, x) => x,
-            _ => unreachable!("Expected nonterminal state")
-        };
-        match thing_1.step(&0) {
-            NodeResult::Terminal(_) => (),
-            _ => unreachable!("Expected terminal state"),
-        }
+        assert_steps!(thing;
+            4 => Nonterminal(()),
+            0 => Terminal(())
+        );
     }
 
     #[test]
     fn evaluation_test() {
-        use behavior_tree_node::{BehaviorTreeNode, NodeResult};
         use base_nodes::Evaluation;
         let thing = Evaluation::new(|val: &i64| *val);
-        match thing.step(&5) {
-            NodeResult::Terminal(t) => assert!(t == 5),
-            _ => unreachable!("Expected terminal"),
-        };
+        assert_steps!(thing; 5 => Terminal(5));
+    }
+
+    struct Halver;
+
+    impl CallWrapper for Halver {
+        type Input = i64;
+        type Output = i64;
+        fn call(input: &i64) -> i64 {
+            input / 2
+        }
+    }
+
+    struct Positive;
+
+    impl WaitCondition for Positive {
+        type Input = i64;
+        type Nonterminal = i64;
+        type Terminal = ();
+        fn do_end(input: &i64) -> Statepoint<i64, ()> {
+            if *input > 0 {
+                Statepoint::Nonterminal(*input)
+            } else {
+                Statepoint::Terminal(())
+            }
+        }
+    }
+
+    #[test]
+    fn trait_constructor_test() {
+        use base_nodes::{CallLoop, Evaluation, PredicateWait};
+        assert_steps!(Evaluation::with(Halver); 9 => Terminal(4));
+        let node = assert_steps!(CallLoop::with(Halver); 4 => Nonterminal(2));
+        assert_steps!(node; 2 => Nonterminal(1));
+        let node = assert_steps!(PredicateWait::with(Positive); 3 => Nonterminal(3));
+        assert_steps!(node; 0 => Terminal(()));
     }
 
     #[derive(Copy, Clone)]
@@ -378,28 +736,107 @@ mod tests {
 
     #[test]
     fn leaf_test() {
-        use behavior_tree_node::{BehaviorTreeNode, NodeResult};
-        use stackbt_automata_impl::internal_state_machine::InternalStateMachine;
         use base_nodes::MachineWrapper;
         let machine = InternalStateMachine::new(ThingLeaf, 0);
-        let thing = MachineWrapper::new(machine);
-        let thing_1 = match thing.step(&4) {
-            NodeResult::Nonterminal(a, b) => {
-                assert_eq!(a, 0);
-                b
-            },
-            _ => unreachable!("Expected nonterminal state")
-        };
-        let thing_2 = match thing_1.step(&3) {
-            NodeResult::Nonterminal(a, b) => {
-                assert_eq!(a, 4);
-                b
-            },
-            _ => unreachable!("Expected nonterminal state")
-        };
-        match thing_2.step(&0) {
-            NodeResult::Terminal(t) => assert_eq!(t, 7),
-            _ => unreachable!("Expected terminal state"),
-        };
-    }
-}
\ No newline at end of file
+        assert_steps!(MachineWrapper::new(machine);
+            4 => Nonterminal(0),
+            3 => Nonterminal(4),
+            0 => Terminal(7)
+        );
+    }
+
+    #[test]
+    fn leaf_snapshot_test() {
+        use base_nodes::MachineWrapper;
+        use stackbt_automata_impl::snapshot::Snapshot;
+        let machine = InternalStateMachine::new(ThingLeaf, 0);
+        let node = assert_steps!(MachineWrapper::new(machine);
+            4 => Nonterminal(0)
+        );
+        let saved = node.snapshot();
+        assert_eq!(saved, 4);
+        assert_steps!(node; 3 => Nonterminal(4), 0 => Terminal(7));
+        let restored: MachineWrapper<InternalStateMachine<ThingLeaf>, _, _> =
+            MachineWrapper::restore(saved);
+        assert_steps!(restored; 0 => Terminal(4));
+    }
+
+    #[test]
+    fn leaf_state_test() {
+        use base_nodes::MachineWrapper;
+        let machine = InternalStateMachine::new(ThingLeaf, 0);
+        let mut node = assert_steps!(MachineWrapper::new(machine);
+            4 => Nonterminal(0)
+        );
+        assert_eq!(*node.state().state(), 4);
+        *node.state_mut().state_mut() = 10;
+        let node = assert_steps!(node; 1 => Nonterminal(10));
+        assert_eq!(node.into_inner().into_inner(), 11);
+    }
+
+    #[test]
+    fn leaf_node_test() {
+        use base_nodes::LeafNode;
+        let machine = InternalStateMachine::with(|input: &i64, total: &mut i64| {
+            *total += *input;
+            *total
+        }, 0);
+        let node = LeafNode::new(machine, |total: &i64| *total >= 10);
+        let node = assert_steps!(node; 4 => Nonterminal(4), 5 => Nonterminal(9));
+        assert_steps!(node; 3 => Terminal(12));
+        let node = LeafNode::new(machine, |total: &i64| *total >= 10);
+        assert_steps!(node; 10 => Terminal(10));
+    }
+
+    #[derive(Copy, Clone)]
+    struct Frame;
+
+    impl InternalTransition for Frame {
+        type Internal = i64;
+        type Input = i64;
+        type Action = PushdownTransition<i64, InternalStateMachine<'static, Frame>>;
+
+        fn step(&self, input: &i64, depth: &mut i64) -> Self::Action {
+            if *input < 0 {
+                PushdownTransition::Pop(*depth)
+            } else {
+                PushdownTransition::Stay(*depth)
+            }
+        }
+    }
+
+    #[derive(Copy, Clone)]
+    struct Bottom;
+
+    impl InternalTransition for Bottom {
+        type Internal = i64;
+        type Input = i64;
+        type Action = TerminalTransition<i64, InternalStateMachine<'static, Frame>>;
+
+        fn step(&self, input: &i64, calls: &mut i64) -> Self::Action {
+            if *input == 0 {
+                *calls += 1;
+                TerminalTransition::Push(0, InternalStateMachine::new(Frame, *calls))
+            } else if *input < 0 {
+                TerminalTransition::Finish(*calls)
+            } else {
+                TerminalTransition::Stay(0)
+            }
+        }
+    }
+
+    #[test]
+    fn pushdown_leaf_test() {
+        use stackbt_automata_impl::pushdown_automaton::PushdownAutomaton;
+        use base_nodes::PushdownWrapper;
+        let machine = PushdownAutomaton::new(InternalStateMachine::new(Bottom, 0));
+        assert_steps!(PushdownWrapper::new(machine);
+            0 => Nonterminal(0),
+            5 => Nonterminal(1),
+            -1 => Nonterminal(1),
+            0 => Nonterminal(0),
+            -1 => Nonterminal(2),
+            -1 => Terminal(2)
+        );
+    }
+}
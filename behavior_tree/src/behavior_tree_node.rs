@@ -84,6 +84,70 @@ pub trait BehaviorTreeNode {
     fn step(self, input: &Self::Input) -> 
         NodeResult<Self::Nonterminal, Self::Terminal, Self> where 
         Self: Sized;
+
+    /// Given the input by value, perform a single step of the behavior node. 
+    /// By default, this borrows the input and defers to step, but nodes whose
+    /// inputs are consumable events can override it to take ownership of the 
+    /// input instead of cloning it. 
+    #[inline]
+    fn step_with(self, input: Self::Input) -> 
+        NodeResult<Self::Nonterminal, Self::Terminal, Self> where 
+        Self: Sized
+    {
+        self.step(&input)
+    }
+
+    /// Given the input, halt the behavior node before it is dropped without 
+    /// having terminated, such as when its parent abandons it for another 
+    /// node or exits, giving it a chance to clean up after itself, such as 
+    /// by releasing reservations or stopping animations. By default, this 
+    /// does nothing. Nodes with children should halt their running children 
+    /// when halted, and when abandoning them. 
+    #[inline]
+    fn halt(self, _input: &Self::Input) where 
+        Self: Sized
+    {}
+
+    /// Step the behavior node with each of the given inputs by value until 
+    /// it terminates, returning the terminal state along with the number of 
+    /// steps it took, or the node itself if the inputs run out first. 
+    fn run_until_terminal<J>(self, inputs: J) -> Result<(Self::Terminal, usize), Self> 
+        where 
+        J: IntoIterator<Item=Self::Input>,
+        Self: Sized
+    {
+        let mut node = self;
+        let mut steps = 0;
+        for input in inputs {
+            steps += 1;
+            match node.step_with(input) {
+                NodeResult::Nonterminal(_, next) => node = next,
+                NodeResult::Terminal(t) => return Result::Ok((t, steps))
+            }
+        }
+        Result::Err(node)
+    }
+
+    /// Step the behavior node with the given input up to the given number of 
+    /// times, returning the nonterminal states it passed through, along with 
+    /// either the node itself, or the terminal state if it terminated early. 
+    fn step_n(self, input: &Self::Input, count: usize) -> (Vec<Self::Nonterminal>, 
+        Statepoint<Self, Self::Terminal>) where 
+        Self: Sized
+    {
+        let mut node = self;
+        let mut nonterminals = Vec::with_capacity(count);
+        for _ in 0..count {
+            match node.step(input) {
+                NodeResult::Nonterminal(n, next) => {
+                    nonterminals.push(n);
+                    node = next;
+                },
+                NodeResult::Terminal(t) => return (nonterminals, Statepoint::Terminal(t))
+            }
+        }
+        (nonterminals, Statepoint::Nonterminal(node))
+    }
 }
 
 #[cfg(all(test, feature = "try_trait"))]
@@ -112,4 +176,106 @@ mod tests_try {
         assert_eq!(NodeResult::<i64, i64, i64>::from_ok((5, 4)), 
             NodeResult::Nonterminal(5, 4));
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use behavior_tree_node::{BehaviorTreeNode, NodeResult};
+
+    #[derive(PartialEq, Debug)]
+    struct Letter(String);
+
+    struct Mailbox;
+
+    impl BehaviorTreeNode for Mailbox {
+        type Input = Option<Letter>;
+        type Nonterminal = ();
+        type Terminal = Option<Letter>;
+
+        fn step(self, input: &Option<Letter>) -> NodeResult<(), 
+            Option<Letter>, Self> 
+        {
+            match input {
+                Option::Some(_) => NodeResult::Terminal(Option::None),
+                Option::None => NodeResult::Nonterminal((), self)
+            }
+        }
+
+        fn step_with(self, input: Option<Letter>) -> NodeResult<(), 
+            Option<Letter>, Self> 
+        {
+            match input {
+                Option::Some(l) => NodeResult::Terminal(Option::Some(l)),
+                Option::None => NodeResult::Nonterminal((), self)
+            }
+        }
+    }
+
+    #[test]
+    fn step_with_test() {
+        let mailbox = Mailbox;
+        let mailbox_1 = match mailbox.step_with(Option::None) {
+            NodeResult::Nonterminal(_, n) => n,
+            _ => unreachable!("Expected nonterminal state")
+        };
+        match mailbox_1.step_with(Option::Some(Letter("Hello".to_owned()))) {
+            NodeResult::Terminal(t) => assert_eq!(t, 
+                Option::Some(Letter("Hello".to_owned()))),
+            _ => unreachable!("Expected terminal state")
+        };
+    }
+
+    #[test]
+    fn default_step_with_test() {
+        use base_nodes::Evaluation;
+        let node = Evaluation::new(|val: &i64| *val + 1);
+        match node.step_with(4) {
+            NodeResult::Terminal(t) => assert_eq!(t, 5),
+            _ => unreachable!("Expected terminal state")
+        };
+    }
+
+    #[test]
+    fn run_until_terminal_test() {
+        let mailbox = Mailbox;
+        let inputs = vec![Option::None, Option::None, 
+            Option::Some(Letter("Hello".to_owned())), Option::None];
+        match mailbox.run_until_terminal(inputs) {
+            Result::Ok((t, steps)) => {
+                assert_eq!(t, Option::Some(Letter("Hello".to_owned())));
+                assert_eq!(steps, 3);
+            },
+            Result::Err(_) => unreachable!("Expected terminal state")
+        };
+        assert!(Mailbox.run_until_terminal(vec![Option::None, Option::None]).is_err());
+    }
+
+    #[test]
+    fn step_n_test() {
+        use base_nodes::MachineWrapper;
+        use behavior_tree_node::Statepoint;
+        use stackbt_automata_impl::internal_state_machine::InternalStateMachine;
+        let node = MachineWrapper::<_, i64, i64>::new(InternalStateMachine::with(
+            |input: &i64, total: &mut i64| {
+                *total += *input;
+                if *total < 10 {
+                    Statepoint::Nonterminal(*total)
+                } else {
+                    Statepoint::Terminal(*total)
+                }
+            }, 0));
+        let (nonterminals, node) = node.step_n(&3, 2);
+        assert_eq!(nonterminals, vec![3, 6]);
+        let node = match node {
+            Statepoint::Nonterminal(n) => n,
+            Statepoint::Terminal(_) => unreachable!("Expected nonterminal state")
+        };
+        match node.step_n(&3, 5) {
+            (nonterminals, Statepoint::Terminal(t)) => {
+                assert_eq!(nonterminals, vec![9]);
+                assert_eq!(t, 12);
+            },
+            _ => unreachable!("Expected terminal state")
+        };
+    }
+}
@@ -0,0 +1,199 @@
+//! Boxed nodes, for dynamic dispatch at chosen points of a tree. 
+//!
+//! Trees composed entirely of generic nodes compile down to a single type, 
+//! which costs nothing at runtime, but whose name can grow enormous, and 
+//! which is monomorphized anew for each distinct tree. A boxed node erases 
+//! the type of the subtree it holds behind a trait object, keeping only its 
+//! input, nonterminal and terminal types, so that subtrees of different 
+//! types can be swapped for each other, collected together, and assembled 
+//! at runtime, at the cost of an indirect call per step. 
+
+use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+
+/// Object-safe counterpart of the behavior tree node trait, which steps the 
+/// node in place instead of taking it by move. 
+trait InPlaceNode<I, N, T> {
+    fn step_in_place(&mut self, input: &I) -> Statepoint<N, T>;
+
+    fn step_with_in_place(&mut self, input: I) -> Statepoint<N, T>;
+
+    fn halt_in_place(&mut self, input: &I);
+}
+
+/// Holder of a node stepped in place, which is emptied once the node 
+/// terminates or is halted. 
+struct Held<X>(Option<X>);
+
+impl<X> Held<X> where X: BehaviorTreeNode {
+    // Keep the node if it has not terminated
+    fn hold(&mut self, result: NodeResult<X::Nonterminal, X::Terminal, X>) -> 
+        Statepoint<X::Nonterminal, X::Terminal>
+    {
+        match result {
+            NodeResult::Nonterminal(n, next) => {
+                self.0 = Option::Some(next);
+                Statepoint::Nonterminal(n)
+            },
+            NodeResult::Terminal(t) => Statepoint::Terminal(t)
+        }
+    }
+}
+
+impl<X> InPlaceNode<X::Input, X::Nonterminal, X::Terminal> for Held<X> where
+    X: BehaviorTreeNode
+{
+    fn step_in_place(&mut self, input: &X::Input) -> Statepoint<X::Nonterminal,
+        X::Terminal>
+    {
+        let node = self.0.take().expect("Boxed node stepped after terminating");
+        self.hold(node.step(input))
+    }
+
+    fn step_with_in_place(&mut self, input: X::Input) -> Statepoint<X::Nonterminal,
+        X::Terminal>
+    {
+        let node = self.0.take().expect("Boxed node stepped after terminating");
+        self.hold(node.step_with(input))
+    }
+
+    fn halt_in_place(&mut self, input: &X::Input) {
+        if let Option::Some(node) = self.0.take() {
+            node.halt(input);
+        }
+    }
+}
+
+/// A node holding a subtree of any type with the given input, nonterminal 
+/// and terminal types behind a trait object. The subtree is stepped in place, 
+/// so the box is only allocated when the boxed node is created. 
+pub struct BoxedNode<I, N, T> {
+    node: Box<dyn InPlaceNode<I, N, T>>
+}
+
+impl<I, N, T> BoxedNode<I, N, T> {
+    /// Box the given node. 
+    pub fn new<X>(node: X) -> BoxedNode<I, N, T> where
+        X: BehaviorTreeNode<Input=I, Nonterminal=N, Terminal=T> + 'static
+    {
+        BoxedNode {
+            node: Box::new(Held(Option::Some(node)))
+        }
+    }
+}
+
+impl<I, N, T> BehaviorTreeNode for BoxedNode<I, N, T> {
+    type Input = I;
+    type Nonterminal = N;
+    type Terminal = T;
+
+    #[inline]
+    fn step(self, input: &I) -> NodeResult<N, T, BoxedNode<I, N, T>> {
+        let mut mut_self = self;
+        match mut_self.node.step_in_place(input) {
+            Statepoint::Nonterminal(n) => NodeResult::Nonterminal(n, mut_self),
+            Statepoint::Terminal(t) => NodeResult::Terminal(t)
+        }
+    }
+
+    #[inline]
+    fn step_with(self, input: I) -> NodeResult<N, T, BoxedNode<I, N, T>> {
+        let mut mut_self = self;
+        match mut_self.node.step_with_in_place(input) {
+            Statepoint::Nonterminal(n) => NodeResult::Nonterminal(n, mut_self),
+            Statepoint::Terminal(t) => NodeResult::Terminal(t)
+        }
+    }
+
+    #[inline]
+    fn halt(self, input: &I) {
+        let mut mut_self = self;
+        mut_self.node.halt_in_place(input);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base_nodes::MachineWrapper;
+    use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+    use boxed_node::BoxedNode;
+    use stackbt_automata_impl::internal_state_machine::{InternalTransition,
+        InternalStateMachine};
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[derive(Copy, Clone)]
+    struct Accumulate;
+
+    impl InternalTransition for Accumulate {
+        type Input = i64;
+        type Internal = i64;
+        type Action = Statepoint<i64, i64>;
+
+        fn step(&self, input: &i64, total: &mut i64) -> Statepoint<i64, i64> {
+            *total += *input;
+            if *total < 10 {
+                Statepoint::Nonterminal(*total)
+            } else {
+                Statepoint::Terminal(*total)
+            }
+        }
+    }
+
+    struct Immediate;
+
+    impl BehaviorTreeNode for Immediate {
+        type Input = i64;
+        type Nonterminal = i64;
+        type Terminal = i64;
+
+        fn step(self, input: &i64) -> NodeResult<i64, i64, Immediate> {
+            NodeResult::Terminal(-input)
+        }
+    }
+
+    fn choose(patient: bool) -> BoxedNode<i64, i64, i64> {
+        if patient {
+            BoxedNode::new(MachineWrapper::<_, i64, i64>::new(InternalStateMachine::new(
+                Accumulate, 0)))
+        } else {
+            BoxedNode::new(Immediate)
+        }
+    }
+
+    #[test]
+    fn boxed_node_test() {
+        let node = choose(true);
+        let node = assert_steps!(node;
+            4 => Nonterminal(4),
+            4 => Nonterminal(8)
+        );
+        assert_steps!(node; 4 => Terminal(12));
+        assert_steps!(choose(false); 4 => Terminal(-4));
+    }
+
+    #[test]
+    fn boxed_node_halt_test() {
+        struct Watched(Rc<Cell<bool>>);
+
+        impl BehaviorTreeNode for Watched {
+            type Input = ();
+            type Nonterminal = ();
+            type Terminal = ();
+
+            fn step(self, _input: &()) -> NodeResult<(), (), Watched> {
+                NodeResult::Nonterminal((), self)
+            }
+
+            fn halt(self, _input: &()) {
+                self.0.set(true);
+            }
+        }
+
+        let halted = Rc::new(Cell::new(false));
+        let node = BoxedNode::new(Watched(halted.clone()));
+        let node = assert_steps!(node; () => Nonterminal(()));
+        assert!(!halted.get());
+        node.halt(&());
+        assert!(halted.get());
+    }
+}
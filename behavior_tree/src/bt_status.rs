@@ -0,0 +1,62 @@
+//! Conventional behavior trees describe the state of every node with one of 
+//! three statuses: running, succeeded or failed. In this library, a running 
+//! node is one which stepped to a nonterminal, so only the success or 
+//! failure of a node that has terminated needs a type of its own. BtStatus 
+//! is that type, and the Sequence, Selector and Parallel deciders of the 
+//! node compositions module build the classic combinators on top of it. 
+//!
+//! ```
+//! use stackbt_behavior_tree::behavior_tree_node::Statepoint;
+//! use stackbt_behavior_tree::bt_status::{BtStatus, BtStatepoint};
+//!
+//! let running: BtStatepoint<()> = Statepoint::Nonterminal(());
+//! let done: BtStatepoint<()> = Statepoint::Terminal(BtStatus::from(true));
+//! assert_eq!(done, Statepoint::Terminal(BtStatus::Success));
+//! assert!(running != done);
+//! ```
+
+use behavior_tree_node::Statepoint;
+
+/// Terminal status of a conventional behavior tree node. 
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum BtStatus {
+    /// The node succeeded. 
+    Success,
+    /// The node failed. 
+    Failure
+}
+
+/// Statepoint of a conventional behavior tree node, which is running while 
+/// it is nonterminal. 
+pub type BtStatepoint<N> = Statepoint<N, BtStatus>;
+
+impl BtStatus {
+    /// Return whether the status is a success. 
+    pub fn is_success(&self) -> bool {
+        *self == BtStatus::Success
+    }
+
+    /// Return whether the status is a failure. 
+    pub fn is_failure(&self) -> bool {
+        *self == BtStatus::Failure
+    }
+}
+
+impl From<bool> for BtStatus {
+    fn from(success: bool) -> BtStatus {
+        if success {
+            BtStatus::Success
+        } else {
+            BtStatus::Failure
+        }
+    }
+}
+
+impl<S, F> From<Result<S, F>> for BtStatus {
+    fn from(result: Result<S, F>) -> BtStatus {
+        match result {
+            Result::Ok(_) => BtStatus::Success,
+            Result::Err(_) => BtStatus::Failure
+        }
+    }
+}
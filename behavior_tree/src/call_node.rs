@@ -0,0 +1,636 @@
+//! Reusing a sub-behavior in several places of a tree normally requires 
+//! duplicating it structurally in each of its parents. The call stack node 
+//! instead gives nodes call and return semantics, in the manner of a 
+//! pushdown automaton: a node may, at any step, call another node, which 
+//! suspends the caller and pushes it onto a stack, and when the callee 
+//! terminates, the caller is popped back off and resumes where it left off. 
+//!
+//! Since callers and callees are kept on the same stack, they are all of 
+//! the same node type, which in practice is an enumeration of the 
+//! behaviors that can call each other. The stack is kept in a pushdown 
+//! automaton, whose frames are finite state machines, so the node type 
+//! and its terminal must be Copy. 
+//! 
+//! Behaviors can also be used like functions. Arguments are passed by 
+//! constructing the callee with them, as in a GoTo(position) variant, and 
+//! with the subroutine node, the terminal of the callee is returned to the 
+//! caller through the Resume trait, which decides how the caller proceeds 
+//! with the return value. 
+
+use behavior_tree_node::{BehaviorTreeNode, NodeResult};
+use stackbt_automata_impl::automaton::{Automaton, FiniteStateAutomaton};
+use stackbt_automata_impl::pushdown_automaton::{PushdownAutomaton, PushdownStatus, 
+    PushdownTransition, TerminalTransition};
+use stackbt_automata_impl::snapshot::Snapshot;
+use std::fmt::{self, Debug};
+use std::marker::PhantomData;
+
+/// Nonterminal of the nodes run by a call stack node. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum CallNonterm<N, V> {
+    /// Continue stepping the current node. 
+    Continue(V),
+    /// Suspend the current node, and call the given node. 
+    Call(N, V)
+}
+
+/// Nonterminal of a call stack node. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum CallState<V, T> {
+    /// The current node continued or called another node. 
+    Running(V),
+    /// The current node terminated with the given value, and its caller 
+    /// was resumed. 
+    Returned(T)
+}
+
+/// Trait for nodes which can receive the return values of the nodes they 
+/// call. 
+pub trait Resume<V>: BehaviorTreeNode<Nonterminal=CallNonterm<Self, V>> + Sized {
+    /// Resume the node with the terminal value returned by its callee. This 
+    /// takes the place of a step of the node, so the node may continue, 
+    /// call another node, or terminate in turn. 
+    fn resume(self, returned: Self::Terminal) -> NodeResult<CallNonterm<Self, V>, 
+        Self::Terminal, Self>;
+}
+
+// Marker for the frames of suspended callers
+#[derive(Copy, Clone, PartialEq, Debug)]
+struct Upper;
+
+// Marker for the frame of the bottommost node
+#[derive(Copy, Clone, PartialEq, Debug)]
+struct Bottom;
+
+// Frame of the call stack, holding a node along with the terminal returned 
+// to it by its callee, which it resumes with in place of its next step
+#[derive(PartialEq, Debug)]
+struct CallFrame<N, V, P> where
+    N: Resume<V>
+{
+    node: Option<N>,
+    returned: Option<N::Terminal>,
+    _v: PhantomData<(V, P)>
+}
+
+impl<N, V, P> CallFrame<N, V, P> where
+    N: Resume<V>
+{
+    fn new(node: N) -> CallFrame<N, V, P> {
+        CallFrame {
+            node: Option::Some(node),
+            returned: Option::None,
+            _v: PhantomData
+        }
+    }
+
+    // Step the node, or resume it if its callee returned to it
+    fn advance(&mut self, input: &N::Input) -> NodeResult<CallNonterm<N, V>, 
+        N::Terminal, N> 
+    {
+        let node = self.node.take().expect("Call stack frame was poisoned");
+        match self.returned.take() {
+            Option::Some(returned) => node.resume(returned),
+            Option::None => node.step(input)
+        }
+    }
+
+    fn halt(self, input: &N::Input) {
+        if let Option::Some(node) = self.node {
+            node.halt(input);
+        }
+    }
+}
+
+impl<N, V, P> Clone for CallFrame<N, V, P> where
+    N: Resume<V> + Copy,
+    N::Terminal: Copy
+{
+    fn clone(&self) -> CallFrame<N, V, P> {
+        *self
+    }
+}
+
+impl<N, V, P> Copy for CallFrame<N, V, P> where
+    N: Resume<V> + Copy,
+    N::Terminal: Copy
+{}
+
+impl<N, V> Automaton<'static> for CallFrame<N, V, Upper> where
+    N: Resume<V> + Copy + 'static,
+    N::Input: 'static,
+    N::Terminal: Copy + 'static,
+    V: 'static
+{
+    type Input = N::Input;
+    type Action = PushdownTransition<CallState<V, N::Terminal>, CallFrame<N, V, Upper>>;
+
+    fn transition(&mut self, input: &N::Input) -> Self::Action {
+        match self.advance(input) {
+            NodeResult::Nonterminal(CallNonterm::Continue(v), n) => {
+                self.node = Option::Some(n);
+                PushdownTransition::Stay(CallState::Running(v))
+            },
+            NodeResult::Nonterminal(CallNonterm::Call(callee, v), n) => {
+                self.node = Option::Some(n);
+                PushdownTransition::Push(CallState::Running(v), CallFrame::new(callee))
+            },
+            NodeResult::Terminal(t) => PushdownTransition::Pop(CallState::Returned(t))
+        }
+    }
+}
+
+impl<N, V> FiniteStateAutomaton<'static> for CallFrame<N, V, Upper> where
+    N: Resume<V> + Copy + 'static,
+    N::Input: 'static,
+    N::Terminal: Copy + 'static,
+    V: 'static
+{}
+
+impl<N, V> Automaton<'static> for CallFrame<N, V, Bottom> where
+    N: Resume<V> + Copy + 'static,
+    N::Input: 'static,
+    N::Terminal: Copy + 'static,
+    V: 'static
+{
+    type Input = N::Input;
+    type Action = TerminalTransition<CallState<V, N::Terminal>, CallFrame<N, V, Upper>>;
+
+    fn transition(&mut self, input: &N::Input) -> Self::Action {
+        match self.advance(input) {
+            NodeResult::Nonterminal(CallNonterm::Continue(v), n) => {
+                self.node = Option::Some(n);
+                TerminalTransition::Stay(CallState::Running(v))
+            },
+            NodeResult::Nonterminal(CallNonterm::Call(callee, v), n) => {
+                self.node = Option::Some(n);
+                TerminalTransition::Push(CallState::Running(v), CallFrame::new(callee))
+            },
+            NodeResult::Terminal(t) => TerminalTransition::Finish(CallState::Returned(t))
+        }
+    }
+}
+
+impl<N, V> FiniteStateAutomaton<'static> for CallFrame<N, V, Bottom> where
+    N: Resume<V> + Copy + 'static,
+    N::Input: 'static,
+    N::Terminal: Copy + 'static,
+    V: 'static
+{}
+
+impl<N, V, P> Snapshot for CallFrame<N, V, P> where
+    N: Resume<V> + Snapshot
+{
+    type State = N::State;
+
+    fn snapshot(&self) -> N::State {
+        self.node.as_ref().expect("Call stack frame was poisoned").snapshot()
+    }
+
+    fn restore(state: N::State) -> CallFrame<N, V, P> {
+        CallFrame::new(N::restore(state))
+    }
+}
+
+// Pushdown automaton holding the frames of a subroutine node
+type CallStack<N, V> = PushdownAutomaton<'static, <N as BehaviorTreeNode>::Input, 
+    CallState<V, <N as BehaviorTreeNode>::Terminal>, CallFrame<N, V, Upper>, 
+    CallFrame<N, V, Bottom>>;
+
+/// Node which runs nodes with call and return semantics, returning the 
+/// terminals of callees to their callers, and terminating when the 
+/// bottommost node terminates. 
+pub struct SubroutineNode<N, V> where
+    N: Resume<V> + Copy + 'static,
+    N::Input: 'static,
+    N::Terminal: Copy + 'static,
+    V: 'static
+{
+    machine: CallStack<N, V>
+}
+
+impl<N, V> Clone for SubroutineNode<N, V> where
+    N: Resume<V> + Copy + 'static,
+    N::Input: 'static,
+    N::Terminal: Copy + 'static,
+    V: 'static,
+    CallStack<N, V>: Clone
+{
+    fn clone(&self) -> SubroutineNode<N, V> {
+        SubroutineNode {
+            machine: self.machine.clone()
+        }
+    }
+}
+
+impl<N, V> PartialEq for SubroutineNode<N, V> where
+    N: Resume<V> + Copy + 'static,
+    N::Input: 'static,
+    N::Terminal: Copy + 'static,
+    V: 'static,
+    CallStack<N, V>: PartialEq
+{
+    fn eq(&self, other: &SubroutineNode<N, V>) -> bool {
+        self.machine == other.machine
+    }
+}
+
+impl<N, V> Debug for SubroutineNode<N, V> where
+    N: Resume<V> + Copy + 'static,
+    N::Input: 'static,
+    N::Terminal: Copy + 'static,
+    V: 'static,
+    CallStack<N, V>: Debug
+{
+    fn fmt(&self, fmter: &mut fmt::Formatter) -> fmt::Result {
+        fmter.debug_struct("SubroutineNode")
+            .field("machine", &self.machine)
+            .finish()
+    }
+}
+
+impl<N, V> SubroutineNode<N, V> where
+    N: Resume<V> + Copy + 'static,
+    N::Input: 'static,
+    N::Terminal: Copy + 'static,
+    V: 'static
+{
+    /// Create a new subroutine node with the given bottommost node. 
+    pub fn new(node: N) -> SubroutineNode<N, V> {
+        SubroutineNode {
+            machine: PushdownAutomaton::new(CallFrame::new(node))
+        }
+    }
+
+    /// Return the number of suspended callers. 
+    pub fn depth(&self) -> usize {
+        self.machine.stack_depth()
+    }
+
+    // Hand the terminal of a callee which just returned to its caller, 
+    // which is now at the top of the stack
+    fn return_to_caller(&mut self, returned: N::Terminal) {
+        match self.machine.peek_mut() {
+            Option::Some(caller) => caller.returned = Option::Some(returned),
+            Option::None => {
+                let bottom = self.machine.bottom_mut().expect("Call stack was poisoned");
+                bottom.returned = Option::Some(returned);
+            }
+        }
+    }
+}
+
+impl<N, V> BehaviorTreeNode for SubroutineNode<N, V> where
+    N: Resume<V> + Copy + 'static,
+    N::Input: 'static,
+    N::Terminal: Copy + 'static,
+    V: 'static
+{
+    type Input = N::Input;
+    type Nonterminal = V;
+    type Terminal = N::Terminal;
+
+    #[inline]
+    fn step(self, input: &N::Input) -> NodeResult<V, N::Terminal, Self> {
+        let mut mut_self = self;
+        loop {
+            match mut_self.machine.transition_status(input) {
+                PushdownStatus::Running(CallState::Running(v)) => {
+                    return NodeResult::Nonterminal(v, mut_self);
+                },
+                PushdownStatus::Running(CallState::Returned(t)) => {
+                    mut_self.return_to_caller(t);
+                },
+                PushdownStatus::Finished(CallState::Returned(t)) => {
+                    return NodeResult::Terminal(t);
+                },
+                PushdownStatus::Finished(CallState::Running(_)) => {
+                    unreachable!("The bottommost node finishes only by terminating")
+                }
+            }
+        }
+    }
+
+    #[inline]
+    fn halt(self, input: &N::Input) {
+        let (bottom, frames) = self.machine.into_parts();
+        for frame in frames.into_iter().rev() {
+            frame.halt(input);
+        }
+        if let Option::Some(frame) = bottom {
+            frame.halt(input);
+        }
+    }
+}
+
+impl<N, V> Snapshot for SubroutineNode<N, V> where
+    N: Resume<V> + Copy + Snapshot + 'static,
+    N::Input: 'static,
+    N::Terminal: Copy + 'static,
+    V: 'static
+{
+    type State = (Option<N::State>, Vec<N::State>);
+
+    fn snapshot(&self) -> (Option<N::State>, Vec<N::State>) {
+        self.machine.snapshot()
+    }
+
+    fn restore(state: (Option<N::State>, Vec<N::State>)) -> SubroutineNode<N, V> {
+        SubroutineNode {
+            machine: PushdownAutomaton::restore(state)
+        }
+    }
+}
+
+// Wrapper which resumes the wrapped node by reporting the value returned to 
+// it, so that a call stack node can run as a subroutine node
+#[derive(Copy, Clone, PartialEq, Debug)]
+struct Reporting<N>(N);
+
+impl<N, V> BehaviorTreeNode for Reporting<N> where
+    N: BehaviorTreeNode<Nonterminal=CallNonterm<N, V>>
+{
+    type Input = N::Input;
+    type Nonterminal = CallNonterm<Reporting<N>, CallState<V, N::Terminal>>;
+    type Terminal = N::Terminal;
+
+    #[inline]
+    fn step(self, input: &N::Input) -> NodeResult<Self::Nonterminal, N::Terminal, Self> {
+        match self.0.step(input) {
+            NodeResult::Nonterminal(CallNonterm::Continue(v), n) => NodeResult::Nonterminal(
+                CallNonterm::Continue(CallState::Running(v)), Reporting(n)),
+            NodeResult::Nonterminal(CallNonterm::Call(callee, v), n) => NodeResult::Nonterminal(
+                CallNonterm::Call(Reporting(callee), CallState::Running(v)), Reporting(n)),
+            NodeResult::Terminal(t) => NodeResult::Terminal(t)
+        }
+    }
+
+    #[inline]
+    fn halt(self, input: &N::Input) {
+        self.0.halt(input);
+    }
+}
+
+impl<N, V> Resume<CallState<V, N::Terminal>> for Reporting<N> where
+    N: BehaviorTreeNode<Nonterminal=CallNonterm<N, V>>
+{
+    fn resume(self, returned: N::Terminal) -> NodeResult<Self::Nonterminal, 
+        N::Terminal, Self> 
+    {
+        NodeResult::Nonterminal(CallNonterm::Continue(CallState::Returned(returned)), self)
+    }
+}
+
+impl<N> Snapshot for Reporting<N> where
+    N: Snapshot
+{
+    type State = N::State;
+
+    fn snapshot(&self) -> N::State {
+        self.0.snapshot()
+    }
+
+    fn restore(state: N::State) -> Reporting<N> {
+        Reporting(N::restore(state))
+    }
+}
+
+/// Node which runs nodes with call and return semantics, terminating when 
+/// the bottommost node terminates. 
+pub struct CallStackNode<N, V> where
+    N: BehaviorTreeNode<Nonterminal=CallNonterm<N, V>> + Copy + 'static,
+    N::Input: 'static,
+    N::Terminal: Copy + 'static,
+    V: 'static
+{
+    inner: SubroutineNode<Reporting<N>, CallState<V, N::Terminal>>
+}
+
+impl<N, V> Clone for CallStackNode<N, V> where
+    N: BehaviorTreeNode<Nonterminal=CallNonterm<N, V>> + Copy + 'static,
+    N::Input: 'static,
+    N::Terminal: Copy + 'static,
+    V: 'static,
+    SubroutineNode<Reporting<N>, CallState<V, N::Terminal>>: Clone
+{
+    fn clone(&self) -> CallStackNode<N, V> {
+        CallStackNode {
+            inner: self.inner.clone()
+        }
+    }
+}
+
+impl<N, V> PartialEq for CallStackNode<N, V> where
+    N: BehaviorTreeNode<Nonterminal=CallNonterm<N, V>> + Copy + 'static,
+    N::Input: 'static,
+    N::Terminal: Copy + 'static,
+    V: 'static,
+    SubroutineNode<Reporting<N>, CallState<V, N::Terminal>>: PartialEq
+{
+    fn eq(&self, other: &CallStackNode<N, V>) -> bool {
+        self.inner == other.inner
+    }
+}
+
+impl<N, V> Debug for CallStackNode<N, V> where
+    N: BehaviorTreeNode<Nonterminal=CallNonterm<N, V>> + Copy + 'static,
+    N::Input: 'static,
+    N::Terminal: Copy + 'static,
+    V: 'static,
+    SubroutineNode<Reporting<N>, CallState<V, N::Terminal>>: Debug
+{
+    fn fmt(&self, fmter: &mut fmt::Formatter) -> fmt::Result {
+        fmter.debug_struct("CallStackNode")
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+impl<N, V> CallStackNode<N, V> where
+    N: BehaviorTreeNode<Nonterminal=CallNonterm<N, V>> + Copy + 'static,
+    N::Input: 'static,
+    N::Terminal: Copy + 'static,
+    V: 'static
+{
+    /// Create a new call stack node with the given bottommost node. 
+    pub fn new(node: N) -> CallStackNode<N, V> {
+        CallStackNode {
+            inner: SubroutineNode::new(Reporting(node))
+        }
+    }
+
+    /// Return the number of suspended callers. 
+    pub fn depth(&self) -> usize {
+        self.inner.depth()
+    }
+}
+
+impl<N, V> BehaviorTreeNode for CallStackNode<N, V> where
+    N: BehaviorTreeNode<Nonterminal=CallNonterm<N, V>> + Copy + 'static,
+    N::Input: 'static,
+    N::Terminal: Copy + 'static,
+    V: 'static
+{
+    type Input = N::Input;
+    type Nonterminal = CallState<V, N::Terminal>;
+    type Terminal = N::Terminal;
+
+    #[inline]
+    fn step(self, input: &N::Input) -> NodeResult<Self::Nonterminal, N::Terminal, Self> {
+        match self.inner.step(input) {
+            NodeResult::Nonterminal(state, inner) => NodeResult::Nonterminal(state, 
+                CallStackNode {
+                    inner: inner
+                }),
+            NodeResult::Terminal(t) => NodeResult::Terminal(t)
+        }
+    }
+
+    #[inline]
+    fn halt(self, input: &N::Input) {
+        self.inner.halt(input);
+    }
+}
+
+impl<N, V> Snapshot for CallStackNode<N, V> where
+    N: BehaviorTreeNode<Nonterminal=CallNonterm<N, V>> + Copy + Snapshot + 'static,
+    N::Input: 'static,
+    N::Terminal: Copy + 'static,
+    V: 'static
+{
+    type State = (Option<N::State>, Vec<N::State>);
+
+    fn snapshot(&self) -> (Option<N::State>, Vec<N::State>) {
+        self.inner.snapshot()
+    }
+
+    fn restore(state: (Option<N::State>, Vec<N::State>)) -> CallStackNode<N, V> {
+        CallStackNode {
+            inner: SubroutineNode::restore(state)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use behavior_tree_node::{BehaviorTreeNode, NodeResult};
+    use call_node::{CallNonterm, CallState, CallStackNode, Resume, SubroutineNode};
+
+    // Patrol counts steps, calling Alert whenever the input is negative.
+    // Alert lasts until the input is positive again.
+    #[derive(Copy, Clone, PartialEq, Debug)]
+    enum Guard {
+        Patrol(i64),
+        Alert
+    }
+
+    impl BehaviorTreeNode for Guard {
+        type Input = i64;
+        type Nonterminal = CallNonterm<Guard, i64>;
+        type Terminal = i64;
+
+        fn step(self, input: &i64) -> NodeResult<CallNonterm<Guard, i64>, i64, Guard> {
+            match self {
+                Guard::Patrol(count) => if *input == 0 {
+                    NodeResult::Terminal(count)
+                } else if *input < 0 {
+                    NodeResult::Nonterminal(CallNonterm::Call(Guard::Alert, count),
+                        Guard::Patrol(count))
+                } else {
+                    NodeResult::Nonterminal(CallNonterm::Continue(count + 1),
+                        Guard::Patrol(count + 1))
+                },
+                Guard::Alert => if *input > 0 {
+                    NodeResult::Terminal(-1)
+                } else {
+                    NodeResult::Nonterminal(CallNonterm::Continue(-1), Guard::Alert)
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn call_return_test() {
+        let node = assert_steps!(CallStackNode::new(Guard::Patrol(0));
+            1 => Nonterminal(CallState::Running(1)),
+            -1 => Nonterminal(CallState::Running(1))
+        );
+        assert_eq!(node.depth(), 1);
+        let node = assert_steps!(node;
+            -1 => Nonterminal(CallState::Running(-1)),
+            1 => Nonterminal(CallState::Returned(-1))
+        );
+        assert_eq!(node.depth(), 0);
+        assert_steps!(node;
+            1 => Nonterminal(CallState::Running(2)),
+            0 => Terminal(2)
+        );
+    }
+
+    // Patrol walks from the origin to each distance given by the input by 
+    // calling GoTo with it, and sums up the distances walked, which GoTo 
+    // returns. 
+    #[derive(Copy, Clone, PartialEq, Debug)]
+    enum Walker {
+        Patrol(i64),
+        GoTo(i64, i64)
+    }
+
+    impl BehaviorTreeNode for Walker {
+        type Input = i64;
+        type Nonterminal = CallNonterm<Walker, i64>;
+        type Terminal = i64;
+
+        fn step(self, input: &i64) -> NodeResult<CallNonterm<Walker, i64>, i64, Walker> {
+            match self {
+                Walker::Patrol(total) => if *input < 0 {
+                    NodeResult::Terminal(total)
+                } else {
+                    NodeResult::Nonterminal(CallNonterm::Call(Walker::GoTo(0, *input), 
+                        total), Walker::Patrol(total))
+                },
+                Walker::GoTo(at, target) => if at == target {
+                    NodeResult::Terminal(at)
+                } else {
+                    NodeResult::Nonterminal(CallNonterm::Continue(at + 1), 
+                        Walker::GoTo(at + 1, target))
+                }
+            }
+        }
+    }
+
+    impl Resume<i64> for Walker {
+        fn resume(self, returned: i64) -> NodeResult<CallNonterm<Walker, i64>, i64, 
+            Walker> 
+        {
+            match self {
+                Walker::Patrol(total) => NodeResult::Nonterminal(
+                    CallNonterm::Continue(total + returned), Walker::Patrol(total + returned)),
+                Walker::GoTo(_, _) => unreachable!("GoTo does not call other nodes")
+            }
+        }
+    }
+
+    #[test]
+    fn subroutine_test() {
+        let node = assert_steps!(SubroutineNode::new(Walker::Patrol(0));
+            2 => Nonterminal(0),
+            -1 => Nonterminal(1),
+            -1 => Nonterminal(2)
+        );
+        assert_eq!(node.depth(), 1);
+        let node = assert_steps!(node;
+            -1 => Nonterminal(2),
+            1 => Nonterminal(2),
+            -1 => Nonterminal(1)
+        );
+        assert_eq!(node.machine.peek().and_then(|frame| frame.node), 
+            Option::Some(Walker::GoTo(1, 1)));
+        assert_eq!(node.machine.bottom().and_then(|frame| frame.node), 
+            Option::Some(Walker::Patrol(2)));
+        assert_steps!(node;
+            -1 => Nonterminal(3),
+            -1 => Terminal(3)
+        );
+    }
+}
@@ -0,0 +1,146 @@
+//! Isolation of panics in behavior tree nodes. 
+//!
+//! When one tree is stepped for each of hundreds of entities, a bug in a 
+//! single leaf that panics on some rare input would otherwise unwind 
+//! through the whole game tick. The node of this module catches panics of 
+//! its child with the standard library's unwinding machinery, and turns 
+//! them into a terminal of its own, so that the parent can fall back to 
+//! some other behavior while the rest of the tick goes on. 
+
+use behavior_tree_node::{BehaviorTreeNode, NodeResult};
+use tree_description::{DescribeTree, TreeDescription};
+use std::any::Any;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+/// Terminal of a panic-isolating node. 
+#[derive(Clone, PartialEq, Debug)]
+pub enum CaughtExit<T> {
+    /// The child terminated with the given value. 
+    Finished(T),
+    /// The child panicked, with the given message if the panic payload was 
+    /// a string. 
+    Panicked(Option<String>)
+}
+
+/// A panic-isolating wrapper for a node, which steps the node within 
+/// catch_unwind, and terminates with CaughtExit::Panicked if it panics. 
+/// Panics while halting the node are caught and discarded. 
+/// 
+/// The panic hook still runs before the panic is caught, so the panic 
+/// message is printed unless the hook is replaced, and panics are only 
+/// caught if the crate is built with unwinding panics. The child is 
+/// dropped when it panics, so no broken node is observed afterwards, but 
+/// inputs with interior mutability may be left halfway modified. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct CatchUnwindNode<N> where N: BehaviorTreeNode {
+    node: N
+}
+
+// Outcome of a step of the node under catch_unwind
+type Caught<N> = Result<NodeResult<<N as BehaviorTreeNode>::Nonterminal, 
+    <N as BehaviorTreeNode>::Terminal, N>, Box<dyn Any + Send>>;
+
+impl<N> CatchUnwindNode<N> where N: BehaviorTreeNode {
+    /// Create a new panic-isolating node. 
+    pub fn new(node: N) -> CatchUnwindNode<N> {
+        CatchUnwindNode {
+            node: node
+        }
+    }
+
+    fn caught(result: Caught<N>) -> NodeResult<N::Nonterminal, CaughtExit<N::Terminal>, 
+        Self> 
+    {
+        match result {
+            Result::Ok(NodeResult::Nonterminal(v, n)) => {
+                NodeResult::Nonterminal(v, CatchUnwindNode::new(n))
+            },
+            Result::Ok(NodeResult::Terminal(t)) => {
+                NodeResult::Terminal(CaughtExit::Finished(t))
+            },
+            Result::Err(payload) => {
+                NodeResult::Terminal(CaughtExit::Panicked(panic_message(payload)))
+            }
+        }
+    }
+}
+
+// Extract the message of a panic, which is usually a string literal or a
+// formatted string
+fn panic_message(payload: Box<dyn Any + Send>) -> Option<String> {
+    match payload.downcast::<String>() {
+        Result::Ok(message) => Option::Some(*message),
+        Result::Err(payload) => payload.downcast_ref::<&'static str>()
+            .map(|message| (*message).to_owned())
+    }
+}
+
+impl<N> BehaviorTreeNode for CatchUnwindNode<N> where N: BehaviorTreeNode {
+    type Input = N::Input;
+    type Nonterminal = N::Nonterminal;
+    type Terminal = CaughtExit<N::Terminal>;
+
+    #[inline]
+    fn step(self, input: &N::Input) -> NodeResult<N::Nonterminal,
+        CaughtExit<N::Terminal>, Self>
+    {
+        let node = self.node;
+        Self::caught(catch_unwind(AssertUnwindSafe(move || node.step(input))))
+    }
+
+    #[inline]
+    fn step_with(self, input: N::Input) -> NodeResult<N::Nonterminal,
+        CaughtExit<N::Terminal>, Self>
+    {
+        let node = self.node;
+        Self::caught(catch_unwind(AssertUnwindSafe(move || node.step_with(input))))
+    }
+
+    #[inline]
+    fn halt(self, input: &N::Input) {
+        let node = self.node;
+        let _ = catch_unwind(AssertUnwindSafe(move || node.halt(input)));
+    }
+}
+
+impl<N> DescribeTree for CatchUnwindNode<N> where N: DescribeTree {
+    fn describe_tree() -> TreeDescription {
+        TreeDescription::branch::<Self>("CatchUnwindNode", vec![
+            ("node", N::describe_tree())
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use behavior_tree_node::Statepoint;
+    use base_nodes::PredicateWait;
+    use catch_unwind_node::{CatchUnwindNode, CaughtExit};
+
+    fn fragile(i: &i64) -> Statepoint<i64, i64> {
+        if *i < 0 {
+            panic!("Negative input {}", i);
+        } else if *i > 0 {
+            Statepoint::Nonterminal(*i)
+        } else {
+            Statepoint::Terminal(*i)
+        }
+    }
+
+    #[test]
+    fn finished_test() {
+        assert_steps!(CatchUnwindNode::new(PredicateWait::new(fragile));
+            3 => Nonterminal(3),
+            0 => Terminal(CaughtExit::Finished(0))
+        );
+    }
+
+    #[test]
+    fn panicked_test() {
+        let message = Option::Some("Negative input -2".to_owned());
+        assert_steps!(CatchUnwindNode::new(PredicateWait::new(fragile));
+            1 => Nonterminal(1),
+            -2 => Terminal(CaughtExit::Panicked(ref m)) if *m == message
+        );
+    }
+}
@@ -1,4 +1,9 @@
 use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+use bt_status::BtStatus;
+#[cfg(feature = "compositions")]
+use node_compositions::{Classify, Outcome};
+use restart_node::{Restart, RestartPolicy, RestartNode};
+use stackbt_automata_impl::snapshot::Snapshot;
 
 #[derive(Copy, Clone, PartialEq, Debug)]
 pub struct GuardFailure<N>(pub N); 
@@ -35,6 +40,8 @@ impl<N, G> BehaviorTreeNode for GuardedNode<N, G> where
     type Nonterminal = N::Nonterminal;
     type Terminal = Result<N::Terminal, GuardFailure<N::Nonterminal>>;
 
+    // The guard reads the input after the node is stepped, so step_with 
+    // keeps lending the input instead of passing it on
     #[inline]
     fn step(self, input: &N::Input) -> NodeResult<N::Nonterminal, 
         Self::Terminal, Self> 
@@ -44,6 +51,7 @@ impl<N, G> BehaviorTreeNode for GuardedNode<N, G> where
                 if (self.guard)(input, &n) {
                     NodeResult::Nonterminal(n, GuardedNode::new(self.guard, m))
                 } else {
+                    m.halt(input);
                     NodeResult::Terminal(Result::Err(GuardFailure(n)))
                 }
             },
@@ -52,171 +60,1264 @@ impl<N, G> BehaviorTreeNode for GuardedNode<N, G> where
             )
         }
     }
+
+    #[inline]
+    fn halt(self, input: &N::Input) {
+        self.node.halt(input);
+    }
+}
+
+/// Failure of a context guarded node, carrying the nonterminal the guard 
+/// rejected along with context about the failure. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct GuardFailureContext<N, C> {
+    /// The nonterminal rejected by the guard. 
+    pub nonterminal: N,
+    /// The projection of the input the guard rejected the nonterminal for. 
+    pub context: C,
+    /// The number of steps the guarded node took, including the one in 
+    /// which the guard failed. 
+    pub ticks: u64
+}
+
+/// Guard wrapper for a node like GuardedNode, which on failure of the guard 
+/// also captures a projection of the offending input and the number of 
+/// steps taken, so that the parent node can tell why the guard tripped. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct ContextGuardedNode<N, G, P, C> where
+    N: BehaviorTreeNode,
+    G: Fn(&N::Input, &N::Nonterminal) -> bool,
+    P: Fn(&N::Input) -> C
+{
+    node: N,
+    guard: G,
+    projection: P,
+    ticks: u64
+}
+
+impl<N, G, P, C> ContextGuardedNode<N, G, P, C> where 
+    N: BehaviorTreeNode,
+    G: Fn(&N::Input, &N::Nonterminal) -> bool,
+    P: Fn(&N::Input) -> C
+{
+    /// Create a new context guarded node, which captures the given 
+    /// projection of the input on failure. 
+    pub fn new(guard: G, projection: P, node: N) -> ContextGuardedNode<N, G, P, C> {
+        ContextGuardedNode {
+            node: node,
+            guard: guard,
+            projection: projection,
+            ticks: 0
+        }
+    }
+}
+
+impl<N, G> ContextGuardedNode<N, G, fn(&N::Input) -> N::Input, N::Input> where 
+    N: BehaviorTreeNode,
+    N::Input: Clone,
+    G: Fn(&N::Input, &N::Nonterminal) -> bool
+{
+    /// Create a new context guarded node, which captures a clone of the 
+    /// input on failure. 
+    pub fn cloning(guard: G, node: N) -> Self {
+        ContextGuardedNode::new(guard, N::Input::clone, node)
+    }
+}
+
+impl<N, G, P, C> BehaviorTreeNode for ContextGuardedNode<N, G, P, C> where
+    N: BehaviorTreeNode,
+    G: Fn(&N::Input, &N::Nonterminal) -> bool,
+    P: Fn(&N::Input) -> C
+{
+    type Input = N::Input;
+    type Nonterminal = N::Nonterminal;
+    type Terminal = Result<N::Terminal, GuardFailureContext<N::Nonterminal, C>>;
+
+    // As with the guarded node, the guard reads the input after the node is 
+    // stepped, so step_with keeps lending the input
+    #[inline]
+    fn step(self, input: &N::Input) -> NodeResult<N::Nonterminal, 
+        Self::Terminal, Self> 
+    {
+        let ticks = self.ticks + 1;
+        match self.node.step(input) {
+            NodeResult::Nonterminal(n, m) => {
+                if (self.guard)(input, &n) {
+                    NodeResult::Nonterminal(n, ContextGuardedNode {
+                        node: m,
+                        guard: self.guard,
+                        projection: self.projection,
+                        ticks: ticks
+                    })
+                } else {
+                    m.halt(input);
+                    NodeResult::Terminal(Result::Err(GuardFailureContext {
+                        nonterminal: n,
+                        context: (self.projection)(input),
+                        ticks: ticks
+                    }))
+                }
+            },
+            NodeResult::Terminal(t) => NodeResult::Terminal(
+                Result::Ok(t)
+            )
+        }
+    }
+
+    #[inline]
+    fn halt(self, input: &N::Input) {
+        self.node.halt(input);
+    }
+}
+
+/// Trait for terminal types whose success or failure can be swapped. 
+pub trait Invert {
+    /// The type of the inverted terminal. 
+    type Inverted;
+
+    /// Turn a success into a failure, and a failure into a success. 
+    fn invert(self) -> Self::Inverted;
+}
+
+impl Invert for bool {
+    type Inverted = bool;
+
+    fn invert(self) -> bool {
+        !self
+    }
+}
+
+impl Invert for BtStatus {
+    type Inverted = BtStatus;
+
+    fn invert(self) -> BtStatus {
+        match self {
+            BtStatus::Success => BtStatus::Failure,
+            BtStatus::Failure => BtStatus::Success
+        }
+    }
+}
+
+#[cfg(feature = "compositions")]
+impl Invert for Outcome {
+    type Inverted = Outcome;
+
+    fn invert(self) -> Outcome {
+        match self {
+            Outcome::Success => Outcome::Failure,
+            Outcome::Failure => Outcome::Success
+        }
+    }
+}
+
+impl<S, F> Invert for Result<S, F> {
+    type Inverted = Result<F, S>;
+
+    fn invert(self) -> Result<F, S> {
+        match self {
+            Result::Ok(s) => Result::Err(s),
+            Result::Err(f) => Result::Ok(f)
+        }
+    }
+}
+
+impl<T> Invert for Option<T> {
+    type Inverted = Result<(), T>;
+
+    fn invert(self) -> Result<(), T> {
+        match self {
+            Option::Some(t) => Result::Err(t),
+            Option::None => Result::Ok(())
+        }
+    }
+}
+
+/// Trait for terminal types which can be forced to a success or a failure. 
+/// Terminals which are only a status are overwritten, while terminals 
+/// carrying values are kept whole, wrapped in Ok or Err. 
+pub trait Force {
+    /// The type of the forced terminal. 
+    type Forced;
+
+    /// Force the terminal to a success. 
+    fn succeed(self) -> Self::Forced;
+
+    /// Force the terminal to a failure. 
+    fn fail(self) -> Self::Forced;
+}
+
+impl Force for bool {
+    type Forced = bool;
+
+    fn succeed(self) -> bool {
+        true
+    }
+
+    fn fail(self) -> bool {
+        false
+    }
+}
+
+impl Force for BtStatus {
+    type Forced = BtStatus;
+
+    fn succeed(self) -> BtStatus {
+        BtStatus::Success
+    }
+
+    fn fail(self) -> BtStatus {
+        BtStatus::Failure
+    }
+}
+
+#[cfg(feature = "compositions")]
+impl Force for Outcome {
+    type Forced = Outcome;
+
+    fn succeed(self) -> Outcome {
+        Outcome::Success
+    }
+
+    fn fail(self) -> Outcome {
+        Outcome::Failure
+    }
+}
+
+impl<S, F> Force for Result<S, F> {
+    type Forced = Result<Result<S, F>, Result<S, F>>;
+
+    fn succeed(self) -> Self::Forced {
+        Result::Ok(self)
+    }
+
+    fn fail(self) -> Self::Forced {
+        Result::Err(self)
+    }
+}
+
+impl<T> Force for Option<T> {
+    type Forced = Result<Option<T>, Option<T>>;
+
+    fn succeed(self) -> Self::Forced {
+        Result::Ok(self)
+    }
+
+    fn fail(self) -> Self::Forced {
+        Result::Err(self)
+    }
+}
+
+/// Inverting wrapper for a node, which turns the successes of the node 
+/// into failures, and its failures into successes. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct InverterNode<N> where 
+    N: BehaviorTreeNode,
+    N::Terminal: Invert
+{
+    node: N
+}
+
+impl<N> InverterNode<N> where 
+    N: BehaviorTreeNode,
+    N::Terminal: Invert
+{
+    /// Create a new inverting node. 
+    pub fn new(node: N) -> InverterNode<N> {
+        InverterNode {
+            node: node
+        }
+    }
+}
+
+impl<N> BehaviorTreeNode for InverterNode<N> where 
+    N: BehaviorTreeNode,
+    N::Terminal: Invert
+{
+    type Input = N::Input;
+    type Nonterminal = N::Nonterminal;
+    type Terminal = <N::Terminal as Invert>::Inverted;
+
+    #[inline]
+    fn step(self, input: &N::Input) -> NodeResult<N::Nonterminal, 
+        Self::Terminal, Self> 
+    {
+        match self.node.step(input) {
+            NodeResult::Nonterminal(v, n) => NodeResult::Nonterminal(
+                v, 
+                InverterNode::new(n)
+            ),
+            NodeResult::Terminal(t) => NodeResult::Terminal(t.invert())
+        }
+    }
+
+    #[inline]
+    fn step_with(self, input: N::Input) -> NodeResult<N::Nonterminal, 
+        Self::Terminal, Self> 
+    {
+        match self.node.step_with(input) {
+            NodeResult::Nonterminal(v, n) => NodeResult::Nonterminal(
+                v, 
+                InverterNode::new(n)
+            ),
+            NodeResult::Terminal(t) => NodeResult::Terminal(t.invert())
+        }
+    }
+
+    #[inline]
+    fn halt(self, input: &N::Input) {
+        self.node.halt(input);
+    }
+}
+
+impl<N> Snapshot for InverterNode<N> where 
+    N: BehaviorTreeNode + Snapshot,
+    N::Terminal: Invert
+{
+    type State = N::State;
+
+    fn snapshot(&self) -> N::State {
+        self.node.snapshot()
+    }
+
+    fn restore(state: N::State) -> InverterNode<N> {
+        InverterNode::new(N::restore(state))
+    }
+}
+
+/// Wrapper for a node, which reports whatever the node terminates with as 
+/// a success. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct AlwaysSucceed<N> where 
+    N: BehaviorTreeNode,
+    N::Terminal: Force
+{
+    node: N
+}
+
+impl<N> AlwaysSucceed<N> where 
+    N: BehaviorTreeNode,
+    N::Terminal: Force
+{
+    /// Create a new always succeeding node. 
+    pub fn new(node: N) -> AlwaysSucceed<N> {
+        AlwaysSucceed {
+            node: node
+        }
+    }
+}
+
+impl<N> BehaviorTreeNode for AlwaysSucceed<N> where 
+    N: BehaviorTreeNode,
+    N::Terminal: Force
+{
+    type Input = N::Input;
+    type Nonterminal = N::Nonterminal;
+    type Terminal = <N::Terminal as Force>::Forced;
+
+    #[inline]
+    fn step(self, input: &N::Input) -> NodeResult<N::Nonterminal, 
+        Self::Terminal, Self> 
+    {
+        match self.node.step(input) {
+            NodeResult::Nonterminal(v, n) => NodeResult::Nonterminal(
+                v, 
+                AlwaysSucceed::new(n)
+            ),
+            NodeResult::Terminal(t) => NodeResult::Terminal(t.succeed())
+        }
+    }
+
+    #[inline]
+    fn step_with(self, input: N::Input) -> NodeResult<N::Nonterminal, 
+        Self::Terminal, Self> 
+    {
+        match self.node.step_with(input) {
+            NodeResult::Nonterminal(v, n) => NodeResult::Nonterminal(
+                v, 
+                AlwaysSucceed::new(n)
+            ),
+            NodeResult::Terminal(t) => NodeResult::Terminal(t.succeed())
+        }
+    }
+
+    #[inline]
+    fn halt(self, input: &N::Input) {
+        self.node.halt(input);
+    }
+}
+
+impl<N> Snapshot for AlwaysSucceed<N> where 
+    N: BehaviorTreeNode + Snapshot,
+    N::Terminal: Force
+{
+    type State = N::State;
+
+    fn snapshot(&self) -> N::State {
+        self.node.snapshot()
+    }
+
+    fn restore(state: N::State) -> AlwaysSucceed<N> {
+        AlwaysSucceed::new(N::restore(state))
+    }
+}
+
+/// Wrapper for a node, which reports whatever the node terminates with as 
+/// a failure. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct AlwaysFail<N> where 
+    N: BehaviorTreeNode,
+    N::Terminal: Force
+{
+    node: N
+}
+
+impl<N> AlwaysFail<N> where 
+    N: BehaviorTreeNode,
+    N::Terminal: Force
+{
+    /// Create a new always failing node. 
+    pub fn new(node: N) -> AlwaysFail<N> {
+        AlwaysFail {
+            node: node
+        }
+    }
+}
+
+impl<N> BehaviorTreeNode for AlwaysFail<N> where 
+    N: BehaviorTreeNode,
+    N::Terminal: Force
+{
+    type Input = N::Input;
+    type Nonterminal = N::Nonterminal;
+    type Terminal = <N::Terminal as Force>::Forced;
+
+    #[inline]
+    fn step(self, input: &N::Input) -> NodeResult<N::Nonterminal, 
+        Self::Terminal, Self> 
+    {
+        match self.node.step(input) {
+            NodeResult::Nonterminal(v, n) => NodeResult::Nonterminal(
+                v, 
+                AlwaysFail::new(n)
+            ),
+            NodeResult::Terminal(t) => NodeResult::Terminal(t.fail())
+        }
+    }
+
+    #[inline]
+    fn step_with(self, input: N::Input) -> NodeResult<N::Nonterminal, 
+        Self::Terminal, Self> 
+    {
+        match self.node.step_with(input) {
+            NodeResult::Nonterminal(v, n) => NodeResult::Nonterminal(
+                v, 
+                AlwaysFail::new(n)
+            ),
+            NodeResult::Terminal(t) => NodeResult::Terminal(t.fail())
+        }
+    }
+
+    #[inline]
+    fn halt(self, input: &N::Input) {
+        self.node.halt(input);
+    }
+}
+
+impl<N> Snapshot for AlwaysFail<N> where 
+    N: BehaviorTreeNode + Snapshot,
+    N::Terminal: Force
+{
+    type State = N::State;
+
+    fn snapshot(&self) -> N::State {
+        self.node.snapshot()
+    }
+
+    fn restore(state: N::State) -> AlwaysFail<N> {
+        AlwaysFail::new(N::restore(state))
+    }
+}
+
+/// Enumeration of the possible decisions of a StepControl controller.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum StepDecision<N> {
+    /// Don't step the machine. 
+    Pause, 
+    /// Step the machine as normal. 
+    Play, 
+    /// Dispose the current machine, and initialize a new one in its place. 
+    Reset(N), 
+    /// Reset the machine, and then subsequently step it. 
+    ResetPlay(N)
+}
+
+/// Nonterminal enum for a step-controlled node. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum StepCtrlNonterm<I> {
+    /// The node was stepped as normal, perhaps after resetting it. 
+    Stepped(I),
+    /// The node was paused, and maybe reset. 
+    Paused
+}
+
+/// A step-controlling wrapper for a node, which may pause, step, and/or 
+/// reset a node depending on inputs, before the node goes forward. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct StepControlledNode<N, S> where 
+    N: BehaviorTreeNode,
+    S: Fn(&N::Input) -> StepDecision<N>
+{
+    node: N,
+    stepper: S
+}
+
+impl<N, S> StepControlledNode<N, S> where 
+    N: BehaviorTreeNode,
+    S: Fn(&N::Input) -> StepDecision<N>
+{
+    /// Create a new step controlled node. 
+    pub fn new(stepper: S, node: N) -> StepControlledNode<N, S> {
+        StepControlledNode {
+            node: node,
+            stepper: stepper
+        }
+    }
+
+    fn stepped(stepper: S, result: NodeResult<N::Nonterminal, N::Terminal, N>) -> 
+        NodeResult<StepCtrlNonterm<N::Nonterminal>, N::Terminal, Self> 
+    {
+        match result {
+            NodeResult::Nonterminal(n, m) => NodeResult::Nonterminal(
+                StepCtrlNonterm::Stepped(n), 
+                Self::new(stepper, m)
+            ),
+            NodeResult::Terminal(t) => NodeResult::Terminal(t)
+        }
+    }
+}
+
+impl<N, S> BehaviorTreeNode for StepControlledNode<N, S> where 
+    N: BehaviorTreeNode,
+    S: Fn(&N::Input) -> StepDecision<N>
+{
+    type Input = N::Input;
+    type Nonterminal = StepCtrlNonterm<N::Nonterminal>;
+    type Terminal = N::Terminal;
+    
+    #[inline]
+    fn step(self, input: &N::Input) -> NodeResult<Self::Nonterminal, 
+        N::Terminal, Self> 
+    {
+        match (self.stepper)(input) {
+            StepDecision::Pause => {
+                NodeResult::Nonterminal(StepCtrlNonterm::Paused, self)
+            },
+            StepDecision::Play => {
+                let result = self.node.step(input);
+                Self::stepped(self.stepper, result)
+            },
+            StepDecision::Reset(new_node) => {
+                self.node.halt(input);
+                NodeResult::Nonterminal(StepCtrlNonterm::Paused, Self::new(
+                    self.stepper,
+                    new_node
+                ))
+            },
+            StepDecision::ResetPlay(new_machine) => {
+                self.node.halt(input);
+                Self::stepped(self.stepper, new_machine.step(input))
+            }
+        }
+    }
+
+    #[inline]
+    fn step_with(self, input: N::Input) -> NodeResult<Self::Nonterminal, 
+        N::Terminal, Self> 
+    {
+        match (self.stepper)(&input) {
+            StepDecision::Pause => {
+                NodeResult::Nonterminal(StepCtrlNonterm::Paused, self)
+            },
+            StepDecision::Play => {
+                let result = self.node.step_with(input);
+                Self::stepped(self.stepper, result)
+            },
+            StepDecision::Reset(new_node) => {
+                self.node.halt(&input);
+                NodeResult::Nonterminal(StepCtrlNonterm::Paused, Self::new(
+                    self.stepper,
+                    new_node
+                ))
+            },
+            StepDecision::ResetPlay(new_machine) => {
+                self.node.halt(&input);
+                Self::stepped(self.stepper, new_machine.step_with(input))
+            }
+        }
+    }
+
+    #[inline]
+    fn halt(self, input: &N::Input) {
+        self.node.halt(input);
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum PostResetNonterm<N, T> {
+    /// The node was not reset. 
+    NoReset(N),
+    /// The node was reset from a nonterminal state. 
+    ManualReset(N),
+    /// The node was reset from a terminal state. 
+    EndReset(T)
+}
+
+/// A post-run resetting wrapper for a node, which may reset a node after 
+/// it runs. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct PostResetNode<N, P> where 
+    N: BehaviorTreeNode,
+    P: Fn(&N::Input, Statepoint<&N::Nonterminal, &N::Terminal>) -> Option<N>
+{
+    node: N,
+    resetter: P
+}
+
+impl<N, P> PostResetNode<N, P> where 
+    N: BehaviorTreeNode,
+    P: Fn(&N::Input, Statepoint<&N::Nonterminal, &N::Terminal>) -> Option<N>
+{
+    /// Create a new step controlled node. 
+    pub fn new(resetter: P, node: N) -> PostResetNode<N, P> {
+        PostResetNode {
+            node: node,
+            resetter: resetter
+        }
+    }
+}
+
+impl <N, P> BehaviorTreeNode for PostResetNode<N, P> where 
+    N: BehaviorTreeNode,
+    P: Fn(&N::Input, Statepoint<&N::Nonterminal, &N::Terminal>) -> Option<N>
+{
+    type Input = N::Input;
+    type Nonterminal = PostResetNonterm<N::Nonterminal, N::Terminal>;
+    type Terminal = N::Terminal;
+
+    // The resetter reads the input after the node is stepped, so step_with 
+    // keeps lending the input instead of passing it on
+    #[inline]
+    fn step(self, input: &N::Input) -> NodeResult<Self::Nonterminal, 
+        N::Terminal, Self> 
+    {
+        match self.node.step(input) {
+            NodeResult::Nonterminal(v, n) => {
+                match (self.resetter)(input, Statepoint::Nonterminal(&v)) {
+                    Option::Some(k) => {
+                        n.halt(input);
+                        NodeResult::Nonterminal(
+                            PostResetNonterm::ManualReset(v),
+                            Self::new(self.resetter, k)
+                        )
+                    },
+                    Option::None => NodeResult::Nonterminal(
+                        PostResetNonterm::NoReset(v),
+                        Self::new(self.resetter, n)
+                    )
+                }
+            },
+            NodeResult::Terminal(t) => {
+                match (self.resetter)(input, Statepoint::Terminal(&t)) {
+                    Option::Some(n) => NodeResult::Nonterminal(
+                        PostResetNonterm::EndReset(t),
+                        Self::new(self.resetter, n)
+                    ),
+                    Option::None => NodeResult::Terminal(t)
+                }
+            }
+        }
+    }
+
+    #[inline]
+    fn halt(self, input: &N::Input) {
+        self.node.halt(input);
+    }
+}
+
+/// Reason for a restarting node to rebuild its child. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum RestartCause<T> {
+    /// The child terminated with the given value. 
+    Terminated(T),
+    /// The reset condition fired on the input. 
+    Reset
+}
+
+/// Restart policy of a restarting node, which rebuilds the node from a 
+/// factory whenever it terminates, or whenever the reset condition fires. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct FactoryRestart<F, R> {
+    factory: F,
+    reset: R
+}
+
+impl<N, F, R> RestartPolicy<N> for FactoryRestart<F, R> where 
+    N: BehaviorTreeNode,
+    F: Fn(RestartCause<&N::Terminal>) -> N,
+    R: Fn(&N::Input) -> bool
+{
+    type Exit = ();
+
+    fn node(&mut self, input: &N::Input, current: Option<N>) -> N {
+        let node = current.expect("Restarting node was left without a node");
+        if (self.reset)(input) {
+            node.halt(input);
+            (self.factory)(RestartCause::Reset)
+        } else {
+            node
+        }
+    }
+
+    fn restart(&mut self, terminal: N::Terminal) -> Restart<N, N::Terminal, ()> {
+        let next = (self.factory)(RestartCause::Terminated(&terminal));
+        Restart::Rebuild(terminal, next)
+    }
+}
+
+/// A restarting wrapper for a node, which rebuilds the node from a factory 
+/// whenever it terminates, or whenever the reset condition fires on the 
+/// input. The factory is given the terminal the node ended with, so that 
+/// the new run can pick up where the last one ended. As the wrapper never 
+/// terminates itself, it reports the terminals of its node as nonterminals. 
+pub type RestartingNode<N, F, R> = RestartNode<N, FactoryRestart<F, R>>;
+
+impl<N, F> RestartingNode<N, F, fn(&N::Input) -> bool> where 
+    N: BehaviorTreeNode,
+    F: Fn(RestartCause<&N::Terminal>) -> N
+{
+    /// Create a new restarting node, which rebuilds the node only when it 
+    /// terminates. 
+    pub fn new(factory: F, node: N) -> Self {
+        RestartingNode::with_reset(factory, |_| false, node)
+    }
+}
+
+impl<N, F, R> RestartingNode<N, F, R> where 
+    N: BehaviorTreeNode,
+    F: Fn(RestartCause<&N::Terminal>) -> N,
+    R: Fn(&N::Input) -> bool
+{
+    /// Create a new restarting node, which also rebuilds the node before 
+    /// stepping it whenever the reset condition holds for the input. 
+    pub fn with_reset(factory: F, reset: R, node: N) -> RestartingNode<N, F, R> {
+        RestartNode::with_policy(FactoryRestart {
+            factory: factory,
+            reset: reset
+        }, node)
+    }
+}
+
+/// Terminal of a repeating node, summarizing its run. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct RepeatSummary<T> {
+    /// The number of iterations the node ran to completion. 
+    pub iterations: u64,
+    /// The terminal of the final iteration. 
+    pub last: T
+}
+
+/// Restart policy of a repeating node, which rebuilds the node from a 
+/// factory until it has run for a set number of iterations. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct RepeatPolicy<F> {
+    factory: F,
+    limit: Option<u64>,
+    iterations: u64
+}
+
+impl<N, F> RestartPolicy<N> for RepeatPolicy<F> where 
+    N: BehaviorTreeNode,
+    F: Fn(&N::Terminal) -> N
+{
+    type Exit = RepeatSummary<N::Terminal>;
+
+    fn restart(&mut self, terminal: N::Terminal) -> Restart<N, N::Terminal, 
+        RepeatSummary<N::Terminal>> 
+    {
+        self.iterations = self.iterations.saturating_add(1);
+        match self.limit {
+            Option::Some(limit) if self.iterations >= limit => {
+                Restart::Exit(RepeatSummary {
+                    iterations: self.iterations,
+                    last: terminal
+                })
+            },
+            _ => {
+                let next = (self.factory)(&terminal);
+                Restart::Rebuild(terminal, next)
+            }
+        }
+    }
+}
+
+/// A repeating wrapper for a node, which rebuilds the node from a factory 
+/// each time it terminates, until it has run for a set number of 
+/// iterations, or indefinitely if no limit is set. The factory is given the 
+/// terminal of the iteration that just ended. The terminals of all but the 
+/// final iteration are reported as nonterminals, and the wrapper terminates 
+/// with a summary after the final iteration. 
+pub type RepeatNode<N, F> = RestartNode<N, RepeatPolicy<F>>;
+
+impl<N, F> RepeatNode<N, F> where 
+    N: BehaviorTreeNode,
+    F: Fn(&N::Terminal) -> N
+{
+    /// Create a new repeating node, which terminates after the node has run 
+    /// for the given number of iterations. The node always runs at least 
+    /// once, so a limit of zero acts as a limit of one. 
+    pub fn new(limit: u64, factory: F, node: N) -> RepeatNode<N, F> {
+        RestartNode::with_policy(RepeatPolicy {
+            factory: factory,
+            limit: Option::Some(limit),
+            iterations: 0
+        }, node)
+    }
+
+    /// Create a new repeating node, which repeats the node indefinitely. 
+    pub fn unbounded(factory: F, node: N) -> RepeatNode<N, F> {
+        RestartNode::with_policy(RepeatPolicy {
+            factory: factory,
+            limit: Option::None,
+            iterations: 0
+        }, node)
+    }
+
+    /// Return the number of iterations completed so far. 
+    pub fn iterations(&self) -> u64 {
+        self.policy().iterations
+    }
+}
+
+/// Clock of a clocked node, which reads the time from each input the node 
+/// is stepped with. 
+pub trait Clock<I> {
+    /// Time read from the clock. 
+    type Time: PartialOrd;
+
+    /// Read the time, given the input the node is about to be stepped with. 
+    fn now(&mut self, input: &I) -> Self::Time;
+}
+
+/// Clock which reads an absolute time from each input, such as a tick 
+/// number or a timestamp carried in the input. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct InputClock<C>(pub C);
+
+impl<I, C, D> Clock<I> for InputClock<C> where 
+    C: Fn(&I) -> D,
+    D: PartialOrd
+{
+    type Time = D;
+
+    fn now(&mut self, input: &I) -> D {
+        (self.0)(input)
+    }
+}
+
+/// Clock which adds up the time passed since the previous step, as read 
+/// from each input, such as a frame time, or one per step. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct TickClock<C> {
+    delta: C,
+    elapsed: u64
+}
+
+impl<C> TickClock<C> {
+    /// Create a new tick clock from the time passed per step. 
+    pub fn new(delta: C) -> TickClock<C> {
+        TickClock {
+            delta: delta,
+            elapsed: 0
+        }
+    }
+
+    /// Return the time elapsed so far. 
+    pub fn elapsed(&self) -> u64 {
+        self.elapsed
+    }
+}
+
+impl<I, C> Clock<I> for TickClock<C> where C: Fn(&I) -> u64 {
+    type Time = u64;
+
+    fn now(&mut self, input: &I) -> u64 {
+        self.elapsed = self.elapsed.saturating_add((self.delta)(input));
+        self.elapsed
+    }
+}
+
+/// Terminal of a clocked node. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum DeadlineExit<T, D> {
+    /// The node terminated with the given value before the deadline, on 
+    /// the step at the given time. 
+    Finished(T, D),
+    /// The deadline passed before the node terminated, and the node was 
+    /// aborted at the given time. 
+    DeadlinePassed(D)
+}
+
+/// A timekeeping wrapper for a node, which reads its clock before each 
+/// step of the node, and aborts the node once the time is past the 
+/// deadline, if it has one. A node is never stepped once it has run past 
+/// its deadline. The deadline, time limited and timed nodes are clocked 
+/// nodes with particular clocks. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct ClockedNode<N, K> where 
+    N: BehaviorTreeNode,
+    K: Clock<N::Input>
+{
+    node: N,
+    clock: K,
+    deadline: Option<K::Time>
+}
+
+impl<N, K> ClockedNode<N, K> where 
+    N: BehaviorTreeNode,
+    K: Clock<N::Input>
+{
+    /// Create a new clocked node, which aborts the node once the clock 
+    /// reads past the deadline. 
+    pub fn limited(clock: K, deadline: K::Time, node: N) -> ClockedNode<N, K> {
+        ClockedNode {
+            node: node,
+            clock: clock,
+            deadline: Option::Some(deadline)
+        }
+    }
+
+    /// Create a new clocked node without a deadline, which only keeps time. 
+    pub fn unlimited(clock: K, node: N) -> ClockedNode<N, K> {
+        ClockedNode {
+            node: node,
+            clock: clock,
+            deadline: Option::None
+        }
+    }
+
+    /// Return the clock of the node. 
+    pub fn clock(&self) -> &K {
+        &self.clock
+    }
+
+    /// Return the deadline of the node, if it has one. 
+    pub fn time_limit(&self) -> Option<&K::Time> {
+        self.deadline.as_ref()
+    }
+
+    // Read the clock, and whether the deadline has passed
+    fn read(&mut self, input: &N::Input) -> (K::Time, bool) {
+        let now = self.clock.now(input);
+        let expired = match self.deadline {
+            Option::Some(ref deadline) => now > *deadline,
+            Option::None => false
+        };
+        (now, expired)
+    }
+
+    fn advance(clock: K, deadline: Option<K::Time>, now: K::Time, 
+        result: NodeResult<N::Nonterminal, N::Terminal, N>) -> 
+        NodeResult<N::Nonterminal, DeadlineExit<N::Terminal, K::Time>, Self> 
+    {
+        match result {
+            NodeResult::Nonterminal(v, n) => NodeResult::Nonterminal(
+                v, 
+                ClockedNode {
+                    node: n,
+                    clock: clock,
+                    deadline: deadline
+                }
+            ),
+            NodeResult::Terminal(t) => NodeResult::Terminal(DeadlineExit::Finished(t, now))
+        }
+    }
+}
+
+impl<N, K> BehaviorTreeNode for ClockedNode<N, K> where 
+    N: BehaviorTreeNode,
+    K: Clock<N::Input>
+{
+    type Input = N::Input;
+    type Nonterminal = N::Nonterminal;
+    type Terminal = DeadlineExit<N::Terminal, K::Time>;
+
+    #[inline]
+    fn step(self, input: &N::Input) -> NodeResult<N::Nonterminal, 
+        Self::Terminal, Self> 
+    {
+        let mut mut_self = self;
+        let (now, expired) = mut_self.read(input);
+        if expired {
+            mut_self.node.halt(input);
+            return NodeResult::Terminal(DeadlineExit::DeadlinePassed(now));
+        }
+        let ClockedNode { node, clock, deadline } = mut_self;
+        Self::advance(clock, deadline, now, node.step(input))
+    }
+
+    #[inline]
+    fn step_with(self, input: N::Input) -> NodeResult<N::Nonterminal, 
+        Self::Terminal, Self> 
+    {
+        let mut mut_self = self;
+        let (now, expired) = mut_self.read(&input);
+        if expired {
+            mut_self.node.halt(&input);
+            return NodeResult::Terminal(DeadlineExit::DeadlinePassed(now));
+        }
+        let ClockedNode { node, clock, deadline } = mut_self;
+        Self::advance(clock, deadline, now, node.step_with(input))
+    }
+
+    #[inline]
+    fn halt(self, input: &N::Input) {
+        self.node.halt(input);
+    }
+}
+
+/// A deadline wrapper for a node, which aborts the node once the time read 
+/// from the input by the clock is past an absolute deadline. The time may 
+/// be anything ordered, such as a tick number or a timestamp carried in the 
+/// input. 
+pub type DeadlineNode<N, C> = ClockedNode<N, InputClock<C>>;
+
+impl<N, C, D> DeadlineNode<N, C> where 
+    N: BehaviorTreeNode,
+    C: Fn(&N::Input) -> D,
+    D: PartialOrd
+{
+    /// Create a new deadline node. 
+    pub fn new(clock: C, deadline: D, node: N) -> DeadlineNode<N, C> {
+        ClockedNode::limited(InputClock(clock), deadline, node)
+    }
+
+    /// Return the deadline of the node. 
+    pub fn deadline(&self) -> &D {
+        self.time_limit().expect("Deadline node was built without a deadline")
+    }
 }
 
-/// Enumeration of the possible decisions of a StepControl controller.
-#[derive(Copy, Clone, PartialEq, Debug)]
-pub enum StepDecision<N> {
-    /// Don't step the machine. 
-    Pause, 
-    /// Step the machine as normal. 
-    Play, 
-    /// Dispose the current machine, and initialize a new one in its place. 
-    Reset(N), 
-    /// Reset the machine, and then subsequently step it. 
-    ResetPlay(N)
+fn one_tick<I>(_input: &I) -> u64 {
+    1
 }
 
-/// Nonterminal enum for a step-controlled node. 
+/// A timeout wrapper for a node, which forces an abnormal exit of the node 
+/// if it has not terminated within a time limit relative to when it started. 
+/// By default, time is measured in steps, but a clock can be supplied which 
+/// reads the time passed since the previous step from the input, such as a 
+/// frame time. Before each step, the time is added to the elapsed time, and 
+/// the node is only stepped if the elapsed time is still within the limit. 
+pub type TimeLimitedNode<N, C> = ClockedNode<N, TickClock<C>>;
+
+impl<N> TimeLimitedNode<N, fn(&N::Input) -> u64> where 
+    N: BehaviorTreeNode
+{
+    /// Create a new time limited node, which times out the node if it has 
+    /// not terminated within the given number of steps. 
+    pub fn new(limit: u64, node: N) -> TimeLimitedNode<N, fn(&N::Input) -> u64> {
+        TimeLimitedNode::with_clock(one_tick, limit, node)
+    }
+}
+
+impl<N, C> TimeLimitedNode<N, C> where 
+    N: BehaviorTreeNode,
+    C: Fn(&N::Input) -> u64
+{
+    /// Create a new time limited node, whose clock returns the time passed 
+    /// since the previous step. 
+    pub fn with_clock(clock: C, limit: u64, node: N) -> TimeLimitedNode<N, C> {
+        ClockedNode::limited(TickClock::new(clock), limit, node)
+    }
+
+    /// Return the time elapsed so far. 
+    pub fn elapsed(&self) -> u64 {
+        self.clock().elapsed()
+    }
+
+    /// Return the time limit of the node. 
+    pub fn limit(&self) -> u64 {
+        *self.time_limit().expect("Time limited node was built without a limit")
+    }
+}
+
+/// Window of ticks recurring with a fixed period, such as ticks 0 to 100 of 
+/// every 1000. 
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct PeriodicWindow {
+    period: u64,
+    start: u64,
+    end: u64
+}
+
+impl PeriodicWindow {
+    /// Create a new periodic window, open from the start tick up to but not 
+    /// including the end tick of each period. 
+    /// 
+    /// # Panics 
+    /// 
+    /// Panics if the period is zero. 
+    pub fn new(period: u64, start: u64, end: u64) -> PeriodicWindow {
+        assert!(period > 0, "Periodic window has a zero period");
+        PeriodicWindow {
+            period: period,
+            start: start,
+            end: end
+        }
+    }
+
+    /// Return whether the window is open at the given tick. 
+    pub fn contains(&self, tick: u64) -> bool {
+        let phase = tick % self.period;
+        phase >= self.start && phase < self.end
+    }
+}
+
+/// Nonterminal of a scheduled node. 
 #[derive(Copy, Clone, PartialEq, Debug)]
-pub enum StepCtrlNonterm<I> {
-    /// The node was stepped as normal, perhaps after resetting it. 
-    Stepped(I),
-    /// The node was paused, and maybe reset. 
+pub enum ScheduleNonterm<N> {
+    /// The schedule was open, and the node stepped to the given nonterminal. 
+    Running(N),
+    /// The schedule was closed, and the node was paused. 
     Paused
 }
 
-/// A step-controlling wrapper for a node, which may pause, step, and/or 
-/// reset a node depending on inputs, before the node goes forward. 
+/// A scheduling wrapper for a node, which only steps the node while the 
+/// schedule is open for the input, and otherwise pauses it in place. 
 #[derive(Copy, Clone, PartialEq, Debug)]
-pub struct StepControlledNode<N, S> where 
+pub struct ScheduledNode<N, S> where 
     N: BehaviorTreeNode,
-    S: Fn(&N::Input) -> StepDecision<N>
+    S: Fn(&N::Input) -> bool
 {
     node: N,
-    stepper: S
+    schedule: S
 }
 
-impl<N, S> StepControlledNode<N, S> where 
+impl<N, S> ScheduledNode<N, S> where 
     N: BehaviorTreeNode,
-    S: Fn(&N::Input) -> StepDecision<N>
+    S: Fn(&N::Input) -> bool
 {
-    /// Create a new step controlled node. 
-    pub fn new(stepper: S, node: N) -> StepControlledNode<N, S> {
-        StepControlledNode {
+    /// Create a new scheduled node. 
+    pub fn new(schedule: S, node: N) -> ScheduledNode<N, S> {
+        ScheduledNode {
             node: node,
-            stepper: stepper
+            schedule: schedule
         }
     }
 }
 
-impl<N, S> BehaviorTreeNode for StepControlledNode<N, S> where 
+impl<N, S> BehaviorTreeNode for ScheduledNode<N, S> where 
     N: BehaviorTreeNode,
-    S: Fn(&N::Input) -> StepDecision<N>
+    S: Fn(&N::Input) -> bool
 {
     type Input = N::Input;
-    type Nonterminal = StepCtrlNonterm<N::Nonterminal>;
+    type Nonterminal = ScheduleNonterm<N::Nonterminal>;
     type Terminal = N::Terminal;
-    
+
     #[inline]
     fn step(self, input: &N::Input) -> NodeResult<Self::Nonterminal, 
         N::Terminal, Self> 
     {
-        match (self.stepper)(input) {
-            StepDecision::Pause => {
-                NodeResult::Nonterminal(StepCtrlNonterm::Paused, self)
-            },
-            StepDecision::Play => {
-                match self.node.step(input) {
-                    NodeResult::Nonterminal(n, m) => {
-                        NodeResult::Nonterminal(
-                            StepCtrlNonterm::Stepped(n), 
-                            Self::new(self.stepper, m)
-                        )
-                    },
-                    NodeResult::Terminal(t) => NodeResult::Terminal(t)
-                }
-            },
-            StepDecision::Reset(new_node) => {
-                NodeResult::Nonterminal(StepCtrlNonterm::Paused, Self::new(
-                    self.stepper,
-                    new_node
-                ))
-            },
-            StepDecision::ResetPlay(mut new_machine) => {
-                match new_machine.step(input) {
-                    NodeResult::Nonterminal(n, m) => {
-                        NodeResult::Nonterminal(
-                            StepCtrlNonterm::Stepped(n), 
-                            Self::new(self.stepper, m)
-                        )
-                    },
-                    NodeResult::Terminal(t) => NodeResult::Terminal(t)
-                }
-            }
+        if !(self.schedule)(input) {
+            return NodeResult::Nonterminal(ScheduleNonterm::Paused, self);
+        }
+        match self.node.step(input) {
+            NodeResult::Nonterminal(v, n) => NodeResult::Nonterminal(
+                ScheduleNonterm::Running(v),
+                ScheduledNode::new(self.schedule, n)
+            ),
+            NodeResult::Terminal(t) => NodeResult::Terminal(t)
         }
     }
-}
 
-#[derive(Copy, Clone, PartialEq, Debug)]
-pub enum PostResetNonterm<N, T> {
-    /// The node was not reset. 
-    NoReset(N),
-    /// The node was reset from a nonterminal state. 
-    ManualReset(N),
-    /// The node was reset from a terminal state. 
-    EndReset(T)
+    #[inline]
+    fn step_with(self, input: N::Input) -> NodeResult<Self::Nonterminal, 
+        N::Terminal, Self> 
+    {
+        if !(self.schedule)(&input) {
+            return NodeResult::Nonterminal(ScheduleNonterm::Paused, self);
+        }
+        match self.node.step_with(input) {
+            NodeResult::Nonterminal(v, n) => NodeResult::Nonterminal(
+                ScheduleNonterm::Running(v),
+                ScheduledNode::new(self.schedule, n)
+            ),
+            NodeResult::Terminal(t) => NodeResult::Terminal(t)
+        }
+    }
+
+    #[inline]
+    fn halt(self, input: &N::Input) {
+        self.node.halt(input);
+    }
 }
 
-/// A post-run resetting wrapper for a node, which may reset a node after 
-/// it runs. 
+/// Restart policy of a soft-failing node, which rebuilds the node from a 
+/// factory after each failure while failures are still allowed. 
+#[cfg(feature = "compositions")]
 #[derive(Copy, Clone, PartialEq, Debug)]
-pub struct PostResetNode<N, P> where 
-    N: BehaviorTreeNode,
-    P: Fn(&N::Input, Statepoint<&N::Nonterminal, &N::Terminal>) -> Option<N>
-{
-    node: N,
-    resetter: P
+pub struct SoftFailPolicy<F> {
+    factory: F,
+    allowance: u64,
+    failures: u64
 }
 
-impl<N, P> PostResetNode<N, P> where 
+#[cfg(feature = "compositions")]
+impl<N, F> RestartPolicy<N> for SoftFailPolicy<F> where 
     N: BehaviorTreeNode,
-    P: Fn(&N::Input, Statepoint<&N::Nonterminal, &N::Terminal>) -> Option<N>
+    N::Terminal: Classify,
+    F: Fn(&N::Terminal) -> N
 {
-    /// Create a new step controlled node. 
-    pub fn new(resetter: P, node: N) -> PostResetNode<N, P> {
-        PostResetNode {
-            node: node,
-            resetter: resetter
+    type Exit = N::Terminal;
+
+    fn restart(&mut self, terminal: N::Terminal) -> Restart<N, N::Terminal, N::Terminal> {
+        if terminal.classify() == Outcome::Failure && self.failures < self.allowance {
+            self.failures += 1;
+            let next = (self.factory)(&terminal);
+            Restart::Rebuild(terminal, next)
+        } else {
+            Restart::Exit(terminal)
         }
     }
 }
 
-impl <N, P> BehaviorTreeNode for PostResetNode<N, P> where 
+/// A soft-failing wrapper for a node, which absorbs up to a set number of 
+/// failures of its node. Whenever the node terminates with a terminal 
+/// classified as a failure while failures are still allowed, the wrapper 
+/// reports the terminal as a nonterminal instead, and rebuilds the node 
+/// from the factory to be stepped on the next step. Successes, and the 
+/// failure after the allowance is spent, terminate the wrapper as usual. 
+#[cfg(feature = "compositions")]
+pub type SoftFailNode<N, F> = RestartNode<N, SoftFailPolicy<F>>;
+
+#[cfg(feature = "compositions")]
+impl<N, F> SoftFailNode<N, F> where 
     N: BehaviorTreeNode,
-    P: Fn(&N::Input, Statepoint<&N::Nonterminal, &N::Terminal>) -> Option<N>
+    N::Terminal: Classify,
+    F: Fn(&N::Terminal) -> N
 {
-    type Input = N::Input;
-    type Nonterminal = PostResetNonterm<N::Nonterminal, N::Terminal>;
-    type Terminal = N::Terminal;
+    /// Create a new soft-failing node, which absorbs up to the allowance of 
+    /// failures of the node. 
+    pub fn new(allowance: u64, factory: F, node: N) -> SoftFailNode<N, F> {
+        RestartNode::with_policy(SoftFailPolicy {
+            factory: factory,
+            allowance: allowance,
+            failures: 0
+        }, node)
+    }
 
-    #[inline]
-    fn step(self, input: &N::Input) -> NodeResult<Self::Nonterminal, 
-        N::Terminal, Self> 
-    {
-        match self.node.step(input) {
-            NodeResult::Nonterminal(v, n) => {
-                match (self.resetter)(input, Statepoint::Nonterminal(&v)) {
-                    Option::Some(k) => NodeResult::Nonterminal(
-                        PostResetNonterm::ManualReset(v),
-                        Self::new(self.resetter, k)
-                    ),
-                    Option::None => NodeResult::Nonterminal(
-                        PostResetNonterm::NoReset(v),
-                        Self::new(self.resetter, n)
-                    )
-                }
-            },
-            NodeResult::Terminal(t) => {
-                match (self.resetter)(input, Statepoint::Terminal(&t)) {
-                    Option::Some(n) => NodeResult::Nonterminal(
-                        PostResetNonterm::EndReset(t),
-                        Self::new(self.resetter, n)
-                    ),
-                    Option::None => NodeResult::Terminal(t)
-                }
-            }
-        }
+    /// Return the number of failures absorbed so far. 
+    pub fn failures(&self) -> u64 {
+        self.policy().failures
     }
 }
 
@@ -260,20 +1361,248 @@ mod tests {
         };
     }
 
-    #[derive(Copy, Clone)]
+    #[test]
+    fn context_guarded_node_test() {
+        use control_wrappers::{ContextGuardedNode, GuardFailureContext};
+        let base_node = PredicateWait::new(|input: &(i64, &'static str)| {
+            Statepoint::Nonterminal::<i64, ()>(input.0)
+        });
+        let wrapped_node = ContextGuardedNode::cloning(|_i: &(i64, &'static str), 
+            o: &i64| *o < 10, base_node);
+        let wrapped_node = assert_steps!(wrapped_node;
+            (3, "walk") => Nonterminal(3),
+            (5, "run") => Nonterminal(5)
+        );
+        assert_steps!(wrapped_node;
+            (12, "sprint") => Terminal(Result::Err(GuardFailureContext {
+                nonterminal: 12,
+                context: (12, "sprint"),
+                ticks: 3
+            }))
+        );
+        let projected_node = ContextGuardedNode::new(|_i: &(i64, &'static str), 
+            o: &i64| *o < 10, |i: &(i64, &'static str)| i.1, base_node);
+        assert_steps!(projected_node;
+            (11, "leap") => Terminal(Result::Err(GuardFailureContext {
+                nonterminal: 11,
+                context: "leap",
+                ticks: 1
+            }))
+        );
+    }
+
+    #[test]
+    fn restarting_node_test() {
+        use control_wrappers::{RestartingNode, RestartCause};
+        // Walks along waypoints until the input is negative, terminating 
+        // with the waypoint it stopped at 
+        let walker = |from: i64| PredicateWait::new(move |input: &i64| {
+            if *input >= 0 {
+                Statepoint::Nonterminal(from + *input)
+            } else {
+                Statepoint::Terminal(from - *input)
+            }
+        });
+        let factory = |cause: RestartCause<&i64>| match cause {
+            RestartCause::Terminated(at) => walker(*at),
+            RestartCause::Reset => walker(0)
+        };
+        let node = RestartingNode::with_reset(factory, |input: &i64| *input == 100, 
+            walker(0));
+        assert_steps!(node;
+            1 => Nonterminal(Statepoint::Nonterminal(1)),
+            -3 => Nonterminal(Statepoint::Terminal(3)),
+            2 => Nonterminal(Statepoint::Nonterminal(5)),
+            100 => Nonterminal(Statepoint::Nonterminal(100)),
+            -1 => Nonterminal(Statepoint::Terminal(1))
+        );
+        let node = RestartingNode::new(factory, walker(10));
+        assert_steps!(node;
+            -2 => Nonterminal(Statepoint::Terminal(12)),
+            100 => Nonterminal(Statepoint::Nonterminal(112))
+        );
+    }
+
+    #[test]
+    fn repeat_node_test() {
+        use control_wrappers::{RepeatNode, RepeatSummary};
+        let count_down = |from: i64| PredicateWait::new(move |input: &i64| {
+            if *input < from {
+                Statepoint::Nonterminal(*input)
+            } else {
+                Statepoint::Terminal(from)
+            }
+        });
+        let node = RepeatNode::new(3, |last: &i64| count_down(*last + 1), 
+            count_down(1));
+        let node = assert_steps!(node;
+            0 => Nonterminal(Statepoint::Nonterminal(0)),
+            1 => Nonterminal(Statepoint::Terminal(1)),
+            2 => Nonterminal(Statepoint::Terminal(2))
+        );
+        assert_eq!(node.iterations(), 2);
+        assert_steps!(node;
+            2 => Nonterminal(Statepoint::Nonterminal(2)),
+            3 => Terminal(RepeatSummary { iterations: 3, last: 3 })
+        );
+        let node = RepeatNode::unbounded(|_: &i64| count_down(0), count_down(0));
+        let node = assert_steps!(node;
+            0 => Nonterminal(Statepoint::Terminal(0)),
+            0 => Nonterminal(Statepoint::Terminal(0)),
+            0 => Nonterminal(Statepoint::Terminal(0))
+        );
+        assert_eq!(node.iterations(), 3);
+    }
+
+    #[test]
+    fn polarity_wrappers_test() {
+        use bt_status::BtStatus;
+        use control_wrappers::{InverterNode, AlwaysSucceed, AlwaysFail};
+        let wait = PredicateWait::new(|input: &i64| {
+            if *input > 0 {
+                Statepoint::Nonterminal(*input)
+            } else {
+                Statepoint::Terminal(BtStatus::from(*input == 0))
+            }
+        });
+        let node = InverterNode::new(wait);
+        assert_steps!(node; 1 => Nonterminal(1), 0 => Terminal(BtStatus::Failure));
+        let node = InverterNode::new(wait);
+        assert_steps!(node; -1 => Terminal(BtStatus::Success));
+        let node = AlwaysSucceed::new(wait);
+        assert_steps!(node; 2 => Nonterminal(2), -1 => Terminal(BtStatus::Success));
+        let node = AlwaysFail::new(wait);
+        assert_steps!(node; 0 => Terminal(BtStatus::Failure));
+
+        let checked = PredicateWait::new(|input: &i64| {
+            if *input > 0 {
+                Statepoint::Nonterminal(*input)
+            } else if *input == 0 {
+                Statepoint::Terminal(Result::Ok(()))
+            } else {
+                Statepoint::Terminal(Result::Err(*input))
+            }
+        });
+        let node = InverterNode::new(checked);
+        assert_steps!(node; -3 => Terminal(Result::Ok(-3)));
+        let node = AlwaysSucceed::new(checked);
+        assert_steps!(node; -3 => Terminal(Result::Ok(Result::Err(-3))));
+        let node = AlwaysFail::new(checked);
+        assert_steps!(node; 0 => Terminal(Result::Err(Result::Ok(()))));
+    }
+
+    #[test]
+    fn deadline_node_test() {
+        use control_wrappers::{DeadlineNode, DeadlineExit};
+        let wait = PredicateWait::new(|input: &(u64, i64)| {
+            if input.1 > 0 {
+                Statepoint::Nonterminal(input.1)
+            } else {
+                Statepoint::Terminal(input.1)
+            }
+        });
+        let node = DeadlineNode::new(|input: &(u64, i64)| input.0, 3, wait);
+        assert_eq!(*node.deadline(), 3);
+        assert_steps!(node;
+            (1, 5) => Nonterminal(5),
+            (3, 4) => Nonterminal(4),
+            (4, 2) => Terminal(DeadlineExit::DeadlinePassed(4))
+        );
+        let node = DeadlineNode::new(|input: &(u64, i64)| input.0, 3, wait);
+        assert_steps!(node;
+            (2, 0) => Terminal(DeadlineExit::Finished(0, 2))
+        );
+    }
+
+    #[test]
+    fn time_limited_node_test() {
+        use control_wrappers::{TimeLimitedNode, DeadlineExit};
+        let wait = PredicateWait::new(|input: &(u64, i64)| {
+            if input.1 > 0 {
+                Statepoint::Nonterminal(input.1)
+            } else {
+                Statepoint::Terminal(input.1)
+            }
+        });
+        let node = TimeLimitedNode::new(2, wait);
+        assert_eq!(node.limit(), 2);
+        let node = assert_steps!(node;
+            (0, 5) => Nonterminal(5),
+            (0, 4) => Nonterminal(4)
+        );
+        assert_eq!(node.elapsed(), 2);
+        assert_steps!(node;
+            (0, 0) => Terminal(DeadlineExit::DeadlinePassed(3))
+        );
+        let node = TimeLimitedNode::new(2, wait);
+        assert_steps!(node;
+            (0, 5) => Nonterminal(5),
+            (0, 0) => Terminal(DeadlineExit::Finished(0, 2))
+        );
+        let node = TimeLimitedNode::with_clock(|input: &(u64, i64)| input.0, 10, wait);
+        assert_steps!(node;
+            (4, 3) => Nonterminal(3),
+            (6, 2) => Nonterminal(2),
+            (1, 1) => Terminal(DeadlineExit::DeadlinePassed(11))
+        );
+    }
+
+    #[test]
+    fn scheduled_node_test() {
+        use control_wrappers::{ScheduledNode, ScheduleNonterm, PeriodicWindow};
+        let window = PeriodicWindow::new(10, 2, 5);
+        assert!(!window.contains(1));
+        assert!(window.contains(12));
+        assert!(!window.contains(15));
+        let wait = PredicateWait::new(|input: &(u64, i64)| {
+            if input.1 > 0 {
+                Statepoint::Nonterminal(input.1)
+            } else {
+                Statepoint::Terminal(input.1)
+            }
+        });
+        let node = ScheduledNode::new(move |input: &(u64, i64)| window.contains(input.0), 
+            wait);
+        assert_steps!(node;
+            (0, 0) => Nonterminal(ScheduleNonterm::Paused),
+            (3, 7) => Nonterminal(ScheduleNonterm::Running(7)),
+            (5, 0) => Nonterminal(ScheduleNonterm::Paused),
+            (14, 0) => Terminal(0)
+        );
+    }
+
+    #[cfg(feature = "compositions")]
+    #[test]
+    fn soft_fail_node_test() {
+        use control_wrappers::SoftFailNode;
+        let attempt = || PredicateWait::new(|input: &i64| {
+            if *input > 0 {
+                Statepoint::Nonterminal(*input)
+            } else {
+                Statepoint::Terminal(*input == 0)
+            }
+        });
+        let node = SoftFailNode::new(2, |_: &bool| attempt(), attempt());
+        let node = assert_steps!(node;
+            3 => Nonterminal(Statepoint::Nonterminal(3)),
+            -1 => Nonterminal(Statepoint::Terminal(false)),
+            -1 => Nonterminal(Statepoint::Terminal(false))
+        );
+        assert_eq!(node.failures(), 2);
+        assert_steps!(node; -1 => Terminal(false));
+        let node = SoftFailNode::new(2, |_: &bool| attempt(), attempt());
+        assert_steps!(node; 0 => Terminal(true));
+    }
+
+    #[derive(Copy, Clone, Default)]
     enum Ratchet {
+        #[default]
         Zero,
         One,
         Two,
         Three
     }
 
-    impl Default for Ratchet {
-        fn default() -> Ratchet {
-            Ratchet::Zero
-        }
-    }
-
     impl ReferenceTransition for Ratchet {
         type Input = i64;
         type Action = Statepoint<i64, ()>;
@@ -319,55 +1648,13 @@ mod tests {
                 _ => StepDecision::Play
             }
         }, base_node);
-        let wrapped_node_1 = match wrapped_node.step(&-1) {
-            NodeResult::Nonterminal(v, m) => {
-                match v {
-                    StepCtrlNonterm::Paused => (),
-                    _ => unreachable!("Node was paused")
-                };
-                m
-            },
-            _ => unreachable!("Expected nonterminal transition"),
-        };
-        let wrapped_node_2 = match wrapped_node_1.step(&2) {
-            NodeResult::Terminal(_) => unreachable!("Expected nonterminal transition"),
-            NodeResult::Nonterminal(v, m) => {
-                match v {
-                    StepCtrlNonterm::Stepped(x) => assert_eq!(x, 2),
-                    _ => unreachable!("Node was played"),
-                };
-                m
-            }
-        };
-        let wrapped_node_3 = match wrapped_node_2.step(&-2) {
-            NodeResult::Nonterminal(v, m) => {
-                match v {
-                    StepCtrlNonterm::Paused => (),
-                    _ => unreachable!("Node was reset")
-                };
-                m
-            },
-            _ => unreachable!("Expected nonterminal transition"),
-        };
-        let wrapped_node_4 = match wrapped_node_3.step(&2) {
-            NodeResult::Nonterminal(v, m) => {
-                match v {
-                    StepCtrlNonterm::Paused => unreachable!("Node was played"),
-                    StepCtrlNonterm::Stepped(x) => assert_eq!(x, 2)
-                };
-                m
-            },
-            _ => unreachable!("Expected nonterminal transition"),
-        };
-        match wrapped_node_4.step(&7) {
-            NodeResult::Nonterminal(v, _) => {
-                match v {
-                    StepCtrlNonterm::Paused => unreachable!("Node was played"),
-                    StepCtrlNonterm::Stepped(x) => assert_eq!(x, 0)
-                };
-            },
-            _ => unreachable!("Expected nonterminal transition"),
-        };
+        assert_steps!(wrapped_node;
+            -1 => Nonterminal(StepCtrlNonterm::Paused),
+            2 => Nonterminal(StepCtrlNonterm::Stepped(2)),
+            -2 => Nonterminal(StepCtrlNonterm::Paused),
+            2 => Nonterminal(StepCtrlNonterm::Stepped(2)),
+            7 => Nonterminal(StepCtrlNonterm::Stepped(0))
+        );
     }
 
     #[test]
@@ -375,6 +1662,7 @@ mod tests {
         use control_wrappers::{PostResetNode, PostResetNonterm};
         use base_nodes::MachineWrapper;
         use stackbt_automata_impl::ref_state_machine::RefStateMachine;
+        use test_utils::StepHarness;
         let machine = RefStateMachine::new(Ratchet::Zero);
         let base_node = MachineWrapper::new(machine);
         let wrapped_node = PostResetNode::new(|input: &i64, _o: Statepoint<&i64, &()>|{
@@ -384,69 +1672,93 @@ mod tests {
                 Option::None
             }
         }, base_node);
-        let wrapped_node_1 = match wrapped_node.step(&1) {
-            NodeResult::Nonterminal(v, n) => {
-                match v {
-                    PostResetNonterm::NoReset(val) => assert_eq!(val, 1),
-                    _ => unreachable!("Node was not reset")
-                };
-                n
-            },
-            _ => unreachable!("Expected nonterminal transition")
-        };
-        let wrapped_node_2 = match wrapped_node_1.step(&5) {
-            NodeResult::Nonterminal(v, n) => {
-                match v {
-                    PostResetNonterm::ManualReset(val) => assert_eq!(val, 1),
-                    _ => unreachable!("Node was not reset")
-                };
-                n
-            },
-            _ => unreachable!("Expected nonterminal transition")
-        };
-        let wrapped_node_3 = match wrapped_node_2.step(&0) {
-            NodeResult::Nonterminal(v, n) => {
-                match v {
-                    PostResetNonterm::NoReset(val) => assert_eq!(val, 0),
-                    _ => unreachable!("Node was manually reset")
-                };
-                n
-            },
-            _ => unreachable!("Expected nonterminal transition")
+        StepHarness::new(wrapped_node)
+            .step(1, Statepoint::Nonterminal(PostResetNonterm::NoReset(1)))
+            .step(5, Statepoint::Nonterminal(PostResetNonterm::ManualReset(1)))
+            .step(0, Statepoint::Nonterminal(PostResetNonterm::NoReset(0)))
+            .step(3, Statepoint::Nonterminal(PostResetNonterm::NoReset(3)))
+            .step(5, Statepoint::Nonterminal(PostResetNonterm::EndReset(())))
+            .step(3, Statepoint::Nonterminal(PostResetNonterm::NoReset(3)))
+            .step(3, Statepoint::Terminal(()))
+            .run();
+    }
+
+    #[test]
+    fn halt_on_abandon_test() {
+        use control_wrappers::{GuardedNode, StepControlledNode};
+        use map_wrappers::InputMappedNode;
+        use test_utils::HaltCounter;
+
+        let probe: HaltCounter<i64, ()> = HaltCounter::new();
+        let guarded = GuardedNode::new(
+            |_i: &i64, n: &i64| *n >= 0,
+            InputMappedNode::new(|i: &i64| *i - 1, probe.clone())
+        );
+        let guarded = assert_steps!(guarded; 1 => Nonterminal(0));
+        assert_eq!(probe.halts(), 0);
+        assert_steps!(guarded; 0 => Terminal(Result::Err(_)));
+        assert_eq!(probe.halts(), 1);
+
+        let reset_probe = probe.clone();
+        let controlled = StepControlledNode::new(move |i: &i64| {
+            if *i < 0 {
+                StepDecision::Reset(reset_probe.clone())
+            } else {
+                StepDecision::Play
+            }
+        }, probe.clone());
+        let controlled = assert_steps!(controlled; -1 => Nonterminal(_));
+        assert_eq!(probe.halts(), 2);
+        controlled.halt(&0);
+        assert_eq!(probe.halts(), 3);
+    }
+    struct Token(i64);
+
+    // Only terminates when given its input by value
+    struct TokenEater;
+
+    impl BehaviorTreeNode for TokenEater {
+        type Input = Token;
+        type Nonterminal = ();
+        type Terminal = i64;
+
+        fn step(self, _input: &Token) -> NodeResult<(), i64, Self> {
+            NodeResult::Nonterminal((), self)
+        }
+
+        fn step_with(self, input: Token) -> NodeResult<(), i64, Self> {
+            let Token(val) = input;
+            NodeResult::Terminal(val)
+        }
+    }
+
+    #[test]
+    fn owned_input_forwarding_test() {
+        use control_wrappers::{StepControlledNode, ScheduledNode, TimeLimitedNode, 
+            DeadlineExit, RepeatNode, RepeatSummary};
+        let controlled = StepControlledNode::new(|_: &Token| StepDecision::Play, TokenEater);
+        match controlled.step_with(Token(1)) {
+            NodeResult::Terminal(1) => (),
+            _ => unreachable!("Expected terminal state")
         };
-        let wrapped_node_4 = match wrapped_node_3.step(&3) {
-            NodeResult::Nonterminal(v, n) => {
-                match v {
-                    PostResetNonterm::NoReset(val) => assert_eq!(val, 3),
-                    _ => unreachable!("Node was end reset")
-                };
-                n
-            },
-            _ => unreachable!("Expected nonterminal transition")
+        let scheduled = ScheduledNode::new(|t: &Token| t.0 > 0, TokenEater);
+        let scheduled = match scheduled.step_with(Token(0)) {
+            NodeResult::Nonterminal(_, n) => n,
+            _ => unreachable!("Expected nonterminal state")
         };
-        let wrapped_node_5 = match wrapped_node_4.step(&5) {
-            NodeResult::Nonterminal(v, n) => {
-                match v {
-                    PostResetNonterm::EndReset(val) => assert_eq!(val, ()),
-                    _ => unreachable!("Node was end reset")
-                };
-                n
-            },
-            _ => unreachable!("Expected nonterminal transition")
+        match scheduled.step_with(Token(2)) {
+            NodeResult::Terminal(2) => (),
+            _ => unreachable!("Expected terminal state")
         };
-        let wrapped_node_6 = match wrapped_node_5.step(&3) {
-            NodeResult::Nonterminal(v, n) => {
-                match v {
-                    PostResetNonterm::NoReset(val) => assert_eq!(val, 3),
-                    _ => unreachable!("Node was end reset")
-                };
-                n
-            },
-            _ => unreachable!("Expected nonterminal transition")
+        let limited = TimeLimitedNode::new(3, TokenEater);
+        match limited.step_with(Token(3)) {
+            NodeResult::Terminal(DeadlineExit::Finished(3, 1)) => (),
+            _ => unreachable!("Expected finished terminal")
         };
-        match wrapped_node_6.step(&3) {
-            NodeResult::Terminal(()) => (),
-            _ => unreachable!("Expected terminal transition")
+        let repeated = RepeatNode::new(1, |_: &i64| TokenEater, TokenEater);
+        match repeated.step_with(Token(4)) {
+            NodeResult::Terminal(RepeatSummary { iterations: 1, last: 4 }) => (),
+            _ => unreachable!("Expected terminal state")
         };
     }
-}
\ No newline at end of file
+}
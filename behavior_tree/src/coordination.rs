@@ -0,0 +1,525 @@
+//! Coordination between the trees of several agents. 
+//!
+//! Squad behaviors, such as flanking or focusing fire, need agents to agree 
+//! on who does what. A group board is a blackboard shared by the trees of a 
+//! group of agents, recording which agent has claimed which key, where the 
+//! keys are whatever the agents coordinate over, such as targets or roles. 
+//! The claim and release nodes of this module let trees take and give up 
+//! keys as steps of their behavior, with the conflicts between agents 
+//! claiming the same key resolved by the policy of the board. Successful 
+//! claims return handles, through which an agent can tell whether its claim 
+//! was since taken away by a claim with a higher priority. 
+//!
+//! For behaviors which only a limited number of agents should run at once, 
+//! such as only letting two enemies attack at the same time, the token node 
+//! only runs its node while holding a token from a shared token pool, and 
+//! returns the token when the node terminates or is halted. 
+
+use behavior_tree_node::{BehaviorTreeNode, NodeResult};
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Policy for resolving claims on keys which are already claimed. 
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ClaimPolicy {
+    /// The agent which claimed the key first keeps it until it releases it. 
+    FirstCome,
+    /// A claim with a higher priority than the current claim takes the key 
+    /// away from the agent holding it. 
+    Priority
+}
+
+// Claims on the keys of a group board, each with the agent holding it, its 
+// priority, and the ticket of the claim handle given out for it
+#[derive(Debug)]
+struct Claims<K, A> {
+    held: HashMap<K, (A, i64, u64)>,
+    next_ticket: u64
+}
+
+/// Blackboard shared by the trees of a group of agents, recording which 
+/// agent holds the claim on each key. Clones of a board share the same 
+/// claims, and may be used from different threads. 
+#[derive(Debug)]
+pub struct GroupBoard<K, A> {
+    claims: Arc<Mutex<Claims<K, A>>>,
+    policy: ClaimPolicy
+}
+
+impl<K, A> GroupBoard<K, A> where
+    K: Hash + Eq + Clone,
+    A: PartialEq + Clone
+{
+    /// Create a new, empty group board. 
+    pub fn new(policy: ClaimPolicy) -> GroupBoard<K, A> {
+        GroupBoard {
+            claims: Arc::new(Mutex::new(Claims {
+                held: HashMap::new(),
+                next_ticket: 0
+            })),
+            policy: policy
+        }
+    }
+
+    // Lock the claims. A panic while they were locked cannot leave them 
+    // half updated, so a poisoned lock is recovered
+    fn lock(&self) -> MutexGuard<'_, Claims<K, A>> {
+        self.claims.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Claim the key for the agent with the given priority, returning a 
+    /// handle to the claim, or the agent holding the key if the claim loses 
+    /// to it. An agent claiming a key it already holds keeps it, with the 
+    /// new priority, and the handles to its earlier claim stay valid. 
+    pub fn claim(&self, key: K, agent: A, priority: i64) -> Result<ClaimHandle<K, A>, A> {
+        let mut claims = self.lock();
+        let kept = match claims.held.get(&key) {
+            Option::Some(&(ref holder, held_priority, ticket)) => {
+                if *holder == agent {
+                    Option::Some(ticket)
+                } else {
+                    let wins = match self.policy {
+                        ClaimPolicy::FirstCome => false,
+                        ClaimPolicy::Priority => priority > held_priority
+                    };
+                    if !wins {
+                        return Result::Err(holder.clone());
+                    }
+                    Option::None
+                }
+            },
+            Option::None => Option::None
+        };
+        let ticket = match kept {
+            Option::Some(ticket) => ticket,
+            Option::None => {
+                claims.next_ticket += 1;
+                claims.next_ticket
+            }
+        };
+        claims.held.insert(key.clone(), (agent.clone(), priority, ticket));
+        Result::Ok(ClaimHandle {
+            board: self.clone(),
+            key: key,
+            agent: agent,
+            ticket: ticket
+        })
+    }
+
+    /// Release the agent's claim on the key, returning whether the agent 
+    /// held it. 
+    pub fn release(&self, key: &K, agent: &A) -> bool {
+        let mut claims = self.lock();
+        let held = match claims.held.get(key) {
+            Option::Some(claim) => claim.0 == *agent,
+            Option::None => false
+        };
+        if held {
+            claims.held.remove(key);
+        }
+        held
+    }
+
+    /// Release all of the agent's claims, such as when the agent is removed 
+    /// from the group. 
+    pub fn release_all(&self, agent: &A) {
+        self.lock().held.retain(|_, claim| claim.0 != *agent);
+    }
+
+    /// Return the agent holding the key, if any does. 
+    pub fn holder(&self, key: &K) -> Option<A> {
+        self.lock().held.get(key).map(|claim| claim.0.clone())
+    }
+
+    /// Return whether the agent holds the key. 
+    pub fn holds(&self, key: &K, agent: &A) -> bool {
+        match self.lock().held.get(key) {
+            Option::Some(claim) => claim.0 == *agent,
+            Option::None => false
+        }
+    }
+}
+
+impl<K, A> Clone for GroupBoard<K, A> {
+    fn clone(&self) -> GroupBoard<K, A> {
+        GroupBoard {
+            claims: self.claims.clone(),
+            policy: self.policy
+        }
+    }
+}
+
+/// Handle to a claim an agent made on a key of a group board, through which 
+/// the agent can tell whether it still holds the key, or whether the claim 
+/// was released, or taken away by a claim with a higher priority. 
+#[derive(Clone, Debug)]
+pub struct ClaimHandle<K, A> {
+    board: GroupBoard<K, A>,
+    key: K,
+    agent: A,
+    ticket: u64
+}
+
+impl<K, A> ClaimHandle<K, A> where
+    K: Hash + Eq + Clone,
+    A: PartialEq + Clone
+{
+    /// Return the claimed key. 
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Return the agent which made the claim. 
+    pub fn agent(&self) -> &A {
+        &self.agent
+    }
+
+    /// Return whether the claim still holds the key. 
+    pub fn is_held(&self) -> bool {
+        match self.board.lock().held.get(&self.key) {
+            Option::Some(claim) => claim.2 == self.ticket,
+            Option::None => false
+        }
+    }
+
+    /// Return whether another agent took the key away from the claim. A 
+    /// claim which was released is not preempted. 
+    pub fn is_preempted(&self) -> bool {
+        match self.board.lock().held.get(&self.key) {
+            Option::Some(claim) => claim.2 != self.ticket,
+            Option::None => false
+        }
+    }
+
+    /// Release the claim, returning whether it still held the key. 
+    pub fn release(self) -> bool {
+        let mut claims = self.board.lock();
+        let held = match claims.held.get(&self.key) {
+            Option::Some(claim) => claim.2 == self.ticket,
+            Option::None => false
+        };
+        if held {
+            claims.held.remove(&self.key);
+        }
+        held
+    }
+}
+
+/// Leaf node which claims the key picked from the input for its agent, 
+/// terminating immediately with Ok and a handle to the claim if the claim 
+/// succeeded, or with Err, the key and the agent holding it if the claim 
+/// lost. 
+#[derive(Clone, Debug)]
+pub struct ClaimNode<I, K, A, F> where
+    F: Fn(&I) -> K
+{
+    board: GroupBoard<K, A>,
+    agent: A,
+    priority: i64,
+    key: F,
+    _junk: PhantomData<I>
+}
+
+impl<I, K, A, F> ClaimNode<I, K, A, F> where
+    K: Hash + Eq + Clone,
+    A: PartialEq + Clone,
+    F: Fn(&I) -> K
+{
+    /// Create a new claim node for the agent. 
+    pub fn new(board: GroupBoard<K, A>, agent: A, priority: i64, key: F) ->
+        ClaimNode<I, K, A, F>
+    {
+        ClaimNode {
+            board: board,
+            agent: agent,
+            priority: priority,
+            key: key,
+            _junk: PhantomData
+        }
+    }
+}
+
+impl<I, K, A, F> BehaviorTreeNode for ClaimNode<I, K, A, F> where
+    K: Hash + Eq + Clone,
+    A: PartialEq + Clone,
+    F: Fn(&I) -> K
+{
+    type Input = I;
+    type Nonterminal = ();
+    type Terminal = Result<ClaimHandle<K, A>, (K, A)>;
+
+    #[inline]
+    fn step(self, input: &I) -> NodeResult<(), Result<ClaimHandle<K, A>, (K, A)>, Self> {
+        let key = (self.key)(input);
+        match self.board.claim(key.clone(), self.agent, self.priority) {
+            Result::Ok(handle) => NodeResult::Terminal(Result::Ok(handle)),
+            Result::Err(holder) => NodeResult::Terminal(Result::Err((key, holder)))
+        }
+    }
+}
+
+/// Leaf node which releases its agent's claim on the key picked from the 
+/// input, terminating immediately with whether the agent held the key. 
+#[derive(Clone, Debug)]
+pub struct ReleaseNode<I, K, A, F> where
+    F: Fn(&I) -> K
+{
+    board: GroupBoard<K, A>,
+    agent: A,
+    key: F,
+    _junk: PhantomData<I>
+}
+
+impl<I, K, A, F> ReleaseNode<I, K, A, F> where
+    K: Hash + Eq + Clone,
+    A: PartialEq + Clone,
+    F: Fn(&I) -> K
+{
+    /// Create a new release node for the agent. 
+    pub fn new(board: GroupBoard<K, A>, agent: A, key: F) -> ReleaseNode<I, K, A, F> {
+        ReleaseNode {
+            board: board,
+            agent: agent,
+            key: key,
+            _junk: PhantomData
+        }
+    }
+}
+
+impl<I, K, A, F> BehaviorTreeNode for ReleaseNode<I, K, A, F> where
+    K: Hash + Eq + Clone,
+    A: PartialEq + Clone,
+    F: Fn(&I) -> K
+{
+    type Input = I;
+    type Nonterminal = ();
+    type Terminal = bool;
+
+    #[inline]
+    fn step(self, input: &I) -> NodeResult<(), bool, Self> {
+        NodeResult::Terminal(self.board.release(&(self.key)(input), &self.agent))
+    }
+}
+
+/// Pool of a fixed number of tokens, shared by the trees of a group of 
+/// agents, which may be run on different threads. Clones of a pool share 
+/// the same tokens. 
+#[derive(Clone, Debug)]
+pub struct TokenPool {
+    taken: Arc<AtomicUsize>,
+    capacity: usize
+}
+
+impl TokenPool {
+    /// Create a new token pool with the given number of tokens. 
+    pub fn new(capacity: usize) -> TokenPool {
+        TokenPool {
+            taken: Arc::new(AtomicUsize::new(0)),
+            capacity: capacity
+        }
+    }
+
+    /// Take a token from the pool, or return None if the pool is exhausted. 
+    /// The token is returned to the pool when it is dropped. 
+    pub fn try_acquire(&self) -> Option<Token> {
+        let capacity = self.capacity;
+        let acquired = self.taken.fetch_update(Ordering::AcqRel, Ordering::Acquire, 
+            |taken| if taken < capacity {
+                Option::Some(taken + 1)
+            } else {
+                Option::None
+            });
+        match acquired {
+            Result::Ok(_) => Option::Some(Token {
+                taken: self.taken.clone()
+            }),
+            Result::Err(_) => Option::None
+        }
+    }
+
+    /// Return the number of tokens left in the pool. 
+    pub fn available(&self) -> usize {
+        self.capacity - self.taken.load(Ordering::Acquire)
+    }
+
+    /// Return the number of tokens the pool was created with. 
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+/// Token taken from a token pool, which is returned to the pool when it is 
+/// dropped. 
+#[derive(Debug)]
+pub struct Token {
+    taken: Arc<AtomicUsize>
+}
+
+impl Drop for Token {
+    fn drop(&mut self) {
+        self.taken.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// Nonterminal of a token node. 
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum TokenState<N> {
+    /// The pool is exhausted, so the node is waiting for a token. 
+    Waiting,
+    /// The node holds a token, and its wrapped node reached the nonterminal. 
+    Holding(N)
+}
+
+/// Wrapper for a node, which waits for a token from a token pool, then 
+/// steps the node while holding the token, and returns the token when the 
+/// node terminates or the wrapper is halted. The token is also returned if 
+/// the wrapper is dropped. 
+#[derive(Debug)]
+pub struct TokenNode<N> where 
+    N: BehaviorTreeNode
+{
+    node: N,
+    pool: TokenPool,
+    token: Option<Token>
+}
+
+impl<N> TokenNode<N> where 
+    N: BehaviorTreeNode
+{
+    /// Create a new token node, which takes its token from the given pool. 
+    pub fn new(pool: TokenPool, node: N) -> TokenNode<N> {
+        TokenNode {
+            node: node,
+            pool: pool,
+            token: Option::None
+        }
+    }
+
+    /// Return whether the node currently holds a token. 
+    pub fn holds_token(&self) -> bool {
+        self.token.is_some()
+    }
+}
+
+impl<N> BehaviorTreeNode for TokenNode<N> where 
+    N: BehaviorTreeNode
+{
+    type Input = N::Input;
+    type Nonterminal = TokenState<N::Nonterminal>;
+    type Terminal = N::Terminal;
+
+    #[inline]
+    fn step(self, input: &N::Input) -> NodeResult<Self::Nonterminal, N::Terminal, Self> {
+        let token = match self.token {
+            Option::Some(token) => token,
+            Option::None => match self.pool.try_acquire() {
+                Option::Some(token) => token,
+                Option::None => return NodeResult::Nonterminal(TokenState::Waiting, self)
+            }
+        };
+        match self.node.step(input) {
+            NodeResult::Nonterminal(v, n) => NodeResult::Nonterminal(
+                TokenState::Holding(v), 
+                TokenNode {
+                    node: n,
+                    pool: self.pool,
+                    token: Option::Some(token)
+                }
+            ),
+            // The token is dropped, and so returned, along with the wrapper
+            NodeResult::Terminal(t) => NodeResult::Terminal(t)
+        }
+    }
+
+    #[inline]
+    fn halt(self, input: &N::Input) {
+        self.node.halt(input);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use coordination::{GroupBoard, ClaimPolicy, ClaimNode, ReleaseNode};
+
+    #[test]
+    fn first_come_test() {
+        let board = GroupBoard::new(ClaimPolicy::FirstCome);
+        let first = ClaimNode::new(board.clone(), "alice", 0, |target: &u32| *target);
+        let second = ClaimNode::new(board.clone(), "bob", 5, |target: &u32| *target);
+        assert_steps!(first; 3 => Terminal(Result::Ok(ref claim)) if *claim.key() == 3);
+        assert_steps!(second.clone(); 3 => Terminal(Result::Err((3, "alice"))));
+        assert_steps!(second; 4 => Terminal(Result::Ok(ref claim)) if claim.is_held());
+        assert_eq!(board.holder(&3), Option::Some("alice"));
+        let release = ReleaseNode::new(board.clone(), "bob", |target: &u32| *target);
+        assert_steps!(release.clone(); 3 => Terminal(false));
+        assert_steps!(release; 4 => Terminal(true));
+        assert_eq!(board.holder(&4), Option::None);
+        board.release_all(&"alice");
+        assert!(!board.holds(&3, &"alice"));
+    }
+
+    #[test]
+    fn priority_test() {
+        let board = GroupBoard::new(ClaimPolicy::Priority);
+        let first = board.claim("flank", 1, 2).unwrap();
+        assert_eq!(board.claim("flank", 2, 2).unwrap_err(), 1);
+        let second = board.claim("flank", 2, 3).unwrap();
+        assert!(board.holds(&"flank", &2));
+        assert!(first.is_preempted() && !first.is_held());
+        assert!(second.is_held() && !second.is_preempted());
+        assert_eq!(board.claim("flank", 1, 1).unwrap_err(), 2);
+        assert!(!first.release());
+        let renewed = board.claim("flank", 2, 4).unwrap();
+        assert!(second.is_held());
+        assert!(renewed.release());
+        assert!(!second.is_held() && !second.is_preempted());
+    }
+
+    #[test]
+    fn token_pool_test() {
+        use coordination::TokenPool;
+        let pool = TokenPool::new(2);
+        let first = pool.try_acquire().unwrap();
+        let second = pool.clone().try_acquire().unwrap();
+        assert_eq!(pool.available(), 0);
+        assert!(pool.try_acquire().is_none());
+        drop(first);
+        assert_eq!(pool.available(), 1);
+        drop(second);
+        assert_eq!(pool.available(), pool.capacity());
+    }
+
+    #[test]
+    fn token_node_test() {
+        use base_nodes::PredicateWait;
+        use behavior_tree_node::{BehaviorTreeNode, Statepoint};
+        use coordination::{TokenPool, TokenNode, TokenState};
+        let wait = PredicateWait::new(|input: &i64| {
+            if *input > 0 {
+                Statepoint::Nonterminal(*input)
+            } else {
+                Statepoint::Terminal(*input)
+            }
+        });
+        let pool = TokenPool::new(1);
+        let holding = assert_steps!(TokenNode::new(pool.clone(), wait); 
+            1 => Nonterminal(TokenState::Holding(1))
+        );
+        assert!(holding.holds_token());
+        assert_eq!(pool.available(), 0);
+        let waiting = assert_steps!(TokenNode::new(pool.clone(), wait); 
+            1 => Nonterminal(TokenState::Waiting),
+            1 => Nonterminal(TokenState::Waiting)
+        );
+        assert!(!waiting.holds_token());
+        assert_steps!(holding; 2 => Nonterminal(TokenState::Holding(2)), 0 => Terminal(0));
+        assert_eq!(pool.available(), 1);
+        let holding = assert_steps!(waiting; 3 => Nonterminal(TokenState::Holding(3)));
+        assert_eq!(pool.available(), 0);
+        holding.halt(&0);
+        assert_eq!(pool.available(), 1);
+    }
+}
@@ -0,0 +1,143 @@
+//! Nodes which are one of two node types. 
+//!
+//! A function building a subtree often picks between two different 
+//! subtrees depending on its arguments, and since the two are of different 
+//! types, it cannot return them as they are. Boxing them works, but costs an 
+//! allocation and an indirect call per step. An either node holds one of two 
+//! node types with the same input, nonterminal and terminal types instead, 
+//! and steps whichever one it holds. 
+
+use behavior_tree_node::{BehaviorTreeNode, NodeResult};
+use stackbt_automata_impl::snapshot::Snapshot;
+use tree_description::{DescribeTree, TreeDescription};
+
+/// One of two nodes of different types with the same input, nonterminal and 
+/// terminal types. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum EitherNode<L, R> {
+    /// The first node. 
+    Left(L),
+    /// The second node. 
+    Right(R)
+}
+
+impl<L, R> BehaviorTreeNode for EitherNode<L, R> where
+    L: BehaviorTreeNode,
+    R: BehaviorTreeNode<Input=L::Input, Nonterminal=L::Nonterminal, Terminal=L::Terminal>
+{
+    type Input = L::Input;
+    type Nonterminal = L::Nonterminal;
+    type Terminal = L::Terminal;
+
+    #[inline]
+    fn step(self, input: &L::Input) -> NodeResult<L::Nonterminal, L::Terminal, Self> {
+        match self {
+            EitherNode::Left(node) => match node.step(input) {
+                NodeResult::Nonterminal(n, next) => NodeResult::Nonterminal(n,
+                    EitherNode::Left(next)),
+                NodeResult::Terminal(t) => NodeResult::Terminal(t)
+            },
+            EitherNode::Right(node) => match node.step(input) {
+                NodeResult::Nonterminal(n, next) => NodeResult::Nonterminal(n,
+                    EitherNode::Right(next)),
+                NodeResult::Terminal(t) => NodeResult::Terminal(t)
+            }
+        }
+    }
+
+    #[inline]
+    fn step_with(self, input: L::Input) -> NodeResult<L::Nonterminal, L::Terminal, Self> {
+        match self {
+            EitherNode::Left(node) => match node.step_with(input) {
+                NodeResult::Nonterminal(n, next) => NodeResult::Nonterminal(n,
+                    EitherNode::Left(next)),
+                NodeResult::Terminal(t) => NodeResult::Terminal(t)
+            },
+            EitherNode::Right(node) => match node.step_with(input) {
+                NodeResult::Nonterminal(n, next) => NodeResult::Nonterminal(n,
+                    EitherNode::Right(next)),
+                NodeResult::Terminal(t) => NodeResult::Terminal(t)
+            }
+        }
+    }
+
+    #[inline]
+    fn halt(self, input: &L::Input) {
+        match self {
+            EitherNode::Left(node) => node.halt(input),
+            EitherNode::Right(node) => node.halt(input)
+        }
+    }
+}
+
+impl<L, R> Snapshot for EitherNode<L, R> where
+    L: Snapshot,
+    R: Snapshot
+{
+    type State = EitherNode<L::State, R::State>;
+
+    fn snapshot(&self) -> EitherNode<L::State, R::State> {
+        match *self {
+            EitherNode::Left(ref node) => EitherNode::Left(node.snapshot()),
+            EitherNode::Right(ref node) => EitherNode::Right(node.snapshot())
+        }
+    }
+
+    fn restore(state: EitherNode<L::State, R::State>) -> EitherNode<L, R> {
+        match state {
+            EitherNode::Left(state) => EitherNode::Left(L::restore(state)),
+            EitherNode::Right(state) => EitherNode::Right(R::restore(state))
+        }
+    }
+}
+
+impl<L, R> DescribeTree for EitherNode<L, R> where
+    L: DescribeTree,
+    R: DescribeTree + BehaviorTreeNode<Input=L::Input, Nonterminal=L::Nonterminal,
+        Terminal=L::Terminal>
+{
+    fn describe_tree() -> TreeDescription {
+        TreeDescription::branch::<Self>("EitherNode", vec![
+            ("left", L::describe_tree()),
+            ("right", R::describe_tree())
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base_nodes::PredicateWait;
+    use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+    use either_node::EitherNode;
+
+    fn countdown(input: &i64) -> Statepoint<i64, i64> {
+        if *input > 0 {
+            Statepoint::Nonterminal(*input)
+        } else {
+            Statepoint::Terminal(*input)
+        }
+    }
+
+    fn negate(input: &i64) -> Statepoint<i64, i64> {
+        Statepoint::Terminal(-input)
+    }
+
+    #[test]
+    fn either_node_test() {
+        let pick = |wait: bool| if wait {
+            EitherNode::Left(PredicateWait::new(countdown))
+        } else {
+            EitherNode::Right(PredicateWait::new(negate))
+        };
+        let node = assert_steps!(pick(true); 3 => Nonterminal(3));
+        match node {
+            EitherNode::Left(_) => (),
+            EitherNode::Right(_) => unreachable!("Expected the left node")
+        };
+        assert_steps!(node; 0 => Terminal(0));
+        match pick(false).step(&3) {
+            NodeResult::Terminal(t) => assert_eq!(t, -3),
+            NodeResult::Nonterminal(_, _) => unreachable!("Expected terminal state")
+        };
+    }
+}
@@ -0,0 +1,175 @@
+//! Parallel node over a fixed number of children of the same type. 
+//!
+//! The parallel branch node can run any number of children, but keeps their 
+//! statepoints in a buffer on the heap, and hands them to its decider 
+//! through it. When a tree runs a small, fixed number of identical 
+//! subtrees, such as the limbs of a creature, the number of children can 
+//! instead be part of the type of the node. The fixed parallel node holds 
+//! its children in an array, and returns their statepoints in an array, so 
+//! that nothing it does touches the heap. 
+//!
+//! The number of children is a const generic parameter, which needs a 
+//! compiler supporting const generics, so this module is behind the 
+//! const_generics feature. 
+
+use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+use std::array;
+
+/// A parallel node over an array of children of the same type, which steps 
+/// all of the running children with the same input each step, and returns 
+/// an array of the statepoints they reached. Children which have already 
+/// terminated are reported with their terminal. Once all of the children 
+/// have terminated, the node terminates with an array of their terminals. 
+#[derive(Clone, PartialEq, Debug)]
+pub struct FixedParallelNode<N, const K: usize> where
+    N: BehaviorTreeNode,
+    N::Terminal: Clone
+{
+    children: [Option<N>; K],
+    terminals: [Option<N::Terminal>; K]
+}
+
+impl<N, const K: usize> FixedParallelNode<N, K> where
+    N: BehaviorTreeNode,
+    N::Terminal: Clone
+{
+    /// Create a new fixed parallel node with the given children. 
+    pub fn new(children: [N; K]) -> FixedParallelNode<N, K> {
+        FixedParallelNode {
+            children: children.map(Option::Some),
+            terminals: array::from_fn(|_| Option::None)
+        }
+    }
+
+    /// Return the number of children which have not terminated yet. 
+    pub fn running(&self) -> usize {
+        self.children.iter().filter(|child| child.is_some()).count()
+    }
+}
+
+impl<N, const K: usize> BehaviorTreeNode for FixedParallelNode<N, K> where
+    N: BehaviorTreeNode,
+    N::Terminal: Clone
+{
+    type Input = N::Input;
+    type Nonterminal = [Statepoint<N::Nonterminal, N::Terminal>; K];
+    type Terminal = [N::Terminal; K];
+
+    #[inline]
+    fn step(self, input: &N::Input) -> NodeResult<Self::Nonterminal, [N::Terminal; K],
+        Self>
+    {
+        let mut mut_self = self;
+        let points = array::from_fn(|index| match mut_self.children[index].take() {
+            Option::Some(child) => match child.step(input) {
+                NodeResult::Nonterminal(v, n) => {
+                    mut_self.children[index] = Option::Some(n);
+                    Statepoint::Nonterminal(v)
+                },
+                NodeResult::Terminal(t) => {
+                    mut_self.terminals[index] = Option::Some(t.clone());
+                    Statepoint::Terminal(t)
+                }
+            },
+            Option::None => Statepoint::Terminal(mut_self.terminals[index].clone()
+                .expect("Terminated child has no terminal"))
+        });
+        if mut_self.running() > 0 {
+            NodeResult::Nonterminal(points, mut_self)
+        } else {
+            NodeResult::Terminal(points.map(|point| match point {
+                Statepoint::Terminal(t) => t,
+                Statepoint::Nonterminal(_) => unreachable!("No currently running children")
+            }))
+        }
+    }
+
+    #[inline]
+    fn halt(self, input: &N::Input) {
+        for child in IntoIterator::into_iter(self.children).flatten() {
+            child.halt(input);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base_nodes::MachineWrapper;
+    use behavior_tree_node::{BehaviorTreeNode, Statepoint};
+    use fixed_parallel_node::FixedParallelNode;
+    use stackbt_automata_impl::internal_state_machine::{InternalTransition,
+        InternalStateMachine};
+
+    #[derive(Copy, Clone)]
+    struct Countdown;
+
+    impl InternalTransition for Countdown {
+        type Input = i64;
+        type Internal = i64;
+        type Action = Statepoint<i64, i64>;
+
+        fn step(&self, input: &i64, left: &mut i64) -> Statepoint<i64, i64> {
+            *left -= *input;
+            if *left > 0 {
+                Statepoint::Nonterminal(*left)
+            } else {
+                Statepoint::Terminal(*left)
+            }
+        }
+    }
+
+    type CountdownNode = MachineWrapper<InternalStateMachine<'static, Countdown>, i64, i64>;
+
+    fn countdown(from: i64) -> CountdownNode {
+        MachineWrapper::new(InternalStateMachine::new(Countdown, from))
+    }
+
+    #[test]
+    fn fixed_parallel_node_test() {
+        let node = FixedParallelNode::new([countdown(1), countdown(2), countdown(3)]);
+        assert_eq!(node.running(), 3);
+        let node = assert_steps!(node;
+            1 => Nonterminal([
+                Statepoint::Terminal(0),
+                Statepoint::Nonterminal(1),
+                Statepoint::Nonterminal(2)
+            ]),
+            1 => Nonterminal([
+                Statepoint::Terminal(0),
+                Statepoint::Terminal(0),
+                Statepoint::Nonterminal(1)
+            ])
+        );
+        assert_eq!(node.running(), 1);
+        assert_steps!(node; 2 => Terminal([0, 0, -1]));
+    }
+
+    #[test]
+    fn fixed_parallel_halt_test() {
+        use std::cell::Cell;
+        use std::rc::Rc;
+        use behavior_tree_node::NodeResult;
+
+        struct Watched(Rc<Cell<u64>>);
+
+        impl BehaviorTreeNode for Watched {
+            type Input = ();
+            type Nonterminal = ();
+            type Terminal = ();
+
+            fn step(self, _input: &()) -> NodeResult<(), (), Watched> {
+                NodeResult::Nonterminal((), self)
+            }
+
+            fn halt(self, _input: &()) {
+                self.0.set(self.0.get() + 1);
+            }
+        }
+
+        let halts = Rc::new(Cell::new(0));
+        let node = FixedParallelNode::new([Watched(halts.clone()), Watched(halts.clone())]);
+        let node = assert_steps!(node; () => Nonterminal(_));
+        node.halt(&());
+        assert_eq!(halts.get(), 2);
+    }
+}
@@ -0,0 +1,180 @@
+//! Fuel-limited stepping of behavior tree nodes and automata. 
+//!
+//! Games usually give their AI a fixed share of each frame, and an 
+//! expensive subtree left unchecked can eat into everything else. A 
+//! fuel-limited wrapper holds a budget of fuel, and charges one unit of it 
+//! for each step of the node or transition of the automaton it wraps. Once 
+//! the budget is exhausted, it reports running out of fuel instead of 
+//! stepping, and the budget can be topped up again at the start of the 
+//! next frame. 
+
+use behavior_tree_node::{BehaviorTreeNode, NodeResult};
+use stackbt_automata_impl::automaton::Automaton;
+use tree_description::{DescribeTree, TreeDescription};
+
+/// Terminal of a fuel-limited node, or action of a fuel-limited automaton. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Fueled<T> {
+    /// The wrapped node terminated, or the wrapped automaton acted, with 
+    /// the given value. 
+    Ran(T),
+    /// The budget was exhausted before the step. 
+    OutOfFuel
+}
+
+/// Wrapper for a node or automaton, which charges one unit of fuel for each 
+/// step or transition. A fuel-limited node halts its node and terminates 
+/// once it runs out of fuel, while a fuel-limited automaton returns 
+/// OutOfFuel without transitioning until it is refueled. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct FuelLimited<N> {
+    inner: N,
+    fuel: usize
+}
+
+impl<N> FuelLimited<N> {
+    /// Create a new fuel-limited wrapper with the given budget. 
+    pub fn new(fuel: usize, inner: N) -> FuelLimited<N> {
+        FuelLimited {
+            inner: inner,
+            fuel: fuel
+        }
+    }
+
+    /// Return the fuel left in the budget. 
+    pub fn fuel(&self) -> usize {
+        self.fuel
+    }
+
+    /// Add the given amount of fuel to the budget. 
+    pub fn refuel(&mut self, fuel: usize) {
+        self.fuel = self.fuel.saturating_add(fuel);
+    }
+
+    /// Return the wrapped node or automaton. 
+    pub fn into_inner(self) -> N {
+        self.inner
+    }
+}
+
+impl<N> BehaviorTreeNode for FuelLimited<N> where N: BehaviorTreeNode {
+    type Input = N::Input;
+    type Nonterminal = N::Nonterminal;
+    type Terminal = Fueled<N::Terminal>;
+
+    #[inline]
+    fn step(self, input: &N::Input) -> NodeResult<N::Nonterminal, Fueled<N::Terminal>,
+        Self>
+    {
+        if self.fuel == 0 {
+            self.inner.halt(input);
+            return NodeResult::Terminal(Fueled::OutOfFuel);
+        }
+        match self.inner.step(input) {
+            NodeResult::Nonterminal(v, n) => NodeResult::Nonterminal(v, FuelLimited {
+                inner: n,
+                fuel: self.fuel - 1
+            }),
+            NodeResult::Terminal(t) => NodeResult::Terminal(Fueled::Ran(t))
+        }
+    }
+
+    #[inline]
+    fn step_with(self, input: N::Input) -> NodeResult<N::Nonterminal, 
+        Fueled<N::Terminal>, Self>
+    {
+        if self.fuel == 0 {
+            self.inner.halt(&input);
+            return NodeResult::Terminal(Fueled::OutOfFuel);
+        }
+        match self.inner.step_with(input) {
+            NodeResult::Nonterminal(v, n) => NodeResult::Nonterminal(v, FuelLimited {
+                inner: n,
+                fuel: self.fuel - 1
+            }),
+            NodeResult::Terminal(t) => NodeResult::Terminal(Fueled::Ran(t))
+        }
+    }
+
+    #[inline]
+    fn halt(self, input: &N::Input) {
+        self.inner.halt(input);
+    }
+}
+
+impl<'k, M> Automaton<'k> for FuelLimited<M> where M: Automaton<'k> {
+    type Input = M::Input;
+    type Action = Fueled<M::Action>;
+
+    #[inline]
+    fn transition(&mut self, input: &M::Input) -> Fueled<M::Action> {
+        if self.fuel == 0 {
+            Fueled::OutOfFuel
+        } else {
+            self.fuel -= 1;
+            Fueled::Ran(self.inner.transition(input))
+        }
+    }
+}
+
+impl<N> DescribeTree for FuelLimited<N> where N: DescribeTree {
+    fn describe_tree() -> TreeDescription {
+        TreeDescription::branch::<Self>("FuelLimited", vec![
+            ("inner", N::describe_tree())
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use behavior_tree_node::Statepoint;
+    use base_nodes::PredicateWait;
+    use fuel::{FuelLimited, Fueled};
+    use stackbt_automata_impl::automaton::Automaton;
+    use stackbt_automata_impl::internal_state_machine::InternalStateMachine;
+
+    fn countdown(i: &i64) -> Statepoint<i64, i64> {
+        if *i > 0 {
+            Statepoint::Nonterminal(*i)
+        } else {
+            Statepoint::Terminal(*i)
+        }
+    }
+
+    #[test]
+    fn fuel_limited_node_test() {
+        let node = FuelLimited::new(2, PredicateWait::new(countdown));
+        let node = assert_steps!(node;
+            3 => Nonterminal(3),
+            2 => Nonterminal(2)
+        );
+        assert_eq!(node.fuel(), 0);
+        assert_steps!(node; 1 => Terminal(Fueled::OutOfFuel));
+        let node = FuelLimited::new(1, PredicateWait::new(countdown));
+        assert_steps!(node; 0 => Terminal(Fueled::Ran(0)));
+    }
+
+    #[test]
+    fn refuel_node_test() {
+        let node = FuelLimited::new(1, PredicateWait::new(countdown));
+        let mut node = assert_steps!(node; 1 => Nonterminal(_));
+        node.refuel(1);
+        assert_steps!(node; 1 => Nonterminal(1), 0 => Terminal(Fueled::OutOfFuel));
+    }
+
+    #[test]
+    fn fuel_limited_automaton_test() {
+        let mut machine = FuelLimited::new(2, InternalStateMachine::with(
+            |input: &i64, total: &mut i64| {
+                *total += *input;
+                *total
+            }, 0));
+        assert_eq!(machine.transition(&3), Fueled::Ran(3));
+        assert_eq!(machine.transition(&3), Fueled::Ran(6));
+        assert_eq!(machine.transition(&3), Fueled::OutOfFuel);
+        assert_eq!(machine.transition(&3), Fueled::OutOfFuel);
+        machine.refuel(1);
+        assert_eq!(machine.transition(&3), Fueled::Ran(9));
+        assert_eq!(machine.fuel(), 0);
+    }
+}
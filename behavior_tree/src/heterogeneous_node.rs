@@ -0,0 +1,541 @@
+//! Serial and parallel nodes over children of different types. 
+//!
+//! The serial branch node can only switch between children which share 
+//! their nonterminal and terminal types, and the fixed and homogeneous 
+//! parallel nodes can only run children of a single type. The macros in 
+//! this module declare nodes over a list of children which can each have 
+//! their own type, wrapping the children's nonterminals and terminals in 
+//! generated enums or tuples so that the node as a whole has single ones. 
+//! Any number of children can be given, so that a five-way branch does not 
+//! have to be written as nested pairs. 
+
+#[cfg(feature = "parallel")]
+use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+
+/// Declarative macro for declaring an enumerated node over children of 
+/// different types, for use with the serial branch node. 
+///
+/// Alongside the node enum, this declares the enumerating discriminant, 
+/// with its variants in the order given, and an enum each for the 
+/// nonterminals and the terminals of the children, with a variant per 
+/// child, which the serial decider of the serial branch node is given. 
+/// Each child is given an expression which constructs it when it is 
+/// transitioned to. 
+#[cfg(feature = "serial")]
+#[macro_export]
+macro_rules! hetero_serial {
+    (
+        type Input = $inputtype:ty ;
+        $( #[ $mval:meta ] )*
+        $vis:vis enum $name:ident : $itername:ident {
+            $( $variant:ident ( $childtype:ty ) = $init:expr ),+ $(,)*
+        }
+        $( #[ $nmval:meta ] )*
+        $nvis:vis enum $nontermname:ident ;
+        $( #[ $tmval:meta ] )*
+        $tvis:vis enum $termname:ident ;
+    ) => {
+        $( #[ $mval ] )*
+        $vis enum $name {
+            $( $variant ( $childtype ) ),+
+        }
+
+        #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+        $vis enum $itername {
+            $( $variant ),+
+        }
+
+        impl_ordinal!( $itername { $( $variant ),+ } );
+
+        $( #[ $nmval ] )*
+        $nvis enum $nontermname {
+            $( $variant ( <$childtype as
+                $crate::behavior_tree_node::BehaviorTreeNode>::Nonterminal ) ),+
+        }
+
+        $( #[ $tmval ] )*
+        $tvis enum $termname {
+            $( $variant ( <$childtype as
+                $crate::behavior_tree_node::BehaviorTreeNode>::Terminal ) ),+
+        }
+
+        impl $crate::behavior_tree_node::BehaviorTreeNode for $name {
+            type Input = $inputtype;
+            type Nonterminal = $nontermname;
+            type Terminal = $termname;
+
+            fn step(self, input: & $inputtype) -> $crate::behavior_tree_node::NodeResult<
+                $nontermname, $termname, $name>
+            {
+                match self {
+                    $(
+                        $name :: $variant (val) => match val.step(input) {
+                            $crate::behavior_tree_node::NodeResult::Nonterminal(v, o) =>
+                                $crate::behavior_tree_node::NodeResult::Nonterminal(
+                                    $nontermname :: $variant (v),
+                                    $name :: $variant (o)
+                                ),
+                            $crate::behavior_tree_node::NodeResult::Terminal(v) =>
+                                $crate::behavior_tree_node::NodeResult::Terminal(
+                                    $termname :: $variant (v)
+                                )
+                        }
+                    ),+
+                }
+            }
+
+            fn halt(self, input: & $inputtype) {
+                match self {
+                    $(
+                        $name :: $variant (val) => val.halt(input)
+                    ),+
+                }
+            }
+        }
+
+        impl $crate::serial_node::EnumNode for $name {
+            type Discriminant = $itername;
+
+            fn new(discriminant: $itername) -> $name {
+                match discriminant {
+                    $(
+                        $itername :: $variant => $name :: $variant ( $init )
+                    ),+
+                }
+            }
+
+            fn discriminant_of(&self) -> $itername {
+                match self {
+                    $( $name :: $variant (_) => $itername :: $variant ),+
+                }
+            }
+        }
+    };
+}
+
+/// A child slot of a heterogeneous parallel node, holding either the child 
+/// while it runs, or its terminal once it has terminated. 
+#[cfg(feature = "parallel")]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum ParallelSlot<N> where
+    N: BehaviorTreeNode,
+    N::Terminal: Clone
+{
+    /// The child is still running. 
+    Running(N),
+    /// The child has terminated with the given terminal. 
+    Done(N::Terminal)
+}
+
+#[cfg(feature = "parallel")]
+impl<N> ParallelSlot<N> where
+    N: BehaviorTreeNode,
+    N::Terminal: Clone
+{
+    /// Step the child if it is still running, and return the statepoint it 
+    /// reached, or its terminal again if it has terminated, with the slot 
+    /// for the next step. 
+    pub fn step(self, input: &N::Input) -> (Statepoint<N::Nonterminal, N::Terminal>,
+        ParallelSlot<N>)
+    {
+        match self {
+            ParallelSlot::Running(child) => match child.step(input) {
+                NodeResult::Nonterminal(v, n) => (Statepoint::Nonterminal(v),
+                    ParallelSlot::Running(n)),
+                NodeResult::Terminal(t) => (Statepoint::Terminal(t.clone()),
+                    ParallelSlot::Done(t))
+            },
+            ParallelSlot::Done(t) => (Statepoint::Terminal(t.clone()), ParallelSlot::Done(t))
+        }
+    }
+
+    /// Return whether the child is still running. 
+    pub fn is_running(&self) -> bool {
+        match self {
+            ParallelSlot::Running(_) => true,
+            ParallelSlot::Done(_) => false
+        }
+    }
+
+    /// Return the terminal of the child, if it has terminated. 
+    pub fn terminal(self) -> Option<N::Terminal> {
+        match self {
+            ParallelSlot::Running(_) => Option::None,
+            ParallelSlot::Done(t) => Option::Some(t)
+        }
+    }
+
+    /// Halt the child if it is still running. 
+    pub fn halt(self, input: &N::Input) {
+        if let ParallelSlot::Running(child) = self {
+            child.halt(input);
+        }
+    }
+}
+
+/// Decider for a heterogeneous parallel node, which given the input and the 
+/// tuple of statepoints reached by the children, decides whether to keep 
+/// running them or to exit. Children which have terminated report their 
+/// terminal again on each step. As with the parallel decider, the decider 
+/// is borrowed mutably each step, so it can keep state of its own. 
+#[cfg(feature = "parallel")]
+pub trait HeteroParallelDecider<I, S> {
+    /// Type of the terminal returned by the parallel node itself. 
+    type Exit;
+    /// Given the input and the tuple of statepoints, either return None to 
+    /// keep running, or return the terminal value to exit with. 
+    fn each_step(&mut self, input: &I, statepoints: &S) -> Option<Self::Exit>;
+}
+
+/// Heterogeneous parallel decider which exits once all of the children have 
+/// terminated, with the tuple of their terminals. 
+#[cfg(feature = "parallel")]
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub struct HeteroWaitAll;
+
+/// Heterogeneous parallel decider which exits once any of the children has 
+/// terminated, with a tuple holding the terminals of the children which 
+/// have terminated by then, and None for the rest. 
+#[cfg(feature = "parallel")]
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub struct HeteroWaitAny;
+
+#[cfg(feature = "parallel")]
+macro_rules! hetero_decider_impl {
+    ( $( ( $( $n:ident $t:ident $idx:tt ),+ ) )+ ) => {
+        $(
+            impl<I, $( $n, $t ),+> HeteroParallelDecider<I, ( $( Statepoint<$n, $t>, )+ )>
+                for HeteroWaitAll where $( $t: Clone ),+
+            {
+                type Exit = ( $( $t, )+ );
+
+                fn each_step(&mut self, _input: &I,
+                    statepoints: &( $( Statepoint<$n, $t>, )+ )) -> Option<( $( $t, )+ )>
+                {
+                    Option::Some(( $(
+                        match statepoints.$idx {
+                            Statepoint::Nonterminal(_) => return Option::None,
+                            Statepoint::Terminal(ref t) => t.clone()
+                        },
+                    )+ ))
+                }
+            }
+
+            impl<I, $( $n, $t ),+> HeteroParallelDecider<I, ( $( Statepoint<$n, $t>, )+ )>
+                for HeteroWaitAny where $( $t: Clone ),+
+            {
+                type Exit = ( $( Option<$t>, )+ );
+
+                fn each_step(&mut self, _input: &I,
+                    statepoints: &( $( Statepoint<$n, $t>, )+ )) -> Option<( $( Option<$t>, )+ )>
+                {
+                    let settled = ( $(
+                        match statepoints.$idx {
+                            Statepoint::Nonterminal(_) => Option::None,
+                            Statepoint::Terminal(ref t) => Option::Some(t.clone())
+                        },
+                    )+ );
+                    if false $( || settled.$idx.is_some() )+ {
+                        Option::Some(settled)
+                    } else {
+                        Option::None
+                    }
+                }
+            }
+        )+
+    };
+}
+
+#[cfg(feature = "parallel")]
+hetero_decider_impl! {
+    ( N0 T0 0 )
+    ( N0 T0 0, N1 T1 1 )
+    ( N0 T0 0, N1 T1 1, N2 T2 2 )
+    ( N0 T0 0, N1 T1 1, N2 T2 2, N3 T3 3 )
+    ( N0 T0 0, N1 T1 1, N2 T2 2, N3 T3 3, N4 T4 4 )
+    ( N0 T0 0, N1 T1 1, N2 T2 2, N3 T3 3, N4 T4 4, N5 T5 5 )
+    ( N0 T0 0, N1 T1 1, N2 T2 2, N3 T3 3, N4 T4 4, N5 T5 5, N6 T6 6 )
+    ( N0 T0 0, N1 T1 1, N2 T2 2, N3 T3 3, N4 T4 4, N5 T5 5, N6 T6 6, N7 T7 7 )
+    ( N0 T0 0, N1 T1 1, N2 T2 2, N3 T3 3, N4 T4 4, N5 T5 5, N6 T6 6, N7 T7 7,
+        N8 T8 8 )
+    ( N0 T0 0, N1 T1 1, N2 T2 2, N3 T3 3, N4 T4 4, N5 T5 5, N6 T6 6, N7 T7 7,
+        N8 T8 8, N9 T9 9 )
+    ( N0 T0 0, N1 T1 1, N2 T2 2, N3 T3 3, N4 T4 4, N5 T5 5, N6 T6 6, N7 T7 7,
+        N8 T8 8, N9 T9 9, N10 T10 10 )
+    ( N0 T0 0, N1 T1 1, N2 T2 2, N3 T3 3, N4 T4 4, N5 T5 5, N6 T6 6, N7 T7 7,
+        N8 T8 8, N9 T9 9, N10 T10 10, N11 T11 11 )
+}
+
+/// Declarative macro for declaring a parallel node over children of 
+/// different types, which all take the same input. 
+///
+/// The declared struct is generic over a heterogeneous parallel decider, 
+/// and has a constructor taking the decider and then each child in order. 
+/// It steps all of its running children with the same input each step, 
+/// and gives the tuple of the statepoints they reached to the decider. If 
+/// the decider keeps running, the node returns the tuple, and if the 
+/// decider exits, the node halts its running children and terminates with 
+/// the terminal of the decider. The terminals of the children must 
+/// implement Clone, and none of the children can be named decider. 
+#[cfg(feature = "parallel")]
+#[macro_export]
+macro_rules! hetero_parallel {
+    (
+        type Input = $inputtype:ty ;
+        $( #[ $mval:meta ] )*
+        $vis:vis struct $name:ident {
+            $( $field:ident : $childtype:ty ),+ $(,)*
+        }
+    ) => {
+        $( #[ $mval ] )*
+        $vis struct $name<D> {
+            $( $field : $crate::heterogeneous_node::ParallelSlot<$childtype>, )+
+            decider: D
+        }
+
+        impl<D> $name<D> {
+            /// Create a new node running the given children, with the given 
+            /// decider. 
+            pub fn new(decider: D, $( $field : $childtype ),+ ) -> $name<D> {
+                $name {
+                    $( $field : $crate::heterogeneous_node::ParallelSlot::Running($field), )+
+                    decider: decider
+                }
+            }
+
+            /// Return the number of children which have not terminated yet. 
+            pub fn running(&self) -> usize {
+                0 $( + if self.$field.is_running() { 1 } else { 0 } )+
+            }
+        }
+
+        impl<D> $crate::behavior_tree_node::BehaviorTreeNode for $name<D> where
+            D: $crate::heterogeneous_node::HeteroParallelDecider<$inputtype, ( $(
+                $crate::behavior_tree_node::Statepoint<
+                    <$childtype as $crate::behavior_tree_node::BehaviorTreeNode>::Nonterminal,
+                    <$childtype as $crate::behavior_tree_node::BehaviorTreeNode>::Terminal>,
+            )+ )>
+        {
+            type Input = $inputtype;
+            type Nonterminal = ( $( $crate::behavior_tree_node::Statepoint<
+                <$childtype as $crate::behavior_tree_node::BehaviorTreeNode>::Nonterminal,
+                <$childtype as $crate::behavior_tree_node::BehaviorTreeNode>::Terminal>, )+ );
+            type Terminal = D::Exit;
+
+            fn step(self, input: & $inputtype) -> $crate::behavior_tree_node::NodeResult<
+                Self::Nonterminal, Self::Terminal, $name<D>>
+            {
+                let $name { $( $field, )+ mut decider } = self;
+                $( let $field = $field.step(input); )+
+                let statepoints = ( $( $field.0, )+ );
+                match decider.each_step(input, &statepoints) {
+                    Option::None => $crate::behavior_tree_node::NodeResult::Nonterminal(
+                        statepoints,
+                        $name { $( $field : $field.1, )+ decider: decider }
+                    ),
+                    Option::Some(exit) => {
+                        $( $field.1.halt(input); )+
+                        $crate::behavior_tree_node::NodeResult::Terminal(exit)
+                    }
+                }
+            }
+
+            fn halt(self, input: & $inputtype) {
+                $( self.$field.halt(input); )+
+            }
+        }
+    };
+}
+
+#[cfg(all(test, feature = "serial", feature = "compositions"))]
+mod tests_serial {
+    use behavior_tree_node::{BehaviorTreeNode, NodeResult};
+    use serial_node::{NontermReturn, SerialBranchNode};
+    use node_compositions::SerialRunner;
+
+    struct Counter(i64);
+
+    impl BehaviorTreeNode for Counter {
+        type Input = i64;
+        type Nonterminal = i64;
+        type Terminal = bool;
+
+        fn step(self, input: &i64) -> NodeResult<i64, bool, Counter> {
+            if self.0 + input < 3 {
+                NodeResult::Nonterminal(self.0 + input, Counter(self.0 + input))
+            } else {
+                NodeResult::Terminal(true)
+            }
+        }
+    }
+
+    struct Echo;
+
+    impl BehaviorTreeNode for Echo {
+        type Input = i64;
+        type Nonterminal = ();
+        type Terminal = i64;
+
+        fn step(self, input: &i64) -> NodeResult<(), i64, Echo> {
+            NodeResult::Terminal(*input)
+        }
+    }
+
+    struct Wait(u8);
+
+    impl BehaviorTreeNode for Wait {
+        type Input = i64;
+        type Nonterminal = u8;
+        type Terminal = ();
+
+        fn step(self, _input: &i64) -> NodeResult<u8, (), Wait> {
+            match self.0 {
+                0 => NodeResult::Terminal(()),
+                n => NodeResult::Nonterminal(n, Wait(n - 1))
+            }
+        }
+    }
+
+    hetero_serial! {
+        type Input = i64;
+        enum Routine : Stage {
+            Count(Counter) = Counter(0),
+            Report(Echo) = Echo,
+            Pause(Wait) = Wait(1)
+        }
+        #[derive(Debug, PartialEq)]
+        enum RoutineNonterminal;
+        #[derive(Debug, PartialEq)]
+        enum RoutineTerminal;
+    }
+
+    #[test]
+    fn hetero_serial_test() {
+        let node = SerialBranchNode::<Routine, _>::new(SerialRunner::new(), Stage::Count);
+        assert_steps!(node;
+            1 => Nonterminal(NontermReturn::Nonterminal(Stage::Count,
+                RoutineNonterminal::Count(1))),
+            2 => Nonterminal(NontermReturn::Terminal(Stage::Count,
+                RoutineTerminal::Count(true))),
+            7 => Nonterminal(NontermReturn::Terminal(Stage::Report,
+                RoutineTerminal::Report(7))),
+            0 => Nonterminal(NontermReturn::Nonterminal(Stage::Pause,
+                RoutineNonterminal::Pause(1))),
+            0 => Terminal(_)
+        );
+    }
+}
+
+#[cfg(all(test, feature = "parallel"))]
+mod tests_parallel {
+    use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+
+    struct Countdown(u8);
+
+    impl BehaviorTreeNode for Countdown {
+        type Input = u8;
+        type Nonterminal = u8;
+        type Terminal = &'static str;
+
+        fn step(self, input: &u8) -> NodeResult<u8, &'static str, Countdown> {
+            match self.0.saturating_sub(*input) {
+                0 => NodeResult::Terminal("liftoff"),
+                n => NodeResult::Nonterminal(n, Countdown(n))
+            }
+        }
+    }
+
+    struct Doubler;
+
+    impl BehaviorTreeNode for Doubler {
+        type Input = u8;
+        type Nonterminal = ();
+        type Terminal = u16;
+
+        fn step(self, input: &u8) -> NodeResult<(), u16, Doubler> {
+            NodeResult::Terminal(2 * u16::from(*input))
+        }
+    }
+
+    struct Forever;
+
+    impl BehaviorTreeNode for Forever {
+        type Input = u8;
+        type Nonterminal = bool;
+        type Terminal = ();
+
+        fn step(self, input: &u8) -> NodeResult<bool, (), Forever> {
+            match input {
+                0 => NodeResult::Terminal(()),
+                _ => NodeResult::Nonterminal(true, Forever)
+            }
+        }
+    }
+
+    hetero_parallel! {
+        type Input = u8;
+        struct Launch {
+            count: Countdown,
+            double: Doubler,
+            spin: Forever
+        }
+    }
+
+    #[test]
+    fn hetero_parallel_test() {
+        use heterogeneous_node::HeteroWaitAll;
+        let node = Launch::new(HeteroWaitAll, Countdown(3), Doubler, Forever);
+        assert_eq!(node.running(), 3);
+        let node = assert_steps!(node;
+            2 => Nonterminal((Statepoint::Nonterminal(1), Statepoint::Terminal(4),
+                Statepoint::Nonterminal(true))),
+            5 => Nonterminal((Statepoint::Terminal("liftoff"), Statepoint::Terminal(4),
+                Statepoint::Nonterminal(true)))
+        );
+        assert_eq!(node.running(), 1);
+        assert_steps!(node; 0 => Terminal(("liftoff", 4, ())));
+    }
+
+    #[test]
+    fn hetero_wait_any_test() {
+        use heterogeneous_node::HeteroWaitAny;
+        let node = Launch::new(HeteroWaitAny, Countdown(3), Doubler, Forever);
+        assert_steps!(node; 1 => Terminal((Option::None, Option::Some(2), Option::None)));
+    }
+
+    #[test]
+    fn hetero_custom_decider_test() {
+        use heterogeneous_node::HeteroParallelDecider;
+
+        // Exits once the countdown lifts off, with the number of steps taken
+        struct UntilLiftoff(u8);
+
+        impl<S1, S2> HeteroParallelDecider<u8, (Statepoint<u8, &'static str>, S1, S2)>
+            for UntilLiftoff
+        {
+            type Exit = u8;
+
+            fn each_step(&mut self, _input: &u8,
+                statepoints: &(Statepoint<u8, &'static str>, S1, S2)) -> Option<u8>
+            {
+                self.0 += 1;
+                match statepoints.0 {
+                    Statepoint::Nonterminal(_) => Option::None,
+                    Statepoint::Terminal(_) => Option::Some(self.0)
+                }
+            }
+        }
+
+        let node = Launch::new(UntilLiftoff(0), Countdown(3), Doubler, Forever);
+        let node = assert_steps!(node;
+            1 => Nonterminal((Statepoint::Nonterminal(2), Statepoint::Terminal(2),
+                Statepoint::Nonterminal(true)))
+        );
+        assert_eq!(node.running(), 2);
+        assert_steps!(node;
+            0 => Nonterminal((Statepoint::Nonterminal(2), Statepoint::Terminal(2),
+                Statepoint::Terminal(()))),
+            2 => Terminal(3)
+        );
+    }
+}
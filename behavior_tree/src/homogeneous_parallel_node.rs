@@ -0,0 +1,288 @@
+//! Parallel node over a collection of children of the same type. 
+//!
+//! Where the fixed parallel node has its number of children fixed in its 
+//! type, the homogeneous parallel node is generic over the collection which 
+//! holds its children, so that the same node can run a swarm of identical 
+//! agent subtrees held in a vector, a boxed slice, or an array. Each child 
+//! sits in a slot which is emptied once the child terminates. 
+
+use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+use std::fmt;
+
+/// A collection of slots holding behavior tree nodes of the same type, which 
+/// the homogeneous parallel node steps. 
+pub trait NodeCollection {
+    /// The type of the nodes in the collection. 
+    type Node: BehaviorTreeNode;
+
+    /// Return the slots of the collection. 
+    fn slots(&self) -> &[Option<Self::Node>];
+
+    /// Return the slots of the collection mutably. 
+    fn slots_mut(&mut self) -> &mut [Option<Self::Node>];
+}
+
+impl<N> NodeCollection for Vec<Option<N>> where
+    N: BehaviorTreeNode
+{
+    type Node = N;
+
+    fn slots(&self) -> &[Option<N>] {
+        self
+    }
+
+    fn slots_mut(&mut self) -> &mut [Option<N>] {
+        self
+    }
+}
+
+impl<N> NodeCollection for Box<[Option<N>]> where
+    N: BehaviorTreeNode
+{
+    type Node = N;
+
+    fn slots(&self) -> &[Option<N>] {
+        self
+    }
+
+    fn slots_mut(&mut self) -> &mut [Option<N>] {
+        self
+    }
+}
+
+#[cfg(feature = "const_generics")]
+impl<N, const K: usize> NodeCollection for [Option<N>; K] where
+    N: BehaviorTreeNode
+{
+    type Node = N;
+
+    fn slots(&self) -> &[Option<N>] {
+        self
+    }
+
+    fn slots_mut(&mut self) -> &mut [Option<N>] {
+        self
+    }
+}
+
+/// A parallel node over a collection of children of the same type, which 
+/// steps all of the running children with the same input each step, and 
+/// returns the statepoints they reached in order. Children which have 
+/// already terminated are reported with their terminal. Once all of the 
+/// children have terminated, the node terminates with their terminals. 
+pub struct HomogeneousParallelNode<C> where
+    C: NodeCollection,
+    <C::Node as BehaviorTreeNode>::Terminal: Clone
+{
+    children: C,
+    terminals: Vec<Option<<C::Node as BehaviorTreeNode>::Terminal>>
+}
+
+impl<C> Clone for HomogeneousParallelNode<C> where
+    C: NodeCollection + Clone,
+    <C::Node as BehaviorTreeNode>::Terminal: Clone
+{
+    fn clone(&self) -> HomogeneousParallelNode<C> {
+        HomogeneousParallelNode {
+            children: self.children.clone(),
+            terminals: self.terminals.clone()
+        }
+    }
+}
+
+impl<C> PartialEq for HomogeneousParallelNode<C> where
+    C: NodeCollection + PartialEq,
+    <C::Node as BehaviorTreeNode>::Terminal: Clone + PartialEq
+{
+    fn eq(&self, other: &HomogeneousParallelNode<C>) -> bool {
+        self.children == other.children && self.terminals == other.terminals
+    }
+}
+
+impl<C> fmt::Debug for HomogeneousParallelNode<C> where
+    C: NodeCollection + fmt::Debug,
+    <C::Node as BehaviorTreeNode>::Terminal: Clone + fmt::Debug
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("HomogeneousParallelNode")
+            .field("children", &self.children)
+            .field("terminals", &self.terminals)
+            .finish()
+    }
+}
+
+impl<C> HomogeneousParallelNode<C> where
+    C: NodeCollection,
+    <C::Node as BehaviorTreeNode>::Terminal: Clone
+{
+    /// Create a new homogeneous parallel node with the given collection of 
+    /// children. Panics if any of the slots of the collection is empty. 
+    pub fn new(children: C) -> HomogeneousParallelNode<C> {
+        let count = children.slots().len();
+        if let Option::Some(index) = children.slots().iter()
+            .position(|slot| slot.is_none())
+        {
+            panic!("Collection slot {} is empty", index);
+        }
+        HomogeneousParallelNode {
+            children: children,
+            terminals: (0..count).map(|_| Option::None).collect()
+        }
+    }
+
+    /// Return the number of children which have not terminated yet. 
+    pub fn running(&self) -> usize {
+        self.children.slots().iter().filter(|slot| slot.is_some()).count()
+    }
+}
+
+impl<N> HomogeneousParallelNode<Vec<Option<N>>> where
+    N: BehaviorTreeNode,
+    N::Terminal: Clone
+{
+    /// Create a new homogeneous parallel node with the children in the 
+    /// given vector. 
+    pub fn from_nodes(nodes: Vec<N>) -> HomogeneousParallelNode<Vec<Option<N>>> {
+        HomogeneousParallelNode::new(nodes.into_iter().map(Option::Some).collect())
+    }
+}
+
+impl<C> BehaviorTreeNode for HomogeneousParallelNode<C> where
+    C: NodeCollection,
+    <C::Node as BehaviorTreeNode>::Terminal: Clone
+{
+    type Input = <C::Node as BehaviorTreeNode>::Input;
+    type Nonterminal = Vec<Statepoint<<C::Node as BehaviorTreeNode>::Nonterminal,
+        <C::Node as BehaviorTreeNode>::Terminal>>;
+    type Terminal = Vec<<C::Node as BehaviorTreeNode>::Terminal>;
+
+    #[inline]
+    fn step(self, input: &Self::Input) -> NodeResult<Self::Nonterminal, Self::Terminal,
+        Self>
+    {
+        let mut mut_self = self;
+        let mut points = Vec::with_capacity(mut_self.terminals.len());
+        let mut running = 0;
+        for (slot, terminal) in mut_self.children.slots_mut().iter_mut()
+            .zip(mut_self.terminals.iter_mut())
+        {
+            match slot.take() {
+                Option::Some(child) => match child.step(input) {
+                    NodeResult::Nonterminal(v, n) => {
+                        *slot = Option::Some(n);
+                        running += 1;
+                        points.push(Statepoint::Nonterminal(v));
+                    },
+                    NodeResult::Terminal(t) => {
+                        *terminal = Option::Some(t.clone());
+                        points.push(Statepoint::Terminal(t));
+                    }
+                },
+                Option::None => points.push(Statepoint::Terminal(terminal.clone()
+                    .expect("Terminated child has no terminal")))
+            }
+        }
+        if running > 0 {
+            NodeResult::Nonterminal(points, mut_self)
+        } else {
+            NodeResult::Terminal(points.into_iter().map(|point| match point {
+                Statepoint::Terminal(t) => t,
+                Statepoint::Nonterminal(_) => unreachable!("No currently running children")
+            }).collect())
+        }
+    }
+
+    #[inline]
+    fn halt(self, input: &Self::Input) {
+        let mut mut_self = self;
+        for slot in mut_self.children.slots_mut() {
+            if let Option::Some(child) = slot.take() {
+                child.halt(input);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base_nodes::MachineWrapper;
+    use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+    use homogeneous_parallel_node::HomogeneousParallelNode;
+    use stackbt_automata_impl::internal_state_machine::{InternalTransition,
+        InternalStateMachine};
+
+    #[derive(Copy, Clone)]
+    struct Forager;
+
+    impl InternalTransition for Forager {
+        type Input = i64;
+        type Internal = i64;
+        type Action = Statepoint<i64, i64>;
+
+        fn step(&self, food: &i64, hunger: &mut i64) -> Statepoint<i64, i64> {
+            *hunger -= *food;
+            if *hunger > 0 {
+                Statepoint::Nonterminal(*hunger)
+            } else {
+                Statepoint::Terminal(*hunger)
+            }
+        }
+    }
+
+    type ForagerNode = MachineWrapper<InternalStateMachine<'static, Forager>, i64, i64>;
+
+    fn forager(hunger: i64) -> ForagerNode {
+        MachineWrapper::new(InternalStateMachine::new(Forager, hunger))
+    }
+
+    #[test]
+    fn swarm_test() {
+        let swarm = HomogeneousParallelNode::from_nodes((1..5).map(forager).collect());
+        assert_eq!(swarm.running(), 4);
+        let swarm = match swarm.step(&2) {
+            NodeResult::Nonterminal(points, swarm) => {
+                assert_eq!(points, vec![
+                    Statepoint::Terminal(-1),
+                    Statepoint::Terminal(0),
+                    Statepoint::Nonterminal(1),
+                    Statepoint::Nonterminal(2)
+                ]);
+                swarm
+            },
+            NodeResult::Terminal(_) => unreachable!("Swarm still hungry")
+        };
+        assert_eq!(swarm.running(), 2);
+        match swarm.step(&2) {
+            NodeResult::Terminal(terminals) => {
+                assert_eq!(terminals, vec![-1, 0, -1, 0]);
+            },
+            NodeResult::Nonterminal(_, _) => unreachable!("Swarm is fed")
+        }
+    }
+
+    #[test]
+    fn boxed_swarm_test() {
+        let slots: Box<[_]> = vec![Option::Some(forager(3)), Option::Some(forager(6))]
+            .into_boxed_slice();
+        let swarm = HomogeneousParallelNode::new(slots);
+        let swarm = assert_steps!(swarm; 3 => Nonterminal(_));
+        assert_eq!(swarm.running(), 1);
+        assert_steps!(swarm; 3 => Terminal(_));
+    }
+
+    #[cfg(feature = "const_generics")]
+    #[test]
+    fn array_swarm_test() {
+        let swarm = HomogeneousParallelNode::new([Option::Some(forager(1)),
+            Option::Some(forager(1)), Option::Some(forager(4))]);
+        let swarm = assert_steps!(swarm; 2 => Nonterminal(_));
+        assert_eq!(swarm.running(), 1);
+        assert_steps!(swarm; 2 => Terminal(_));
+    }
+
+    #[test]
+    #[should_panic(expected = "Collection slot 1 is empty")]
+    fn empty_slot_test() {
+        HomogeneousParallelNode::new(vec![Option::Some(forager(1)), Option::None]);
+    }
+}
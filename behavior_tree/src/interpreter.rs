@@ -0,0 +1,451 @@
+//! Runtime interpretation of trees described in data files. 
+//!
+//! Trees composed in code are zero-cost, but have to be recompiled whenever 
+//! their structure changes. For tweaking the structure of a tree during 
+//! development, a tree can instead be described in JSON as sequences, 
+//! selectors and decorators over leaves, which are looked up by name in a 
+//! registry of leaf factories. The description is checked and resolved 
+//! once when it is loaded into a blueprint, from which any number of 
+//! interpreted trees can be instantiated, built out of boxed nodes. 
+//!
+//! A node is described by one of: 
+//!
+//! - `"name"`, the registered leaf with the given name, 
+//! - `{"leaf": "name", "args": ...}`, the same, with arguments passed to 
+//!   the leaf factory, 
+//! - `{"sequence": [...]}`, which runs its children in order until one 
+//!   fails, 
+//! - `{"selector": [...]}`, which runs its children in order until one 
+//!   succeeds, 
+//! - `{"invert": ...}`, which swaps the success and failure of its child, 
+//! - `{"repeat": ..., "times": n}`, which runs its child again each time it 
+//!   succeeds, until it fails or has succeeded n times. 
+//!
+//! Inversion is done by an InverterNode, and repetition by a restart node, 
+//! like the RepeatNode of the control wrappers. Unlike RepeatNode, which 
+//! runs its node for every iteration regardless of how each one ends, a 
+//! repeat in a description stops at the first failure and fails with it, 
+//! which is what a sequence of n copies of its child would do. 
+//!
+//! ```
+//! use stackbt_behavior_tree::behavior_tree_node::{BehaviorTreeNode, NodeResult};
+//! use stackbt_behavior_tree::boxed_node::BoxedNode;
+//! use stackbt_behavior_tree::bt_status::BtStatus;
+//! use stackbt_behavior_tree::interpreter::LeafRegistry;
+//!
+//! struct Check;
+//!
+//! impl BehaviorTreeNode for Check {
+//!     type Input = i64;
+//!     type Nonterminal = ();
+//!     type Terminal = BtStatus;
+//!
+//!     fn step(self, input: &i64) -> NodeResult<(), BtStatus, Check> {
+//!         NodeResult::Terminal(BtStatus::from(*input > 0))
+//!     }
+//! }
+//!
+//! let registry = LeafRegistry::new()
+//!     .leaf("check", |_args| BoxedNode::new(Check));
+//! let blueprint = registry.load("{\"invert\": \"check\"}").unwrap();
+//! match blueprint.instantiate().step(&-3) {
+//!     NodeResult::Terminal(status) => assert_eq!(status, BtStatus::Success),
+//!     NodeResult::Nonterminal(_, _) => unreachable!("Check terminates at once")
+//! }
+//! ```
+
+use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+use boxed_node::BoxedNode;
+use bt_status::BtStatus;
+use control_wrappers::InverterNode;
+use map_wrappers::OutputMappedNode;
+use restart_node::{Restart, RestartNode, RestartPolicy};
+use serde_json::{self, Value};
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+
+/// Node instantiated from a blueprint, which terminates with the success or 
+/// failure of its tree. 
+pub type InterpretedNode<I> = BoxedNode<I, (), BtStatus>;
+
+/// Factory of a leaf node, given the arguments of the leaf in the 
+/// description, or null if it has none. 
+pub type LeafFactory<I> = Rc<dyn Fn(&Value) -> InterpretedNode<I>>;
+
+/// Problem found while loading a tree description. 
+#[derive(Clone, PartialEq, Debug)]
+pub enum InterpretError {
+    /// The description is not valid JSON, with the message of the parser. 
+    Parse(String),
+    /// The description refers to a leaf which is not registered. 
+    UnknownLeaf(String),
+    /// The description of a node is not one of the recognized forms. 
+    Malformed(String)
+}
+
+impl fmt::Display for InterpretError {
+    fn fmt(&self, fmter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InterpretError::Parse(message) => write!(fmter,
+                "Tree description is not valid JSON: {}", message),
+            InterpretError::UnknownLeaf(name) => write!(fmter,
+                "Leaf {:?} is not registered", name),
+            InterpretError::Malformed(node) => write!(fmter,
+                "Node {} is not a recognized node description", node)
+        }
+    }
+}
+
+/// Resolved description of a node. 
+enum Spec<I> {
+    Leaf(LeafFactory<I>, Value),
+    Sequence(Rc<Vec<Spec<I>>>),
+    Selector(Rc<Vec<Spec<I>>>),
+    Invert(Rc<Spec<I>>),
+    Repeat(Rc<Spec<I>>, u64)
+}
+
+impl<I> Clone for Spec<I> {
+    fn clone(&self) -> Spec<I> {
+        match self {
+            Spec::Leaf(factory, args) => Spec::Leaf(factory.clone(), args.clone()),
+            Spec::Sequence(children) => Spec::Sequence(children.clone()),
+            Spec::Selector(children) => Spec::Selector(children.clone()),
+            Spec::Invert(child) => Spec::Invert(child.clone()),
+            Spec::Repeat(child, times) => Spec::Repeat(child.clone(), *times)
+        }
+    }
+}
+
+impl<I> Spec<I> where I: 'static {
+    fn instantiate(&self) -> InterpretedNode<I> {
+        match self {
+            Spec::Leaf(factory, args) => factory(args),
+            Spec::Sequence(children) => BoxedNode::new(CompositeNode::new(
+                children.clone(), BtStatus::Success)),
+            Spec::Selector(children) => BoxedNode::new(CompositeNode::new(
+                children.clone(), BtStatus::Failure)),
+            Spec::Invert(child) => BoxedNode::new(InverterNode::new(child.instantiate())),
+            Spec::Repeat(child, times) => {
+                let repeat = RestartNode::with_policy(RepeatWhileSuccess {
+                    spec: child.clone(),
+                    remaining: *times
+                }, child.instantiate());
+                let forget: fn(Statepoint<(), BtStatus>) = |_| ();
+                let keep: fn(BtStatus) -> BtStatus = |status| status;
+                BoxedNode::new(OutputMappedNode::new(forget, keep, repeat))
+            }
+        }
+    }
+}
+
+/// Registry of the leaf factories tree descriptions can refer to. 
+pub struct LeafRegistry<I> {
+    leaves: HashMap<String, LeafFactory<I>>
+}
+
+impl<I> LeafRegistry<I> where I: 'static {
+    /// Create a new registry without any leaves. 
+    pub fn new() -> LeafRegistry<I> {
+        LeafRegistry {
+            leaves: HashMap::new()
+        }
+    }
+
+    /// Register a leaf factory under the given name, replacing any factory 
+    /// previously registered under it. 
+    pub fn leaf<F>(mut self, name: &str, factory: F) -> LeafRegistry<I> where
+        F: Fn(&Value) -> InterpretedNode<I> + 'static
+    {
+        self.leaves.insert(name.to_owned(), Rc::new(factory));
+        self
+    }
+
+    /// Parse and resolve a tree description. 
+    pub fn load(&self, description: &str) -> Result<TreeBlueprint<I>, InterpretError> {
+        let value: Value = serde_json::from_str(description)
+            .map_err(|e| InterpretError::Parse(e.to_string()))?;
+        Result::Ok(TreeBlueprint {
+            root: self.resolve(&value)?
+        })
+    }
+
+    fn resolve_leaf(&self, name: &str, args: Value) -> Result<Spec<I>, InterpretError> {
+        match self.leaves.get(name) {
+            Option::Some(factory) => Result::Ok(Spec::Leaf(factory.clone(), args)),
+            Option::None => Result::Err(InterpretError::UnknownLeaf(name.to_owned()))
+        }
+    }
+
+    fn resolve_children(&self, value: &Value) -> Result<Rc<Vec<Spec<I>>>, InterpretError> {
+        match value {
+            Value::Array(children) if !children.is_empty() => {
+                let resolved: Result<Vec<_>, _> = children.iter()
+                    .map(|child| self.resolve(child))
+                    .collect();
+                Result::Ok(Rc::new(resolved?))
+            },
+            _ => Result::Err(InterpretError::Malformed(value.to_string()))
+        }
+    }
+
+    fn resolve(&self, value: &Value) -> Result<Spec<I>, InterpretError> {
+        let malformed = || InterpretError::Malformed(value.to_string());
+        let object = match value {
+            Value::String(name) => return self.resolve_leaf(name, Value::Null),
+            Value::Object(object) => object,
+            _ => return Result::Err(malformed())
+        };
+        let has_only = |keys: &[&str]| object.keys().all(|key| keys.contains(&key.as_str()));
+        if let Option::Some(name) = object.get("leaf") {
+            match name {
+                Value::String(name) if has_only(&["leaf", "args"]) => self.resolve_leaf(name,
+                    object.get("args").cloned().unwrap_or(Value::Null)),
+                _ => Result::Err(malformed())
+            }
+        } else if let Option::Some(children) = object.get("sequence") {
+            if !has_only(&["sequence"]) {
+                return Result::Err(malformed());
+            }
+            Result::Ok(Spec::Sequence(self.resolve_children(children)?))
+        } else if let Option::Some(children) = object.get("selector") {
+            if !has_only(&["selector"]) {
+                return Result::Err(malformed());
+            }
+            Result::Ok(Spec::Selector(self.resolve_children(children)?))
+        } else if let Option::Some(child) = object.get("invert") {
+            if !has_only(&["invert"]) {
+                return Result::Err(malformed());
+            }
+            Result::Ok(Spec::Invert(Rc::new(self.resolve(child)?)))
+        } else if let Option::Some(child) = object.get("repeat") {
+            match object.get("times").and_then(Value::as_u64) {
+                Option::Some(times) if times > 0 && has_only(&["repeat", "times"]) =>
+                    Result::Ok(Spec::Repeat(Rc::new(self.resolve(child)?), times)),
+                _ => Result::Err(malformed())
+            }
+        } else {
+            Result::Err(malformed())
+        }
+    }
+}
+
+impl<I> Default for LeafRegistry<I> where I: 'static {
+    fn default() -> LeafRegistry<I> {
+        LeafRegistry::new()
+    }
+}
+
+/// Loaded tree description, from which trees can be instantiated. 
+pub struct TreeBlueprint<I> {
+    root: Spec<I>
+}
+
+impl<I> Clone for TreeBlueprint<I> {
+    fn clone(&self) -> TreeBlueprint<I> {
+        TreeBlueprint {
+            root: self.root.clone()
+        }
+    }
+}
+
+impl<I> TreeBlueprint<I> where I: 'static {
+    /// Instantiate a new tree from the blueprint. 
+    pub fn instantiate(&self) -> InterpretedNode<I> {
+        self.root.instantiate()
+    }
+}
+
+/// Sequence or selector, which moves on to the next child while its 
+/// children terminate with the status it continues on. 
+/// 
+/// This is not a serial branch node over the Sequence and Selector 
+/// deciders, as those need an EnumNode whose children can be built from a 
+/// discriminant alone, while the children of a description are only known 
+/// once it is loaded. The semantics are the same as theirs otherwise. 
+struct CompositeNode<I> {
+    children: Rc<Vec<Spec<I>>>,
+    index: usize,
+    current: InterpretedNode<I>,
+    continue_on: BtStatus
+}
+
+impl<I> CompositeNode<I> where I: 'static {
+    fn new(children: Rc<Vec<Spec<I>>>, continue_on: BtStatus) -> CompositeNode<I> {
+        let current = children[0].instantiate();
+        CompositeNode {
+            children: children,
+            index: 0,
+            current: current,
+            continue_on: continue_on
+        }
+    }
+}
+
+impl<I> BehaviorTreeNode for CompositeNode<I> where I: 'static {
+    type Input = I;
+    type Nonterminal = ();
+    type Terminal = BtStatus;
+
+    fn step(self, input: &I) -> NodeResult<(), BtStatus, CompositeNode<I>> {
+        let mut mut_self = self;
+        match mut_self.current.step(input) {
+            NodeResult::Nonterminal(_, current) => {
+                mut_self.current = current;
+                NodeResult::Nonterminal((), mut_self)
+            },
+            NodeResult::Terminal(status) => {
+                if status == mut_self.continue_on && mut_self.index + 1 < mut_self.children.len() {
+                    mut_self.index += 1;
+                    mut_self.current = mut_self.children[mut_self.index].instantiate();
+                    NodeResult::Nonterminal((), mut_self)
+                } else {
+                    NodeResult::Terminal(status)
+                }
+            }
+        }
+    }
+
+    fn halt(self, input: &I) {
+        self.current.halt(input);
+    }
+}
+
+/// Restart policy of a repeat, which rebuilds the child while it succeeds, 
+/// up to a number of times. 
+struct RepeatWhileSuccess<I> {
+    spec: Rc<Spec<I>>,
+    remaining: u64
+}
+
+impl<I> RestartPolicy<InterpretedNode<I>> for RepeatWhileSuccess<I> where I: 'static {
+    type Exit = BtStatus;
+
+    fn restart(&mut self, terminal: BtStatus) -> Restart<InterpretedNode<I>, BtStatus, 
+        BtStatus> 
+    {
+        if terminal == BtStatus::Success && self.remaining > 1 {
+            self.remaining -= 1;
+            Restart::Rebuild(terminal, self.spec.instantiate())
+        } else {
+            Restart::Exit(terminal)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use behavior_tree_node::{BehaviorTreeNode, NodeResult};
+    use boxed_node::BoxedNode;
+    use bt_status::BtStatus;
+    use interpreter::{InterpretError, LeafRegistry};
+    use serde_json::Value;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    struct Act {
+        name: String,
+        turns: u64,
+        succeed: bool,
+        log: Rc<RefCell<Vec<String>>>
+    }
+
+    impl BehaviorTreeNode for Act {
+        type Input = ();
+        type Nonterminal = ();
+        type Terminal = BtStatus;
+
+        fn step(self, _input: &()) -> NodeResult<(), BtStatus, Act> {
+            self.log.borrow_mut().push(self.name.clone());
+            if self.turns > 1 {
+                NodeResult::Nonterminal((), Act {
+                    turns: self.turns - 1,
+                    .. self
+                })
+            } else {
+                NodeResult::Terminal(BtStatus::from(self.succeed))
+            }
+        }
+    }
+
+    fn registry(log: &Rc<RefCell<Vec<String>>>) -> LeafRegistry<()> {
+        let (pass_log, fail_log) = (log.clone(), log.clone());
+        LeafRegistry::new()
+            .leaf("pass", move |args: &Value| BoxedNode::new(Act {
+                name: args.as_str().unwrap_or("pass").to_owned(),
+                turns: 1,
+                succeed: true,
+                log: pass_log.clone()
+            }))
+            .leaf("fail", move |args: &Value| BoxedNode::new(Act {
+                name: "fail".to_owned(),
+                turns: args.as_u64().unwrap_or(1),
+                succeed: false,
+                log: fail_log.clone()
+            }))
+    }
+
+    #[test]
+    fn interpreter_test() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let blueprint = registry(&log).load("{\"sequence\": [
+            {\"leaf\": \"pass\", \"args\": \"greet\"},
+            {\"selector\": [{\"leaf\": \"fail\", \"args\": 2}, \"pass\"]},
+            {\"repeat\": {\"invert\": \"fail\"}, \"times\": 2}
+        ]}").unwrap();
+        let node = blueprint.instantiate();
+        let node = assert_steps!(node;
+            () => Nonterminal(()),
+            () => Nonterminal(()),
+            () => Nonterminal(()),
+            () => Nonterminal(()),
+            () => Nonterminal(())
+        );
+        assert_steps!(node; () => Terminal(BtStatus::Success));
+        assert_eq!(*log.borrow(), vec!["greet", "fail", "fail", "pass", "fail", "fail"]);
+        log.borrow_mut().clear();
+        let node = blueprint.instantiate();
+        let node = assert_steps!(node; () => Nonterminal(()));
+        node.halt(&());
+        assert_eq!(*log.borrow(), vec!["greet"]);
+    }
+
+    #[test]
+    fn interpreter_repeat_test() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let registry = registry(&log);
+        let node = registry.load("{\"repeat\": \"pass\", \"times\": 3}").unwrap()
+            .instantiate();
+        let node = assert_steps!(node;
+            () => Nonterminal(()),
+            () => Nonterminal(())
+        );
+        assert_steps!(node; () => Terminal(BtStatus::Success));
+        assert_eq!(log.borrow().len(), 3);
+        log.borrow_mut().clear();
+        let node = registry.load("{\"repeat\": \"fail\", \"times\": 3}").unwrap()
+            .instantiate();
+        assert_steps!(node; () => Terminal(BtStatus::Failure));
+        assert_eq!(*log.borrow(), vec!["fail"]);
+    }
+
+    #[test]
+    fn interpreter_error_test() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let registry = registry(&log);
+        assert_eq!(registry.load("{\"selector\": [\"pass\", \"wander\"]}").err(),
+            Option::Some(InterpretError::UnknownLeaf("wander".to_owned())));
+        assert_eq!(registry.load("{\"sequence\": []}").err(),
+            Option::Some(InterpretError::Malformed("[]".to_owned())));
+        assert_eq!(registry.load("{\"repeat\": \"pass\"}").err(),
+            Option::Some(InterpretError::Malformed("{\"repeat\":\"pass\"}".to_owned())));
+        assert_eq!(registry.load("{\"invert\": \"pass\", \"times\": 2}").err(),
+            Option::Some(InterpretError::Malformed(
+                "{\"invert\":\"pass\",\"times\":2}".to_owned())));
+        match registry.load("{\"sequence\": [") {
+            Result::Err(InterpretError::Parse(_)) => (),
+            _ => panic!("Truncated description parsed")
+        }
+    }
+}
@@ -24,28 +24,120 @@
 //! hold the state of the active nodes is used, and the conceptual tree-walk 
 //! is translated to something more like a state machine transition in code, 
 //! especially if optimizations are turned on. 
+//!
+//! The node machinery beyond the core is split into cargo features, none of 
+//! which are enabled by default, so that projects only compile the parts 
+//! they use: serial for the serial and utility nodes, parallel for the 
+//! parallel node, wrappers for the mapping and controlling wrappers, 
+//! compositions for the ready-made serial and parallel controllers, 
+//! heterogeneous for nodes over children of different types, and 
+//! coordination for sharing state between the trees of groups of agents. 
+//! The nightly feature, which needs a nightly compiler, is off by default 
+//! as well. 
 
 #![cfg_attr(feature = "try_trait", feature(try_trait))]
 #![cfg_attr(feature = "existential_type", feature(existential_type))]
 
 extern crate stackbt_automata_impl;
-extern crate num_traits;
-extern crate num_derive;
+#[cfg(feature = "derive")]
+extern crate stackbt_derive;
+// Lets derived implementations name this crate by path within its own tests
+#[cfg(all(test, feature = "derive"))]
+extern crate self as stackbt_behavior_tree;
+#[cfg(feature = "tracing")]
+#[macro_use]
+extern crate log;
+#[cfg(feature = "rand")]
+extern crate rand;
+#[cfg(feature = "interpreter")]
+extern crate serde_json;
+#[cfg(feature = "rayon")]
+extern crate rayon;
 
+/// Macros for testing behavior tree nodes. 
+#[macro_use]
+pub mod testing;
+/// Scripted mock nodes and automata, and a step harness, for testing trees. 
+pub mod test_utils;
 /// The base leaf nodes on which behavior trees are built. 
 pub mod base_nodes;
 /// The behavior tree node trait and associated enums. 
 pub mod behavior_tree_node;
+/// Conventional success and failure statuses. 
+pub mod bt_status;
+/// A wrapper which rebuilds its node when it terminates. 
+pub mod restart_node;
 /// An automaton wrapper for behavior tree nodes. 
 pub mod node_runner;
+/// Behavior tree nodes whose steps are futures. 
+#[cfg(feature = "async")]
+pub mod async_node;
 /// A serial running node controller. 
+#[cfg(feature = "serial")]
 #[macro_use]
 pub mod serial_node;
+/// A call stack node, giving nodes call and return semantics. 
+pub mod call_node;
+/// Boxed nodes, for dynamic dispatch at chosen points of a tree. 
+pub mod boxed_node;
+/// Nodes which are one of two node types. 
+pub mod either_node;
+/// Runtime interpretation of trees described in data files. 
+#[cfg(feature = "interpreter")]
+pub mod interpreter;
 /// A parallel running node controller. 
+#[cfg(feature = "parallel")]
 pub mod parallel_node;
+/// Parallel node over a fixed number of children of the same type. 
+#[cfg(feature = "const_generics")]
+pub mod fixed_parallel_node;
+/// Parallel node over a collection of children of the same type. 
+#[cfg(feature = "parallel")]
+pub mod homogeneous_parallel_node;
+/// Serial and parallel nodes over children of different types. 
+#[cfg(feature = "heterogeneous")]
+#[macro_use]
+pub mod heterogeneous_node;
 /// An assortment of mapping wrappers for behavior tree nodes. 
+#[cfg(feature = "wrappers")]
 pub mod map_wrappers;
 /// An assortment of controlling wrappers for behavior tree nodes. 
+#[cfg(feature = "wrappers")]
 pub mod control_wrappers;
+/// Chainable construction of wrapped nodes. 
+#[cfg(feature = "wrappers")]
+pub mod node_ext;
 /// An assortment of serial and parallel node controllers. 
-pub mod node_compositions;
\ No newline at end of file
+#[cfg(feature = "compositions")]
+pub mod node_compositions;
+/// A utility-scored selection node. 
+#[cfg(feature = "serial")]
+pub mod utility_node;
+/// Fuel-limited stepping of nodes and automata. 
+pub mod fuel;
+/// Cooperative scheduling of the node runners of many agents. 
+pub mod scheduler;
+/// Coordination between the trees of groups of agents. 
+#[cfg(feature = "coordination")]
+pub mod coordination;
+/// Simulation of nodes and automata against scripted input. 
+pub mod simulation;
+/// Recording and replaying of node runs. 
+pub mod replay;
+/// Diffing of node state snapshots. 
+pub mod state_diff;
+/// Static descriptions of the structure of trees. 
+#[macro_use]
+pub mod tree_description;
+/// Wall-clock timing of nodes. 
+#[cfg(feature = "std_time")]
+pub mod timing;
+/// Isolation of panics in nodes. 
+#[cfg(feature = "catch_unwind")]
+pub mod catch_unwind_node;
+/// Tracing of node steps through the log crate. 
+#[cfg(feature = "tracing")]
+pub mod tracing;
+/// Machine-readable schemas of node payload types. 
+#[cfg(feature = "schema")]
+pub mod schema;
@@ -1,5 +1,7 @@
 use behavior_tree_node::{BehaviorTreeNode, NodeResult};
+use restart_node::{Restart, RestartPolicy, RestartNode};
 use std::marker::PhantomData;
+use std::collections::VecDeque;
 
 /// Wrapper for a node which converts between the provided input type and 
 /// the input type expected by the node. 
@@ -63,6 +65,22 @@ impl<N, M, I> BehaviorTreeNode for InputMappedNode<N, M, I> where
             NodeResult::Terminal(t) => NodeResult::Terminal(t)
         }
     }
+
+    #[inline]
+    fn step_with(self, input: I) -> NodeResult<N::Nonterminal, N::Terminal, Self> {
+        match self.node.step_with((self.mapper)(&input)) {
+            NodeResult::Nonterminal(n, m) => NodeResult::Nonterminal(
+                n,
+                InputMappedNode::new(self.mapper, m)
+            ),
+            NodeResult::Terminal(t) => NodeResult::Terminal(t)
+        }
+    }
+
+    #[inline]
+    fn halt(self, input: &I) {
+        self.node.halt(&(self.mapper)(input));
+    }
 }
 
 /// Wrapper for a node which converts between the statepoints emitted by the 
@@ -142,6 +160,136 @@ impl<N, M, O, S, T> BehaviorTreeNode for OutputMappedNode<N, M, O, S, T> where
             )
         }
     }
+
+    #[inline]
+    fn step_with(self, input: N::Input) -> NodeResult<S, T, Self> {
+        match self.node.step_with(input) {
+            NodeResult::Nonterminal(n, m) => NodeResult::Nonterminal(
+                (self.nonterminal_mapper)(n),
+                OutputMappedNode::new(
+                    self.nonterminal_mapper,
+                    self.terminal_mapper, 
+                    m
+                )
+            ),
+            NodeResult::Terminal(t) => NodeResult::Terminal(
+                (self.terminal_mapper)(t)
+            )
+        }
+    }
+
+    #[inline]
+    fn halt(self, input: &N::Input) {
+        self.node.halt(input);
+    }
+}
+
+/// Wrapper for a node which buffers the most recent inputs it was given in a 
+/// window of fixed capacity, and each step, presents a reduction of the 
+/// whole window to the node. This allows for smoothing of inputs and 
+/// conditions on trends in the input without external bookkeeping. 
+#[derive(Clone, PartialEq, Debug)]
+pub struct WindowedInputNode<N, R, I> where 
+    N: BehaviorTreeNode,
+    R: Fn(&VecDeque<I>) -> N::Input,
+    I: Clone
+{
+    node: N,
+    reducer: R,
+    window: VecDeque<I>,
+    capacity: usize
+}
+
+impl<N, R, I> WindowedInputNode<N, R, I> where 
+    N: BehaviorTreeNode,
+    R: Fn(&VecDeque<I>) -> N::Input,
+    I: Clone
+{
+    /// Create a new windowed input node, which keeps at most the given 
+    /// number of inputs. 
+    pub fn new(reducer: R, capacity: usize, node: N) -> WindowedInputNode<N, R, I> {
+        assert!(capacity > 0, "Window capacity must be nonzero");
+        WindowedInputNode {
+            node: node,
+            reducer: reducer,
+            window: VecDeque::with_capacity(capacity),
+            capacity: capacity
+        }
+    }
+
+    /// Create a new windowed input node whose window is prefilled with the 
+    /// given inputs, from oldest to newest. 
+    pub fn with_window<K>(reducer: R, capacity: usize, prefill: K, node: N) -> 
+        WindowedInputNode<N, R, I> where 
+        K: IntoIterator<Item=I>
+    {
+        let mut new_node = WindowedInputNode::new(reducer, capacity, node);
+        for item in prefill {
+            new_node.push_input(item);
+        }
+        new_node
+    }
+
+    /// Get the current window of inputs, from oldest to newest. 
+    pub fn window(&self) -> &VecDeque<I> {
+        &self.window
+    }
+
+    fn push_input(&mut self, input: I) {
+        if self.window.len() >= self.capacity {
+            self.window.pop_front();
+        }
+        self.window.push_back(input);
+    }
+
+    fn step_window(self) -> NodeResult<N::Nonterminal, N::Terminal, Self> {
+        let reduced = (self.reducer)(&self.window);
+        let WindowedInputNode { node, reducer, window, capacity } = self;
+        match node.step_with(reduced) {
+            NodeResult::Nonterminal(n, m) => NodeResult::Nonterminal(
+                n,
+                WindowedInputNode {
+                    node: m,
+                    reducer: reducer,
+                    window: window,
+                    capacity: capacity
+                }
+            ),
+            NodeResult::Terminal(t) => NodeResult::Terminal(t)
+        }
+    }
+}
+
+impl<N, R, I> BehaviorTreeNode for WindowedInputNode<N, R, I> where 
+    N: BehaviorTreeNode,
+    R: Fn(&VecDeque<I>) -> N::Input,
+    I: Clone
+{
+    type Input = I;
+    type Nonterminal = N::Nonterminal;
+    type Terminal = N::Terminal;
+
+    #[inline]
+    fn step(self, input: &I) -> NodeResult<N::Nonterminal, N::Terminal, Self> {
+        let mut mut_self = self;
+        mut_self.push_input(input.clone());
+        mut_self.step_window()
+    }
+
+    #[inline]
+    fn step_with(self, input: I) -> NodeResult<N::Nonterminal, N::Terminal, Self> {
+        let mut mut_self = self;
+        mut_self.push_input(input);
+        mut_self.step_window()
+    }
+
+    #[inline]
+    fn halt(self, input: &I) {
+        let mut mut_self = self;
+        mut_self.push_input(input.clone());
+        let reduced = (mut_self.reducer)(&mut_self.window);
+        mut_self.node.halt(&reduced);
+    }
 }
 
 #[derive(Copy, Clone, PartialEq, Debug)]
@@ -207,6 +355,138 @@ impl<N, M> BehaviorTreeNode for LazyConstructedNode<N, M> where
             NodeResult::Terminal(t) => NodeResult::Terminal(t)
         }
     }
+
+    #[inline]
+    fn step_with(self, input: N::Input) -> NodeResult<N::Nonterminal, 
+        N::Terminal, Self> 
+    {
+        let mut mut_self = self;
+        let node = match mut_self.inside.take().unwrap() {
+            LazyConstructedInner::Node(n) => n,
+            LazyConstructedInner::Pending(c) => c(&input)
+        };
+        match node.step_with(input) {
+            NodeResult::Nonterminal(v, n) => NodeResult::Nonterminal(
+                v,
+                LazyConstructedNode::from_existing(n)
+            ),
+            NodeResult::Terminal(t) => NodeResult::Terminal(t)
+        }
+    }
+
+    #[inline]
+    fn halt(self, input: &N::Input) {
+        // A node which was never constructed has nothing to clean up
+        if let Option::Some(LazyConstructedInner::Node(n)) = self.inside {
+            n.halt(input);
+        }
+    }
+}
+
+/// Restart policy of a relazily constructed node, which builds each node 
+/// from the first input it is to be stepped with. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct RelazyPolicy<M> {
+    maker: M
+}
+
+impl<N, M> RestartPolicy<N> for RelazyPolicy<M> where
+    N: BehaviorTreeNode,
+    M: Fn(&N::Input) -> N
+{
+    type Exit = ();
+
+    fn node(&mut self, input: &N::Input, current: Option<N>) -> N {
+        match current {
+            Option::Some(n) => n,
+            Option::None => (self.maker)(input)
+        }
+    }
+
+    fn restart(&mut self, terminal: N::Terminal) -> Restart<N, N::Terminal, ()> {
+        Restart::Defer(terminal)
+    }
+}
+
+/// Wrapper for a node like LazyConstructedNode, which after its node 
+/// terminates, returns to deferring initialization, so that a new node is 
+/// constructed from the next input supplied. This gives each run of the 
+/// node fresh parameters. As it can always construct another node, the 
+/// wrapper never terminates itself, and instead reports the terminals of its 
+/// node as nonterminals. 
+pub type RelazyConstructedNode<N, M> = RestartNode<N, RelazyPolicy<M>>;
+
+impl<N, M> RelazyConstructedNode<N, M> where
+    N: BehaviorTreeNode,
+    M: Fn(&N::Input) -> N
+{
+    /// Create a new relazily constructed behavior tree node. 
+    pub fn new(maker: M) -> RelazyConstructedNode<N, M> {
+        RestartNode::deferred(RelazyPolicy {
+            maker: maker
+        })
+    }
+
+    /// Return whether the node is waiting for an input to be constructed 
+    /// from. 
+    pub fn is_pending(&self) -> bool {
+        self.node().is_none()
+    }
+}
+
+/// Restart policy of a custom constructed node, which builds a fresh node 
+/// from the constructor whenever the node terminates. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct ConstructorRestart<C> {
+    constructor: C
+}
+
+impl<N, C> RestartPolicy<N> for ConstructorRestart<C> where
+    N: BehaviorTreeNode,
+    C: Fn() -> N
+{
+    type Exit = ();
+
+    fn restart(&mut self, terminal: N::Terminal) -> Restart<N, N::Terminal, ()> {
+        Restart::Rebuild(terminal, (self.constructor)())
+    }
+}
+
+/// Wrapper for a node, which holds a constructor for the node, and resets 
+/// the node to a freshly constructed one whenever it terminates. Parents and 
+/// wrappers which need to reset a child, such as the step controlled node, 
+/// can also request a fresh instance from the constructor, so that nodes 
+/// holding closures, which cannot implement Default, can still be reset. As 
+/// the wrapper never terminates itself, it reports the terminals of its node 
+/// as nonterminals. 
+pub type CustomConstructedNode<N, C> = RestartNode<N, ConstructorRestart<C>>;
+
+impl<N, C> CustomConstructedNode<N, C> where
+    N: BehaviorTreeNode,
+    C: Fn() -> N
+{
+    /// Create a new custom constructed node, constructing its first node. 
+    pub fn new(constructor: C) -> CustomConstructedNode<N, C> {
+        let node = constructor();
+        RestartNode::with_policy(ConstructorRestart {
+            constructor: constructor
+        }, node)
+    }
+
+    /// Halt the node, and replace it with a freshly constructed one. 
+    pub fn reset(self, input: &N::Input) -> CustomConstructedNode<N, C> {
+        let (node, policy) = self.into_parts();
+        if let Option::Some(n) = node {
+            n.halt(input);
+        }
+        CustomConstructedNode::new(policy.constructor)
+    }
+
+    /// Return a new wrapper with a freshly constructed node and a copy of 
+    /// the constructor, leaving this one untouched. 
+    pub fn fresh(&self) -> CustomConstructedNode<N, C> where C: Clone {
+        CustomConstructedNode::new(self.policy().constructor.clone())
+    }
 }
 
 #[cfg(test)]
@@ -227,17 +507,10 @@ mod tests {
             }
         });
         let wrapped_node = InputMappedNode::new(|input: &i64| -input, base_node);
-        let wrapped_node_1 = match wrapped_node.step(&-5) {
-            NodeResult::Nonterminal(v, m) => {
-                assert_eq!(v, 5);
-                m
-            },
-            _ => unreachable!("Expected nonterminal state")
-        };
-        match wrapped_node_1.step(&4) {
-            NodeResult::Terminal(x) => assert_eq!(x, -4),
-            _ => unreachable!("Expected terminal state"),
-        };
+        assert_steps!(wrapped_node;
+            -5 => Nonterminal(5),
+            4 => Terminal(-4)
+        );
     }
 
     #[test]
@@ -255,17 +528,10 @@ mod tests {
             |val: i64| val-1,
             base_node
         );
-        let wrapped_node_1 = match wrapped_node.step(&5) {
-            NodeResult::Nonterminal(v, m) => {
-                assert_eq!(v, 6);
-                m
-            },
-            _ => unreachable!("Expected nonterminal state")
-        };
-        match wrapped_node_1.step(&-4) {
-            NodeResult::Terminal(x) => assert_eq!(x, -5),
-            _ => unreachable!("Expected terminal state"),
-        };
+        assert_steps!(wrapped_node;
+            5 => Nonterminal(6),
+            -4 => Terminal(-5)
+        );
     }
 
     #[derive(Copy, Clone, Default)]
@@ -291,30 +557,127 @@ mod tests {
         let new_node = LazyConstructedNode::new(|input: &i64| {
             MachineWrapper::new(InternalStateMachine::new(IndefinitePlayback, *input))
         });
-        let new_node_1 = match new_node.step(&2) {
-            NodeResult::Nonterminal(x, y) => {
-                assert_eq!(x, 2);
-                y
-            },
-            _ => unreachable!("Expected nonterminal state")
-        };
-        match new_node_1.step(&4) {
-            NodeResult::Nonterminal(x, _) => assert_eq!(x, 2),
-            _ => unreachable!("Expected nonterminal state")
-        };
+        assert_steps!(new_node;
+            2 => Nonterminal(2),
+            4 => Nonterminal(2)
+        );
         let new_node_2 = LazyConstructedNode::new(|input: &i64| {
             MachineWrapper::new(InternalStateMachine::new(IndefinitePlayback, *input))
         });
-        let new_node_3 = match new_node_2.step(&5) {
-            NodeResult::Nonterminal(x, y) => {
-                assert_eq!(x, 5);
-                y
+        assert_steps!(new_node_2;
+            5 => Nonterminal(5),
+            10 => Nonterminal(5)
+        );
+    }
+
+    #[test]
+    fn relazy_constructor_test() {
+        use map_wrappers::RelazyConstructedNode;
+        // Counts down from the first input given to each run
+        let new_node = RelazyConstructedNode::new(|input: &i64| {
+            let start = *input;
+            MachineWrapper::new(InternalStateMachine::with(|_i: &i64, left: &mut i64| {
+                *left -= 1;
+                if *left > 0 {
+                    Statepoint::Nonterminal(*left)
+                } else {
+                    Statepoint::Terminal(())
+                }
+            }, start))
+        });
+        assert!(new_node.is_pending());
+        let new_node = assert_steps!(new_node;
+            2 => Nonterminal(Statepoint::Nonterminal(1)),
+            7 => Nonterminal(Statepoint::Terminal(()))
+        );
+        assert!(new_node.is_pending());
+        assert_steps!(new_node;
+            3 => Nonterminal(Statepoint::Nonterminal(2)),
+            9 => Nonterminal(Statepoint::Nonterminal(1)),
+            9 => Nonterminal(Statepoint::Terminal(()))
+        );
+    }
+
+    #[test]
+    fn custom_constructor_test() {
+        use map_wrappers::CustomConstructedNode;
+        use control_wrappers::{StepControlledNode, StepDecision, StepCtrlNonterm};
+        // Sums its inputs, terminating when the sum passes 5
+        let constructor = | | MachineWrapper::new(InternalStateMachine::with(
+            |input: &i64, sum: &mut i64| {
+                *sum += input;
+                if *sum > 5 {
+                    Statepoint::Terminal(*sum)
+                } else {
+                    Statepoint::Nonterminal(*sum)
+                }
+            }, 0));
+        let node = CustomConstructedNode::new(constructor);
+        let node = assert_steps!(node;
+            3 => Nonterminal(Statepoint::Nonterminal(3)),
+            4 => Nonterminal(Statepoint::Terminal(7)),
+            2 => Nonterminal(Statepoint::Nonterminal(2))
+        );
+        let fresh = node.fresh();
+        assert_steps!(node.reset(&0);
+            1 => Nonterminal(Statepoint::Nonterminal(1))
+        );
+        let controlled = StepControlledNode::new(|input: &i64| if *input < 0 {
+            StepDecision::Reset(CustomConstructedNode::new(constructor))
+        } else {
+            StepDecision::Play
+        }, fresh);
+        assert_steps!(controlled;
+            2 => Nonterminal(StepCtrlNonterm::Stepped(Statepoint::Nonterminal(2))),
+            -1 => Nonterminal(StepCtrlNonterm::Paused),
+            1 => Nonterminal(StepCtrlNonterm::Stepped(Statepoint::Nonterminal(1)))
+        );
+    }
+
+    #[test]
+    fn custom_constructor_reset_test() {
+        use map_wrappers::CustomConstructedNode;
+        use test_utils::HaltCounter;
+        let probe: HaltCounter<i64, i64> = HaltCounter::new();
+        let counter = probe.clone();
+        let node = CustomConstructedNode::new(move | | counter.clone());
+        let node = assert_steps!(node; 1 => Nonterminal(Statepoint::Nonterminal(1)));
+        let node = node.reset(&2);
+        assert_eq!(probe.halts(), 1);
+        assert_steps!(node; 3 => Nonterminal(Statepoint::Nonterminal(3)));
+    }
+
+    #[test]
+    fn windowed_input_test() {
+        use map_wrappers::WindowedInputNode;
+        use std::collections::VecDeque;
+        let base_node = PredicateWait::new(|input: &i64| {
+            if *input > 0 {
+                Statepoint::Nonterminal(*input)
+            } else {
+                Statepoint::Terminal(*input)
+            }
+        });
+        let wrapped_node = WindowedInputNode::new(
+            |window: &VecDeque<i64>| window.iter().sum::<i64>(), 
+            3, 
+            base_node
+        );
+        let wrapped_node_1 = assert_steps!(wrapped_node;
+            4 => Nonterminal(4),
+            3 => Nonterminal(7),
+            -2 => Nonterminal(5)
+        );
+        let wrapped_node_2 = match wrapped_node_1.step_with(1) {
+            NodeResult::Nonterminal(v, m) => {
+                assert_eq!(v, 2);
+                m
             },
             _ => unreachable!("Expected nonterminal state")
         };
-        match new_node_3.step(&10) {
-            NodeResult::Nonterminal(x, _) => assert_eq!(x, 5),
-            _ => unreachable!("Expected nonterminal state")
-        };
+        assert_eq!(wrapped_node_2.window().len(), 3);
+        assert_steps!(wrapped_node_2;
+            -4 => Terminal(-5)
+        );
     }
-}
\ No newline at end of file
+}
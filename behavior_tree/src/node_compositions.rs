@@ -1,35 +1,175 @@
 use behavior_tree_node::Statepoint;
-use serial_node::{SerialDecider, NontermDecision, TermDecision};
+use bt_status::BtStatus;
+use serial_node::{SerialDecider, NontermDecision, TermDecision, Ordinal};
 use parallel_node::ParallelDecider;
 use std::marker::PhantomData;
 use std::iter::Iterator;
-use num_traits::{FromPrimitive, ToPrimitive};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::rc::Rc;
+#[cfg(feature = "rand")]
+use rand::Rng;
+
+/// Trait for the order in which serial runners and repeaters step through 
+/// the children of a serial branch node. 
+pub trait Traversal<E> {
+    /// Return the first child in the order, or None if the order is empty. 
+    fn first(&self) -> Option<E>;
+
+    /// Return the child after the given one in the order, or None if the 
+    /// given child is the last one or is not in the order. 
+    fn after(&self, current: E) -> Option<E>;
+}
+
+/// Traversal of the children in the order of their successors. 
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct Ascending;
+
+impl<E> Traversal<E> for Ascending where E: Ordinal {
+    fn first(&self) -> Option<E> {
+        Option::Some(E::first())
+    }
+
+    fn after(&self, current: E) -> Option<E> {
+        current.next()
+    }
+}
+
+/// Traversal of the children in the reverse order of their successors. 
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct Descending;
+
+impl<E> Traversal<E> for Descending where E: Ordinal + PartialEq {
+    fn first(&self) -> Option<E> {
+        Option::Some(E::last())
+    }
+
+    fn after(&self, current: E) -> Option<E> {
+        let mut before = E::first();
+        if before == current {
+            return Option::None;
+        }
+        while let Option::Some(e) = before.next() {
+            if e == current {
+                return Option::Some(before);
+            }
+            before = e;
+        }
+        Option::None
+    }
+}
+
+/// Traversal of the children in an explicitly listed order. Each child may 
+/// be listed at most once, as the child after a listed child would be 
+/// ambiguous otherwise. 
+#[derive(Clone, PartialEq, Debug)]
+pub struct ExplicitOrder<E> {
+    order: Vec<E>
+}
+
+impl<E> ExplicitOrder<E> where E: Copy + PartialEq {
+    /// Create a new explicit order with the given list of children, or 
+    /// return the first child listed more than once. 
+    pub fn new(order: Vec<E>) -> Result<ExplicitOrder<E>, E> {
+        for (index, e) in order.iter().enumerate() {
+            if order[..index].contains(e) {
+                return Result::Err(*e);
+            }
+        }
+        Result::Ok(ExplicitOrder {
+            order: order
+        })
+    }
+}
+
+impl<E> Traversal<E> for ExplicitOrder<E> where E: Copy + PartialEq {
+    fn first(&self) -> Option<E> {
+        self.order.first().cloned()
+    }
+
+    fn after(&self, current: E) -> Option<E> {
+        let position = self.order.iter().position(|e| *e == current)?;
+        self.order.get(position + 1).cloned()
+    }
+}
+
+/// Traversal which follows another traversal, but skips over the listed 
+/// children. 
+#[derive(Clone, PartialEq, Debug)]
+pub struct Skipping<E, P> {
+    order: P,
+    skipped: Vec<E>
+}
+
+impl<E, P> Skipping<E, P> where P: Traversal<E> {
+    /// Create a new skipping traversal over the given traversal. 
+    pub fn new(order: P, skipped: Vec<E>) -> Skipping<E, P> {
+        Skipping {
+            order: order,
+            skipped: skipped
+        }
+    }
+
+    fn skip_from(&self, mut candidate: Option<E>) -> Option<E> where E: Copy + PartialEq {
+        while let Option::Some(e) = candidate {
+            if !self.skipped.contains(&e) {
+                return Option::Some(e);
+            }
+            candidate = self.order.after(e);
+        }
+        Option::None
+    }
+}
+
+impl<E, P> Traversal<E> for Skipping<E, P> where 
+    E: Copy + PartialEq, 
+    P: Traversal<E> 
+{
+    fn first(&self) -> Option<E> {
+        self.skip_from(self.order.first())
+    }
+
+    fn after(&self, current: E) -> Option<E> {
+        self.skip_from(self.order.after(current))
+    }
+}
 
 /// Runs all nodes in sequence, one at a time, regardless of how they resolve 
-/// in the end. 
+/// in the end. By default, the nodes are run in the order of their 
+/// successors, but another traversal may be given. 
 #[derive(Copy, Clone, PartialEq, Debug)]
-pub struct SerialRunner<E, I, N, T> where E: Copy + FromPrimitive + ToPrimitive {
+pub struct SerialRunner<E, I, N, T, P=Ascending> where P: Traversal<E> {
+    order: P,
     _who_cares: PhantomData<(E, I, N, T)>
 }
 
-impl<E, I, N, T> SerialRunner<E, I, N, T> where E: Copy + FromPrimitive + ToPrimitive {
+impl<E, I, N, T> SerialRunner<E, I, N, T> where E: Ordinal {
     pub fn new() -> SerialRunner<E, I, N, T> {
+        SerialRunner::with_order(Ascending)
+    }
+}
+
+impl<E, I, N, T, P> SerialRunner<E, I, N, T, P> where P: Traversal<E> {
+    /// Create a new serial runner which runs the nodes in the given order. 
+    pub fn with_order(order: P) -> SerialRunner<E, I, N, T, P> {
         SerialRunner {
+            order: order,
             _who_cares: PhantomData
         }
     }
 }
 
 impl<E, I, N, T> Default for SerialRunner<E, I, N, T> where 
-    E: Copy + FromPrimitive + ToPrimitive 
+    E: Ordinal 
 {
     fn default() -> SerialRunner<E, I, N, T> {
         SerialRunner::new()
     }
 }
 
-impl<E, I, N, T> SerialDecider for SerialRunner<E, I, N, T> where 
-    E: Copy + FromPrimitive + ToPrimitive 
+impl<E, I, N, T, P> SerialDecider for SerialRunner<E, I, N, T, P> where 
+    P: Traversal<E> 
 {
     type Enum = E;
     type Input = I;
@@ -37,12 +177,12 @@ impl<E, I, N, T> SerialDecider for SerialRunner<E, I, N, T> where
     type Term = T;
     type Exit = ();
 
-    fn on_nonterminal(&self, _i: &I, _o: E, statept: N) -> NontermDecision<E, N, ()> {
+    fn on_nonterminal(&mut self, _i: &I, _o: E, statept: N) -> NontermDecision<E, N, ()> {
         NontermDecision::Step(statept)
     }
 
-    fn on_terminal(&self, _i: &I, ordinal: E, statept: T) -> TermDecision<E, T, ()> {
-        match E::from_u64(ordinal.to_u64().unwrap()+1) {
+    fn on_terminal(&mut self, _i: &I, ordinal: E, statept: T) -> TermDecision<E, T, ()> {
+        match self.order.after(ordinal) {
             Option::Some(e) => {
                 TermDecision::Trans(e, statept)
             },
@@ -51,87 +191,1090 @@ impl<E, I, N, T> SerialDecider for SerialRunner<E, I, N, T> where
     }
 }
 
-/// Runs nodes in sequence until one resolves into an Option::Some, which 
-/// depending on context may be either success or failure. 
-#[derive(Copy, Clone, PartialEq, Debug)]
-pub struct SerialSelector<E, I, N, T> where E: Copy + FromPrimitive + ToPrimitive {
+/// Runs nodes in sequence until one resolves into an Option::Some, which 
+/// depending on context may be either success or failure. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct SerialSelector<E, I, N, T> where E: Ordinal {
+    _who_cares: PhantomData<(E, I, N, T)>
+}
+
+impl<E, I, N, T> SerialSelector<E, I, N, T> where 
+    E: Ordinal 
+{
+    pub fn new() -> SerialSelector<E, I, N, T> {
+        SerialSelector {
+            _who_cares: PhantomData
+        }
+    }
+}
+
+impl<E, I, N, T> Default for SerialSelector<E, I, N, T> where 
+    E: Ordinal 
+{
+    fn default() -> SerialSelector<E, I, N, T> {
+        SerialSelector::new()
+    }
+}
+
+impl<E, I, N, T> SerialDecider for SerialSelector<E, I, N, T> where 
+    E: Ordinal 
+{
+    type Enum = E;
+    type Input = I;
+    type Nonterm = N;
+    type Term = Option<T>;
+    type Exit = Option<(E, T)>;
+
+    fn on_nonterminal(&mut self, _i: &I, _o: E, statept: N) -> NontermDecision<E, N, 
+        Option<(E, T)>> 
+    {
+        NontermDecision::Step(statept)
+    }
+
+    fn on_terminal(&mut self, _i: &I, ord: E, statept: Option<T>) -> TermDecision<E, Option<T>, 
+        Option<(E, T)>> 
+    {
+        match statept {
+            Option::Some(t) => TermDecision::Exit(Option::Some((ord, t))),
+            Option::None => match ord.next() {
+                Option::Some(e) => TermDecision::Trans(e, Option::None),
+                Option::None => TermDecision::Exit(Option::None)
+            }
+        }
+    }
+}
+
+/// Runs all nodes in sequence, one at a time, and from the end, repeat 
+/// back to the beginning. By default, the nodes are run in the order of 
+/// their successors, but another traversal may be given. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct SerialRepeater<E, I, N, T, P=Ascending> where P: Traversal<E> {
+    order: P,
+    _who_cares: PhantomData<(E, I, N, T)>
+}
+
+impl<E, I, N, T> SerialRepeater<E, I, N, T> where 
+    E: Ordinal 
+{
+    pub fn new() -> SerialRepeater<E, I, N, T> {
+        SerialRepeater::with_order(Ascending)
+    }
+}
+
+impl<E, I, N, T, P> SerialRepeater<E, I, N, T, P> where P: Traversal<E> {
+    /// Create a new serial repeater which runs the nodes in the given order. 
+    /// 
+    /// # Panics 
+    /// 
+    /// Panics if the order is empty. 
+    pub fn with_order(order: P) -> SerialRepeater<E, I, N, T, P> {
+        assert!(order.first().is_some(), "Serial repeater order is empty");
+        SerialRepeater {
+            order: order,
+            _who_cares: PhantomData
+        }
+    }
+}
+
+impl<E, I, N, T> Default for SerialRepeater<E, I, N, T> where 
+    E: Ordinal 
+{
+    fn default() -> SerialRepeater<E, I, N, T> {
+        SerialRepeater::new()
+    }
+}
+
+impl<E, I, N, T, P> SerialDecider for SerialRepeater<E, I, N, T, P> where 
+    P: Traversal<E> 
+{
+    type Enum = E;
+    type Input = I;
+    type Nonterm = N;
+    type Term = T;
+    type Exit = ();
+
+    fn on_nonterminal(&mut self, _i: &I, _o: E, statept: N) -> NontermDecision<E, N, ()> {
+        NontermDecision::Step(statept)
+    }
+
+    fn on_terminal(&mut self, _i: &I, ordinal: E, statept: T) -> TermDecision<E, T, ()> {
+        match self.order.after(ordinal) {
+            Option::Some(e) => TermDecision::Trans(e, statept),
+            Option::None => TermDecision::Trans(self.order.first()
+                .expect("Serial repeater order is empty"), statept)
+        }
+    }
+}
+
+/// Classification of a terminal as either a success or a failure. 
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Outcome {
+    /// The node succeeded. 
+    Success,
+    /// The node failed. 
+    Failure
+}
+
+/// Trait for terminal types which can be classified as successes or 
+/// failures, for use with the deciders which run until all or any of their 
+/// children succeed. 
+pub trait Classify {
+    /// Classify the terminal as a success or a failure. 
+    fn classify(&self) -> Outcome;
+}
+
+impl Classify for Outcome {
+    fn classify(&self) -> Outcome {
+        *self
+    }
+}
+
+impl Classify for bool {
+    fn classify(&self) -> Outcome {
+        if *self {
+            Outcome::Success
+        } else {
+            Outcome::Failure
+        }
+    }
+}
+
+impl Classify for BtStatus {
+    fn classify(&self) -> Outcome {
+        match *self {
+            BtStatus::Success => Outcome::Success,
+            BtStatus::Failure => Outcome::Failure
+        }
+    }
+}
+
+impl<S, F> Classify for Result<S, F> {
+    fn classify(&self) -> Outcome {
+        match *self {
+            Result::Ok(_) => Outcome::Success,
+            Result::Err(_) => Outcome::Failure
+        }
+    }
+}
+
+/// Runs nodes in sequence until one fails, exiting with the failing child 
+/// and its terminal, or with Ok if all of them succeed. By default, the 
+/// nodes are run in the order of their successors, but another traversal 
+/// may be given. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct SerialAll<E, I, N, T, P=Ascending> where P: Traversal<E> {
+    order: P,
+    _who_cares: PhantomData<(E, I, N, T)>
+}
+
+impl<E, I, N, T> SerialAll<E, I, N, T> where E: Ordinal {
+    pub fn new() -> SerialAll<E, I, N, T> {
+        SerialAll::with_order(Ascending)
+    }
+}
+
+impl<E, I, N, T, P> SerialAll<E, I, N, T, P> where P: Traversal<E> {
+    /// Create a new serial decider which runs the nodes in the given order. 
+    pub fn with_order(order: P) -> SerialAll<E, I, N, T, P> {
+        SerialAll {
+            order: order,
+            _who_cares: PhantomData
+        }
+    }
+}
+
+impl<E, I, N, T> Default for SerialAll<E, I, N, T> where 
+    E: Ordinal 
+{
+    fn default() -> SerialAll<E, I, N, T> {
+        SerialAll::new()
+    }
+}
+
+impl<E, I, N, T, P> SerialDecider for SerialAll<E, I, N, T, P> where 
+    T: Classify,
+    P: Traversal<E> 
+{
+    type Enum = E;
+    type Input = I;
+    type Nonterm = N;
+    type Term = T;
+    type Exit = Result<(), (E, T)>;
+
+    fn on_nonterminal(&mut self, _i: &I, _o: E, statept: N) -> NontermDecision<E, N, 
+        Result<(), (E, T)>> 
+    {
+        NontermDecision::Step(statept)
+    }
+
+    fn on_terminal(&mut self, _i: &I, ord: E, statept: T) -> TermDecision<E, T, 
+        Result<(), (E, T)>> 
+    {
+        match statept.classify() {
+            Outcome::Success => match self.order.after(ord) {
+                Option::Some(e) => TermDecision::Trans(e, statept),
+                Option::None => TermDecision::Exit(Result::Ok(()))
+            },
+            Outcome::Failure => TermDecision::Exit(Result::Err((ord, statept)))
+        }
+    }
+}
+
+/// Runs nodes in sequence until one succeeds, exiting with the succeeding 
+/// child and its terminal, or with Err if all of them fail. By default, the 
+/// nodes are run in the order of their successors, but another traversal 
+/// may be given. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct SerialAny<E, I, N, T, P=Ascending> where P: Traversal<E> {
+    order: P,
+    _who_cares: PhantomData<(E, I, N, T)>
+}
+
+impl<E, I, N, T> SerialAny<E, I, N, T> where E: Ordinal {
+    pub fn new() -> SerialAny<E, I, N, T> {
+        SerialAny::with_order(Ascending)
+    }
+}
+
+impl<E, I, N, T, P> SerialAny<E, I, N, T, P> where P: Traversal<E> {
+    /// Create a new serial decider which runs the nodes in the given order. 
+    pub fn with_order(order: P) -> SerialAny<E, I, N, T, P> {
+        SerialAny {
+            order: order,
+            _who_cares: PhantomData
+        }
+    }
+}
+
+impl<E, I, N, T> Default for SerialAny<E, I, N, T> where 
+    E: Ordinal 
+{
+    fn default() -> SerialAny<E, I, N, T> {
+        SerialAny::new()
+    }
+}
+
+impl<E, I, N, T, P> SerialDecider for SerialAny<E, I, N, T, P> where 
+    T: Classify,
+    P: Traversal<E> 
+{
+    type Enum = E;
+    type Input = I;
+    type Nonterm = N;
+    type Term = T;
+    type Exit = Result<(E, T), ()>;
+
+    fn on_nonterminal(&mut self, _i: &I, _o: E, statept: N) -> NontermDecision<E, N, 
+        Result<(E, T), ()>> 
+    {
+        NontermDecision::Step(statept)
+    }
+
+    fn on_terminal(&mut self, _i: &I, ord: E, statept: T) -> TermDecision<E, T, 
+        Result<(E, T), ()>> 
+    {
+        match statept.classify() {
+            Outcome::Success => TermDecision::Exit(Result::Ok((ord, statept))),
+            Outcome::Failure => match self.order.after(ord) {
+                Option::Some(e) => TermDecision::Trans(e, statept),
+                Option::None => TermDecision::Exit(Result::Err(()))
+            }
+        }
+    }
+}
+
+/// Record of which child of a history selector most recently succeeded for 
+/// each classification of the input. Clones of a history share the same 
+/// record, so that it outlives the selectors built with it. 
+#[derive(Debug)]
+pub struct SelectionHistory<K, E> {
+    record: Rc<RefCell<HashMap<K, E>>>
+}
+
+impl<K, E> SelectionHistory<K, E> where K: Hash + Eq {
+    /// Create a new, empty selection history. 
+    pub fn new() -> SelectionHistory<K, E> {
+        SelectionHistory {
+            record: Rc::new(RefCell::new(HashMap::new()))
+        }
+    }
+
+    /// Return the child which most recently succeeded for the given 
+    /// classification, if any did. 
+    pub fn recall(&self, key: &K) -> Option<E> where E: Copy {
+        self.record.borrow().get(key).cloned()
+    }
+
+    /// Forget all of the recorded successes. 
+    pub fn clear(&self) {
+        self.record.borrow_mut().clear();
+    }
+
+    fn remember(&self, key: K, child: E) {
+        self.record.borrow_mut().insert(key, child);
+    }
+}
+
+impl<K, E> Clone for SelectionHistory<K, E> {
+    fn clone(&self) -> SelectionHistory<K, E> {
+        SelectionHistory {
+            record: self.record.clone()
+        }
+    }
+}
+
+impl<K, E> Default for SelectionHistory<K, E> where K: Hash + Eq {
+    fn default() -> SelectionHistory<K, E> {
+        SelectionHistory::new()
+    }
+}
+
+/// Runs nodes in sequence until one succeeds, like SerialAny, but first 
+/// tries the child which most recently succeeded for the same 
+/// classification of the input, as kept in a shared selection history. 
+/// After the remembered child, the rest of the children are tried in the 
+/// order of their successors. 
+/// 
+/// The remembered child is looked up in initial, which the serial branch 
+/// node calls when it is entered, so it is the first child to run. 
+#[derive(Clone, Debug)]
+pub struct HistorySelector<E, I, N, T, K, C> where 
+    E: Ordinal,
+    C: Fn(&I) -> K
+{
+    classifier: C,
+    history: SelectionHistory<K, E>,
+    preferred: Option<E>,
+    _who_cares: PhantomData<(I, N, T)>
+}
+
+impl<E, I, N, T, K, C> HistorySelector<E, I, N, T, K, C> where 
+    E: Ordinal + PartialEq,
+    K: Hash + Eq,
+    C: Fn(&I) -> K
+{
+    /// Create a new history selector, which classifies inputs with the 
+    /// classifier and records its successes in the history. 
+    pub fn new(classifier: C, history: SelectionHistory<K, E>) -> 
+        HistorySelector<E, I, N, T, K, C> 
+    {
+        HistorySelector {
+            classifier: classifier,
+            history: history,
+            preferred: Option::None,
+            _who_cares: PhantomData
+        }
+    }
+
+    /// Return the child to start at for the input, which is the child that 
+    /// most recently succeeded for its classification, or the first child 
+    /// if none did. 
+    pub fn start(&mut self, input: &I) -> E {
+        let preferred = self.history.recall(&(self.classifier)(input));
+        self.preferred = preferred;
+        preferred.unwrap_or_else(E::first)
+    }
+
+    fn after(&self, current: E) -> Option<E> {
+        let preferred = self.preferred;
+        let mut candidate = if preferred == Option::Some(current) {
+            Option::Some(E::first())
+        } else {
+            current.next()
+        };
+        while candidate.is_some() && candidate == preferred {
+            candidate = candidate.and_then(E::next);
+        }
+        candidate
+    }
+}
+
+impl<E, I, N, T, K, C> SerialDecider for HistorySelector<E, I, N, T, K, C> where 
+    E: Ordinal + PartialEq,
+    T: Classify,
+    K: Hash + Eq,
+    C: Fn(&I) -> K
+{
+    type Enum = E;
+    type Input = I;
+    type Nonterm = N;
+    type Term = T;
+    type Exit = Result<(E, T), ()>;
+
+    fn on_nonterminal(&mut self, _i: &I, _o: E, statept: N) -> NontermDecision<E, N, 
+        Result<(E, T), ()>> 
+    {
+        NontermDecision::Step(statept)
+    }
+
+    fn initial(&mut self, input: &I, _built: E) -> Option<E> {
+        Option::Some(self.start(input))
+    }
+
+    fn on_terminal(&mut self, input: &I, ord: E, statept: T) -> TermDecision<E, T, 
+        Result<(E, T), ()>> 
+    {
+        match statept.classify() {
+            Outcome::Success => {
+                self.history.remember((self.classifier)(input), ord);
+                TermDecision::Exit(Result::Ok((ord, statept)))
+            },
+            Outcome::Failure => match self.after(ord) {
+                Option::Some(e) => TermDecision::Trans(e, statept),
+                Option::None => TermDecision::Exit(Result::Err(()))
+            }
+        }
+    }
+}
+
+/// Record of which child of a round-robin selector most recently succeeded. 
+/// Clones of a record share it, so that it outlives the selectors built 
+/// with it, such as when a node runner restarts the node holding one. 
+#[derive(Debug)]
+pub struct RoundRobinRecord<E> where E: Copy {
+    last: Rc<Cell<Option<E>>>
+}
+
+impl<E> RoundRobinRecord<E> where E: Copy {
+    /// Create a new, empty round-robin record. 
+    pub fn new() -> RoundRobinRecord<E> {
+        RoundRobinRecord {
+            last: Rc::new(Cell::new(Option::None))
+        }
+    }
+
+    /// Return the child which most recently succeeded, if any did. 
+    pub fn last(&self) -> Option<E> {
+        self.last.get()
+    }
+
+    /// Forget the recorded success. 
+    pub fn clear(&self) {
+        self.last.set(Option::None);
+    }
+}
+
+impl<E> Clone for RoundRobinRecord<E> where E: Copy {
+    fn clone(&self) -> RoundRobinRecord<E> {
+        RoundRobinRecord {
+            last: self.last.clone()
+        }
+    }
+}
+
+impl<E> Default for RoundRobinRecord<E> where E: Copy {
+    fn default() -> RoundRobinRecord<E> {
+        RoundRobinRecord::new()
+    }
+}
+
+/// Runs nodes in sequence until one succeeds, like SerialAny, but starts 
+/// each pass from the child after the one which most recently succeeded, 
+/// as kept in a shared round-robin record, wrapping around from the last 
+/// child to the first. The pass fails once every child has been tried. 
+/// 
+/// The child to start from is looked up in initial, which the serial branch 
+/// node calls when it is entered. When the selector is driven without 
+/// initial, the pass starts from the first child which terminates. 
+#[derive(Clone, Debug)]
+pub struct RoundRobinSelector<E, I, N, T> where E: Ordinal {
+    record: RoundRobinRecord<E>,
+    started: Option<E>,
+    _who_cares: PhantomData<(I, N, T)>
+}
+
+impl<E, I, N, T> RoundRobinSelector<E, I, N, T> where 
+    E: Ordinal + PartialEq
+{
+    /// Create a new round-robin selector, which records its successes in 
+    /// the record. 
+    pub fn new(record: RoundRobinRecord<E>) -> RoundRobinSelector<E, I, N, T> {
+        RoundRobinSelector {
+            record: record,
+            started: Option::None,
+            _who_cares: PhantomData
+        }
+    }
+
+    /// Return the child to start the pass from, which is the child after the 
+    /// one that most recently succeeded, or the first child if none did. 
+    pub fn start(&mut self) -> E {
+        let start = self.record.last().map_or_else(E::first, E::wrapping_next);
+        self.started = Option::Some(start);
+        start
+    }
+
+    fn after(&mut self, current: E) -> Option<E> {
+        // Without start, the pass started from the child which terminated
+        let started = *self.started.get_or_insert(current);
+        let next = current.wrapping_next();
+        if next == started {
+            Option::None
+        } else {
+            Option::Some(next)
+        }
+    }
+}
+
+impl<E, I, N, T> SerialDecider for RoundRobinSelector<E, I, N, T> where 
+    E: Ordinal + PartialEq,
+    T: Classify
+{
+    type Enum = E;
+    type Input = I;
+    type Nonterm = N;
+    type Term = T;
+    type Exit = Result<(E, T), ()>;
+
+    fn on_nonterminal(&mut self, _i: &I, _o: E, statept: N) -> NontermDecision<E, N, 
+        Result<(E, T), ()>> 
+    {
+        NontermDecision::Step(statept)
+    }
+
+    fn initial(&mut self, _i: &I, _built: E) -> Option<E> {
+        Option::Some(self.start())
+    }
+
+    fn on_terminal(&mut self, _i: &I, ord: E, statept: T) -> TermDecision<E, T, 
+        Result<(E, T), ()>> 
+    {
+        match statept.classify() {
+            Outcome::Success => {
+                self.record.last.set(Option::Some(ord));
+                TermDecision::Exit(Result::Ok((ord, statept)))
+            },
+            Outcome::Failure => match self.after(ord) {
+                Option::Some(e) => TermDecision::Trans(e, statept),
+                Option::None => TermDecision::Exit(Result::Err(()))
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+struct BanditRecord<E> {
+    // Successes and attempts of each child tried so far
+    counts: Vec<(E, u64, u64)>,
+    rng: u64
+}
+
+impl<E> BanditRecord<E> {
+    // Xorshift generator, which is plenty for picking children to explore
+    fn next_unit(&mut self) -> f64 {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 7;
+        self.rng ^= self.rng << 17;
+        (self.rng >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// Per-child success statistics of an epsilon-greedy selector, along with 
+/// the state of its random number generator. Clones of the statistics share 
+/// them, so that they outlive the selectors built with them. 
+#[derive(Debug)]
+pub struct BanditStats<E> {
+    record: Rc<RefCell<BanditRecord<E>>>
+}
+
+impl<E> BanditStats<E> where E: Ordinal + PartialEq {
+    /// Create new, empty statistics, with the random number generator 
+    /// seeded with the given seed. 
+    pub fn new(seed: u64) -> BanditStats<E> {
+        BanditStats {
+            record: Rc::new(RefCell::new(BanditRecord {
+                counts: Vec::new(),
+                // The generator is stuck at zero if started there
+                rng: if seed == 0 { 0x9E37_79B9_7F4A_7C15 } else { seed }
+            }))
+        }
+    }
+
+    /// Return the number of successes and the number of attempts recorded 
+    /// for the child. 
+    pub fn counts(&self, child: E) -> (u64, u64) {
+        self.record.borrow().counts.iter()
+            .find(|entry| entry.0 == child)
+            .map_or((0, 0), |entry| (entry.1, entry.2))
+    }
+
+    /// Return the child with the best success rate. Children which have not 
+    /// been tried yet are counted as always succeeding, so that each child 
+    /// is tried at least once before being judged. 
+    pub fn best(&self) -> E {
+        let mut best = E::first();
+        let mut best_rate = -1.0;
+        let mut current = Option::Some(E::first());
+        while let Option::Some(child) = current {
+            let rate = match self.counts(child) {
+                (_, 0) => 1.0,
+                (successes, attempts) => successes as f64 / attempts as f64
+            };
+            if rate > best_rate {
+                best = child;
+                best_rate = rate;
+            }
+            current = child.next();
+        }
+        best
+    }
+
+    fn record(&self, child: E, outcome: Outcome) {
+        let success = if outcome == Outcome::Success { 1 } else { 0 };
+        let mut record = self.record.borrow_mut();
+        match record.counts.iter().position(|entry| entry.0 == child) {
+            Option::Some(index) => {
+                record.counts[index].1 += success;
+                record.counts[index].2 += 1;
+            },
+            Option::None => record.counts.push((child, success, 1))
+        };
+    }
+
+    fn random_child(&self) -> E {
+        let mut count = 0;
+        let mut current = Option::Some(E::first());
+        while let Option::Some(child) = current {
+            count += 1;
+            current = child.next();
+        }
+        let unit = self.record.borrow_mut().next_unit();
+        let mut index = (unit * count as f64) as usize;
+        let mut child = E::first();
+        while index > 0 {
+            child = child.wrapping_next();
+            index -= 1;
+        }
+        child
+    }
+
+    fn explore(&self, epsilon: f64) -> bool {
+        self.record.borrow_mut().next_unit() < epsilon
+    }
+}
+
+impl<E> Clone for BanditStats<E> {
+    fn clone(&self) -> BanditStats<E> {
+        BanditStats {
+            record: self.record.clone()
+        }
+    }
+}
+
+/// Runs a single node picked by an epsilon-greedy bandit, exiting with Ok or 
+/// Err depending on how the node's terminal classifies. Most of the time, 
+/// the child with the best success rate so far is picked, but with a 
+/// probability of epsilon, a child is picked at random instead, so that the 
+/// statistics keep up with changes in which child works best. 
+/// 
+/// The bandit makes its pick in initial, which the serial branch node calls 
+/// when it is entered, so each entry of the node runs a freshly picked child 
+/// and records one result for it. 
+#[derive(Clone, Debug)]
+pub struct EpsilonGreedySelector<E, I, N, T> where E: Ordinal {
+    stats: BanditStats<E>,
+    epsilon: f64,
+    _who_cares: PhantomData<(I, N, T)>
+}
+
+impl<E, I, N, T> EpsilonGreedySelector<E, I, N, T> where 
+    E: Ordinal + PartialEq
+{
+    /// Create a new epsilon-greedy selector, which records its results in 
+    /// the statistics. 
+    /// 
+    /// # Panics 
+    /// 
+    /// Panics if epsilon is not between 0 and 1. 
+    pub fn new(epsilon: f64, stats: BanditStats<E>) -> EpsilonGreedySelector<E, I, N, T> {
+        assert!((0.0..=1.0).contains(&epsilon), "Epsilon is not between 0 and 1");
+        EpsilonGreedySelector {
+            stats: stats,
+            epsilon: epsilon,
+            _who_cares: PhantomData
+        }
+    }
+
+    /// Pick the child to run. 
+    pub fn start(&self) -> E {
+        if self.stats.explore(self.epsilon) {
+            self.stats.random_child()
+        } else {
+            self.stats.best()
+        }
+    }
+}
+
+impl<E, I, N, T> SerialDecider for EpsilonGreedySelector<E, I, N, T> where 
+    E: Ordinal + PartialEq,
+    T: Classify
+{
+    type Enum = E;
+    type Input = I;
+    type Nonterm = N;
+    type Term = T;
+    type Exit = Result<(E, T), (E, T)>;
+
+    fn on_nonterminal(&mut self, _i: &I, _o: E, statept: N) -> NontermDecision<E, N, 
+        Result<(E, T), (E, T)>> 
+    {
+        NontermDecision::Step(statept)
+    }
+
+    fn initial(&mut self, _i: &I, _built: E) -> Option<E> {
+        Option::Some(self.start())
+    }
+
+    fn on_terminal(&mut self, _i: &I, ord: E, statept: T) -> TermDecision<E, T, 
+        Result<(E, T), (E, T)>> 
+    {
+        let outcome = statept.classify();
+        self.stats.record(ord, outcome);
+        match outcome {
+            Outcome::Success => TermDecision::Exit(Result::Ok((ord, statept))),
+            Outcome::Failure => TermDecision::Exit(Result::Err((ord, statept)))
+        }
+    }
+}
+
+/// Runs nodes in priority order until one succeeds, like SerialAny, but 
+/// keeps checking whether a child of higher priority than the running one 
+/// has become eligible to run, interrupting the running child if so. 
+/// 
+/// Eligibility is decided by the guard, which is given each child and the 
+/// input, and is checked for each child before the running one, in the 
+/// order of their successors, every time the running child steps to a 
+/// nonterminal. Once one is eligible, the serial branch node halts the 
+/// running child and switches to the eligible one, which runs from the next 
+/// step onwards. When a child fails, the next eligible child after it is 
+/// run, and if there are none left, the selector exits with Err. 
+/// 
+/// Ineligible children are also skipped by initial, which the serial branch 
+/// node calls when it is entered. If no child is eligible then, the node 
+/// keeps the child it was built with. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct ReactiveSelector<E, I, N, T, G> where 
+    E: Ordinal,
+    G: Fn(E, &I) -> bool
+{
+    guard: G,
+    _who_cares: PhantomData<(E, I, N, T)>
+}
+
+impl<E, I, N, T, G> ReactiveSelector<E, I, N, T, G> where 
+    E: Ordinal + PartialEq,
+    G: Fn(E, &I) -> bool
+{
+    /// Create a new reactive selector, which checks the eligibility of 
+    /// children with the guard. 
+    pub fn new(guard: G) -> ReactiveSelector<E, I, N, T, G> {
+        ReactiveSelector {
+            guard: guard,
+            _who_cares: PhantomData
+        }
+    }
+
+    /// Return the eligible child with the highest priority for the input, 
+    /// or None if no child is eligible. 
+    pub fn start(&self, input: &I) -> Option<E> {
+        self.eligible_from(Option::Some(E::first()), input)
+    }
+
+    fn eligible_from(&self, mut candidate: Option<E>, input: &I) -> Option<E> {
+        while let Option::Some(e) = candidate {
+            if (self.guard)(e, input) {
+                return Option::Some(e);
+            }
+            candidate = e.next();
+        }
+        Option::None
+    }
+}
+
+impl<E, I, N, T, G> SerialDecider for ReactiveSelector<E, I, N, T, G> where 
+    E: Ordinal + PartialEq,
+    T: Classify,
+    G: Fn(E, &I) -> bool
+{
+    type Enum = E;
+    type Input = I;
+    type Nonterm = N;
+    type Term = T;
+    type Exit = Result<(E, T), ()>;
+
+    fn on_nonterminal(&mut self, input: &I, ord: E, statept: N) -> NontermDecision<E, N, 
+        Result<(E, T), ()>> 
+    {
+        let mut candidate = E::first();
+        while candidate != ord {
+            if (self.guard)(candidate, input) {
+                return NontermDecision::Trans(candidate, statept);
+            }
+            candidate = match candidate.next() {
+                Option::Some(e) => e,
+                Option::None => break
+            };
+        }
+        NontermDecision::Step(statept)
+    }
+
+    fn initial(&mut self, input: &I, _built: E) -> Option<E> {
+        self.start(input)
+    }
+
+    fn on_terminal(&mut self, input: &I, ord: E, statept: T) -> TermDecision<E, T, 
+        Result<(E, T), ()>> 
+    {
+        match statept.classify() {
+            Outcome::Success => TermDecision::Exit(Result::Ok((ord, statept))),
+            Outcome::Failure => match self.eligible_from(ord.next(), input) {
+                Option::Some(e) => TermDecision::Trans(e, statept),
+                Option::None => TermDecision::Exit(Result::Err(()))
+            }
+        }
+    }
+}
+
+/// Runs nodes one after another indefinitely, like SerialRepeater, but 
+/// picks the child to run next by scoring each child against the input 
+/// every time the running child terminates, and running the highest scoring 
+/// one. The chosen child is reported in the nonterminals of the serial 
+/// branch node while it runs. 
+/// 
+/// Each child is scored by its own scorer, and children without one are 
+/// never picked. Scorers of different types can be given by boxing them. 
+/// To keep children which score about the same from taking turns, the 
+/// child which just terminated can be given an inertia bonus, like the 
+/// running child of a UtilityNode, which another child must outscore it by 
+/// to be picked instead of it. Scores which are NaN or infinite are 
+/// skipped, and if no child scores a finite value, the child which just 
+/// terminated is run again, or the first scored child if none did. 
+/// 
+/// The first child is picked by scoring the children in initial, which the 
+/// serial branch node calls when it is entered, so no inertia is given to 
+/// the child the node was built with. 
+#[derive(Clone, PartialEq, Debug)]
+pub struct UtilitySelector<E, I, N, T, S> where 
+    S: Fn(&I) -> f64
+{
+    scorers: Vec<(E, S)>,
+    inertia: f64,
+    _who_cares: PhantomData<(I, N, T)>
+}
+
+impl<E, I, N, T, S> UtilitySelector<E, I, N, T, S> where 
+    E: Copy + PartialEq,
+    S: Fn(&I) -> f64
+{
+    /// Create a new utility selector, with the children paired with their 
+    /// scorers. 
+    /// 
+    /// # Panics 
+    /// 
+    /// Panics if no scorers are given. 
+    pub fn new(scorers: Vec<(E, S)>) -> UtilitySelector<E, I, N, T, S> {
+        assert!(!scorers.is_empty(), "Utility selector has no scorers");
+        UtilitySelector {
+            scorers: scorers,
+            inertia: 0.0,
+            _who_cares: PhantomData
+        }
+    }
+
+    /// Add the inertia to the score of the child which just terminated. 
+    pub fn with_inertia(mut self, inertia: f64) -> UtilitySelector<E, I, N, T, S> {
+        self.inertia = inertia;
+        self
+    }
+
+    /// Return the highest scoring child for the input. Ties go to the child 
+    /// whose scorer was given first. 
+    pub fn start(&self, input: &I) -> E {
+        self.pick(input, Option::None)
+    }
+
+    fn pick(&self, input: &I, previous: Option<E>) -> E {
+        let mut best: Option<(E, f64)> = Option::None;
+        for &(child, ref scorer) in self.scorers.iter() {
+            let mut score = scorer(input);
+            if previous == Option::Some(child) {
+                score += self.inertia;
+            }
+            // A NaN or infinite score never displaces a finite best
+            if !score.is_finite() {
+                continue;
+            }
+            best = match best {
+                Option::Some((_, best_score)) if best_score >= score => best,
+                _ => Option::Some((child, score))
+            };
+        }
+        match best {
+            Option::Some((child, _)) => child,
+            Option::None => previous.unwrap_or(self.scorers[0].0)
+        }
+    }
+}
+
+impl<E, I, N, T, S> SerialDecider for UtilitySelector<E, I, N, T, S> where 
+    E: Copy + PartialEq,
+    S: Fn(&I) -> f64
+{
+    type Enum = E;
+    type Input = I;
+    type Nonterm = N;
+    type Term = T;
+    type Exit = ();
+
+    fn on_nonterminal(&mut self, _i: &I, _o: E, statept: N) -> NontermDecision<E, N, ()> {
+        NontermDecision::Step(statept)
+    }
+
+    fn initial(&mut self, input: &I, _built: E) -> Option<E> {
+        Option::Some(self.start(input))
+    }
+
+    fn on_terminal(&mut self, input: &I, ord: E, statept: T) -> TermDecision<E, T, ()> {
+        TermDecision::Trans(self.pick(input, Option::Some(ord)), statept)
+    }
+}
+
+/// Runs nodes one after another indefinitely, like SerialRepeater, but 
+/// picks the child to run next uniformly at random every time the running 
+/// child terminates. The children are picked with the given random number 
+/// generator, so that seeding it keeps the picks deterministic. 
+/// 
+/// The first child is drawn in initial, which the serial branch node calls 
+/// when it is entered, so the child it was built with is never run unless 
+/// it happens to be drawn. 
+#[cfg(feature = "rand")]
+#[derive(Debug)]
+pub struct RandomSelector<E, I, N, T, R> where 
+    E: Ordinal,
+    R: Rng
+{
+    rng: R,
     _who_cares: PhantomData<(E, I, N, T)>
 }
 
-impl<E, I, N, T> SerialSelector<E, I, N, T> where 
-    E: Copy + FromPrimitive + ToPrimitive 
+#[cfg(feature = "rand")]
+impl<E, I, N, T, R> RandomSelector<E, I, N, T, R> where 
+    E: Ordinal,
+    R: Rng
 {
-    pub fn new() -> SerialSelector<E, I, N, T> {
-        SerialSelector {
+    /// Create a new random selector, which picks children with the random 
+    /// number generator. 
+    pub fn new(rng: R) -> RandomSelector<E, I, N, T, R> {
+        RandomSelector {
+            rng: rng,
             _who_cares: PhantomData
         }
     }
-}
 
-impl<E, I, N, T> Default for SerialSelector<E, I, N, T> where 
-    E: Copy + FromPrimitive + ToPrimitive 
-{
-    fn default() -> SerialSelector<E, I, N, T> {
-        SerialSelector::new()
+    /// Pick the child to run. 
+    pub fn start(&mut self) -> E {
+        let mut count = 0;
+        let mut current = Option::Some(E::first());
+        while let Option::Some(child) = current {
+            count += 1;
+            current = child.next();
+        }
+        let mut index = self.rng.gen_range(0, count);
+        let mut child = E::first();
+        while index > 0 {
+            child = child.wrapping_next();
+            index -= 1;
+        }
+        child
     }
 }
 
-impl<E, I, N, T> SerialDecider for SerialSelector<E, I, N, T> where 
-    E: Copy + FromPrimitive + ToPrimitive 
+#[cfg(feature = "rand")]
+impl<E, I, N, T, R> SerialDecider for RandomSelector<E, I, N, T, R> where 
+    E: Ordinal,
+    R: Rng
 {
     type Enum = E;
     type Input = I;
     type Nonterm = N;
-    type Term = Option<T>;
-    type Exit = Option<(E, T)>;
+    type Term = T;
+    type Exit = ();
 
-    fn on_nonterminal(&self, _i: &I, _o: E, statept: N) -> NontermDecision<E, N, 
-        Option<(E, T)>> 
-    {
+    fn on_nonterminal(&mut self, _i: &I, _o: E, statept: N) -> NontermDecision<E, N, ()> {
         NontermDecision::Step(statept)
     }
 
-    fn on_terminal(&self, _i: &I, ord: E, statept: Option<T>) -> TermDecision<E, Option<T>, 
-        Option<(E, T)>> 
-    {
-        match statept {
-            Option::Some(t) => TermDecision::Exit(Option::Some((ord, t))),
-            // u64 is enough, right? 
-            Option::None => match E::from_u64(ord.to_u64().unwrap()+1) {
-                Option::Some(e) => TermDecision::Trans(e, Option::None),
-                Option::None => TermDecision::Exit(Option::None)
-            }
-        }
+    fn initial(&mut self, _i: &I, _built: E) -> Option<E> {
+        Option::Some(self.start())
+    }
+
+    fn on_terminal(&mut self, _i: &I, _o: E, statept: T) -> TermDecision<E, T, ()> {
+        TermDecision::Trans(self.start(), statept)
     }
 }
 
-/// Runs all nodes in sequence, one at a time, and from the end, repeat 
-/// back to the beginning. 
-#[derive(Copy, Clone, PartialEq, Debug)]
-pub struct SerialRepeater<E, I, N, T> where E: Copy + FromPrimitive + ToPrimitive {
-    _who_cares: PhantomData<(E, I, N, T)>
+/// Runs nodes one after another indefinitely, like RandomSelector, but 
+/// picks each child with a probability proportional to its weight. Children 
+/// without a weight are never picked. 
+/// 
+/// The weights also apply to the first child, which is drawn in initial 
+/// when the serial branch node is entered, rather than being the child 
+/// the serial branch node was built with. 
+#[cfg(feature = "rand")]
+#[derive(Debug)]
+pub struct WeightedRandomSelector<E, I, N, T, R> where R: Rng {
+    weights: Vec<(E, f64)>,
+    total: f64,
+    rng: R,
+    _who_cares: PhantomData<(I, N, T)>
 }
 
-impl<E, I, N, T> SerialRepeater<E, I, N, T> where 
-    E: Copy + FromPrimitive + ToPrimitive 
+#[cfg(feature = "rand")]
+impl<E, I, N, T, R> WeightedRandomSelector<E, I, N, T, R> where 
+    E: Copy,
+    R: Rng
 {
-    pub fn new() -> SerialRepeater<E, I, N, T> {
-        SerialRepeater {
+    /// Create a new weighted random selector, with the children paired with 
+    /// their weights, which picks children with the random number generator. 
+    /// 
+    /// # Panics 
+    /// 
+    /// Panics if a weight is negative, or if the weights do not add up to 
+    /// more than zero. 
+    pub fn new(weights: Vec<(E, f64)>, rng: R) -> WeightedRandomSelector<E, I, N, T, R> {
+        assert!(weights.iter().all(|entry| entry.1 >= 0.0), "Weight is negative");
+        let total = weights.iter().map(|entry| entry.1).sum();
+        assert!(total > 0.0, "Weights do not add up to more than zero");
+        WeightedRandomSelector {
+            weights: weights,
+            total: total,
+            rng: rng,
             _who_cares: PhantomData
         }
     }
-}
 
-impl<E, I, N, T> Default for SerialRepeater<E, I, N, T> where 
-    E: Copy + FromPrimitive + ToPrimitive 
-{
-    fn default() -> SerialRepeater<E, I, N, T> {
-        SerialRepeater::new()
+    /// Pick the child to run. 
+    pub fn start(&mut self) -> E {
+        let mut point = self.rng.gen_range(0.0, self.total);
+        for &(child, weight) in self.weights.iter() {
+            if point < weight {
+                return child;
+            }
+            point -= weight;
+        }
+        // Rounding can leave the point just past the last weight
+        self.weights.iter()
+            .rev()
+            .find(|entry| entry.1 > 0.0)
+            .expect("Weights do not add up to more than zero")
+            .0
     }
 }
 
-impl<E, I, N, T> SerialDecider for SerialRepeater<E, I, N, T> where 
-    E: Copy + FromPrimitive + ToPrimitive 
+#[cfg(feature = "rand")]
+impl<E, I, N, T, R> SerialDecider for WeightedRandomSelector<E, I, N, T, R> where 
+    E: Copy,
+    R: Rng
 {
     type Enum = E;
     type Input = I;
@@ -139,19 +1282,16 @@ impl<E, I, N, T> SerialDecider for SerialRepeater<E, I, N, T> where
     type Term = T;
     type Exit = ();
 
-    fn on_nonterminal(&self, _i: &I, _o: E, statept: N) -> NontermDecision<E, N, ()> {
+    fn on_nonterminal(&mut self, _i: &I, _o: E, statept: N) -> NontermDecision<E, N, ()> {
         NontermDecision::Step(statept)
     }
 
-    fn on_terminal(&self, _i: &I, ordinal: E, statept: T) -> TermDecision<E, T, ()> {
-        match E::from_u64(ordinal.to_u64().unwrap()+1) {
-            Option::Some(e) => {
-                TermDecision::Trans(e, statept)
-            },
-            Option::None => {
-                TermDecision::Trans(E::from_u64(0).unwrap(), statept)
-            }
-        }
+    fn initial(&mut self, _i: &I, _built: E) -> Option<E> {
+        Option::Some(self.start())
+    }
+
+    fn on_terminal(&mut self, _i: &I, _o: E, statept: T) -> TermDecision<E, T, ()> {
+        TermDecision::Trans(self.start(), statept)
     }
 }
 
@@ -204,16 +1344,15 @@ impl<I, N, R, T> ParallelDecider for ParallelRunner<I, N, R, T> where
     type Exit = Box<[Statepoint<R, T>]>;
 
     #[inline]
-    fn each_step(&self, _i: &I, states: Box<[Statepoint<Statepoint<N, R>, T>]>) -> 
-        Statepoint<Box<[Statepoint<Self::Nonterm, T>]>, Self::Exit> 
+    fn each_step(&mut self, _i: &I, states: &mut Vec<Statepoint<Statepoint<N, R>, T>>) -> 
+        Option<Box<[Statepoint<R, T>]>> 
     {
-        if states.iter().any(|val| match val {
-            Statepoint::Nonterminal(Statepoint::Nonterminal(_)) => true,
-            _ => false 
-        }) {
-            Statepoint::Nonterminal(states)
+        if states.iter().any(|val| matches!(val, 
+            Statepoint::Nonterminal(Statepoint::Nonterminal(_))))
+        {
+            Option::None
         } else {
-            let vec = states.into_vec().into_iter().map(|val| 
+            let vec = states.drain(..).map(|val| 
                 match val {
                     Statepoint::Nonterminal(v) => match v {
                         Statepoint::Terminal(k) => Statepoint::Nonterminal(k),
@@ -222,7 +1361,7 @@ impl<I, N, R, T> ParallelDecider for ParallelRunner<I, N, R, T> where
                     Statepoint::Terminal(k) => Statepoint::Terminal(k)
                 }
             ).collect::<Vec<_>>();
-            Statepoint::Terminal(vec.into_boxed_slice())
+            Option::Some(vec.into_boxed_slice())
         }
     }
 }
@@ -267,8 +1406,8 @@ impl<I, N, T> ParallelDecider for ParallelRacer<I, N, T> where
     type Exit = (usize, T);
 
     #[inline]
-    fn each_step(&self, _i: &I, states: Box<[Statepoint<N, T>]>) -> 
-        Statepoint<Box<[Statepoint<N, T>]>, (usize, T)> 
+    fn each_step(&mut self, _i: &I, states: &mut Vec<Statepoint<N, T>>) -> 
+        Option<(usize, T)> 
     {
         let mut take_index = Option::None;
         for value in states.iter().enumerate() {
@@ -278,13 +1417,11 @@ impl<I, N, T> ParallelDecider for ParallelRacer<I, N, T> where
             }
         };
         match take_index {
-            Option::None => {
-                Statepoint::Nonterminal(states)
-            },
+            Option::None => Option::None,
             Option::Some(index) => {
-                let val = states.into_vec().swap_remove(index);
+                let val = states.swap_remove(index);
                 if let Statepoint::Terminal(k) = val {
-                    Statepoint::Terminal((index, k))
+                    Option::Some((index, k))
                 } else {
                     unreachable!("The search specifically found a Terminal")
                 }
@@ -293,11 +1430,387 @@ impl<I, N, T> ParallelDecider for ParallelRacer<I, N, T> where
     }
 }
 
-#[cfg(all(test, feature = "existential_type"))]
+// Resolution of a parallel node, as either the index and value of the 
+// terminal with the sought outcome, or the terminals of all the nodes
+type Resolution<T> = Option<Result<(usize, T), Box<[T]>>>;
+
+// Resolve the statepoints of a parallel node to the first terminal with the 
+// given outcome, or to the terminals each node last reached once every node 
+// has terminated, whether on this step or an earlier one. The terminals 
+// reached are remembered in the settled buffer until the node exits
+fn resolve_classified<N, T>(settled: &mut Vec<Option<T>>, states: &mut Vec<Statepoint<N, T>>, 
+    outcome: Outcome) -> Resolution<T> where 
+    T: Classify + Clone
+{
+    let found = states.iter().position(|val| match *val {
+        Statepoint::Terminal(ref t) => t.classify() == outcome,
+        Statepoint::Nonterminal(_) => false
+    });
+    if let Option::Some(index) = found {
+        settled.clear();
+        return match states.swap_remove(index) {
+            Statepoint::Terminal(t) => Option::Some(Result::Ok((index, t))),
+            Statepoint::Nonterminal(_) => unreachable!("The search specifically found a Terminal")
+        };
+    }
+    settled.resize(states.len(), Option::None);
+    for (settled, state) in settled.iter_mut().zip(states.iter()) {
+        if let Statepoint::Terminal(ref t) = *state {
+            *settled = Option::Some(t.clone());
+        }
+    }
+    if settled.iter().any(Option::is_none) {
+        return Option::None;
+    }
+    let terminals = settled.drain(..).map(|val| match val {
+        Option::Some(t) => t,
+        Option::None => unreachable!("Every node has terminated")
+    }).collect::<Vec<_>>();
+    Option::Some(Result::Err(terminals.into_boxed_slice()))
+}
+
+/// Runs nodes in parallel until one fails, exiting with the index of the 
+/// failing node and its terminal, or with the terminals of all of the nodes 
+/// once they have all succeeded. Nodes may succeed on different steps, in 
+/// which case the terminal each node last succeeded with is kept until the 
+/// parallel exits. 
+#[derive(Clone, PartialEq, Debug)]
+pub struct ParallelAll<I, N, T> {
+    settled: Vec<Option<T>>,
+    _who_cares: PhantomData<(I, N)>
+}
+
+impl<I, N, T> ParallelAll<I, N, T> where 
+    I: 'static,
+    N: 'static,
+    T: 'static + Classify + Clone
+{
+    pub fn new() -> ParallelAll<I, N, T> {
+        ParallelAll {
+            settled: Vec::new(),
+            _who_cares: PhantomData
+        }
+    }
+}
+
+impl<I, N, T> Default for ParallelAll<I, N, T> where 
+    I: 'static,
+    N: 'static,
+    T: 'static + Classify + Clone
+{
+    fn default() -> ParallelAll<I, N, T> {
+        ParallelAll::new()
+    }
+}
+
+impl<I, N, T> ParallelDecider for ParallelAll<I, N, T> where 
+    I: 'static,
+    N: 'static,
+    T: 'static + Classify + Clone
+{
+    type Input = I;
+    type Nonterm = N;
+    type Term = T;
+    type Exit = Result<Box<[T]>, (usize, T)>;
+
+    #[inline]
+    fn each_step(&mut self, _i: &I, states: &mut Vec<Statepoint<N, T>>) -> 
+        Option<Result<Box<[T]>, (usize, T)>> 
+    {
+        match resolve_classified(&mut self.settled, states, Outcome::Failure) {
+            Option::None => Option::None,
+            Option::Some(Result::Ok(failed)) => Option::Some(Result::Err(failed)),
+            Option::Some(Result::Err(all)) => Option::Some(Result::Ok(all))
+        }
+    }
+}
+
+/// Runs nodes in parallel until one succeeds, exiting with the index of the 
+/// succeeding node and its terminal, or with the terminals of all of the 
+/// nodes once they have all failed. Nodes may fail on different steps, in 
+/// which case the terminal each node last failed with is kept until the 
+/// parallel exits. 
+#[derive(Clone, PartialEq, Debug)]
+pub struct ParallelAny<I, N, T> {
+    settled: Vec<Option<T>>,
+    _who_cares: PhantomData<(I, N)>
+}
+
+impl<I, N, T> ParallelAny<I, N, T> where 
+    I: 'static,
+    N: 'static,
+    T: 'static + Classify + Clone
+{
+    pub fn new() -> ParallelAny<I, N, T> {
+        ParallelAny {
+            settled: Vec::new(),
+            _who_cares: PhantomData
+        }
+    }
+}
+
+impl<I, N, T> Default for ParallelAny<I, N, T> where 
+    I: 'static,
+    N: 'static,
+    T: 'static + Classify + Clone
+{
+    fn default() -> ParallelAny<I, N, T> {
+        ParallelAny::new()
+    }
+}
+
+impl<I, N, T> ParallelDecider for ParallelAny<I, N, T> where 
+    I: 'static,
+    N: 'static,
+    T: 'static + Classify + Clone
+{
+    type Input = I;
+    type Nonterm = N;
+    type Term = T;
+    type Exit = Result<(usize, T), Box<[T]>>;
+
+    #[inline]
+    fn each_step(&mut self, _i: &I, states: &mut Vec<Statepoint<N, T>>) -> 
+        Option<Result<(usize, T), Box<[T]>>> 
+    {
+        resolve_classified(&mut self.settled, states, Outcome::Success)
+    }
+}
+
+/// Classic sequence, which runs nodes in order until one fails, exiting 
+/// with Failure if one does, or with Success once all of them succeed. By 
+/// default, the nodes are run in the order of their successors, but another 
+/// traversal may be given. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Sequence<E, I, N, P=Ascending> where P: Traversal<E> {
+    order: P,
+    _who_cares: PhantomData<(E, I, N)>
+}
+
+impl<E, I, N> Sequence<E, I, N> where E: Ordinal {
+    pub fn new() -> Sequence<E, I, N> {
+        Sequence::with_order(Ascending)
+    }
+}
+
+impl<E, I, N, P> Sequence<E, I, N, P> where P: Traversal<E> {
+    /// Create a new sequence which runs the nodes in the given order. 
+    pub fn with_order(order: P) -> Sequence<E, I, N, P> {
+        Sequence {
+            order: order,
+            _who_cares: PhantomData
+        }
+    }
+}
+
+impl<E, I, N> Default for Sequence<E, I, N> where 
+    E: Ordinal 
+{
+    fn default() -> Sequence<E, I, N> {
+        Sequence::new()
+    }
+}
+
+impl<E, I, N, P> SerialDecider for Sequence<E, I, N, P> where 
+    P: Traversal<E> 
+{
+    type Enum = E;
+    type Input = I;
+    type Nonterm = N;
+    type Term = BtStatus;
+    type Exit = BtStatus;
+
+    fn on_nonterminal(&mut self, _i: &I, _o: E, statept: N) -> NontermDecision<E, N, 
+        BtStatus> 
+    {
+        NontermDecision::Step(statept)
+    }
+
+    fn on_terminal(&mut self, _i: &I, ord: E, statept: BtStatus) -> TermDecision<E, 
+        BtStatus, BtStatus> 
+    {
+        match statept {
+            BtStatus::Success => match self.order.after(ord) {
+                Option::Some(e) => TermDecision::Trans(e, statept),
+                Option::None => TermDecision::Exit(BtStatus::Success)
+            },
+            BtStatus::Failure => TermDecision::Exit(BtStatus::Failure)
+        }
+    }
+}
+
+/// Classic selector, which runs nodes in order until one succeeds, exiting 
+/// with Success if one does, or with Failure once all of them fail. By 
+/// default, the nodes are run in the order of their successors, but another 
+/// traversal may be given. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Selector<E, I, N, P=Ascending> where P: Traversal<E> {
+    order: P,
+    _who_cares: PhantomData<(E, I, N)>
+}
+
+impl<E, I, N> Selector<E, I, N> where E: Ordinal {
+    pub fn new() -> Selector<E, I, N> {
+        Selector::with_order(Ascending)
+    }
+}
+
+impl<E, I, N, P> Selector<E, I, N, P> where P: Traversal<E> {
+    /// Create a new selector which runs the nodes in the given order. 
+    pub fn with_order(order: P) -> Selector<E, I, N, P> {
+        Selector {
+            order: order,
+            _who_cares: PhantomData
+        }
+    }
+}
+
+impl<E, I, N> Default for Selector<E, I, N> where 
+    E: Ordinal 
+{
+    fn default() -> Selector<E, I, N> {
+        Selector::new()
+    }
+}
+
+impl<E, I, N, P> SerialDecider for Selector<E, I, N, P> where 
+    P: Traversal<E> 
+{
+    type Enum = E;
+    type Input = I;
+    type Nonterm = N;
+    type Term = BtStatus;
+    type Exit = BtStatus;
+
+    fn on_nonterminal(&mut self, _i: &I, _o: E, statept: N) -> NontermDecision<E, N, 
+        BtStatus> 
+    {
+        NontermDecision::Step(statept)
+    }
+
+    fn on_terminal(&mut self, _i: &I, ord: E, statept: BtStatus) -> TermDecision<E, 
+        BtStatus, BtStatus> 
+    {
+        match statept {
+            BtStatus::Success => TermDecision::Exit(BtStatus::Success),
+            BtStatus::Failure => match self.order.after(ord) {
+                Option::Some(e) => TermDecision::Trans(e, statept),
+                Option::None => TermDecision::Exit(BtStatus::Failure)
+            }
+        }
+    }
+}
+
+/// How many of the nodes of a classic parallel must reach a status for the 
+/// parallel to exit with it. 
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ParallelPolicy {
+    /// One of the nodes must reach the status. 
+    RequireOne,
+    /// All of the nodes must have reached the status, on the same step or on 
+    /// different ones. 
+    RequireAll
+}
+
+/// Classic parallel, which runs nodes in parallel until the success policy 
+/// or the failure policy is met, exiting with Success or Failure 
+/// respectively. Failure is checked first. By default, the parallel 
+/// succeeds once all of the nodes have succeeded, and fails as soon as one 
+/// of them fails. 
+/// 
+/// The parallel remembers the status each node last terminated with, so 
+/// nodes which terminate on different steps, and are restarted or left 
+/// running afterwards, still count towards the policies. The remembered 
+/// statuses are forgotten when the parallel exits. 
+#[derive(Clone, PartialEq, Debug)]
+pub struct Parallel<I, N> {
+    success: ParallelPolicy,
+    failure: ParallelPolicy,
+    settled: Vec<Option<BtStatus>>,
+    _who_cares: PhantomData<(I, N)>
+}
+
+impl<I, N> Parallel<I, N> where 
+    I: 'static,
+    N: 'static
+{
+    /// Create a new parallel with the default policies. 
+    pub fn new() -> Parallel<I, N> {
+        Parallel::with_policies(ParallelPolicy::RequireAll, ParallelPolicy::RequireOne)
+    }
+
+    /// Create a new parallel with the given success and failure policies. 
+    pub fn with_policies(success: ParallelPolicy, failure: ParallelPolicy) -> 
+        Parallel<I, N> 
+    {
+        Parallel {
+            success: success,
+            failure: failure,
+            settled: Vec::new(),
+            _who_cares: PhantomData
+        }
+    }
+}
+
+impl<I, N> Default for Parallel<I, N> where 
+    I: 'static,
+    N: 'static
+{
+    fn default() -> Parallel<I, N> {
+        Parallel::new()
+    }
+}
+
+// Check whether the remembered statuses reach the status as the policy 
+// requires
+fn meets_policy(settled: &[Option<BtStatus>], status: BtStatus, 
+    policy: ParallelPolicy) -> bool 
+{
+    let reached = |val: &Option<BtStatus>| *val == Option::Some(status);
+    match policy {
+        ParallelPolicy::RequireOne => settled.iter().any(reached),
+        ParallelPolicy::RequireAll => settled.iter().all(reached)
+    }
+}
+
+impl<I, N> ParallelDecider for Parallel<I, N> where 
+    I: 'static,
+    N: 'static
+{
+    type Input = I;
+    type Nonterm = N;
+    type Term = BtStatus;
+    type Exit = BtStatus;
+
+    #[inline]
+    fn each_step(&mut self, _i: &I, states: &mut Vec<Statepoint<N, BtStatus>>) -> 
+        Option<BtStatus> 
+    {
+        self.settled.resize(states.len(), Option::None);
+        for (settled, state) in self.settled.iter_mut().zip(states.iter()) {
+            if let Statepoint::Terminal(status) = *state {
+                *settled = Option::Some(status);
+            }
+        }
+        let exit = if meets_policy(&self.settled, BtStatus::Failure, self.failure) {
+            Option::Some(BtStatus::Failure)
+        } else if meets_policy(&self.settled, BtStatus::Success, self.success) {
+            Option::Some(BtStatus::Success)
+        } else {
+            Option::None
+        };
+        if exit.is_some() {
+            self.settled.clear();
+        }
+        exit
+    }
+}
+
+#[cfg(all(test, feature = "existential_type", feature = "wrappers"))]
 mod tests {
     use base_nodes::MachineWrapper;
     use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
-    use stackbt_automata_impl::automaton::Automaton;
+    use stackbt_automata_impl::automaton::{Automaton, BoxedActions};
     use stackbt_automata_impl::internal_state_machine::{InternalTransition,
         InternalStateMachine};
     use stackbt_automata_impl::ref_state_machine::{ReferenceTransition,
@@ -307,7 +1820,6 @@ mod tests {
     use control_wrappers::{GuardedNode};
     use node_runner::NodeRunner;
     use std::marker::PhantomData;
-    use num_derive::{FromPrimitive, ToPrimitive};
 
     #[derive(Copy, Clone, Default)]
     struct IndefiniteIncrement;
@@ -328,12 +1840,14 @@ mod tests {
     }
 
 
-    #[derive(Copy, Clone, PartialEq, Eq, Debug, FromPrimitive, ToPrimitive)]
+    #[derive(Copy, Clone, PartialEq, Eq, Debug)]
     enum SomethingEnum {
         First,
         Second
     }
 
+    impl_ordinal!(SomethingEnum { First, Second });
+
     enum MultiMachine {
         First(MachineWrapper<InternalStateMachine<'static, 
             IndefiniteIncrement>, i64, i64>),
@@ -743,10 +2257,10 @@ mod tests {
     fn parallel_runner_test() {
         use parallel_node::ParallelBranchNode;
         use node_compositions::ParallelRunner;
-        let test_node = ParallelBranchNode::<InternalStateMachine<
-            ParMachineController>, ParallelRunner<_, _, _, _>>::default();
+        let test_node = ParallelBranchNode::<BoxedActions<InternalStateMachine<
+            ParMachineController>>, ParallelRunner<_, _, _, _>>::default();
         let test_node_1 = match test_node.step(&()) {
-            NodeResult::Nonterminal(v, n) => match v.as_ref() {
+            NodeResult::Nonterminal(_, n) => match n.statepoints() {
                 [
                     Statepoint::Nonterminal(Statepoint::Nonterminal(())),
                     Statepoint::Nonterminal(Statepoint::Nonterminal(()))
@@ -756,7 +2270,7 @@ mod tests {
             _ => unreachable!("Expected nonterminal transition")
         };
         let test_node_2 = match test_node_1.step(&()) {
-            NodeResult::Nonterminal(v, n) => match v.as_ref() {
+            NodeResult::Nonterminal(_, n) => match n.statepoints() {
                 [
                     Statepoint::Nonterminal(Statepoint::Terminal(())),
                     Statepoint::Nonterminal(Statepoint::Nonterminal(()))
@@ -766,7 +2280,7 @@ mod tests {
             _ => unreachable!("Expected nonterminal transition")
         };
         let test_node_3 = match test_node_2.step(&()) {
-            NodeResult::Nonterminal(v, n) => match v.as_ref() {
+            NodeResult::Nonterminal(_, n) => match n.statepoints() {
                 [
                     Statepoint::Nonterminal(Statepoint::Nonterminal(())),
                     Statepoint::Nonterminal(Statepoint::Terminal(()))
@@ -776,7 +2290,7 @@ mod tests {
             _ => unreachable!("Expected nonterminal transition")
         };
         let test_node_4 = match test_node_3.step(&()) {
-            NodeResult::Nonterminal(v, n) => match v.as_ref() {
+            NodeResult::Nonterminal(_, n) => match n.statepoints() {
                 [
                     Statepoint::Nonterminal(Statepoint::Terminal(())),
                     Statepoint::Nonterminal(Statepoint::Nonterminal(()))
@@ -786,7 +2300,7 @@ mod tests {
             _ => unreachable!("Expected nonterminal transition")
         };
         let test_node_5 = match test_node_4.step(&()) {
-            NodeResult::Nonterminal(v, n) => match v.as_ref() {
+            NodeResult::Nonterminal(_, n) => match n.statepoints() {
                 [
                     Statepoint::Nonterminal(Statepoint::Nonterminal(())),
                     Statepoint::Nonterminal(Statepoint::Nonterminal(()))
@@ -822,8 +2336,8 @@ mod tests {
     fn parallel_racer_test() {
         use parallel_node::ParallelBranchNode;
         use node_compositions::ParallelRacer;
-        let test_node = ParallelBranchNode::<InternalStateMachine<
-            WrapParMachineController>, ParallelRacer<_, _, _>>::default();
+        let test_node = ParallelBranchNode::<BoxedActions<InternalStateMachine<
+            WrapParMachineController>>, ParallelRacer<_, _, _>>::default();
         let test_node_1 = match test_node.step(&()) {
             NodeResult::Nonterminal(_, n) => n,
             _ => unreachable!("Expected nonterminal transition")
@@ -833,4 +2347,328 @@ mod tests {
             _ => unreachable!("Expected terminal transition")
         };
     }
+}
+
+#[cfg(test)]
+mod tests_successor {
+    use serial_node::{SerialDecider, TermDecision};
+    use node_compositions::{SerialRunner, SerialSelector, SerialRepeater};
+
+    #[derive(Copy, Clone, PartialEq, Eq, Debug)]
+    enum Gapped {
+        Low = 1,
+        Mid = 5,
+        High = 9
+    }
+
+    impl_ordinal!(Gapped { Low, Mid, High });
+
+    #[test]
+    fn gapped_successor_test() {
+        use serial_node::Ordinal;
+        assert_eq!(Gapped::COUNT, 3);
+        assert_eq!(Gapped::last(), Gapped::High);
+        assert_eq!(Gapped::Mid.index(), 1);
+        assert!(Gapped::Low.is_first() && !Gapped::Low.is_last());
+        assert!(Gapped::High.is_last());
+        assert_eq!(200u8.index(), 200);
+        assert_eq!(u8::last(), 255);
+        let mut runner = SerialRunner::<Gapped, (), (), i64>::new();
+        assert_eq!(runner.on_terminal(&(), Gapped::Low, 1), 
+            TermDecision::Trans(Gapped::Mid, 1));
+        assert_eq!(runner.on_terminal(&(), Gapped::High, 1), TermDecision::Exit(()));
+        let mut selector = SerialSelector::<Gapped, (), (), i64>::new();
+        assert_eq!(selector.on_terminal(&(), Gapped::Mid, Option::None), 
+            TermDecision::Trans(Gapped::High, Option::None));
+        assert_eq!(selector.on_terminal(&(), Gapped::High, Option::None), 
+            TermDecision::Exit(Option::None));
+        let mut repeater = SerialRepeater::<Gapped, (), (), i64>::new();
+        assert_eq!(repeater.on_terminal(&(), Gapped::Mid, 2), 
+            TermDecision::Trans(Gapped::High, 2));
+        assert_eq!(repeater.on_terminal(&(), Gapped::High, 2), 
+            TermDecision::Trans(Gapped::Low, 2));
+    }
+
+    #[test]
+    fn traversal_order_test() {
+        use node_compositions::{Traversal, Descending, ExplicitOrder, Skipping};
+        assert_eq!(Traversal::<Gapped>::first(&Descending), Option::Some(Gapped::High));
+        assert_eq!(Descending.after(Gapped::Mid), Option::Some(Gapped::Low));
+        assert_eq!(Descending.after(Gapped::Low), Option::None);
+        assert_eq!(ExplicitOrder::new(vec![Gapped::Mid, Gapped::Low, Gapped::Mid]), 
+            Result::Err(Gapped::Mid));
+        let order = ExplicitOrder::new(vec![Gapped::Low, Gapped::Mid]).unwrap();
+        assert_eq!(order.after(Gapped::Low), Option::Some(Gapped::Mid));
+        assert_eq!(order.after(Gapped::Mid), Option::None);
+        assert_eq!(order.after(Gapped::High), Option::None);
+        let skipping = Skipping::new(Descending, vec![Gapped::High]);
+        assert_eq!(skipping.first(), Option::Some(Gapped::Mid));
+        let mut runner = SerialRunner::<_, (), (), i64, _>::with_order(order);
+        assert_eq!(runner.on_terminal(&(), Gapped::Low, 1), 
+            TermDecision::Trans(Gapped::Mid, 1));
+        assert_eq!(runner.on_terminal(&(), Gapped::High, 1), TermDecision::Exit(()));
+        let mut repeater = SerialRepeater::<_, (), (), i64, _>::with_order(skipping);
+        assert_eq!(repeater.on_terminal(&(), Gapped::Mid, 2), 
+            TermDecision::Trans(Gapped::Low, 2));
+        assert_eq!(repeater.on_terminal(&(), Gapped::Low, 2), 
+            TermDecision::Trans(Gapped::Mid, 2));
+    }
+
+    #[test]
+    fn classified_decider_test() {
+        use behavior_tree_node::Statepoint;
+        use parallel_node::ParallelDecider;
+        use node_compositions::{SerialAll, SerialAny, ParallelAll, ParallelAny};
+        let mut all = SerialAll::<Gapped, (), (), bool>::new();
+        assert_eq!(all.on_terminal(&(), Gapped::Low, true), 
+            TermDecision::Trans(Gapped::Mid, true));
+        assert_eq!(all.on_terminal(&(), Gapped::High, true), 
+            TermDecision::Exit(Result::Ok(())));
+        assert_eq!(all.on_terminal(&(), Gapped::Mid, false), 
+            TermDecision::Exit(Result::Err((Gapped::Mid, false))));
+        let mut any = SerialAny::<Gapped, (), (), Result<i64, i64>>::new();
+        assert_eq!(any.on_terminal(&(), Gapped::Low, Result::Err(1)), 
+            TermDecision::Trans(Gapped::Mid, Result::Err(1)));
+        assert_eq!(any.on_terminal(&(), Gapped::Mid, Result::Ok(2)), 
+            TermDecision::Exit(Result::Ok((Gapped::Mid, Result::Ok(2)))));
+        assert_eq!(any.on_terminal(&(), Gapped::High, Result::Err(3)), 
+            TermDecision::Exit(Result::Err(())));
+        let pending = vec![Statepoint::Terminal(true), Statepoint::Nonterminal(())];
+        let done = vec![Statepoint::<(), _>::Terminal(true), Statepoint::Terminal(true)];
+        let failed = vec![Statepoint::Nonterminal(()), Statepoint::Terminal(false)];
+        let mut all = ParallelAll::<(), (), bool>::new();
+        let mut states = pending.clone();
+        assert_eq!(all.each_step(&(), &mut states), Option::None);
+        assert_eq!(states, pending);
+        assert_eq!(all.each_step(&(), &mut done.clone()), 
+            Option::Some(Result::Ok(vec![true, true].into_boxed_slice())));
+        assert_eq!(all.each_step(&(), &mut failed.clone()), 
+            Option::Some(Result::Err((1, false))));
+        let mut any = ParallelAny::<(), (), bool>::new();
+        assert_eq!(any.each_step(&(), &mut pending.clone()), 
+            Option::Some(Result::Ok((0, true))));
+        let mut states = failed.clone();
+        assert_eq!(any.each_step(&(), &mut states), Option::None);
+        assert_eq!(states, failed);
+        let other_failed = vec![Statepoint::Terminal(false), Statepoint::Nonterminal(())];
+        assert_eq!(any.each_step(&(), &mut other_failed.clone()), 
+            Option::Some(Result::Err(vec![false, false].into_boxed_slice())));
+        assert_eq!(any.each_step(&(), &mut other_failed.clone()), Option::None);
+        let other_pending = vec![Statepoint::Nonterminal(()), Statepoint::Terminal(true)];
+        assert_eq!(all.each_step(&(), &mut pending.clone()), Option::None);
+        assert_eq!(all.each_step(&(), &mut other_pending.clone()), 
+            Option::Some(Result::Ok(vec![true, true].into_boxed_slice())));
+    }
+
+    #[test]
+    fn bt_status_decider_test() {
+        use behavior_tree_node::Statepoint;
+        use bt_status::BtStatus;
+        use parallel_node::ParallelDecider;
+        use node_compositions::{Sequence, Selector, Parallel, ParallelPolicy};
+        let mut sequence = Sequence::<Gapped, (), ()>::new();
+        assert_eq!(sequence.on_terminal(&(), Gapped::Low, BtStatus::Success), 
+            TermDecision::Trans(Gapped::Mid, BtStatus::Success));
+        assert_eq!(sequence.on_terminal(&(), Gapped::High, BtStatus::Success), 
+            TermDecision::Exit(BtStatus::Success));
+        assert_eq!(sequence.on_terminal(&(), Gapped::Low, BtStatus::Failure), 
+            TermDecision::Exit(BtStatus::Failure));
+        let mut selector = Selector::<Gapped, (), ()>::new();
+        assert_eq!(selector.on_terminal(&(), Gapped::Mid, BtStatus::Failure), 
+            TermDecision::Trans(Gapped::High, BtStatus::Failure));
+        assert_eq!(selector.on_terminal(&(), Gapped::High, BtStatus::Failure), 
+            TermDecision::Exit(BtStatus::Failure));
+        assert_eq!(selector.on_terminal(&(), Gapped::Low, BtStatus::Success), 
+            TermDecision::Exit(BtStatus::Success));
+        let one_done = vec![Statepoint::Terminal(BtStatus::Success), 
+            Statepoint::Nonterminal(())];
+        let all_done = vec![Statepoint::<(), _>::Terminal(BtStatus::Success), 
+            Statepoint::Terminal(BtStatus::Success)];
+        let one_failed = vec![Statepoint::Nonterminal(()), 
+            Statepoint::Terminal(BtStatus::Failure)];
+        let other_done = vec![Statepoint::Nonterminal(()), 
+            Statepoint::Terminal(BtStatus::Success)];
+        let mut parallel = Parallel::<(), ()>::new();
+        assert_eq!(parallel.each_step(&(), &mut one_done.clone()), Option::None);
+        assert_eq!(parallel.each_step(&(), &mut all_done.clone()), 
+            Option::Some(BtStatus::Success));
+        assert_eq!(parallel.each_step(&(), &mut one_failed.clone()), 
+            Option::Some(BtStatus::Failure));
+        assert_eq!(parallel.each_step(&(), &mut one_done.clone()), Option::None);
+        assert_eq!(parallel.each_step(&(), &mut other_done.clone()), 
+            Option::Some(BtStatus::Success));
+        assert_eq!(parallel.each_step(&(), &mut other_done.clone()), Option::None);
+        let mut lenient = Parallel::<(), ()>::with_policies(ParallelPolicy::RequireOne, 
+            ParallelPolicy::RequireAll);
+        assert_eq!(lenient.each_step(&(), &mut one_done.clone()), 
+            Option::Some(BtStatus::Success));
+        assert_eq!(lenient.each_step(&(), &mut one_failed.clone()), Option::None);
+        assert_eq!(lenient.each_step(&(), &mut vec![Statepoint::Terminal(BtStatus::Failure), 
+            Statepoint::Nonterminal(())]), Option::Some(BtStatus::Failure));
+    }
+
+    #[test]
+    fn history_selector_test() {
+        use node_compositions::{HistorySelector, SelectionHistory};
+        let history = SelectionHistory::new();
+        let mut selector = HistorySelector::<Gapped, i64, (), bool, bool, _>::new(
+            |input: &i64| *input > 0, history.clone());
+        assert_eq!(selector.start(&1), Gapped::Low);
+        assert_eq!(selector.on_terminal(&1, Gapped::Low, false), 
+            TermDecision::Trans(Gapped::Mid, false));
+        assert_eq!(selector.on_terminal(&1, Gapped::Mid, true), 
+            TermDecision::Exit(Result::Ok((Gapped::Mid, true))));
+        assert_eq!(history.recall(&true), Option::Some(Gapped::Mid));
+        let mut selector = HistorySelector::<Gapped, i64, (), bool, bool, _>::new(
+            |input: &i64| *input > 0, history.clone());
+        assert_eq!(selector.start(&-1), Gapped::Low);
+        assert_eq!(selector.start(&2), Gapped::Mid);
+        assert_eq!(selector.on_terminal(&2, Gapped::Mid, false), 
+            TermDecision::Trans(Gapped::Low, false));
+        assert_eq!(selector.on_terminal(&2, Gapped::Low, false), 
+            TermDecision::Trans(Gapped::High, false));
+        assert_eq!(selector.on_terminal(&2, Gapped::High, false), 
+            TermDecision::Exit(Result::Err(())));
+        history.clear();
+        assert_eq!(history.recall(&true), Option::None);
+    }
+
+    #[test]
+    fn epsilon_greedy_test() {
+        use node_compositions::{EpsilonGreedySelector, BanditStats};
+        let stats = BanditStats::new(7);
+        let mut greedy = EpsilonGreedySelector::<Gapped, (), (), bool>::new(0.0, stats.clone());
+        assert_eq!(greedy.start(), Gapped::Low);
+        assert_eq!(greedy.on_terminal(&(), Gapped::Low, false), 
+            TermDecision::Exit(Result::Err((Gapped::Low, false))));
+        assert_eq!(greedy.start(), Gapped::Mid);
+        greedy.on_terminal(&(), Gapped::Mid, true);
+        assert_eq!(stats.counts(Gapped::Mid), (1, 1));
+        assert_eq!(greedy.start(), Gapped::Mid);
+        greedy.on_terminal(&(), Gapped::Mid, false);
+        assert_eq!(greedy.start(), Gapped::High);
+        let explorer = EpsilonGreedySelector::<Gapped, (), (), bool>::new(1.0, 
+            BanditStats::new(7));
+        let picks = (0..60).map(|_| explorer.start()).collect::<Vec<_>>();
+        assert!(picks.contains(&Gapped::Low));
+        assert!(picks.contains(&Gapped::Mid));
+        assert!(picks.contains(&Gapped::High));
+    }
+    #[test]
+    fn reactive_selector_test() {
+        use serial_node::NontermDecision;
+        use node_compositions::ReactiveSelector;
+        // Inputs at or above a child's discriminant make it eligible
+        let mut selector = ReactiveSelector::<Gapped, i64, (), bool, _>::new(
+            |child: Gapped, input: &i64| *input >= child as i64);
+        assert_eq!(selector.start(&0), Option::None);
+        assert_eq!(selector.start(&6), Option::Some(Gapped::Low));
+        assert_eq!(selector.on_nonterminal(&0, Gapped::High, ()), 
+            NontermDecision::Step(()));
+        assert_eq!(selector.on_nonterminal(&3, Gapped::High, ()), 
+            NontermDecision::Trans(Gapped::Low, ()));
+        assert_eq!(selector.on_nonterminal(&3, Gapped::Low, ()), 
+            NontermDecision::Step(()));
+        assert_eq!(selector.on_terminal(&6, Gapped::Low, false), 
+            TermDecision::Trans(Gapped::Mid, false));
+        assert_eq!(selector.on_terminal(&3, Gapped::Low, false), 
+            TermDecision::Exit(Result::Err(())));
+        assert_eq!(selector.on_terminal(&9, Gapped::Mid, true), 
+            TermDecision::Exit(Result::Ok((Gapped::Mid, true))));
+    }
+    #[test]
+    fn utility_selector_test() {
+        use node_compositions::UtilitySelector;
+        // The input holds the scores of the low and high children
+        let low: fn(&(f64, f64)) -> f64 = |input| input.0;
+        let high: fn(&(f64, f64)) -> f64 = |input| input.1;
+        let scorers = vec![
+            (Gapped::Low, low),
+            (Gapped::High, high)
+        ];
+        let mut selector = UtilitySelector::<_, _, (), i64, _>::new(scorers)
+            .with_inertia(0.5);
+        assert_eq!(selector.start(&(1.0, 1.0)), Gapped::Low);
+        assert_eq!(selector.start(&(1.0, 1.2)), Gapped::High);
+        assert_eq!(selector.on_terminal(&(1.0, 1.2), Gapped::Low, 1), 
+            TermDecision::Trans(Gapped::Low, 1));
+        assert_eq!(selector.on_terminal(&(1.0, 2.0), Gapped::Low, 2), 
+            TermDecision::Trans(Gapped::High, 2));
+        assert_eq!(selector.on_terminal(&(1.2, 1.0), Gapped::High, 3), 
+            TermDecision::Trans(Gapped::High, 3));
+        assert_eq!(selector.on_terminal(&(1.2, 1.0), Gapped::Mid, 4), 
+            TermDecision::Trans(Gapped::Low, 4));
+    }
+    #[test]
+    fn utility_selector_nan_test() {
+        use node_compositions::UtilitySelector;
+        let low: fn(&(f64, f64)) -> f64 = |input| input.0;
+        let high: fn(&(f64, f64)) -> f64 = |input| input.1;
+        let scorers = vec![
+            (Gapped::Low, low),
+            (Gapped::High, high)
+        ];
+        let mut selector = UtilitySelector::<_, _, (), i64, _>::new(scorers);
+        assert_eq!(selector.start(&(1.0, f64::NAN)), Gapped::Low);
+        assert_eq!(selector.start(&(f64::NAN, 1.0)), Gapped::High);
+        assert_eq!(selector.start(&(1.0, f64::INFINITY)), Gapped::Low);
+        assert_eq!(selector.start(&(f64::NAN, f64::NAN)), Gapped::Low);
+        assert_eq!(selector.on_terminal(&(f64::NAN, f64::NAN), Gapped::High, 1), 
+            TermDecision::Trans(Gapped::High, 1));
+    }
+    #[cfg(feature = "rand")]
+    #[test]
+    fn random_selector_test() {
+        use node_compositions::{RandomSelector, WeightedRandomSelector};
+        use rand::SeedableRng;
+        use rand::rngs::StdRng;
+        let mut selector = RandomSelector::<Gapped, (), (), i64, _>::new(
+            StdRng::seed_from_u64(7));
+        let picks = (0..60).map(|_| selector.start()).collect::<Vec<_>>();
+        assert!(picks.contains(&Gapped::Low));
+        assert!(picks.contains(&Gapped::Mid));
+        assert!(picks.contains(&Gapped::High));
+        let mut replayed = RandomSelector::<Gapped, (), (), i64, _>::new(
+            StdRng::seed_from_u64(7));
+        assert_eq!((0..60).map(|_| replayed.start()).collect::<Vec<_>>(), picks);
+        match selector.on_terminal(&(), Gapped::Low, 1) {
+            TermDecision::Trans(_, 1) => (),
+            _ => unreachable!("Expected a transition")
+        };
+        let mut weighted = WeightedRandomSelector::<_, (), (), i64, _>::new(
+            vec![(Gapped::Low, 0.0), (Gapped::Mid, 1.0), (Gapped::High, 3.0)], 
+            StdRng::seed_from_u64(7));
+        let picks = (0..200).map(|_| weighted.start()).collect::<Vec<_>>();
+        let mids = picks.iter().filter(|&&child| child == Gapped::Mid).count();
+        assert!(!picks.contains(&Gapped::Low));
+        assert!(mids > 20 && mids < 80);
+    }
+    #[test]
+    fn round_robin_selector_test() {
+        use node_compositions::{RoundRobinSelector, RoundRobinRecord};
+        let record = RoundRobinRecord::new();
+        let mut selector = RoundRobinSelector::<Gapped, (), (), bool>::new(record.clone());
+        assert_eq!(selector.start(), Gapped::Low);
+        assert_eq!(selector.on_terminal(&(), Gapped::Low, true), 
+            TermDecision::Exit(Result::Ok((Gapped::Low, true))));
+        assert_eq!(record.last(), Option::Some(Gapped::Low));
+        // A selector built anew, as by a restarting node runner, moves on
+        let mut selector = RoundRobinSelector::<Gapped, (), (), bool>::new(record.clone());
+        assert_eq!(selector.start(), Gapped::Mid);
+        assert_eq!(selector.on_terminal(&(), Gapped::Mid, false), 
+            TermDecision::Trans(Gapped::High, false));
+        assert_eq!(selector.on_terminal(&(), Gapped::High, true), 
+            TermDecision::Exit(Result::Ok((Gapped::High, true))));
+        let mut selector = RoundRobinSelector::<Gapped, (), (), bool>::new(record.clone());
+        assert_eq!(selector.start(), Gapped::Low);
+        let mut selector = RoundRobinSelector::<Gapped, (), (), bool>::new(record.clone());
+        assert_eq!(selector.on_terminal(&(), Gapped::Low, false), 
+            TermDecision::Trans(Gapped::Mid, false));
+        assert_eq!(selector.on_terminal(&(), Gapped::Mid, false), 
+            TermDecision::Trans(Gapped::High, false));
+        assert_eq!(selector.on_terminal(&(), Gapped::High, false), 
+            TermDecision::Exit(Result::Err(())));
+        record.clear();
+        assert_eq!(selector.start(), Gapped::Low);
+    }
 }
\ No newline at end of file
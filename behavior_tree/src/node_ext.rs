@@ -0,0 +1,137 @@
+//! Chainable construction of wrapped nodes. 
+//!
+//! Wrapping a node in several decorators with their constructors nests the 
+//! calls inside out, so that the wrapper applied last is read first. The 
+//! extension trait of this module offers the same wrappers as methods on 
+//! nodes, so that decorators read in the order they are applied. 
+
+use behavior_tree_node::{BehaviorTreeNode, Statepoint};
+use control_wrappers::{GuardedNode, PostResetNode, StepControlledNode, StepDecision,
+    TimeLimitedNode};
+use map_wrappers::{InputMappedNode, OutputMappedNode};
+
+/// Extension trait for behavior tree nodes, with a method for each of the 
+/// common wrappers, which wraps the node it is called on. 
+/// 
+/// # Example
+/// ```
+/// use stackbt_behavior_tree::base_nodes::PredicateWait;
+/// use stackbt_behavior_tree::behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+/// use stackbt_behavior_tree::control_wrappers::DeadlineExit;
+/// use stackbt_behavior_tree::node_ext::BehaviorTreeNodeExt;
+/// let node = PredicateWait::new(|input: &i64| {
+///     if *input > 0 {
+///         Statepoint::Nonterminal(*input)
+///     } else {
+///         Statepoint::Terminal(*input)
+///     }
+/// }).map_input(|input: &(i64, i64)| input.0 - input.1).with_timeout(1);
+/// let node = match node.step(&(5, 2)) {
+///     NodeResult::Nonterminal(v, n) => {
+///         assert_eq!(v, 3);
+///         n
+///     },
+///     NodeResult::Terminal(_) => unreachable!("Expected nonterminal state")
+/// };
+/// match node.step(&(5, 2)) {
+///     NodeResult::Terminal(t) => assert_eq!(t, DeadlineExit::DeadlinePassed(2)),
+///     NodeResult::Nonterminal(_, _) => unreachable!("Expected terminal state")
+/// };
+/// ```
+pub trait BehaviorTreeNodeExt: BehaviorTreeNode + Sized {
+    /// Wrap the node in a guarded node with the given guard. 
+    fn guarded<G>(self, guard: G) -> GuardedNode<Self, G> where
+        G: Fn(&Self::Input, &Self::Nonterminal) -> bool
+    {
+        GuardedNode::new(guard, self)
+    }
+
+    /// Wrap the node in an input mapped node with the given mapper. 
+    fn map_input<M, I>(self, mapper: M) -> InputMappedNode<Self, M, I> where
+        M: Fn(&I) -> Self::Input
+    {
+        InputMappedNode::new(mapper, self)
+    }
+
+    /// Wrap the node in an output mapped node with the given nonterminal and 
+    /// terminal mappers. 
+    fn map_output<M, O, S, T>(self, nonterm: M, term: O) -> OutputMappedNode<Self, M,
+        O, S, T> where
+        M: Fn(Self::Nonterminal) -> S,
+        O: Fn(Self::Terminal) -> T
+    {
+        OutputMappedNode::new(nonterm, term, self)
+    }
+
+    /// Wrap the node in a step controlled node with the given stepper. 
+    fn step_controlled<S>(self, stepper: S) -> StepControlledNode<Self, S> where
+        S: Fn(&Self::Input) -> StepDecision<Self>
+    {
+        StepControlledNode::new(stepper, self)
+    }
+
+    /// Wrap the node in a post-reset node with the given resetter. 
+    fn post_reset<P>(self, resetter: P) -> PostResetNode<Self, P> where
+        P: Fn(&Self::Input, Statepoint<&Self::Nonterminal, &Self::Terminal>) ->
+            Option<Self>
+    {
+        PostResetNode::new(resetter, self)
+    }
+
+    /// Wrap the node in a time limited node, which times out the node if it 
+    /// has not terminated within the given number of steps. 
+    fn with_timeout(self, limit: u64) -> TimeLimitedNode<Self, fn(&Self::Input) -> u64> {
+        TimeLimitedNode::new(limit, self)
+    }
+}
+
+impl<N> BehaviorTreeNodeExt for N where N: BehaviorTreeNode {}
+
+#[cfg(test)]
+mod tests {
+    use base_nodes::PredicateWait;
+    use behavior_tree_node::Statepoint;
+    use control_wrappers::{DeadlineExit, GuardFailure, StepDecision};
+    use node_ext::BehaviorTreeNodeExt;
+
+    fn countdown(input: &i64) -> Statepoint<i64, i64> {
+        if *input > 0 {
+            Statepoint::Nonterminal(*input)
+        } else {
+            Statepoint::Terminal(*input)
+        }
+    }
+
+    #[test]
+    fn chained_wrappers_test() {
+        let node = PredicateWait::new(countdown)
+            .map_output(|n: i64| n * 10, |t: i64| t - 1)
+            .guarded(|input: &i64, n: &i64| *input < 5 && *n < 40);
+        assert_steps!(node;
+            1 => Nonterminal(10),
+            3 => Nonterminal(30),
+            0 => Terminal(Result::Ok(-1))
+        );
+        let node = PredicateWait::new(countdown)
+            .map_output(|n: i64| n * 10, |t: i64| t - 1)
+            .guarded(|input: &i64, n: &i64| *input < 5 && *n < 40);
+        assert_steps!(node; 4 => Terminal(Result::Err(GuardFailure(40))));
+    }
+
+    #[test]
+    fn chained_controls_test() {
+        let node = PredicateWait::new(countdown)
+            .step_controlled(|input: &i64| if *input > 10 {
+                StepDecision::Pause
+            } else {
+                StepDecision::Play
+            });
+        let node = assert_steps!(node; 20 => Nonterminal(_), 2 => Nonterminal(_));
+        assert_steps!(node; 0 => Terminal(0));
+        let node = PredicateWait::new(countdown)
+            .post_reset(|_input: &i64, _point| Option::None)
+            .with_timeout(2);
+        let node = assert_steps!(node; 1 => Nonterminal(_), 1 => Nonterminal(_));
+        assert_steps!(node; 1 => Terminal(DeadlineExit::DeadlinePassed(3)));
+    }
+}
@@ -1,18 +1,125 @@
 use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
-use stackbt_automata_impl::automaton::{Automaton, FiniteStateAutomaton};
+use restart_node::{Restart, RestartPolicy, RestartNode};
+use stackbt_automata_impl::automaton::{Automaton, FiniteStateAutomaton, InputDebug, Poisoned};
+use stackbt_automata_impl::input_provider::InputProvider;
+use std::collections::VecDeque;
+use std::fmt::{self, Debug};
+
+// Spelled out through an alias so that derives do not require the input 
+// type to implement the derived traits
+type InputFormatter<N> = InputDebug<<N as BehaviorTreeNode>::Input>;
+type RestartRecord<N> = RestartHook<<N as BehaviorTreeNode>::Terminal>;
+
+/// Trait for what a node runner keeps of the terminals its node returns. 
+pub trait TerminalStore<T> {
+    /// Keep what is needed of the terminal the node just returned. 
+    fn store(&mut self, terminal: &T);
+
+    /// Return the kept terminal, if there is one. 
+    fn last(&self) -> Option<&T>;
+}
+
+/// Terminal store of node runners which keep nothing of the terminals of 
+/// their nodes. 
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub struct NoTerminal;
+
+impl<T> TerminalStore<T> for NoTerminal {
+    fn store(&mut self, _terminal: &T) {}
+
+    fn last(&self) -> Option<&T> {
+        Option::None
+    }
+}
+
+/// Terminal store of node runners which keep a copy of the terminal their 
+/// node most recently returned. 
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct LastTerminal<T>(Option<T>);
+
+impl<T> Default for LastTerminal<T> {
+    fn default() -> LastTerminal<T> {
+        LastTerminal(Option::None)
+    }
+}
+
+impl<T> TerminalStore<T> for LastTerminal<T> where T: Clone {
+    fn store(&mut self, terminal: &T) {
+        self.0 = Option::Some(terminal.clone());
+    }
+
+    fn last(&self) -> Option<&T> {
+        self.0.as_ref()
+    }
+}
+
+// Restarts of a runner's node, with the hook called on each of them, which 
+// are a record of past runs and not part of the state
+struct RestartHook<T> {
+    hook: Option<fn(&T, usize)>,
+    restarts: usize
+}
+
+impl<T> RestartHook<T> {
+    fn new() -> RestartHook<T> {
+        RestartHook {
+            hook: Option::None,
+            restarts: 0
+        }
+    }
+
+    fn restart(&mut self, terminal: &T) {
+        self.restarts += 1;
+        if let Option::Some(hook) = self.hook {
+            hook(terminal, self.restarts);
+        }
+    }
+}
+
+impl<T> Copy for RestartHook<T> {}
+
+impl<T> Clone for RestartHook<T> {
+    fn clone(&self) -> RestartHook<T> {
+        *self
+    }
+}
+
+impl<T> PartialEq for RestartHook<T> {
+    fn eq(&self, _other: &RestartHook<T>) -> bool {
+        true
+    }
+}
+
+impl<T> Debug for RestartHook<T> {
+    fn fmt(&self, fmter: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmter, "RestartHook")
+    }
+}
 
 /// Automaton implementation which wraps a behavior tree node and forwards 
 /// input to it and transitions back from it, automatically restarting the
-/// node if it terminates. 
-#[derive(Copy, Clone, PartialEq, Debug)]
-pub struct NodeRunner<N, C> where 
+/// node if it terminates. What the runner keeps of the terminals of its 
+/// node is decided by its terminal store, which keeps nothing unless the 
+/// runner is built with with_last_terminal. 
+#[derive(Clone, PartialEq, Debug)]
+pub struct NodeRunner<N, C, S=NoTerminal> where 
     N: BehaviorTreeNode + 'static,
     C: Fn() -> N
 {
     constructor: C,
-    node: Option<N>
+    node: Option<N>,
+    name: Option<&'static str>,
+    input_debug: Option<InputFormatter<N>>,
+    terminals: S,
+    on_restart: RestartRecord<N>
 }
 
+impl<N, C, S> Copy for NodeRunner<N, C, S> where 
+    N: BehaviorTreeNode + 'static + Copy,
+    C: Fn() -> N + Copy,
+    S: Copy
+{}
+
 impl<N, C> NodeRunner<N, C> where 
     N: BehaviorTreeNode + 'static,
     C: Fn() -> N
@@ -22,44 +129,430 @@ impl<N, C> NodeRunner<N, C> where
         let new_node = constructor();
         NodeRunner {
             constructor: constructor, 
-            node: Option::Some(new_node)
+            node: Option::Some(new_node),
+            name: Option::None,
+            input_debug: Option::None,
+            terminals: NoTerminal,
+            on_restart: RestartHook::new()
+        }
+    }
+
+    /// Keep a copy of the terminal the node most recently returned. 
+    pub fn with_last_terminal(self) -> NodeRunner<N, C, LastTerminal<N::Terminal>> where 
+        N::Terminal: Clone 
+    {
+        NodeRunner {
+            constructor: self.constructor,
+            node: self.node,
+            name: self.name,
+            input_debug: self.input_debug,
+            terminals: LastTerminal::default(),
+            on_restart: self.on_restart
+        }
+    }
+}
+
+impl<N, C, S> NodeRunner<N, C, S> where 
+    N: BehaviorTreeNode + 'static,
+    C: Fn() -> N,
+    S: TerminalStore<N::Terminal>
+{
+    /// Give the node runner a name, which is reported if it is poisoned. 
+    pub fn with_name(mut self, name: &'static str) -> NodeRunner<N, C, S> {
+        self.name = Option::Some(name);
+        self
+    }
+
+    /// Report the input of the step which panicked if the node runner is 
+    /// poisoned. 
+    pub fn with_input_debug(mut self) -> NodeRunner<N, C, S> where N::Input: Debug {
+        self.input_debug = Option::Some(InputDebug::new());
+        self
+    }
+
+    /// Call the given hook each time the node is restarted, with the 
+    /// terminal the node returned and the number of restarts so far. 
+    pub fn with_restart_hook(mut self, hook: fn(&N::Terminal, usize)) -> NodeRunner<N, C, S> {
+        self.on_restart.hook = Option::Some(hook);
+        self
+    }
+
+    /// Return the name of the node runner, if it was given one. 
+    pub fn name(&self) -> Option<&'static str> {
+        self.name
+    }
+
+    /// Return the number of times the node has been restarted. 
+    pub fn restarts(&self) -> usize {
+        self.on_restart.restarts
+    }
+
+    /// Return the terminal the node most recently returned, if the runner 
+    /// keeps it and the node has terminated since. 
+    pub fn last_terminal(&self) -> Option<&N::Terminal> {
+        self.terminals.last()
+    }
+
+    fn poisoned(&self) -> Poisoned {
+        Poisoned {
+            kind: "Node runner",
+            name: self.name,
+            input: self.input_debug.as_ref().map(InputDebug::last)
+        }
+    }
+
+    // Record the input before stepping on it, so that it can be reported 
+    // if the step panics
+    fn record(&mut self, input: &N::Input) {
+        if let Option::Some(ref mut recorder) = self.input_debug {
+            recorder.record(input);
+        }
+    }
+
+    fn advance(&mut self, result: NodeResult<N::Nonterminal, N::Terminal, N>) -> 
+        Statepoint<N::Nonterminal, N::Terminal> 
+    {
+        match result {
+            NodeResult::Nonterminal(s, a) => {
+                self.node = Option::Some(a);
+                Statepoint::Nonterminal(s)
+            },
+            NodeResult::Terminal(t) => {
+                self.terminals.store(&t);
+                self.on_restart.restart(&t);
+                self.node = Option::Some((self.constructor)());
+                Statepoint::Terminal(t)
+            }
+        }
+    }
+
+    /// Transition the node runner, returning an error instead of panicking 
+    /// if a panic in an earlier step poisoned it. 
+    pub fn try_transition(&mut self, input: &N::Input) -> Result<Statepoint<
+        N::Nonterminal, N::Terminal>, Poisoned> 
+    {
+        match self.node.take() {
+            Option::Some(node) => {
+                self.record(input);
+                let result = node.step(input);
+                Result::Ok(self.advance(result))
+            },
+            Option::None => Result::Err(self.poisoned())
+        }
+    }
+
+    /// Recover the node runner if it was poisoned, reinstalling the node 
+    /// returned by the given function. Returns whether the node runner was 
+    /// poisoned. 
+    pub fn recover_with<F>(&mut self, fresh: F) -> bool where 
+        F: FnOnce() -> N
+    {
+        if self.node.is_some() {
+            false
+        } else {
+            self.node = Option::Some(fresh());
+            true
+        }
+    }
+
+    /// Halt the node the runner is running, consuming the runner. 
+    pub fn halt(self, input: &N::Input) {
+        // A poisoned runner has no node left to halt
+        if let Option::Some(node) = self.node {
+            node.halt(input);
+        }
+    }
+
+    /// Transition the node runner with an input passed by value, which is 
+    /// then passed by value to the wrapped node. 
+    pub fn transition_with(&mut self, input: N::Input) -> Statepoint<
+        N::Nonterminal, N::Terminal> 
+    {
+        match self.node.take() {
+            Option::Some(node) => {
+                self.record(&input);
+                let result = node.step_with(input);
+                self.advance(result)
+            },
+            Option::None => panic!("{}", self.poisoned())
         }
     }
 }
 
-impl<N, C> Automaton<'static> for NodeRunner<N, C> where 
+impl<N, C, S> Automaton<'static> for NodeRunner<N, C, S> where 
+    N: BehaviorTreeNode + 'static,
+    C: Fn() -> N,
+    S: TerminalStore<N::Terminal>
+{
+    type Input = N::Input;
+    type Action = Statepoint<N::Nonterminal, N::Terminal>;
+    #[inline]
+    fn transition(&mut self, input: &N::Input) -> Statepoint<N::Nonterminal, N::Terminal> {
+        match self.try_transition(input) {
+            Result::Ok(point) => point,
+            Result::Err(poisoned) => panic!("{}", poisoned)
+        }
+    }
+}
+
+impl<N, C, S> FiniteStateAutomaton<'static> for NodeRunner<N, C, S> where 
+    N: BehaviorTreeNode + 'static + Copy,
+    C: Fn() -> N + Copy,
+    S: TerminalStore<N::Terminal> + Copy
+{}
+
+// Restart policy of a bounded runner, which rebuilds the node until the 
+// restart count is exhausted or the predicate rejects a terminal
+#[derive(Copy, Clone, PartialEq, Debug)]
+struct BoundedRestart<C, P> {
+    constructor: C,
+    predicate: P,
+    restarts: usize,
+    max_restarts: Option<usize>
+}
+
+impl<N, C, P> RestartPolicy<N> for BoundedRestart<C, P> where 
+    N: BehaviorTreeNode,
+    C: Fn() -> N,
+    P: Fn(&N::Terminal) -> bool
+{
+    type Exit = N::Terminal;
+
+    fn restart(&mut self, terminal: N::Terminal) -> Restart<N, N::Terminal, N::Terminal> {
+        let exhausted = match self.max_restarts {
+            Option::Some(max) => self.restarts >= max,
+            Option::None => false
+        };
+        if !exhausted && (self.predicate)(&terminal) {
+            self.restarts += 1;
+            Restart::Rebuild(terminal, (self.constructor)())
+        } else {
+            Restart::Exit(terminal)
+        }
+    }
+}
+
+/// Node runner which restarts its node a bounded number of times. Once the 
+/// restart count is exhausted, or the predicate rejects a terminal, the 
+/// runner gives up and reports the final terminal on every later step. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct BoundedRunner<N, C, P> where 
+    N: BehaviorTreeNode + 'static,
+    C: Fn() -> N,
+    P: Fn(&N::Terminal) -> bool
+{
+    node: Option<RestartNode<N, BoundedRestart<C, P>>>,
+    last: Option<N::Terminal>,
+    name: Option<&'static str>,
+    on_restart: RestartRecord<N>
+}
+
+impl<N, C> BoundedRunner<N, C, fn(&N::Terminal) -> bool> where 
     N: BehaviorTreeNode + 'static,
     C: Fn() -> N
+{
+    /// Create a new bounded runner which restarts its node at most the 
+    /// given number of times. 
+    pub fn new(constructor: C, max_restarts: usize) -> Self {
+        fn always<T>(_terminal: &T) -> bool {
+            true
+        }
+        let predicate: fn(&N::Terminal) -> bool = always::<N::Terminal>;
+        BoundedRunner::with_predicate(constructor, predicate).with_max_restarts(max_restarts)
+    }
+
+    /// Create a new bounded runner which never restarts its node. 
+    pub fn one_shot(constructor: C) -> Self {
+        BoundedRunner::new(constructor, 0)
+    }
+}
+
+impl<N, C, P> BoundedRunner<N, C, P> where 
+    N: BehaviorTreeNode + 'static,
+    C: Fn() -> N,
+    P: Fn(&N::Terminal) -> bool
+{
+    /// Create a new bounded runner which restarts its node for as long as 
+    /// the predicate accepts the terminals it returns. 
+    pub fn with_predicate(constructor: C, predicate: P) -> BoundedRunner<N, C, P> {
+        let new_node = constructor();
+        BoundedRunner {
+            node: Option::Some(RestartNode::with_policy(BoundedRestart {
+                constructor: constructor,
+                predicate: predicate,
+                restarts: 0,
+                max_restarts: Option::None
+            }, new_node)),
+            last: Option::None,
+            name: Option::None,
+            on_restart: RestartHook::new()
+        }
+    }
+
+    /// Also give up once the node has been restarted the given number of 
+    /// times. 
+    pub fn with_max_restarts(mut self, max_restarts: usize) -> BoundedRunner<N, C, P> {
+        if let Option::Some(ref mut node) = self.node {
+            node.policy_mut().max_restarts = Option::Some(max_restarts);
+        }
+        self
+    }
+
+    /// Give the bounded runner a name, which is reported if it is poisoned. 
+    pub fn with_name(mut self, name: &'static str) -> BoundedRunner<N, C, P> {
+        self.name = Option::Some(name);
+        self
+    }
+
+    /// Call the given hook each time the node is restarted, with the 
+    /// terminal the node returned and the number of restarts so far. 
+    pub fn with_restart_hook(mut self, hook: fn(&N::Terminal, usize)) -> 
+        BoundedRunner<N, C, P> 
+    {
+        self.on_restart.hook = Option::Some(hook);
+        self
+    }
+
+    /// Return the number of times the node has been restarted. 
+    pub fn restarts(&self) -> usize {
+        self.on_restart.restarts
+    }
+
+    /// Return whether the runner has given up on restarting its node. 
+    pub fn has_given_up(&self) -> bool {
+        self.node.is_none() && self.last.is_some()
+    }
+}
+
+impl<N, C, P> Automaton<'static> for BoundedRunner<N, C, P> where 
+    N: BehaviorTreeNode + 'static,
+    N::Terminal: Clone,
+    C: Fn() -> N,
+    P: Fn(&N::Terminal) -> bool
 {
     type Input = N::Input;
     type Action = Statepoint<N::Nonterminal, N::Terminal>;
+
     #[inline]
     fn transition(&mut self, input: &N::Input) -> Statepoint<N::Nonterminal, N::Terminal> {
-        match self.node
-            .take()
-            .expect("Node runner was poisoned")
-            .step(input) 
-        {
-            NodeResult::Nonterminal(s, a) => {
+        let node = match self.node.take() {
+            Option::Some(node) => node,
+            Option::None => match self.last {
+                Option::Some(ref t) => return Statepoint::Terminal(t.clone()),
+                Option::None => panic!("{}", Poisoned {
+                    kind: "Bounded runner",
+                    name: self.name,
+                    input: Option::None
+                })
+            }
+        };
+        match node.step(input) {
+            NodeResult::Nonterminal(Statepoint::Nonterminal(s), a) => {
                 self.node = Option::Some(a);
                 Statepoint::Nonterminal(s)
             },
+            NodeResult::Nonterminal(Statepoint::Terminal(t), a) => {
+                self.on_restart.restart(&t);
+                self.node = Option::Some(a);
+                Statepoint::Terminal(t)
+            },
             NodeResult::Terminal(t) => {
-                self.node = Option::Some((self.constructor)());
+                self.last = Option::Some(t.clone());
                 Statepoint::Terminal(t)
             }
         }
     }
 }
 
-impl<N, C> FiniteStateAutomaton<'static> for NodeRunner<N, C> where 
+impl<N, C, P> FiniteStateAutomaton<'static> for BoundedRunner<N, C, P> where 
     N: BehaviorTreeNode + 'static + Copy,
-    C: Fn() -> N + Copy
+    N::Terminal: Copy,
+    C: Fn() -> N + Copy,
+    P: Fn(&N::Terminal) -> bool + Copy
 {}
 
+/// Bounded runner which restarts its node at most a given number of times, 
+/// created with BoundedRunner::new. 
+pub type CountedRunner<N, C> = BoundedRunner<N, C, 
+    fn(&<N as BehaviorTreeNode>::Terminal) -> bool>;
+
+/// Bounded runner which never restarts its node, and reports the terminal 
+/// of its node on every step after it terminates, created with 
+/// BoundedRunner::one_shot. 
+pub type OneShotRunner<N, C> = CountedRunner<N, C>;
+
+/// Node runner which builds the input of its node each step from several 
+/// sources: the output of an input provider polled with the source passed 
+/// in, and the events queued since the previous step. 
+/// 
+/// Each step, the provider is polled first, then the queued events are 
+/// taken in the order they were pushed, and both are passed to the combiner 
+/// to produce the input of the node. Events pushed during a step are thus 
+/// seen in the following step. 
+pub struct MultiplexedRunner<N, C, P, E, M> where 
+    N: BehaviorTreeNode + 'static,
+    C: Fn() -> N,
+    P: InputProvider<'static>,
+    M: Fn(P::Output, Vec<E>) -> N::Input
+{
+    runner: NodeRunner<N, C>,
+    provider: P,
+    combiner: M,
+    events: VecDeque<E>
+}
+
+impl<N, C, P, E, M> MultiplexedRunner<N, C, P, E, M> where 
+    N: BehaviorTreeNode + 'static,
+    C: Fn() -> N,
+    P: InputProvider<'static>,
+    M: Fn(P::Output, Vec<E>) -> N::Input
+{
+    /// Create a new multiplexed runner. 
+    pub fn new(combiner: M, provider: P, runner: NodeRunner<N, C>) -> 
+        MultiplexedRunner<N, C, P, E, M> 
+    {
+        MultiplexedRunner {
+            runner: runner,
+            provider: provider,
+            combiner: combiner,
+            events: VecDeque::new()
+        }
+    }
+
+    /// Queue an event to be passed to the combiner on the next step. 
+    pub fn push_event(&mut self, event: E) {
+        self.events.push_back(event);
+    }
+
+    /// Return the number of events queued for the next step. 
+    pub fn pending_events(&self) -> usize {
+        self.events.len()
+    }
+}
+
+impl<N, C, P, E, M> Automaton<'static> for MultiplexedRunner<N, C, P, E, M> where 
+    N: BehaviorTreeNode + 'static,
+    C: Fn() -> N,
+    P: InputProvider<'static>,
+    M: Fn(P::Output, Vec<E>) -> N::Input
+{
+    type Input = P::Source;
+    type Action = Statepoint<N::Nonterminal, N::Terminal>;
+
+    #[inline]
+    fn transition(&mut self, source: &P::Source) -> Statepoint<N::Nonterminal, 
+        N::Terminal> 
+    {
+        let polled = self.provider.provide(source);
+        let events = self.events.drain(..).collect();
+        let input = (self.combiner)(polled, events);
+        self.runner.transition_with(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use behavior_tree_node::Statepoint;
+    use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
 
     #[test]
     fn runner_test() {
@@ -87,4 +580,218 @@ mod tests {
             _ => unreachable!("Expected nonterminal state")
         };
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn last_terminal_test() {
+        use stackbt_automata_impl::automaton::Automaton;
+        use base_nodes::PredicateWait;
+        use node_runner::NodeRunner;
+        let constructor = | | PredicateWait::new(|i: &i64| {
+            if *i == 0 {
+                Statepoint::Nonterminal(())
+            } else {
+                Statepoint::Terminal(*i)
+            }
+        });
+        let mut plain = NodeRunner::new(constructor);
+        plain.transition(&1);
+        assert_eq!(plain.last_terminal(), Option::None);
+        let mut machine = NodeRunner::new(constructor).with_last_terminal();
+        assert_eq!(machine.last_terminal(), Option::None);
+        machine.transition(&3);
+        machine.transition(&0);
+        assert_eq!(machine.last_terminal(), Option::Some(&3));
+        let copied = machine;
+        machine.transition(&5);
+        assert_eq!(machine.last_terminal(), Option::Some(&5));
+        assert_eq!(copied.last_terminal(), Option::Some(&3));
+        assert!(machine.terminals != copied.terminals);
+    }
+
+    #[test]
+    fn cloned_terminal_runner_test() {
+        use stackbt_automata_impl::automaton::{Automaton, FiniteStateAutomaton};
+        use base_nodes::PredicateWait;
+        use node_runner::NodeRunner;
+        fn finite<M>(machine: M) -> M where M: FiniteStateAutomaton<'static> {
+            machine
+        }
+        fn name_of(i: &i64) -> Statepoint<(), String> {
+            Statepoint::Terminal(i.to_string())
+        }
+        let constructor = | | PredicateWait::new(name_of as fn(&i64) -> Statepoint<(), String>);
+        let mut machine = finite(NodeRunner::new(constructor));
+        let mut copied = machine;
+        assert_eq!(machine.transition(&4), Statepoint::Terminal("4".to_owned()));
+        assert_eq!(copied.transition(&5), Statepoint::Terminal("5".to_owned()));
+    }
+
+    #[test]
+    fn poisoned_test() {
+        use stackbt_automata_impl::automaton::Poisoned;
+        use base_nodes::PredicateWait;
+        use node_runner::NodeRunner;
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+        let mut machine = NodeRunner::new(| | PredicateWait::new(|i: &i64| {
+            assert!(*i >= 0, "Negative input");
+            Statepoint::Nonterminal::<(), ()>(())
+        })).with_name("counter").with_input_debug();
+        assert_eq!(machine.name(), Option::Some("counter"));
+        assert_eq!(machine.try_transition(&1), Result::Ok(Statepoint::Nonterminal(())));
+        let result = catch_unwind(AssertUnwindSafe(|| machine.try_transition(&-1)));
+        assert!(result.is_err());
+        let poisoned = machine.try_transition(&2).unwrap_err();
+        assert_eq!(poisoned, Poisoned {
+            kind: "Node runner",
+            name: Option::Some("counter"),
+            input: Option::Some("-1".to_owned())
+        });
+        assert_eq!(poisoned.to_string(), "Node runner 'counter' was poisoned (last input: -1)");
+    }
+
+    #[test]
+    fn recover_test() {
+        use base_nodes::PredicateWait;
+        use node_runner::NodeRunner;
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+        let constructor = | | PredicateWait::new(|i: &i64| {
+            assert!(*i >= 0, "Negative input");
+            Statepoint::Nonterminal::<i64, ()>(*i)
+        });
+        let mut machine = NodeRunner::new(constructor);
+        assert!(!machine.recover_with(constructor));
+        let result = catch_unwind(AssertUnwindSafe(|| machine.try_transition(&-1)));
+        assert!(result.is_err());
+        assert!(machine.try_transition(&1).is_err());
+        assert!(machine.recover_with(constructor));
+        assert_eq!(machine.try_transition(&1), Result::Ok(Statepoint::Nonterminal(1)));
+    }
+
+    #[test]
+    fn bounded_runner_test() {
+        use stackbt_automata_impl::automaton::Automaton;
+        use base_nodes::PredicateWait;
+        use node_runner::BoundedRunner;
+        let constructor = | | PredicateWait::new(|i: &i64| {
+            if *i == 0 {
+                Statepoint::Nonterminal(())
+            } else {
+                Statepoint::Terminal(*i)
+            }
+        });
+        let mut machine = BoundedRunner::new(constructor, 1);
+        assert_eq!(machine.transition(&0), Statepoint::Nonterminal(()));
+        assert_eq!(machine.transition(&1), Statepoint::Terminal(1));
+        assert_eq!(machine.restarts(), 1);
+        assert!(!machine.has_given_up());
+        assert_eq!(machine.transition(&2), Statepoint::Terminal(2));
+        assert!(machine.has_given_up());
+        assert_eq!(machine.transition(&0), Statepoint::Terminal(2));
+        let mut machine = BoundedRunner::with_predicate(constructor, |t: &i64| *t > 0);
+        assert_eq!(machine.transition(&3), Statepoint::Terminal(3));
+        assert_eq!(machine.transition(&-1), Statepoint::Terminal(-1));
+        assert_eq!(machine.transition(&0), Statepoint::Terminal(-1));
+        assert_eq!(machine.restarts(), 1);
+    }
+
+    #[test]
+    fn restart_policy_test() {
+        use stackbt_automata_impl::automaton::Automaton;
+        use base_nodes::PredicateWait;
+        use node_runner::{BoundedRunner, CountedRunner, NodeRunner, OneShotRunner};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        static RESTARTED: AtomicUsize = AtomicUsize::new(0);
+        fn count_restart(terminal: &i64, restarts: usize) {
+            assert_eq!(*terminal as usize, restarts);
+            RESTARTED.fetch_add(1, Ordering::SeqCst);
+        }
+        let constructor = | | PredicateWait::new(|i: &i64| {
+            if *i == 0 {
+                Statepoint::Nonterminal(())
+            } else {
+                Statepoint::Terminal(*i)
+            }
+        });
+        let mut once: OneShotRunner<_, _> = BoundedRunner::one_shot(constructor)
+            .with_restart_hook(count_restart);
+        assert_eq!(once.transition(&0), Statepoint::Nonterminal(()));
+        assert_eq!(once.transition(&4), Statepoint::Terminal(4));
+        assert_eq!(once.transition(&0), Statepoint::Terminal(4));
+        assert_eq!(once.restarts(), 0);
+        assert_eq!(RESTARTED.load(Ordering::SeqCst), 0);
+        let mut counted: CountedRunner<_, _> = BoundedRunner::new(constructor, 2)
+            .with_restart_hook(count_restart);
+        assert_eq!(counted.transition(&1), Statepoint::Terminal(1));
+        assert_eq!(counted.transition(&2), Statepoint::Terminal(2));
+        assert_eq!(counted.transition(&3), Statepoint::Terminal(3));
+        assert_eq!(counted.transition(&0), Statepoint::Terminal(3));
+        assert_eq!(RESTARTED.load(Ordering::SeqCst), 2);
+        let mut machine = NodeRunner::new(constructor).with_restart_hook(count_restart);
+        assert_eq!(machine.transition(&1), Statepoint::Terminal(1));
+        assert_eq!(machine.transition(&2), Statepoint::Terminal(2));
+        assert_eq!(machine.restarts(), 2);
+        assert_eq!(RESTARTED.load(Ordering::SeqCst), 4);
+    }
+
+    struct Token(i64);
+
+    struct TokenEater;
+
+    impl BehaviorTreeNode for TokenEater {
+        type Input = Token;
+        type Nonterminal = ();
+        type Terminal = i64;
+
+        fn step(self, _input: &Token) -> NodeResult<(), i64, Self> {
+            NodeResult::Nonterminal((), self)
+        }
+
+        fn step_with(self, input: Token) -> NodeResult<(), i64, Self> {
+            let Token(val) = input;
+            NodeResult::Terminal(val)
+        }
+    }
+
+    #[test]
+    fn runner_owned_input_test() {
+        use stackbt_automata_impl::automaton::Automaton;
+        use node_runner::NodeRunner;
+        let mut machine = NodeRunner::new(| | TokenEater);
+        match machine.transition(&Token(3)) {
+            Statepoint::Nonterminal(_) => (),
+            _ => unreachable!("Expected nonterminal state")
+        };
+        match machine.transition_with(Token(4)) {
+            Statepoint::Terminal(t) => assert_eq!(t, 4),
+            _ => unreachable!("Expected terminal state")
+        };
+    }
+
+    #[test]
+    fn multiplexed_runner_test() {
+        use stackbt_automata_impl::automaton::Automaton;
+        use stackbt_automata_impl::input_provider::ProviderFn;
+        use base_nodes::PredicateWait;
+        use node_runner::{NodeRunner, MultiplexedRunner};
+        let runner = NodeRunner::new(| | PredicateWait::new(|i: &(i64, Vec<char>)| {
+            if i.1.contains(&'q') {
+                Statepoint::Terminal(i.0)
+            } else {
+                Statepoint::Nonterminal(i.1.clone())
+            }
+        }));
+        let mut machine = MultiplexedRunner::new(
+            |polled: i64, events: Vec<char>| (polled, events),
+            ProviderFn::new(|world: &i64| *world * 2),
+            runner
+        );
+        machine.push_event('a');
+        machine.push_event('b');
+        assert_eq!(machine.pending_events(), 2);
+        assert_eq!(machine.transition(&1), Statepoint::Nonterminal(vec!['a', 'b']));
+        assert_eq!(machine.pending_events(), 0);
+        assert_eq!(machine.transition(&2), Statepoint::Nonterminal(vec![]));
+        machine.push_event('q');
+        assert_eq!(machine.transition(&3), Statepoint::Terminal(6));
+    }
+}
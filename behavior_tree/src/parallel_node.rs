@@ -1,9 +1,13 @@
 use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
-use stackbt_automata_impl::automaton::Automaton;
+use node_runner::NodeRunner;
+use stackbt_automata_impl::automaton::BufferedAutomaton;
+use stackbt_automata_impl::snapshot::Snapshot;
+use std::fmt;
 
-/// Parallel decider, which given the input and a slice of statepoints, 
-/// decides whether to forward the statepoint box or to consume the 
-/// statepoint box and exit. 
+/// Parallel decider, which given the input and the statepoints reached by 
+/// the parallel nodes, decides whether to keep running them or to exit. 
+/// The decider is borrowed mutably each step, so it can keep state of its 
+/// own, such as how long some of the parallel nodes have been failing. 
 pub trait ParallelDecider {
     /// Type of the input to distribute among the parallel nodes. 
     type Input: 'static;
@@ -13,53 +17,201 @@ pub trait ParallelDecider {
     type Term: 'static;
     /// Type of the terminal returned by the parallel node itself. 
     type Exit;
-    /// Given the input and the boxed statepoint slice, return a statepoint 
-    /// of either that boxed statepoint slice or a terminal value. 
-    fn each_step(&self, &Self::Input, Box<[Statepoint<Self::Nonterm, Self::Term>]>) -> 
-        Statepoint<Box<[Statepoint<Self::Nonterm, Self::Term>]>, Self::Exit>;
+    /// Given the input and the buffer of statepoints, either return None to 
+    /// keep running, leaving as many statepoints in the buffer as there were, 
+    /// or return the terminal value to exit with, taking what it needs from 
+    /// the buffer. The statepoints may be changed in place either way. 
+    fn each_step(&mut self, &Self::Input, &mut Vec<Statepoint<Self::Nonterm, Self::Term>>) -> 
+        Option<Self::Exit>;
+}
+
+/// Trait for collections of automata which run nodes, and can halt the 
+/// nodes they are running. 
+pub trait HaltChildren {
+    /// Type of the input the nodes are halted with. 
+    type Input;
+    /// Halt all of the nodes, consuming the collection. 
+    fn halt_children(self, &Self::Input);
+}
+
+impl<N, C> HaltChildren for Vec<NodeRunner<N, C>> where 
+    N: BehaviorTreeNode + 'static,
+    C: Fn() -> N
+{
+    type Input = N::Input;
+
+    fn halt_children(self, input: &N::Input) {
+        for runner in self {
+            runner.halt(input);
+        }
+    }
+}
+
+impl<N, C> HaltChildren for Box<[NodeRunner<N, C>]> where 
+    N: BehaviorTreeNode + 'static,
+    C: Fn() -> N
+{
+    type Input = N::Input;
+
+    fn halt_children(self, input: &N::Input) {
+        self.into_vec().halt_children(input);
+    }
+}
+
+// Halts a collection which cannot reach the nodes it runs
+fn halt_nothing<C, I>(_collection: C, _input: &I) {}
+
+// How a parallel node halts its collection, which is a setting of the node 
+// and not part of its state
+struct Halter<C, I>(fn(C, &I));
+
+impl<C, I> Copy for Halter<C, I> {}
+
+impl<C, I> Clone for Halter<C, I> {
+    fn clone(&self) -> Halter<C, I> {
+        *self
+    }
+}
+
+impl<C, I> PartialEq for Halter<C, I> {
+    fn eq(&self, _other: &Halter<C, I>) -> bool {
+        true
+    }
+}
+
+impl<C, I> fmt::Debug for Halter<C, I> {
+    fn fmt(&self, fmter: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmter, "Halter")
+    }
+}
+
+/// Error terminal of a parallel branch node whose statepoint buffer did not 
+/// hold the expected number of statepoints. 
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum CountMismatch {
+    /// The collection wrote the given number of statepoints, instead of the 
+    /// expected number. 
+    Collection {
+        expected: usize,
+        found: usize
+    },
+    /// The decider continued, leaving the given number of statepoints in the 
+    /// buffer, instead of the expected number. 
+    Decider {
+        expected: usize,
+        found: usize
+    }
+}
+
+impl fmt::Display for CountMismatch {
+    fn fmt(&self, fmter: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CountMismatch::Collection { expected, found } => write!(fmter, 
+                "Parallel node automaton returned {} statepoints, expected {}", 
+                found, expected),
+            CountMismatch::Decider { expected, found } => write!(fmter, 
+                "Parallel decider forwarded {} statepoints, expected {}", 
+                found, expected)
+        }
+    }
 }
 
 /// A parallel branch node, which is composed of a ParallelDecider on top of 
-/// an automaton which returns boxed slices of statepoints. 
+/// a collection of automata which return statepoints. 
 /// 
-/// The idea is that the automaton this node is built on is a slice of 
-/// node runners which, each step, are all executed with the same input, 
-/// returning a boxed slice consisting of the statepoints reached by the 
-/// nodes. To this end, StackBT's automata_impl library automatically 
-/// implements the appropriate automaton trait on slices of automata which
-/// return the same inputs and actions. 
+/// The idea is that the collection this node is built on is a vector or 
+/// boxed slice of node runners which, each step, are all executed with the 
+/// same input, writing the statepoints reached by the nodes into a buffer 
+/// kept by this node. To this end, StackBT's automata_impl library 
+/// implements the BufferedAutomaton trait on slices of automata which take 
+/// the same inputs and return the same actions. Since the buffer is reused 
+/// from step to step, the node does not allocate once the buffer has grown 
+/// to hold all of the statepoints. 
 /// 
-/// However, the automaton used does not need to be slices of node runners, 
+/// However, the collection used does not need to be made of node runners, 
 /// and this library does take advantage of this for testing by constructing 
 /// test parallel nodes upon internal state machines returning statepoint 
-/// slices. 
-#[derive(Copy, Clone, PartialEq, Debug)]
+/// slices, adapted with BoxedActions. 
+/// 
+/// Each nonterminal step, the node returns the number of the parallel nodes 
+/// which reached nonterminals. The statepoints themselves can be looked at 
+/// through the node returned. 
+/// 
+/// Deciders assume that the statepoint buffers they are given are always of 
+/// the same length, so the node checks this. The expected length is either 
+/// given upon construction, or taken from the first statepoints written by 
+/// the collection. If the collection writes a different number of 
+/// statepoints, or the decider changes the number while continuing, the 
+/// node halts the collection and terminates with a count mismatch. 
+/// 
+/// Collections of automata cannot halt the nodes they run in general, so 
+/// halting the node does nothing unless the collection implements 
+/// HaltChildren, as vectors and boxed slices of node runners do, and the 
+/// node was set up with with_halt to halt it. The node halts the collection 
+/// in the same way when the decider exits, as the nodes still running are 
+/// abandoned. Snapshots keep the expected number of statepoints, but not 
+/// whether the node halts its collection, so a restored node must be set 
+/// up with with_halt again. 
+#[derive(Clone, PartialEq, Debug)]
 pub struct ParallelBranchNode<C, D> where
-    C: Automaton<'static, Input=D::Input, Action=Box<[Statepoint<D::Nonterm, 
-        D::Term>]>>,
+    C: BufferedAutomaton<'static, Input=D::Input, Action=Statepoint<D::Nonterm, 
+        D::Term>>,
     D: ParallelDecider
 {
     collection: C,
-    decider: D
+    decider: D,
+    count: Option<usize>,
+    buffer: Vec<Statepoint<D::Nonterm, D::Term>>,
+    halter: Halter<C, D::Input>
 }
 
 impl<C, D> ParallelBranchNode<C, D> where
-    C: Automaton<'static, Input=D::Input, Action=Box<[Statepoint<D::Nonterm, 
-        D::Term>]>>,
+    C: BufferedAutomaton<'static, Input=D::Input, Action=Statepoint<D::Nonterm, 
+        D::Term>>,
     D: ParallelDecider
 {
     /// Create a new parallel branch node. 
     pub fn new(decider: D, machine: C) -> ParallelBranchNode<C, D> {
         ParallelBranchNode {
             collection: machine,
-            decider: decider
+            decider: decider,
+            count: Option::None,
+            buffer: Vec::new(),
+            halter: Halter(halt_nothing)
+        }
+    }
+
+    /// Create a new parallel branch node, whose collection is expected to 
+    /// return the given number of statepoints. 
+    pub fn with_count(decider: D, machine: C, count: usize) -> ParallelBranchNode<C, D> {
+        ParallelBranchNode {
+            collection: machine,
+            decider: decider,
+            count: Option::Some(count),
+            buffer: Vec::with_capacity(count),
+            halter: Halter(halt_nothing)
         }
     }
+
+    /// Halt the nodes run by the collection when the node is halted. 
+    pub fn with_halt(mut self) -> ParallelBranchNode<C, D> where 
+        C: HaltChildren<Input=D::Input>
+    {
+        self.halter = Halter(HaltChildren::halt_children);
+        self
+    }
+
+    /// Return the statepoints reached by the parallel nodes on the last 
+    /// step, as left by the decider, or an empty slice before the first 
+    /// step. 
+    pub fn statepoints(&self) -> &[Statepoint<D::Nonterm, D::Term>] {
+        &self.buffer
+    }
 }
 
 impl<C, D> Default for ParallelBranchNode<C, D> where
-    C: Automaton<'static, Input=D::Input, Action=Box<[Statepoint<D::Nonterm, 
-        D::Term>]>> + Default,
+    C: BufferedAutomaton<'static, Input=D::Input, Action=Statepoint<D::Nonterm, 
+        D::Term>> + Default,
     D: ParallelDecider + Default
 {
     fn default() -> ParallelBranchNode<C, D> {
@@ -68,36 +220,84 @@ impl<C, D> Default for ParallelBranchNode<C, D> where
 }
 
 impl<C, D> BehaviorTreeNode for ParallelBranchNode<C, D> where 
-    C: Automaton<'static, Input=D::Input, Action=Box<[Statepoint<D::Nonterm, 
-        D::Term>]>>,
+    C: BufferedAutomaton<'static, Input=D::Input, Action=Statepoint<D::Nonterm, 
+        D::Term>>,
     D: ParallelDecider
 {
     type Input = C::Input;
-    type Nonterminal = C::Action;
-    type Terminal = D::Exit;
+    type Nonterminal = usize;
+    type Terminal = Result<D::Exit, CountMismatch>;
+
+    #[inline]
+    fn step(self, input: &C::Input) -> NodeResult<usize, Self::Terminal, Self> {
+        let mut mut_self = self;
+        mut_self.collection.transition_into(input, &mut mut_self.buffer);
+        let count = mut_self.count.unwrap_or(mut_self.buffer.len());
+        if mut_self.buffer.len() != count {
+            let found = mut_self.buffer.len();
+            mut_self.halt(input);
+            return NodeResult::Terminal(Result::Err(CountMismatch::Collection {
+                expected: count,
+                found: found
+            }));
+        }
+        mut_self.count = Option::Some(count);
+        match mut_self.decider.each_step(input, &mut mut_self.buffer) {
+            Option::None => {
+                if mut_self.buffer.len() != count {
+                    let found = mut_self.buffer.len();
+                    mut_self.halt(input);
+                    return NodeResult::Terminal(Result::Err(CountMismatch::Decider {
+                        expected: count,
+                        found: found
+                    }));
+                }
+                let running = mut_self.buffer.iter().filter(|point| match point {
+                    Statepoint::Nonterminal(_) => true,
+                    Statepoint::Terminal(_) => false
+                }).count();
+                NodeResult::Nonterminal(running, mut_self)
+            },
+            Option::Some(t) => {
+                mut_self.halt(input);
+                NodeResult::Terminal(Result::Ok(t))
+            }
+        }
+    }
 
     #[inline]
-    fn step(self, input: &C::Input) -> NodeResult<Self::Nonterminal, D::Exit, Self> {
-        let mut coll = self.collection;
-        let results = coll.transition(input);
-        let decision = self.decider.each_step(input, results);
-        match decision {
-            Statepoint::Nonterminal(ret) => NodeResult::Nonterminal(
-                ret,
-                Self::new(self.decider, coll)
-            ),
-            Statepoint::Terminal(t) => NodeResult::Terminal(t)
+    fn halt(self, input: &C::Input) {
+        (self.halter.0)(self.collection, input);
+    }
+}
+
+impl<C, D> Snapshot for ParallelBranchNode<C, D> where 
+    C: BufferedAutomaton<'static, Input=D::Input, Action=Statepoint<D::Nonterm, 
+        D::Term>> + Snapshot,
+    D: ParallelDecider + Clone
+{
+    type State = (D, Option<usize>, C::State);
+
+    fn snapshot(&self) -> (D, Option<usize>, C::State) {
+        (self.decider.clone(), self.count, self.collection.snapshot())
+    }
+
+    fn restore(state: (D, Option<usize>, C::State)) -> ParallelBranchNode<C, D> {
+        match state.1 {
+            Option::Some(count) => ParallelBranchNode::with_count(state.0, 
+                C::restore(state.2), count),
+            Option::None => ParallelBranchNode::new(state.0, C::restore(state.2))
         }
     }
 }
 
-#[cfg(all(test, feature = "existential_type"))]
+#[cfg(test)]
 mod tests {
     use base_nodes::MachineWrapper;
     use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
     use node_runner::NodeRunner;
-    use parallel_node::ParallelDecider;
-    use stackbt_automata_impl::automaton::Automaton;
+    use parallel_node::{ParallelDecider, ParallelBranchNode};
+    use stackbt_automata_impl::automaton::{Automaton, BoxedActions};
     use stackbt_automata_impl::internal_state_machine::{InternalTransition,
         InternalStateMachine};
 
@@ -120,21 +320,27 @@ mod tests {
         }
     }
 
-    fn indefinite_increment_construct() -> InternalStateMachine<'static, 
-        IndefiniteIncrement> 
-    {
-        InternalStateMachine::new(IndefiniteIncrement, 0)
-    }
+    type Incrementer = MachineWrapper<InternalStateMachine<'static, IndefiniteIncrement>, 
+        i64, i64>;
 
-    existential type IndefiniteConstructor: Fn() -> InternalStateMachine<'static, 
-        IndefiniteIncrement>;
+    type IndefiniteConstructor = fn() -> Incrementer;
+
+    fn indefinite_increment_construct() -> Incrementer {
+        MachineWrapper::new(InternalStateMachine::new(IndefiniteIncrement, 0))
+    }
 
-    #[derive(Default)]
     struct MultiMachine {
-        first: NodeRunner<MachineWrapper<InternalStateMachine<'static, 
-            IndefiniteIncrement>, i64, i64>, IndefiniteConstructor>,
-        second: NodeRunner<MachineWrapper<InternalStateMachine<'static, 
-            IndefiniteIncrement>, i64, i64>, IndefiniteConstructor>,
+        first: NodeRunner<Incrementer, IndefiniteConstructor>,
+        second: NodeRunner<Incrementer, IndefiniteConstructor>
+    }
+
+    impl MultiMachine {
+        fn new() -> MultiMachine {
+            MultiMachine {
+                first: NodeRunner::new(indefinite_increment_construct),
+                second: NodeRunner::new(indefinite_increment_construct)
+            }
+        }
     }
 
     #[derive(Copy, Clone, Default)]
@@ -166,89 +372,171 @@ mod tests {
         type Term = i64;
         type Exit = ();
 
-        fn each_step(&self, input: &i64, slicebox: Box<[Statepoint<i64, i64>]>) -> 
-            Statepoint<Box<[Statepoint<i64, i64>]>, ()>
+        fn each_step(&mut self, input: &i64, _states: &mut Vec<Statepoint<i64, i64>>) -> 
+            Option<()>
         {
             if *input == 0 {
-                Statepoint::Terminal(())
+                Option::Some(())
             } else {
-                Statepoint::Nonterminal(slicebox)
+                Option::None
             }
         }
     }
 
     #[test]
     fn parallel_node_test() {
-        use parallel_node::ParallelBranchNode;
-        let par_node = ParallelBranchNode::<InternalStateMachine<
-            MultiMachineManipulator>, MagicNumDecider>::default();
-        let par_node_1 = match par_node.step(&4) {
-            NodeResult::Nonterminal(v, n) => {
-                match v[0] {
-                    Statepoint::Nonterminal(i) => assert_eq!(i, 1),
-                    _ => unreachable!( "Expected subordinate nonterminal transition")
-                };
-                match v[1] {
-                    Statepoint::Terminal(i) => assert_eq!(i, 0),
-                    _ => unreachable!("Expected subordinate terminal transition"),
-                };
-                n
-            },
-            NodeResult::Terminal(_) => unreachable!("Expected nonterminal transition")
-        };
-        let par_node_2 = match par_node_1.step(&3) {
-            NodeResult::Nonterminal(v, n) => {
-                match v[0] {
-                    Statepoint::Nonterminal(i) => assert_eq!(i, 2),
-                    Statepoint::Terminal(_) => unreachable!(
-                        "Expected subordinate nonterminal transition"
-                    )
-                };
-                match v[1] {
-                    Statepoint::Nonterminal(_) => unreachable!(
-                        "Expected subordinate terminal transition"
-                    ),
-                    Statepoint::Terminal(i) => assert_eq!(i, 0),
-                };
-                n
-            },
-            NodeResult::Terminal(_) => unreachable!("Expected nonterminal transition")
-        };
-        let par_node_3 = match par_node_2.step(&-3) {
-            NodeResult::Nonterminal(v, n) => {
-                match v[0] {
-                    Statepoint::Nonterminal(_) => unreachable!(
-                        "Expected subordinate terminal transition"
-                    ),
-                    Statepoint::Terminal(i) => assert_eq!(i, 0),
-                };
-                match v[1] {
-                    Statepoint::Nonterminal(i) => assert_eq!(i, 1),
-                    Statepoint::Terminal(_) => unreachable!(
-                        "Expected subordinate nonterminal transition"
-                    )
-                };
-                n
-            },
-            NodeResult::Terminal(_) => unreachable!("Expected nonterminal transition")
-        };
-        let par_node_4 = match par_node_3.step(&-3) {
-            NodeResult::Nonterminal(v, n) => {
-                match v[0] {
-                    Statepoint::Nonterminal(_) => unreachable!(
-                        "Expected subordinate terminal transition"
-                    ),
-                    Statepoint::Terminal(i) => assert_eq!(i, 0),
-                };
-                match v[1] {
-                    Statepoint::Nonterminal(i) => assert_eq!(i, 2),
-                    Statepoint::Terminal(_) => unreachable!(
-                        "Expected subordinate nonterminal transition"
-                    )
-                };
-                n
-            },
-            NodeResult::Terminal(_) => unreachable!("Expected nonterminal transition")
-        };
+        let mut par_node = ParallelBranchNode::new(MagicNumDecider, BoxedActions::new(
+            InternalStateMachine::new(MultiMachineManipulator, MultiMachine::new())));
+        let expected = [
+            (4, [Statepoint::Nonterminal(1), Statepoint::Terminal(0)]),
+            (3, [Statepoint::Nonterminal(2), Statepoint::Terminal(0)]),
+            (-3, [Statepoint::Terminal(0), Statepoint::Nonterminal(1)]),
+            (-3, [Statepoint::Terminal(0), Statepoint::Nonterminal(2)])
+        ];
+        for &(input, ref points) in expected.iter() {
+            par_node = match par_node.step(&input) {
+                NodeResult::Nonterminal(running, n) => {
+                    assert_eq!(running, 1);
+                    assert_eq!(n.statepoints(), &points[..]);
+                    n
+                },
+                NodeResult::Terminal(_) => unreachable!("Expected nonterminal transition")
+            };
+        }
+        assert_steps!(par_node; 
+            0 => Terminal(Result::Ok(()))
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests_count {
+    use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+    use node_runner::NodeRunner;
+    use parallel_node::{ParallelDecider, ParallelBranchNode, CountMismatch};
+    use stackbt_automata_impl::automaton::BoxedActions;
+    use stackbt_automata_impl::internal_state_machine::{InternalTransition,
+        InternalStateMachine};
+    use test_utils::HaltCounter;
+
+    #[derive(Copy, Clone, Default)]
+    struct Widening;
+
+    impl InternalTransition for Widening {
+        type Input = i64;
+        type Internal = usize;
+        type Action = Box<[Statepoint<i64, i64>]>;
+
+        fn step(&self, input: &i64, width: &mut usize) -> Self::Action {
+            let slice = vec![Statepoint::Nonterminal(*input); *width];
+            *width += 1;
+            slice.into_boxed_slice()
+        }
+    }
+
+    #[derive(Copy, Clone, Default)]
+    struct Forwarder;
+
+    impl ParallelDecider for Forwarder {
+        type Input = i64;
+        type Nonterm = i64;
+        type Term = i64;
+        type Exit = ();
+
+        fn each_step(&mut self, _i: &i64, _states: &mut Vec<Statepoint<i64, i64>>) -> 
+            Option<()>
+        {
+            Option::None
+        }
+    }
+
+    #[test]
+    fn learned_count_test() {
+        let node = ParallelBranchNode::new(Forwarder, 
+            BoxedActions::new(InternalStateMachine::new(Widening, 2)));
+        let node = assert_steps!(node; 1 => Nonterminal(2));
+        assert_eq!(node.statepoints().len(), 2);
+        assert_steps!(node; 
+            1 => Terminal(Result::Err(CountMismatch::Collection { expected: 2, found: 3 }))
+        );
+    }
+
+    #[test]
+    fn given_count_test() {
+        let node = ParallelBranchNode::with_count(Forwarder, 
+            BoxedActions::new(InternalStateMachine::new(Widening, 1)), 3);
+        match node.step(&1) {
+            NodeResult::Terminal(Result::Err(mismatch)) => assert_eq!(mismatch.to_string(), 
+                "Parallel node automaton returned 1 statepoints, expected 3"),
+            _ => unreachable!("Expected count mismatch")
+        }
+    }
+
+    #[derive(Copy, Clone, Default)]
+    struct StopOnZero;
+
+    impl ParallelDecider for StopOnZero {
+        type Input = i64;
+        type Nonterm = i64;
+        type Term = i64;
+        type Exit = ();
+
+        fn each_step(&mut self, input: &i64, _states: &mut Vec<Statepoint<i64, i64>>) -> 
+            Option<()>
+        {
+            if *input == 0 {
+                Option::Some(())
+            } else {
+                Option::None
+            }
+        }
+    }
+
+    #[test]
+    fn restored_count_test() {
+        use stackbt_automata_impl::snapshot::Snapshot;
+        let node = ParallelBranchNode::with_count(Forwarder, 
+            BoxedActions::new(InternalStateMachine::new(Widening, 1)), 3);
+        type Widener = BoxedActions<InternalStateMachine<'static, Widening>>;
+        let node = ParallelBranchNode::<Widener, Forwarder>::restore(node.snapshot());
+        assert_steps!(node; 
+            1 => Terminal(Result::Err(CountMismatch::Collection { expected: 3, found: 1 }))
+        );
+    }
+
+    #[test]
+    fn halt_children_test() {
+        let probe: HaltCounter<i64, i64> = HaltCounter::new();
+        let counter = probe.clone();
+        let constructor = move | | counter.clone();
+        let node = ParallelBranchNode::new(Forwarder, vec![
+            NodeRunner::new(constructor.clone()),
+            NodeRunner::new(constructor.clone())
+        ]);
+        let node = assert_steps!(node; 1 => Nonterminal(2));
+        node.halt(&0);
+        assert_eq!(probe.halts(), 0);
+        let node = ParallelBranchNode::new(Forwarder, vec![
+            NodeRunner::new(constructor.clone()),
+            NodeRunner::new(constructor)
+        ]).with_halt();
+        let node = assert_steps!(node; 1 => Nonterminal(2));
+        node.halt(&0);
+        assert_eq!(probe.halts(), 2);
+    }
+
+    #[test]
+    fn halt_on_exit_test() {
+        let probe: HaltCounter<i64, i64> = HaltCounter::new();
+        let counter = probe.clone();
+        let constructor = move | | counter.clone();
+        let node = ParallelBranchNode::new(StopOnZero, vec![
+            NodeRunner::new(constructor.clone()),
+            NodeRunner::new(constructor)
+        ]).with_halt();
+        let node = assert_steps!(node; 1 => Nonterminal(2));
+        assert_eq!(probe.halts(), 0);
+        assert_steps!(node; 0 => Terminal(Result::Ok(())));
+        assert_eq!(probe.halts(), 2);
     }
 }
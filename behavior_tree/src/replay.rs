@@ -0,0 +1,290 @@
+//! Recording and replaying of node runs for deterministic debugging. 
+//!
+//! Behavior tree nodes are deterministic given their inputs, so a bug seen 
+//! in a gameplay session can be reproduced by recording the inputs a tree 
+//! was given, and feeding them back into a fresh tree. The recorder node of 
+//! this module records the inputs given to the node it wraps, along with 
+//! the statepoints the node returned, into a recording shared with the rest 
+//! of the game, which outlives the node. A replayer feeds the recorded 
+//! inputs into another node, checking that it returns the same statepoints, 
+//! which catches both the bug being reproduced and any nondeterminism that 
+//! would stop it from being reproduced. 
+
+use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+use simulation::SimulationTrace;
+use std::cell::RefCell;
+use std::fmt::{self, Debug};
+use std::rc::Rc;
+
+/// Recording of the inputs given to a node and the statepoints it returned. 
+/// Clones of a recording share the same trace. 
+#[derive(Debug)]
+pub struct Recording<I, N, T> {
+    trace: Rc<RefCell<SimulationTrace<I, Statepoint<N, T>>>>
+}
+
+impl<I, N, T> Recording<I, N, T> where
+    I: Clone,
+    N: Clone,
+    T: Clone
+{
+    /// Create a new, empty recording. 
+    pub fn new() -> Recording<I, N, T> {
+        Recording {
+            trace: Rc::new(RefCell::new(SimulationTrace::new()))
+        }
+    }
+
+    /// Return a copy of the trace recorded so far. 
+    pub fn trace(&self) -> SimulationTrace<I, Statepoint<N, T>> {
+        self.trace.borrow().clone()
+    }
+
+    /// Return the number of steps recorded so far. 
+    pub fn len(&self) -> usize {
+        self.trace.borrow().len()
+    }
+
+    /// Return whether no steps have been recorded. 
+    pub fn is_empty(&self) -> bool {
+        self.trace.borrow().is_empty()
+    }
+
+    /// Discard the steps recorded so far. 
+    pub fn clear(&self) {
+        *self.trace.borrow_mut() = SimulationTrace::new();
+    }
+
+    fn record(&self, input: &I, point: Statepoint<N, T>) {
+        let mut trace = self.trace.borrow_mut();
+        trace.inputs.push(input.clone());
+        trace.actions.push(point);
+    }
+}
+
+impl<I, N, T> Clone for Recording<I, N, T> {
+    fn clone(&self) -> Recording<I, N, T> {
+        Recording {
+            trace: self.trace.clone()
+        }
+    }
+}
+
+impl<I, N, T> Default for Recording<I, N, T> where
+    I: Clone,
+    N: Clone,
+    T: Clone
+{
+    fn default() -> Recording<I, N, T> {
+        Recording::new()
+    }
+}
+
+/// Recording wrapper for a node, which records each input given to the node 
+/// and each statepoint it returns into a recording. 
+#[derive(Clone, Debug)]
+pub struct Recorder<N> where
+    N: BehaviorTreeNode,
+    N::Input: Clone,
+    N::Nonterminal: Clone,
+    N::Terminal: Clone
+{
+    node: N,
+    recording: Recording<N::Input, N::Nonterminal, N::Terminal>
+}
+
+impl<N> Recorder<N> where
+    N: BehaviorTreeNode,
+    N::Input: Clone,
+    N::Nonterminal: Clone,
+    N::Terminal: Clone
+{
+    /// Create a new recorder, recording into the given recording. 
+    pub fn new(recording: Recording<N::Input, N::Nonterminal, N::Terminal>, node: N) ->
+        Recorder<N>
+    {
+        Recorder {
+            node: node,
+            recording: recording
+        }
+    }
+
+    /// Return the recording the node records into. 
+    pub fn recording(&self) -> &Recording<N::Input, N::Nonterminal, N::Terminal> {
+        &self.recording
+    }
+}
+
+impl<N> BehaviorTreeNode for Recorder<N> where
+    N: BehaviorTreeNode,
+    N::Input: Clone,
+    N::Nonterminal: Clone,
+    N::Terminal: Clone
+{
+    type Input = N::Input;
+    type Nonterminal = N::Nonterminal;
+    type Terminal = N::Terminal;
+
+    // The input is recorded after the node is stepped, so step_with keeps 
+    // lending the input instead of passing it on
+    #[inline]
+    fn step(self, input: &N::Input) -> NodeResult<N::Nonterminal, N::Terminal, Self> {
+        match self.node.step(input) {
+            NodeResult::Nonterminal(v, n) => {
+                self.recording.record(input, Statepoint::Nonterminal(v.clone()));
+                NodeResult::Nonterminal(v, Recorder::new(self.recording, n))
+            },
+            NodeResult::Terminal(t) => {
+                self.recording.record(input, Statepoint::Terminal(t.clone()));
+                NodeResult::Terminal(t)
+            }
+        }
+    }
+
+    #[inline]
+    fn halt(self, input: &N::Input) {
+        self.node.halt(input);
+    }
+}
+
+/// Mismatch found while replaying a trace, at the step where a node 
+/// returned a different statepoint than the one recorded. 
+#[derive(Clone, PartialEq, Debug)]
+pub struct ReplayMismatch<N, T> {
+    /// The step at which the statepoints differed, counting from zero. 
+    pub step: usize,
+    /// The statepoint recorded. 
+    pub expected: Statepoint<N, T>,
+    /// The statepoint the replayed node returned. 
+    pub actual: Statepoint<N, T>
+}
+
+impl<N, T> fmt::Display for ReplayMismatch<N, T> where
+    N: Debug,
+    T: Debug
+{
+    fn fmt(&self, fmter: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmter, "Replay diverged at step {}: expected {:?}, got {:?}",
+            self.step, self.expected, self.actual)
+    }
+}
+
+/// Replayer of a recorded trace, which feeds the recorded inputs into nodes 
+/// and checks that they return the recorded statepoints. 
+#[derive(Clone, PartialEq, Debug)]
+pub struct Replayer<I, N, T> {
+    trace: SimulationTrace<I, Statepoint<N, T>>
+}
+
+impl<I, N, T> Replayer<I, N, T> where
+    N: PartialEq + Clone,
+    T: PartialEq + Clone
+{
+    /// Create a new replayer of the given trace. 
+    pub fn new(trace: SimulationTrace<I, Statepoint<N, T>>) -> Replayer<I, N, T> {
+        Replayer {
+            trace: trace
+        }
+    }
+
+    /// Return the trace being replayed. 
+    pub fn trace(&self) -> &SimulationTrace<I, Statepoint<N, T>> {
+        &self.trace
+    }
+
+    /// Feed the recorded inputs into the node, returning the node if it is 
+    /// still running at the end of the trace, or the first mismatch found. 
+    pub fn replay<M>(&self, node: M) -> Result<Option<M>, ReplayMismatch<N, T>> where
+        M: BehaviorTreeNode<Input=I, Nonterminal=N, Terminal=T>
+    {
+        let mut current = node;
+        let steps = self.trace.inputs.iter().zip(self.trace.actions.iter());
+        for (step, (input, expected)) in steps.enumerate() {
+            let (actual, next) = match current.step(input) {
+                NodeResult::Nonterminal(v, n) => (Statepoint::Nonterminal(v),
+                    Option::Some(n)),
+                NodeResult::Terminal(t) => (Statepoint::Terminal(t), Option::None)
+            };
+            if actual != *expected {
+                return Result::Err(ReplayMismatch {
+                    step: step,
+                    expected: expected.clone(),
+                    actual: actual
+                });
+            }
+            match next {
+                Option::Some(n) => current = n,
+                Option::None => return Result::Ok(Option::None)
+            }
+        }
+        Result::Ok(Option::Some(current))
+    }
+
+    /// Feed the recorded inputs into the node, returning the node if it is 
+    /// still running at the end of the trace. 
+    /// 
+    /// # Panics 
+    /// 
+    /// Panics if the node returns a statepoint different from the one 
+    /// recorded. 
+    pub fn assert_replay<M>(&self, node: M) -> Option<M> where
+        M: BehaviorTreeNode<Input=I, Nonterminal=N, Terminal=T>,
+        N: Debug,
+        T: Debug
+    {
+        match self.replay(node) {
+            Result::Ok(node) => node,
+            Result::Err(mismatch) => panic!("{}", mismatch)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base_nodes::PredicateWait;
+    use behavior_tree_node::Statepoint;
+    use replay::{Recorder, Recording, Replayer, ReplayMismatch};
+
+    fn countdown(input: &i64) -> Statepoint<i64, i64> {
+        if *input > 0 {
+            Statepoint::Nonterminal(*input)
+        } else {
+            Statepoint::Terminal(*input)
+        }
+    }
+
+    fn countdown_to_one(input: &i64) -> Statepoint<i64, i64> {
+        if *input > 1 {
+            Statepoint::Nonterminal(*input)
+        } else {
+            Statepoint::Terminal(*input)
+        }
+    }
+
+    #[test]
+    fn record_replay_test() {
+        let recording = Recording::new();
+        let node = Recorder::new(recording.clone(), PredicateWait::new(countdown));
+        assert_steps!(node; 2 => Nonterminal(2), 1 => Nonterminal(1), 0 => Terminal(0));
+        let trace = recording.trace();
+        assert_eq!(trace.inputs, vec![2, 1, 0]);
+        assert_eq!(trace.terminals(), vec![(2, &0)]);
+
+        let replayer = Replayer::new(trace);
+        assert!(replayer.assert_replay(PredicateWait::new(countdown)).is_none());
+        assert_eq!(replayer.replay(PredicateWait::new(countdown_to_one)).err(), 
+            Option::Some(ReplayMismatch {
+                step: 1,
+                expected: Statepoint::Nonterminal(1),
+                actual: Statepoint::Terminal(1)
+            })
+        );
+
+        recording.clear();
+        let node = Recorder::new(recording.clone(), PredicateWait::new(countdown));
+        let node = assert_steps!(node; 3 => Nonterminal(3));
+        assert_eq!(node.recording().len(), 1);
+        let replayer = Replayer::new(recording.trace());
+        assert!(replayer.assert_replay(PredicateWait::new(countdown)).is_some());
+    }
+}
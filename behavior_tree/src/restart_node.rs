@@ -0,0 +1,228 @@
+//! A wrapper which rebuilds its node when it terminates. 
+//!
+//! Restarting, repeating, reconstructing and retrying a node on failure all 
+//! come down to the same thing: step the node, and when it terminates, 
+//! decide whether to build another one to step next, or to terminate the 
+//! wrapper. A restart node does the stepping, and leaves the decision to a 
+//! restart policy. The restarting wrappers in the control_wrappers and 
+//! map_wrappers modules, and the bounded node runner, are restart nodes 
+//! with particular policies. 
+
+use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+
+/// Decision of a restart policy on a terminal of the node. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Restart<N, T, X> {
+    /// Report the terminal, and step the given node next. 
+    Rebuild(T, N),
+    /// Report the terminal, and build the next node from the next input. 
+    Defer(T),
+    /// Terminate the restart node with the given value. 
+    Exit(X)
+}
+
+/// Policy of a restart node, which decides whether to build another node 
+/// after the node terminates, and what the restart node terminates with 
+/// otherwise. 
+pub trait RestartPolicy<N> where N: BehaviorTreeNode {
+    /// Terminal of the restart node. 
+    type Exit;
+
+    /// Return the node to step on the input, given the current node, or 
+    /// nothing if the policy deferred building it. The default steps the 
+    /// current node, which suits policies that never defer. 
+    fn node(&mut self, _input: &N::Input, current: Option<N>) -> N {
+        current.expect("Restart policy deferred building a node it cannot build")
+    }
+
+    /// Decide what to do with a terminal of the node. 
+    fn restart(&mut self, terminal: N::Terminal) -> Restart<N, N::Terminal, Self::Exit>;
+}
+
+/// Wrapper for a node, which steps the node, and when it terminates, either 
+/// rebuilds it or terminates itself, as its restart policy decides. The 
+/// terminals of nodes which are rebuilt are reported as nonterminals. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct RestartNode<N, P> where
+    N: BehaviorTreeNode,
+    P: RestartPolicy<N>
+{
+    node: Option<N>,
+    policy: P
+}
+
+impl<N, P> RestartNode<N, P> where
+    N: BehaviorTreeNode,
+    P: RestartPolicy<N>
+{
+    /// Create a new restart node, which steps the given node first. 
+    pub fn with_policy(policy: P, node: N) -> RestartNode<N, P> {
+        RestartNode {
+            node: Option::Some(node),
+            policy: policy
+        }
+    }
+
+    /// Create a new restart node, which has its policy build the first node 
+    /// from the first input. 
+    pub fn deferred(policy: P) -> RestartNode<N, P> {
+        RestartNode {
+            node: Option::None,
+            policy: policy
+        }
+    }
+
+    /// Return the restart policy of the node. 
+    pub fn policy(&self) -> &P {
+        &self.policy
+    }
+
+    /// Return the restart policy of the node mutably. 
+    pub fn policy_mut(&mut self) -> &mut P {
+        &mut self.policy
+    }
+
+    /// Return the node currently being stepped, if it has been built. 
+    pub fn node(&self) -> Option<&N> {
+        self.node.as_ref()
+    }
+
+    /// Return the node being stepped and the policy, consuming the wrapper. 
+    pub fn into_parts(self) -> (Option<N>, P) {
+        (self.node, self.policy)
+    }
+
+    fn advance(mut policy: P, result: NodeResult<N::Nonterminal, N::Terminal, N>) ->
+        NodeResult<Statepoint<N::Nonterminal, N::Terminal>, P::Exit, Self>
+    {
+        match result {
+            NodeResult::Nonterminal(v, n) => NodeResult::Nonterminal(
+                Statepoint::Nonterminal(v),
+                RestartNode::with_policy(policy, n)
+            ),
+            NodeResult::Terminal(t) => match policy.restart(t) {
+                Restart::Rebuild(t, next) => NodeResult::Nonterminal(
+                    Statepoint::Terminal(t),
+                    RestartNode::with_policy(policy, next)
+                ),
+                Restart::Defer(t) => NodeResult::Nonterminal(
+                    Statepoint::Terminal(t),
+                    RestartNode::deferred(policy)
+                ),
+                Restart::Exit(x) => NodeResult::Terminal(x)
+            }
+        }
+    }
+}
+
+impl<N, P> BehaviorTreeNode for RestartNode<N, P> where
+    N: BehaviorTreeNode,
+    P: RestartPolicy<N>
+{
+    type Input = N::Input;
+    type Nonterminal = Statepoint<N::Nonterminal, N::Terminal>;
+    type Terminal = P::Exit;
+
+    #[inline]
+    fn step(self, input: &N::Input) -> NodeResult<Self::Nonterminal, P::Exit, Self> {
+        let mut policy = self.policy;
+        let node = policy.node(input, self.node);
+        Self::advance(policy, node.step(input))
+    }
+
+    #[inline]
+    fn step_with(self, input: N::Input) -> NodeResult<Self::Nonterminal, P::Exit, Self> {
+        let mut policy = self.policy;
+        let node = policy.node(&input, self.node);
+        Self::advance(policy, node.step_with(input))
+    }
+
+    #[inline]
+    fn halt(self, input: &N::Input) {
+        // A node which was never built has nothing to clean up
+        if let Option::Some(n) = self.node {
+            n.halt(input);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+    use restart_node::{Restart, RestartPolicy, RestartNode};
+
+    // Counts up to the input, then terminates with the count
+    #[derive(Copy, Clone, PartialEq, Debug)]
+    struct CountTo(i64);
+
+    impl BehaviorTreeNode for CountTo {
+        type Input = i64;
+        type Nonterminal = i64;
+        type Terminal = i64;
+
+        fn step(self, input: &i64) -> NodeResult<i64, i64, CountTo> {
+            if self.0 + 1 >= *input {
+                NodeResult::Terminal(self.0 + 1)
+            } else {
+                NodeResult::Nonterminal(self.0 + 1, CountTo(self.0 + 1))
+            }
+        }
+
+        fn halt(self, _input: &i64) {}
+    }
+
+    // Rebuilds the node twice, then exits with the number of rebuilds
+    #[derive(Copy, Clone, PartialEq, Debug)]
+    struct Twice(u32);
+
+    impl RestartPolicy<CountTo> for Twice {
+        type Exit = u32;
+
+        fn restart(&mut self, terminal: i64) -> Restart<CountTo, i64, u32> {
+            if self.0 < 2 {
+                self.0 += 1;
+                Restart::Rebuild(terminal, CountTo(0))
+            } else {
+                Restart::Exit(self.0)
+            }
+        }
+    }
+
+    // Builds each node from the input, starting the count at the input
+    #[derive(Copy, Clone, PartialEq, Debug)]
+    struct FromInput;
+
+    impl RestartPolicy<CountTo> for FromInput {
+        type Exit = ();
+
+        fn node(&mut self, input: &i64, current: Option<CountTo>) -> CountTo {
+            current.unwrap_or(CountTo(*input - 2))
+        }
+
+        fn restart(&mut self, terminal: i64) -> Restart<CountTo, i64, ()> {
+            Restart::Defer(terminal)
+        }
+    }
+
+    #[test]
+    fn restart_node_rebuild_test() {
+        let node = RestartNode::with_policy(Twice(0), CountTo(0));
+        assert_steps!(node;
+            2 => Nonterminal(Statepoint::Nonterminal(1)),
+            2 => Nonterminal(Statepoint::Terminal(2)),
+            1 => Nonterminal(Statepoint::Terminal(1)),
+            1 => Terminal(2)
+        );
+    }
+
+    #[test]
+    fn restart_node_defer_test() {
+        let node = RestartNode::deferred(FromInput);
+        assert!(node.node().is_none());
+        assert_steps!(node;
+            5 => Nonterminal(Statepoint::Nonterminal(4)),
+            5 => Nonterminal(Statepoint::Terminal(5)),
+            9 => Nonterminal(Statepoint::Nonterminal(8))
+        );
+    }
+}
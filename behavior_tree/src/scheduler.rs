@@ -0,0 +1,287 @@
+//! Cooperative scheduling of the node runners of many agents. 
+//!
+//! Ticking the tree of every agent on every frame stops being affordable 
+//! once there are thousands of agents, and most of them can react a few 
+//! frames late without anyone noticing. A tick scheduler owns the node 
+//! runners of a crowd of agents, and on each frame steps only as many of 
+//! them as the frame's budget allows, picking which ones by its policy, so 
+//! that the cost of the crowd is spread out over several frames. 
+
+use behavior_tree_node::{BehaviorTreeNode, Statepoint};
+use node_runner::NodeRunner;
+use stackbt_automata_impl::automaton::Automaton;
+use std::cmp::Reverse;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+// Index of an agent stepped on a tick, along with the statepoint it reached
+type Stepped<N> = (usize, Statepoint<<N as BehaviorTreeNode>::Nonterminal,
+    <N as BehaviorTreeNode>::Terminal>);
+
+/// Policy for picking which agents a tick scheduler steps on a tick. 
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum SchedulePolicy {
+    /// Agents are stepped in turn, each of them once before any of them is 
+    /// stepped again. 
+    RoundRobin,
+    /// Each agent builds up credit by its weight on each tick, and the 
+    /// agents with the most credit are stepped, giving up their credit. 
+    /// Agents are stepped at rates roughly proportional to their weights, 
+    /// and agents with low weights are still stepped eventually, unless 
+    /// their weight is zero. 
+    Weighted
+}
+
+// Runner of an agent along with its scheduling state
+#[derive(Clone, PartialEq, Debug)]
+struct Scheduled<N, C> where
+    N: BehaviorTreeNode + 'static,
+    C: Fn() -> N
+{
+    runner: NodeRunner<N, C>,
+    weight: u32,
+    credit: u64
+}
+
+/// Scheduler owning the node runners of a number of agents, which steps a 
+/// limited number of them on each tick. Agents are identified by the index 
+/// returned when they are added, which stays the same until the agent is 
+/// removed, after which it may be given to a newly added agent. 
+#[derive(Clone, PartialEq, Debug)]
+pub struct TickScheduler<N, C> where
+    N: BehaviorTreeNode + 'static,
+    C: Fn() -> N
+{
+    agents: Vec<Option<Scheduled<N, C>>>,
+    policy: SchedulePolicy,
+    cursor: usize
+}
+
+impl<N, C> TickScheduler<N, C> where
+    N: BehaviorTreeNode + 'static,
+    C: Fn() -> N
+{
+    /// Create a new scheduler without any agents. 
+    pub fn new(policy: SchedulePolicy) -> TickScheduler<N, C> {
+        TickScheduler {
+            agents: Vec::new(),
+            policy: policy,
+            cursor: 0
+        }
+    }
+
+    /// Add the runner of an agent with a weight of one, returning the index 
+    /// of the agent. 
+    pub fn add(&mut self, runner: NodeRunner<N, C>) -> usize {
+        self.add_weighted(runner, 1)
+    }
+
+    /// Add the runner of an agent with the given weight, returning the 
+    /// index of the agent. The weight is only used by the weighted policy. 
+    pub fn add_weighted(&mut self, runner: NodeRunner<N, C>, weight: u32) -> usize {
+        let agent = Scheduled {
+            runner: runner,
+            weight: weight,
+            credit: 0
+        };
+        match self.agents.iter().position(Option::is_none) {
+            Option::Some(index) => {
+                self.agents[index] = Option::Some(agent);
+                index
+            },
+            Option::None => {
+                self.agents.push(Option::Some(agent));
+                self.agents.len() - 1
+            }
+        }
+    }
+
+    /// Remove the agent with the given index, returning its runner. 
+    pub fn remove(&mut self, index: usize) -> Option<NodeRunner<N, C>> {
+        match self.agents.get_mut(index) {
+            Option::Some(slot) => slot.take().map(|agent| agent.runner),
+            Option::None => Option::None
+        }
+    }
+
+    /// Return the runner of the agent with the given index. 
+    pub fn runner(&self, index: usize) -> Option<&NodeRunner<N, C>> {
+        self.agents.get(index)
+            .and_then(Option::as_ref)
+            .map(|agent| &agent.runner)
+    }
+
+    /// Return the runner of the agent with the given index mutably. 
+    pub fn runner_mut(&mut self, index: usize) -> Option<&mut NodeRunner<N, C>> {
+        self.agents.get_mut(index)
+            .and_then(Option::as_mut)
+            .map(|agent| &mut agent.runner)
+    }
+
+    /// Return the number of agents in the scheduler. 
+    pub fn len(&self) -> usize {
+        self.agents.iter().filter(|slot| slot.is_some()).count()
+    }
+
+    /// Return whether the scheduler has no agents. 
+    pub fn is_empty(&self) -> bool {
+        self.agents.iter().all(Option::is_none)
+    }
+
+    // Indices of the agents to step on this tick, in the order to step them
+    fn pick(&mut self, budget: usize) -> Vec<usize> {
+        let count = self.agents.len();
+        let cursor = self.cursor;
+        let mut order: Vec<usize> = (0..count)
+            .map(|offset| (cursor + offset) % count)
+            .filter(|index| self.agents[*index].is_some())
+            .collect();
+        if let SchedulePolicy::Weighted = self.policy {
+            for agent in self.agents.iter_mut().flat_map(Option::as_mut) {
+                agent.credit = agent.credit.saturating_add(u64::from(agent.weight));
+            }
+            let agents = &self.agents;
+            let credit = |index: &usize| agents[*index].as_ref().map_or(0, |a| a.credit);
+            order.sort_by_key(|index| Reverse(credit(index)));
+        }
+        order.truncate(budget);
+        if let Option::Some(&last) = order.last() {
+            self.cursor = match self.policy {
+                SchedulePolicy::RoundRobin => last + 1,
+                SchedulePolicy::Weighted => self.cursor + 1
+            } % count;
+        }
+        for index in order.iter() {
+            if let Option::Some(ref mut agent) = self.agents[*index] {
+                agent.credit = 0;
+            }
+        }
+        order
+    }
+
+    /// Step at most the given number of agents, each with the input the 
+    /// given function returns for its index, returning the indices of the 
+    /// agents stepped along with the statepoints they reached. 
+    pub fn tick<F>(&mut self, budget: usize, inputs: F) -> Vec<Stepped<N>> where
+        F: FnMut(usize) -> N::Input
+    {
+        let mut inputs = inputs;
+        let picked = self.pick(budget);
+        picked.into_iter().map(|index| {
+            let input = inputs(index);
+            let agent = self.agents[index].as_mut().expect("Picked agent was removed");
+            (index, agent.runner.transition(&input))
+        }).collect()
+    }
+
+    /// Step at most the given number of agents like tick, but across 
+    /// threads, returning the agents stepped in the order of their indices 
+    /// instead of the order they were picked in. 
+    #[cfg(feature = "rayon")]
+    pub fn par_tick<F>(&mut self, budget: usize, inputs: F) -> Vec<Stepped<N>> where
+        F: Fn(usize) -> N::Input + Sync,
+        N: Send,
+        N::Nonterminal: Send,
+        N::Terminal: Send,
+        C: Send
+    {
+        let mut chosen = vec![false; self.agents.len()];
+        for index in self.pick(budget) {
+            chosen[index] = true;
+        }
+        self.agents.par_iter_mut()
+            .enumerate()
+            .filter(|&(index, _)| chosen[index])
+            .filter_map(|(index, slot)| slot.as_mut().map(|agent| {
+                (index, agent.runner.transition(&inputs(index)))
+            }))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base_nodes::PredicateWait;
+    use behavior_tree_node::Statepoint;
+    use node_runner::NodeRunner;
+    use scheduler::{SchedulePolicy, TickScheduler};
+
+    fn echo(input: &usize) -> Statepoint<usize, usize> {
+        Statepoint::Nonterminal(*input)
+    }
+
+    type Echo = fn(&usize) -> Statepoint<usize, usize>;
+
+    fn waiter() -> PredicateWait<usize, usize, usize, Echo> {
+        PredicateWait::new(echo)
+    }
+
+    #[test]
+    fn round_robin_test() {
+        let mut scheduler = TickScheduler::new(SchedulePolicy::RoundRobin);
+        for _ in 0..5 {
+            scheduler.add(NodeRunner::new(waiter));
+        }
+        let stepped = |points: Vec<(usize, Statepoint<usize, usize>)>| {
+            points.into_iter().map(|(index, _)| index).collect::<Vec<_>>()
+        };
+        assert_eq!(stepped(scheduler.tick(2, |index| index)), vec![0, 1]);
+        assert_eq!(stepped(scheduler.tick(2, |index| index)), vec![2, 3]);
+        assert_eq!(stepped(scheduler.tick(2, |index| index)), vec![4, 0]);
+        assert!(scheduler.remove(2).is_some());
+        assert_eq!(scheduler.len(), 4);
+        assert_eq!(stepped(scheduler.tick(2, |index| index)), vec![1, 3]);
+        assert_eq!(scheduler.tick(10, |index| index * 10), vec![
+            (4, Statepoint::Nonterminal(40)),
+            (0, Statepoint::Nonterminal(0)),
+            (1, Statepoint::Nonterminal(10)),
+            (3, Statepoint::Nonterminal(30))
+        ]);
+        assert_eq!(scheduler.add(NodeRunner::new(waiter)), 2);
+    }
+
+    #[test]
+    fn weighted_test() {
+        let mut scheduler = TickScheduler::new(SchedulePolicy::Weighted);
+        let heavy = scheduler.add_weighted(NodeRunner::new(waiter), 3);
+        let light = scheduler.add_weighted(NodeRunner::new(waiter), 1);
+        let mut counts = [0, 0];
+        for _ in 0..40 {
+            for (index, _) in scheduler.tick(1, |index| index) {
+                counts[index] += 1;
+            }
+        }
+        assert_eq!(counts[heavy], 30);
+        assert_eq!(counts[light], 10);
+    }
+
+    #[test]
+    fn empty_scheduler_test() {
+        let mut scheduler = TickScheduler::new(SchedulePolicy::Weighted);
+        assert!(scheduler.is_empty());
+        assert_eq!(scheduler.tick(4, |index: usize| index), vec![]);
+        let index = scheduler.add(NodeRunner::new(waiter));
+        assert!(scheduler.runner(index).is_some());
+        assert!(scheduler.remove(index).is_some());
+        assert!(scheduler.runner(index).is_none());
+        assert_eq!(scheduler.tick(4, |index: usize| index), vec![]);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn par_tick_test() {
+        let mut scheduler = TickScheduler::new(SchedulePolicy::RoundRobin);
+        for _ in 0..100 {
+            scheduler.add(NodeRunner::new(waiter));
+        }
+        let points = scheduler.par_tick(60, |index| index * 2);
+        assert_eq!(points.len(), 60);
+        assert!(points.iter().enumerate().all(|(k, point)| {
+            *point == (k, Statepoint::Nonterminal(k * 2))
+        }));
+        let points = scheduler.par_tick(60, |index| index);
+        assert_eq!(points.len(), 60);
+        assert_eq!(points[0], (0, Statepoint::Nonterminal(0)));
+        assert_eq!(points[20], (60, Statepoint::Nonterminal(60)));
+    }
+}
@@ -0,0 +1,285 @@
+//! Machine-readable descriptions of the payload types of behavior tree nodes. 
+//!
+//! External tools, such as debug viewers and telemetry pipelines, need to 
+//! know the shape of the nonterminal and terminal values a tree produces in 
+//! order to decode them. Types describe their shape by implementing the 
+//! Describe trait, and the schema of a whole node can then be exported as 
+//! JSON with node_schema. 
+//!
+//! ```
+//! use stackbt_behavior_tree::base_nodes::PredicateWait;
+//! use stackbt_behavior_tree::behavior_tree_node::Statepoint;
+//! use stackbt_behavior_tree::schema::node_schema;
+//!
+//! let schema = node_schema::<PredicateWait<i64, bool, i64,
+//!     fn(&i64) -> Statepoint<bool, i64>>>();
+//! assert_eq!(schema.to_json(), "{\"input\":{\"type\":\"integer\",\
+//!     \"signed\":true,\"bits\":64},\"nonterminal\":{\"type\":\"bool\"},\
+//!     \"terminal\":{\"type\":\"integer\",\"signed\":true,\"bits\":64}}");
+//! ```
+
+use behavior_tree_node::{BehaviorTreeNode, Statepoint};
+#[cfg(feature = "serial")]
+use serial_node::NontermReturn;
+use std::mem;
+use tree_description::write_json_str;
+
+/// Description of the shape of a type. 
+#[derive(Clone, PartialEq, Debug)]
+pub enum Schema {
+    /// The unit type. 
+    Unit,
+    /// A boolean. 
+    Bool,
+    /// An integer, with its signedness and width in bits. 
+    Integer(bool, u8),
+    /// A floating point number, with its width in bits. 
+    Float(u8),
+    /// A single character. 
+    Char,
+    /// A string. 
+    String,
+    /// An optional value. 
+    Option(Box<Schema>),
+    /// A fixed length sequence of values of possibly different types. 
+    Tuple(Vec<Schema>),
+    /// A variable length sequence of values of the same type. 
+    Sequence(Box<Schema>),
+    /// A named structure with named fields. 
+    Struct(&'static str, Vec<(&'static str, Schema)>),
+    /// A named enumeration, with named variants each holding a value. 
+    Enum(&'static str, Vec<(&'static str, Schema)>),
+    /// An object, consisting of named entries, used to group schemas. 
+    Object(Vec<(&'static str, Schema)>)
+}
+
+fn write_json_fields(out: &mut String, fields: &[(&'static str, Schema)]) {
+    out.push('[');
+    for (index, (name, schema)) in fields.iter().enumerate() {
+        if index > 0 {
+            out.push(',');
+        }
+        out.push_str("{\"name\":");
+        write_json_str(out, name);
+        out.push_str(",\"schema\":");
+        schema.write_json(out);
+        out.push('}');
+    }
+    out.push(']');
+}
+
+impl Schema {
+    /// Render the schema as a compact JSON string. 
+    pub fn to_json(&self) -> String {
+        let mut out = String::new();
+        self.write_json(&mut out);
+        out
+    }
+
+    fn write_json(&self, out: &mut String) {
+        match self {
+            Schema::Unit => out.push_str("{\"type\":\"unit\"}"),
+            Schema::Bool => out.push_str("{\"type\":\"bool\"}"),
+            Schema::Integer(signed, bits) => out.push_str(&format!(
+                "{{\"type\":\"integer\",\"signed\":{},\"bits\":{}}}", signed, bits)),
+            Schema::Float(bits) => out.push_str(&format!(
+                "{{\"type\":\"float\",\"bits\":{}}}", bits)),
+            Schema::Char => out.push_str("{\"type\":\"char\"}"),
+            Schema::String => out.push_str("{\"type\":\"string\"}"),
+            Schema::Option(inner) => {
+                out.push_str("{\"type\":\"option\",\"some\":");
+                inner.write_json(out);
+                out.push('}');
+            },
+            Schema::Tuple(members) => {
+                out.push_str("{\"type\":\"tuple\",\"members\":[");
+                for (index, member) in members.iter().enumerate() {
+                    if index > 0 {
+                        out.push(',');
+                    }
+                    member.write_json(out);
+                }
+                out.push_str("]}");
+            },
+            Schema::Sequence(inner) => {
+                out.push_str("{\"type\":\"sequence\",\"items\":");
+                inner.write_json(out);
+                out.push('}');
+            },
+            Schema::Struct(name, fields) => {
+                out.push_str("{\"type\":\"struct\",\"name\":");
+                write_json_str(out, name);
+                out.push_str(",\"fields\":");
+                write_json_fields(out, fields);
+                out.push('}');
+            },
+            Schema::Enum(name, variants) => {
+                out.push_str("{\"type\":\"enum\",\"name\":");
+                write_json_str(out, name);
+                out.push_str(",\"variants\":");
+                write_json_fields(out, variants);
+                out.push('}');
+            },
+            Schema::Object(entries) => {
+                out.push('{');
+                for (index, (name, schema)) in entries.iter().enumerate() {
+                    if index > 0 {
+                        out.push(',');
+                    }
+                    write_json_str(out, name);
+                    out.push(':');
+                    schema.write_json(out);
+                }
+                out.push('}');
+            }
+        }
+    }
+}
+
+/// Trait for types which can describe their own shape. 
+pub trait Describe {
+    /// Return the schema of this type. 
+    fn schema() -> Schema;
+}
+
+/// Return the schema of the input, nonterminal and terminal types of a 
+/// behavior tree node, as an object with those three entries. 
+pub fn node_schema<N>() -> Schema where
+    N: BehaviorTreeNode,
+    N::Input: Describe,
+    N::Nonterminal: Describe,
+    N::Terminal: Describe
+{
+    Schema::Object(vec![
+        ("input", N::Input::schema()),
+        ("nonterminal", N::Nonterminal::schema()),
+        ("terminal", N::Terminal::schema())
+    ])
+}
+
+macro_rules! describe_impl {
+    ($($t:ty => $s:expr),*) => {
+        $(
+            impl Describe for $t {
+                fn schema() -> Schema {
+                    $s
+                }
+            }
+        )*
+    }
+}
+
+describe_impl!(
+    () => Schema::Unit,
+    bool => Schema::Bool,
+    i8 => Schema::Integer(true, 8),
+    i16 => Schema::Integer(true, 16),
+    i32 => Schema::Integer(true, 32),
+    i64 => Schema::Integer(true, 64),
+    isize => Schema::Integer(true, (mem::size_of::<isize>() * 8) as u8),
+    u8 => Schema::Integer(false, 8),
+    u16 => Schema::Integer(false, 16),
+    u32 => Schema::Integer(false, 32),
+    u64 => Schema::Integer(false, 64),
+    usize => Schema::Integer(false, (mem::size_of::<usize>() * 8) as u8),
+    f32 => Schema::Float(32),
+    f64 => Schema::Float(64),
+    char => Schema::Char,
+    String => Schema::String
+);
+
+macro_rules! describe_tuple_impl {
+    ($($t:ident),*) => {
+        impl<$($t),*> Describe for ($($t,)*) where $($t: Describe),* {
+            fn schema() -> Schema {
+                Schema::Tuple(vec![$($t::schema()),*])
+            }
+        }
+    }
+}
+
+describe_tuple_impl!(A);
+describe_tuple_impl!(A, B);
+describe_tuple_impl!(A, B, C);
+describe_tuple_impl!(A, B, C, D);
+describe_tuple_impl!(A, B, C, D, E);
+describe_tuple_impl!(A, B, C, D, E, F);
+
+impl<T> Describe for Option<T> where T: Describe {
+    fn schema() -> Schema {
+        Schema::Option(Box::new(T::schema()))
+    }
+}
+
+impl<T> Describe for Vec<T> where T: Describe {
+    fn schema() -> Schema {
+        Schema::Sequence(Box::new(T::schema()))
+    }
+}
+
+impl<T> Describe for Box<[T]> where T: Describe {
+    fn schema() -> Schema {
+        Schema::Sequence(Box::new(T::schema()))
+    }
+}
+
+impl<N, T> Describe for Statepoint<N, T> where
+    N: Describe,
+    T: Describe
+{
+    fn schema() -> Schema {
+        Schema::Enum("Statepoint", vec![
+            ("Nonterminal", N::schema()),
+            ("Terminal", T::schema())
+        ])
+    }
+}
+
+#[cfg(feature = "serial")]
+impl<E, N, T> Describe for NontermReturn<E, N, T> where
+    E: Describe,
+    N: Describe,
+    T: Describe
+{
+    fn schema() -> Schema {
+        Schema::Enum("NontermReturn", vec![
+            ("Nonterminal", Schema::Tuple(vec![E::schema(), N::schema()])),
+            ("Terminal", Schema::Tuple(vec![E::schema(), T::schema()]))
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use schema::{Describe, Schema};
+    use behavior_tree_node::Statepoint;
+
+    struct Position {
+        _x: f32,
+        _y: f32
+    }
+
+    impl Describe for Position {
+        fn schema() -> Schema {
+            Schema::Struct("Position", vec![
+                ("x", f32::schema()),
+                ("y", f32::schema())
+            ])
+        }
+    }
+
+    #[test]
+    fn schema_json_test() {
+        let schema = <Statepoint<Option<Position>, (u8, String)>>::schema();
+        assert_eq!(schema.to_json(), concat!(
+            "{\"type\":\"enum\",\"name\":\"Statepoint\",\"variants\":[",
+            "{\"name\":\"Nonterminal\",\"schema\":{\"type\":\"option\",\"some\":",
+            "{\"type\":\"struct\",\"name\":\"Position\",\"fields\":[",
+            "{\"name\":\"x\",\"schema\":{\"type\":\"float\",\"bits\":32}},",
+            "{\"name\":\"y\",\"schema\":{\"type\":\"float\",\"bits\":32}}]}}},",
+            "{\"name\":\"Terminal\",\"schema\":{\"type\":\"tuple\",\"members\":[",
+            "{\"type\":\"integer\",\"signed\":false,\"bits\":8},",
+            "{\"type\":\"string\"}]}}]}"
+        ));
+    }
+}
@@ -1,5 +1,8 @@
 use behavior_tree_node::{BehaviorTreeNode, NodeResult};
-use num_traits::FromPrimitive;
+use stackbt_automata_impl::snapshot::Snapshot;
+use std::convert::TryFrom;
+use std::fmt;
+use std::marker::PhantomData;
 
 
 /// Trait for an enumeration of nodes, all of which have the same input, 
@@ -18,6 +21,179 @@ pub trait EnumNode: BehaviorTreeNode {
     fn discriminant_of(&self) -> Self::Discriminant;
 }
 
+#[cfg(feature = "derive")]
+pub use stackbt_derive::Ordinal;
+
+/// Trait for discriminants which can be stepped through in order, as the 
+/// built-in serial deciders do. Unlike converting to and from integers, this 
+/// makes no assumption that the discriminants are numbered contiguously. 
+/// 
+/// For fieldless enums, the trait can be derived with the derive feature 
+/// enabled, stepping through the variants in the order they are declared. 
+/// It is also implemented for the unsigned integer types. For those with 
+/// more values than usize can count, COUNT saturates at usize::MAX, and 
+/// index panics for values past it. 
+pub trait Ordinal: Copy {
+    /// The number of discriminants. 
+    const COUNT: usize;
+
+    /// Return the first discriminant. 
+    fn first() -> Self;
+
+    /// Return the discriminant after this one, or None if this is the last. 
+    fn next(self) -> Option<Self>;
+
+    /// Return the discriminant after this one, wrapping around to the first 
+    /// after the last one. 
+    fn wrapping_next(self) -> Self {
+        match self.next() {
+            Option::Some(e) => e,
+            Option::None => Self::first()
+        }
+    }
+
+    /// Return the last discriminant. 
+    fn last() -> Self {
+        let mut last = Self::first();
+        while let Option::Some(e) = last.next() {
+            last = e;
+        }
+        last
+    }
+
+    /// Return the position of this discriminant in the order, counting from 
+    /// zero at the first one. 
+    /// 
+    /// # Panics 
+    /// 
+    /// Panics if the discriminant is not reached by stepping from the first 
+    /// one. 
+    fn index(self) -> usize where Self: PartialEq {
+        let mut current = Self::first();
+        let mut index = 0;
+        while current != self {
+            current = current.next().expect("Discriminant is out of order");
+            index += 1;
+        }
+        index
+    }
+
+    /// Return whether this is the first discriminant. 
+    fn is_first(self) -> bool where Self: PartialEq {
+        self == Self::first()
+    }
+
+    /// Return whether this is the last discriminant. 
+    fn is_last(self) -> bool {
+        self.next().is_none()
+    }
+}
+
+macro_rules! unsigned_ordinal {
+    ( $( $t:ty ),* ) => {
+        $(
+            impl Ordinal for $t {
+                const COUNT: usize = if <$t>::BITS < usize::BITS {
+                    <$t>::MAX as usize + 1
+                } else {
+                    usize::MAX
+                };
+
+                fn first() -> $t {
+                    0
+                }
+
+                fn next(self) -> Option<$t> {
+                    self.checked_add(1)
+                }
+
+                fn last() -> $t {
+                    <$t>::MAX
+                }
+
+                fn index(self) -> usize {
+                    usize::try_from(self).expect("Discriminant does not fit in usize")
+                }
+            }
+        )*
+    }
+}
+
+unsigned_ordinal!(u8, u16, u32, u64, u128, usize);
+
+/// Declarative macro for implementing the Ordinal trait for a fieldless 
+/// enum, with the variants in the order given. Given an enum declaration 
+/// instead of just the names of the enum and its variants, the macro 
+/// declares the enum as well, much as a derive would. Where proc macros 
+/// are available, deriving Ordinal with the derive feature enabled saves 
+/// listing the variants again. 
+/// 
+/// # Example
+/// ```
+/// #[macro_use]
+/// extern crate stackbt_behavior_tree;
+/// use stackbt_behavior_tree::serial_node::Ordinal;
+/// 
+/// impl_ordinal! {
+///     #[derive(Copy, Clone, PartialEq, Debug)]
+///     enum Phase { Approach, Strike, Retreat }
+/// }
+/// 
+/// # fn main() {
+/// assert_eq!(Phase::COUNT, 3);
+/// assert_eq!(Phase::first(), Phase::Approach);
+/// assert_eq!(Phase::Strike.next(), Option::Some(Phase::Retreat));
+/// assert_eq!(Phase::Retreat.next(), Option::None);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! impl_ordinal {
+    (@one $variant:ident) => {
+        1
+    };
+
+    (@munch $name:ident ; $count:tt ; $first:ident ; $last:ident ; $( $arms:tt )* ) => {
+        impl $crate::serial_node::Ordinal for $name {
+            const COUNT: usize = $count;
+
+            fn first() -> $name {
+                $name :: $first
+            }
+
+            fn next(self) -> Option<$name> {
+                match self {
+                    $( $arms )*
+                    $name :: $last => Option::None
+                }
+            }
+        }
+    };
+
+    (@munch $name:ident ; $count:tt ; $first:ident ; $variant:ident , $nextvariant:ident 
+        $( , $othervariants:ident )* ; $( $arms:tt )* ) => 
+    {
+        impl_ordinal!(@munch $name ; $count ; $first ; $nextvariant $( , $othervariants )* ; 
+            $( $arms )* $name :: $variant => Option::Some($name :: $nextvariant), );
+    };
+
+    ( $name:ident { $first:ident $( , $othervariants:ident )* } ) => {
+        impl_ordinal!(@munch $name ; ( 1 $( + impl_ordinal!(@one $othervariants) )* ) ; 
+            $first ; $first $( , $othervariants )* ; );
+    };
+
+    (
+        $( #[ $mval:meta ] )*
+        $visibility:vis enum $name:ident { $first:ident $( , $othervariants:ident )* }
+    ) => {
+        $( #[ $mval ] )*
+        $visibility enum $name {
+            $first $( , $othervariants )*
+        }
+
+        impl_ordinal!( $name { $first $( , $othervariants )* } );
+    };
+}
+
 /// Declarative macro for quickly and easily declaring an serial node enum.
 #[cfg(feature = "existential_type")]
 #[macro_export]
@@ -48,11 +224,12 @@ macro_rules! enum_node {
         }
 
         #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
-        #[derive(::num_derive::ToPrimitive, ::num_derive::FromPrimitive)]
         enum $itername {
             $( $variant ),*
         }
 
+        impl_ordinal!( $itername { $( $variant ),* } );
+
         impl BehaviorTreeNode for $name {
             type Input = $inputtype;
             type Nonterminal = $nontermtype;
@@ -73,6 +250,14 @@ macro_rules! enum_node {
                     ),*
                 }
             }
+
+            fn halt(self, input: & $inputtype) where Self: Sized {
+                match self {
+                    $(
+                        $name :: $variant (val) => val.halt(input)
+                    ),*
+                }
+            }
         }
 
         impl EnumNode for $name {
@@ -128,7 +313,14 @@ pub enum NontermReturn<E, N, T> {
     Terminal(E, T)
 }
 
-/// Trait for the transition behavior of a SerialBranchNode. 
+/// Trait for the transition behavior of a SerialBranchNode. Deciders whose 
+/// enumeration implements Ordinal can find the number of children through 
+/// Ordinal::COUNT, and the position of the current child among them through 
+/// Ordinal::index and Ordinal::is_last. 
+/// 
+/// The decider is owned by the node and borrowed mutably each step, so it 
+/// can keep state of its own, such as retry counts, across the steps of 
+/// one run of the node. 
 pub trait SerialDecider {
     /// Type of the enumerating discriminant
     type Enum;
@@ -142,12 +334,20 @@ pub trait SerialDecider {
     type Exit;
     /// Given a reference to the input and the current nonterminal state, 
     /// decide what to do from the nonterminal statepoint. 
-    fn on_nonterminal(&self, &Self::Input, Self::Enum, Self::Nonterm) -> NontermDecision<
+    fn on_nonterminal(&mut self, &Self::Input, Self::Enum, Self::Nonterm) -> NontermDecision<
         Self::Enum, Self::Nonterm, Self::Exit>;
     /// Given a reference to the input and the current terminal state, decide 
     /// what to do from the terminal statepoint. 
-    fn on_terminal(&self, &Self::Input, Self::Enum, Self::Term) -> TermDecision<
+    fn on_terminal(&mut self, &Self::Input, Self::Enum, Self::Term) -> TermDecision<
         Self::Enum, Self::Term, Self::Exit>;
+    /// Given a reference to the first input of a run and the child the node 
+    /// was built with, return the child to enter instead, or None to enter 
+    /// the child the node was built with. The node calls this once, before 
+    /// its first step, so deciders which pick their first child from the 
+    /// input or from shared records can do so. 
+    fn initial(&mut self, _input: &Self::Input, _built: Self::Enum) -> Option<Self::Enum> {
+        Option::None
+    }
 }
 
 /// A serial branch node, which is composed of a SerialDecider on top of a 
@@ -166,7 +366,8 @@ pub struct SerialBranchNode<E, D> where
         Term=E::Terminal>
 {
     node: E,
-    decider: D
+    decider: D,
+    entered: bool
 }
 
 impl<E, D> SerialBranchNode<E, D> where 
@@ -174,32 +375,71 @@ impl<E, D> SerialBranchNode<E, D> where
     D: SerialDecider<Enum=E::Discriminant, Input=E::Input, Nonterm=E::Nonterminal, 
         Term=E::Terminal>
 {
-    /// Create a new serial branch node for the given discriminant. 
+    /// Create a new serial branch node for the given discriminant. The 
+    /// decider may pick another child to enter on the first step. 
     pub fn new(decider: D, variant: E::Discriminant) -> SerialBranchNode<E, D> {
         SerialBranchNode {
             node: E::new(variant),
-            decider: decider
+            decider: decider,
+            entered: false
         }
 
     }
 
-    /// Wrap an existing enumerated node in a serial branch node. 
+    /// Wrap an existing enumerated node in a serial branch node. The node is 
+    /// taken to be already running, so the decider is not asked for the 
+    /// child to enter. 
     pub fn from_existing(decider: D, existing: E) -> SerialBranchNode<E, D> {
         SerialBranchNode {
             node: existing,
-            decider: decider
+            decider: decider,
+            entered: true
         }
     }
+
+    // Switch to a fresh child partway through a run
+    fn switch(decider: D, variant: E::Discriminant) -> SerialBranchNode<E, D> {
+        SerialBranchNode::from_existing(decider, E::new(variant))
+    }
+
+    // Let the decider pick the child to enter, if the run has not started
+    fn enter(self, input: &E::Input) -> SerialBranchNode<E, D> {
+        if self.entered {
+            return self;
+        }
+        let mut mut_self = self;
+        let built = mut_self.node.discriminant_of();
+        match mut_self.decider.initial(input, built) {
+            Option::Some(e) => Self::switch(mut_self.decider, e),
+            Option::None => Self::from_existing(mut_self.decider, mut_self.node)
+        }
+    }
+
+    /// Return the discriminant of the child currently being run. 
+    pub fn discriminant(&self) -> E::Discriminant {
+        self.node.discriminant_of()
+    }
+
+    /// Halt the child currently being run with the given input, and switch 
+    /// to a fresh child for the given discriminant, keeping the decider. 
+    /// This lets events from outside the tree, such as a cutscene starting, 
+    /// redirect the node without the decider having to know about them. 
+    pub fn transition_to(self, input: &E::Input, variant: E::Discriminant) -> 
+        SerialBranchNode<E, D> 
+    {
+        self.node.halt(input);
+        SerialBranchNode::switch(self.decider, variant)
+    }
 }
 
 impl<E, D> Default for SerialBranchNode<E, D> where 
     E: EnumNode,
-    E::Discriminant: FromPrimitive, 
+    E::Discriminant: Ordinal, 
     D: SerialDecider<Enum=E::Discriminant, Input=E::Input, Nonterm=E::Nonterminal, 
         Term=E::Terminal> + Default
 {
     fn default() -> SerialBranchNode<E, D> {
-        SerialBranchNode::new(D::default(), E::Discriminant::from_u64(0).unwrap())
+        SerialBranchNode::new(D::default(), E::Discriminant::first())
     }
 }
 
@@ -214,40 +454,302 @@ impl<E, D> BehaviorTreeNode for SerialBranchNode<E, D> where
 
     #[inline]
     fn step(self, input: &E::Input) -> NodeResult<Self::Nonterminal, D::Exit, Self> {
-        let discriminant = self.node.discriminant_of();
-        match self.node.step(input) {
+        let mut mut_self = self.enter(input);
+        let discriminant = mut_self.node.discriminant_of();
+        match mut_self.node.step(input) {
             NodeResult::Nonterminal(i, n) => {
-                match self.decider.on_nonterminal(input, discriminant, i) {
+                match mut_self.decider.on_nonterminal(input, discriminant, i) {
                     NontermDecision::Step(j) => NodeResult::Nonterminal(
                         NontermReturn::Nonterminal(discriminant, j),
-                        Self::from_existing(self.decider, n)
+                        Self::from_existing(mut_self.decider, n)
                     ),
-                    NontermDecision::Trans(e, j) => NodeResult::Nonterminal(
-                        NontermReturn::Nonterminal(discriminant, j),
-                        Self::new(self.decider, e)
-                    ),
-                    NontermDecision::Exit(x) => NodeResult::Terminal(x)
+                    NontermDecision::Trans(e, j) => {
+                        n.halt(input);
+                        NodeResult::Nonterminal(
+                            NontermReturn::Nonterminal(discriminant, j),
+                            Self::switch(mut_self.decider, e)
+                        )
+                    },
+                    NontermDecision::Exit(x) => {
+                        n.halt(input);
+                        NodeResult::Terminal(x)
+                    }
                 }
             },
             NodeResult::Terminal(i) => {
-                match self.decider.on_terminal(input, discriminant, i) {
+                match mut_self.decider.on_terminal(input, discriminant, i) {
                     TermDecision::Trans(e, j) => NodeResult::Nonterminal(
                         NontermReturn::Terminal(discriminant, j),
-                        Self::new(self.decider, e)
+                        Self::switch(mut_self.decider, e)
                     ),
                     TermDecision::Exit(x) => NodeResult::Terminal(x)
                 }
             }
         }
     }
+
+    #[inline]
+    fn halt(self, input: &E::Input) {
+        self.node.halt(input);
+    }
+}
+
+// The snapshot keeps whether the node was entered, so that a node restored 
+// before its first step still lets the decider pick the child to enter
+impl<E, D> Snapshot for SerialBranchNode<E, D> where
+    E: EnumNode + Snapshot,
+    D: SerialDecider<Enum=E::Discriminant, Input=E::Input, Nonterm=E::Nonterminal, 
+        Term=E::Terminal> + Clone
+{
+    type State = (D, bool, E::State);
+
+    fn snapshot(&self) -> (D, bool, E::State) {
+        (self.decider.clone(), self.entered, self.node.snapshot())
+    }
+
+    fn restore(state: (D, bool, E::State)) -> SerialBranchNode<E, D> {
+        SerialBranchNode {
+            node: E::restore(state.2),
+            decider: state.0,
+            entered: state.1
+        }
+    }
+}
+
+/// Marker for a part of a serial builder which has not been given yet. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Unset;
+
+/// Marker for a serial builder given an explicit start child. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct StartAt<E>(E);
+
+/// Trait for the start child markers of a serial builder. 
+pub trait StartChild<E> {
+    /// Return the explicitly given start child, if there is one. 
+    fn start_child(self) -> Option<E>;
+}
+
+impl<E> StartChild<E> for Unset {
+    fn start_child(self) -> Option<E> {
+        Option::None
+    }
+}
+
+impl<E> StartChild<E> for StartAt<E> {
+    fn start_child(self) -> Option<E> {
+        Option::Some(self.0)
+    }
+}
+
+/// Type-level count of the children given to a serial builder, for a 
+/// builder given a single child. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct OneChild;
+
+/// Type-level count of the children given to a serial builder, for a 
+/// builder given one more child than the inner count. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct MoreChildren<C>(PhantomData<C>);
+
+/// Trait for the type-level child counts of a serial builder. 
+pub trait ChildCount {
+    /// The number of children counted. 
+    const COUNT: usize;
+}
+
+impl ChildCount for OneChild {
+    const COUNT: usize = 1;
+}
+
+impl<C> ChildCount for MoreChildren<C> where C: ChildCount {
+    const COUNT: usize = C::COUNT + 1;
+}
+
+// Evaluated when a serial builder is built, so that a builder given too few 
+// or too many children fails to compile
+struct CoverageCheck<E, C>(PhantomData<(E, C)>);
+
+impl<E, C> CoverageCheck<E, C> where 
+    E: Ordinal,
+    C: ChildCount
+{
+    const COUNT: usize = {
+        assert!(C::COUNT == E::COUNT, 
+            "The children of a serial builder must cover the whole enumeration");
+        C::COUNT
+    };
+}
+
+/// Inconsistency found while building a serial branch node. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum SerialBuildError<E> {
+    /// The child given at the index is not the successor of the child before 
+    /// it, or for the first child, not the first discriminant. 
+    OutOfOrder(usize, E)
+}
+
+impl<E> fmt::Display for SerialBuildError<E> where E: fmt::Debug {
+    fn fmt(&self, fmter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SerialBuildError::OutOfOrder(index, child) => write!(fmter, 
+                "Child {:?} given at index {} is out of order", child, index)
+        }
+    }
+}
+
+/// Builder which assembles a serial branch node child by child. 
+/// 
+/// The decider, the start child and the number of children are tracked in 
+/// the type of the builder, so that a decider whose enumeration, input, 
+/// nonterminal or terminal types do not match those of the enumerated node 
+/// is rejected where it is given, a start child can only be chosen once, 
+/// the node cannot be built without a decider, and a builder given a 
+/// different number of children than there are discriminants fails to 
+/// compile where it is built. The children themselves are values, so their 
+/// ordering is checked when the node is built instead of when it first 
+/// transitions between them. 
+/// 
+/// # Example
+/// ```compile_fail
+/// # #[macro_use]
+/// # extern crate stackbt_behavior_tree;
+/// # use stackbt_behavior_tree::behavior_tree_node::{BehaviorTreeNode, NodeResult};
+/// # use stackbt_behavior_tree::serial_node::{EnumNode, SerialBuilder, SerialDecider, 
+/// #     NontermDecision, TermDecision};
+/// # #[derive(Copy, Clone, PartialEq, Eq, Debug)]
+/// # enum Phase { First, Second, Third }
+/// # impl_ordinal!(Phase { First, Second, Third });
+/// # struct Phases(Phase);
+/// # impl BehaviorTreeNode for Phases {
+/// #     type Input = ();
+/// #     type Nonterminal = ();
+/// #     type Terminal = ();
+/// #     fn step(self, _input: &()) -> NodeResult<(), (), Phases> {
+/// #         NodeResult::Terminal(())
+/// #     }
+/// # }
+/// # impl EnumNode for Phases {
+/// #     type Discriminant = Phase;
+/// #     fn new(phase: Phase) -> Phases { Phases(phase) }
+/// #     fn discriminant_of(&self) -> Phase { self.0 }
+/// # }
+/// # struct Runner;
+/// # impl SerialDecider for Runner {
+/// #     type Enum = Phase;
+/// #     type Input = ();
+/// #     type Nonterm = ();
+/// #     type Term = ();
+/// #     type Exit = ();
+/// #     fn on_nonterminal(&mut self, _i: &(), _e: Phase, _n: ()) -> 
+/// #         NontermDecision<Phase, (), ()> { NontermDecision::Step(()) }
+/// #     fn on_terminal(&mut self, _i: &(), _e: Phase, _t: ()) -> 
+/// #         TermDecision<Phase, (), ()> { TermDecision::Exit(()) }
+/// # }
+/// # fn main() {
+/// // Phase::Third was left out
+/// let node = SerialBuilder::<Phases, _, _, _>::new(Phase::First)
+///     .child(Phase::Second)
+///     .decider(Runner)
+///     .build();
+/// # }
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct SerialBuilder<E, D, S, C> where E: EnumNode {
+    children: Vec<E::Discriminant>,
+    decider: D,
+    start: S,
+    _count: PhantomData<(E, C)>
+}
+
+impl<E> SerialBuilder<E, Unset, Unset, OneChild> where E: EnumNode {
+    /// Create a new serial builder, with the given first child. 
+    pub fn new(first: E::Discriminant) -> SerialBuilder<E, Unset, Unset, OneChild> {
+        SerialBuilder {
+            children: vec![first],
+            decider: Unset,
+            start: Unset,
+            _count: PhantomData
+        }
+    }
+}
+
+impl<E, D, S, C> SerialBuilder<E, D, S, C> where E: EnumNode {
+    /// Add the next child, which should be the successor of the last one. 
+    pub fn child(self, child: E::Discriminant) -> SerialBuilder<E, D, S, MoreChildren<C>> {
+        let mut children = self.children;
+        children.push(child);
+        SerialBuilder {
+            children: children,
+            decider: self.decider,
+            start: self.start,
+            _count: PhantomData
+        }
+    }
+}
+
+impl<E, S, C> SerialBuilder<E, Unset, S, C> where E: EnumNode {
+    /// Give the decider of the serial branch node. 
+    pub fn decider<D>(self, decider: D) -> SerialBuilder<E, D, S, C> where 
+        D: SerialDecider<Enum=E::Discriminant, Input=E::Input, Nonterm=E::Nonterminal, 
+            Term=E::Terminal>
+    {
+        SerialBuilder {
+            children: self.children,
+            decider: decider,
+            start: self.start,
+            _count: PhantomData
+        }
+    }
+}
+
+impl<E, D, C> SerialBuilder<E, D, Unset, C> where E: EnumNode {
+    /// Start at the given child instead of the first one. 
+    pub fn start(self, child: E::Discriminant) -> SerialBuilder<E, D, 
+        StartAt<E::Discriminant>, C> 
+    {
+        SerialBuilder {
+            children: self.children,
+            decider: self.decider,
+            start: StartAt(child),
+            _count: PhantomData
+        }
+    }
+}
+
+impl<E, D, S, C> SerialBuilder<E, D, S, C> where 
+    E: EnumNode,
+    E::Discriminant: Ordinal + PartialEq,
+    D: SerialDecider<Enum=E::Discriminant, Input=E::Input, Nonterm=E::Nonterminal, 
+        Term=E::Terminal>,
+    S: StartChild<E::Discriminant>,
+    C: ChildCount
+{
+    /// Check that the children were given in order of succession, then 
+    /// build the serial branch node. That they cover the whole enumeration 
+    /// is checked at compile time. 
+    pub fn build(self) -> Result<SerialBranchNode<E, D>, SerialBuildError<
+        E::Discriminant>> 
+    {
+        debug_assert_eq!(self.children.len(), CoverageCheck::<E::Discriminant, C>::COUNT);
+        let mut expected = Option::Some(E::Discriminant::first());
+        for (index, child) in self.children.iter().enumerate() {
+            if expected != Option::Some(*child) {
+                return Result::Err(SerialBuildError::OutOfOrder(index, *child));
+            }
+            expected = child.next();
+        }
+        // The children cover the whole enumeration, so any start child is one
+        let start = self.start.start_child().unwrap_or(self.children[0]);
+        Result::Ok(SerialBranchNode::new(self.decider, start))
+    }
 }
 
 #[cfg(all(test, feature = "existential_type"))]
 mod tests {
     use base_nodes::{PredicateWait};
-    use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+    use behavior_tree_node::Statepoint;
     use serial_node::{EnumNode, SerialDecider, NontermDecision, TermDecision};
-    use num_derive::{FromPrimitive, ToPrimitive};
 
     enum_node! {
         type Input = i64;
@@ -281,13 +783,13 @@ mod tests {
         type Term = i64;
         type Exit = ();
         
-        fn on_nonterminal(&self, _i: &i64, _s: PosNegEnum, o: i64) -> NontermDecision<
+        fn on_nonterminal(&mut self, _i: &i64, _s: PosNegEnum, o: i64) -> NontermDecision<
             PosNegEnum, i64, ()> 
         {
             NontermDecision::Step(o)
         }
 
-        fn on_terminal(&self, _i: &i64, state: PosNegEnum, o: i64) -> TermDecision<
+        fn on_terminal(&mut self, _i: &i64, state: PosNegEnum, o: i64) -> TermDecision<
             PosNegEnum, i64, ()> 
         {
             match state {
@@ -302,91 +804,325 @@ mod tests {
         use serial_node::{SerialBranchNode, NontermReturn};
         let test_node = SerialBranchNode::<
             MultiMachine, _>::new(Switcharound, PosNegEnum::Positive);
-        let test_node_1 = match test_node.step(&5) {
-            NodeResult::Nonterminal(r, n) => {
-                match r {
-                    NontermReturn::Nonterminal(s, v) => {
-                        let _: i64 = v;
-                        match s {
-                            PosNegEnum::Positive => (),
-                            _ => unreachable!("Expected positive")
-                        }
-                        assert_eq!(v, 5_i64);
-                    },
-                    _ => unreachable!("Expected subordinate nonterminal transition")
-                };
-                n
-            },
-            _ => unreachable!("Expected nonterminal transition")
-        };
-        let test_node_2 = match test_node_1.step(&-5) {
-            NodeResult::Nonterminal(r, n) => {
-                match r {
-                    NontermReturn::Terminal(s, v) => {
-                        let _: i64 = v;
-                        match s {
-                            PosNegEnum::Positive => (),
-                            _ => unreachable!("Expected positive")
-                        }
-                        assert_eq!(v, -5)
-                    },
-                    _ => unreachable!("Expected subordinate nonterminal transition"),
-                };
-                n
-            },
+        assert_steps!(test_node;
+            5 => Nonterminal(NontermReturn::Nonterminal(PosNegEnum::Positive, 5)),
+            -5 => Nonterminal(NontermReturn::Terminal(PosNegEnum::Positive, -5)),
+            5 => Nonterminal(NontermReturn::Nonterminal(PosNegEnum::Negative, -5)),
+            -5 => Nonterminal(NontermReturn::Terminal(PosNegEnum::Negative, 5)),
+            5 => Nonterminal(NontermReturn::Nonterminal(PosNegEnum::Positive, 5))
+        );
+    }
+
+}
+
+#[cfg(all(test, feature = "compositions"))]
+mod tests_builder {
+    use base_nodes::PredicateWait;
+    use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+    use serial_node::{EnumNode, SerialBuilder, SerialBuildError, SerialBranchNode, 
+        SerialDecider, NontermDecision, TermDecision, NontermReturn};
+    use node_compositions::SerialRunner;
+    use stackbt_automata_impl::snapshot::Snapshot;
+
+    fn count_up(input: &i64) -> Statepoint<i64, i64> {
+        if *input >= 0 {
+            Statepoint::Nonterminal(*input)
+        } else {
+            Statepoint::Terminal(*input)
+        }
+    }
+
+    type Waiter = PredicateWait<i64, i64, i64, fn(&i64) -> Statepoint<i64, i64>>;
+
+    #[derive(Copy, Clone, PartialEq, Eq, Debug)]
+    enum Phase {
+        First,
+        Second,
+        Third
+    }
+
+    impl_ordinal!(Phase { First, Second, Third });
+
+    struct Phases(Phase, Waiter);
+
+    impl BehaviorTreeNode for Phases {
+        type Input = i64;
+        type Nonterminal = i64;
+        type Terminal = i64;
+
+        fn step(self, input: &i64) -> NodeResult<i64, i64, Phases> {
+            let phase = self.0;
+            match self.1.step(input) {
+                NodeResult::Nonterminal(v, n) => NodeResult::Nonterminal(v, Phases(phase, n)),
+                NodeResult::Terminal(t) => NodeResult::Terminal(t)
+            }
+        }
+    }
+
+    impl EnumNode for Phases {
+        type Discriminant = Phase;
+
+        fn new(phase: Phase) -> Phases {
+            Phases(phase, PredicateWait::new(count_up))
+        }
+
+        fn discriminant_of(&self) -> Phase {
+            self.0
+        }
+    }
+
+    #[test]
+    fn serial_builder_test() {
+        let node = SerialBuilder::<Phases, _, _, _>::new(Phase::First)
+            .child(Phase::Second)
+            .child(Phase::Third)
+            .decider(SerialRunner::new())
+            .start(Phase::Second)
+            .build()
+            .unwrap();
+        assert_steps!(node;
+            1 => Nonterminal(NontermReturn::Nonterminal(Phase::Second, 1)),
+            -1 => Nonterminal(NontermReturn::Terminal(Phase::Second, -1)),
+            -2 => Terminal(())
+        );
+        let node = SerialBuilder::<Phases, _, _, _>::new(Phase::First)
+            .decider(SerialRunner::new())
+            .child(Phase::Second)
+            .child(Phase::Third)
+            .build()
+            .unwrap();
+        assert_steps!(node;
+            2 => Nonterminal(NontermReturn::Nonterminal(Phase::First, 2))
+        );
+    }
+
+    #[test]
+    fn serial_builder_error_test() {
+        let result = SerialBuilder::<Phases, _, _, _>::new(Phase::First)
+            .child(Phase::Third)
+            .child(Phase::Second)
+            .decider(SerialRunner::new())
+            .build();
+        assert_eq!(result.err(), Option::Some(SerialBuildError::OutOfOrder(1, Phase::Third)));
+    }
+
+    #[test]
+    fn transition_to_test() {
+        let node = SerialBranchNode::<Phases, _>::new(SerialRunner::new(), Phase::First);
+        assert_eq!(node.discriminant(), Phase::First);
+        let node = match node.step(&1) {
+            NodeResult::Nonterminal(_, n) => n,
             NodeResult::Terminal(_) => unreachable!("Expected nonterminal transition")
         };
-        let test_node_3 = match test_node_2.step(&5) {
-            NodeResult::Nonterminal(r, n) => {
-                match r {
-                    NontermReturn::Nonterminal(s, v) => {
-                        let _: i64 = v;
-                        match s {
-                            PosNegEnum::Negative => (),
-                            _ => unreachable!("Expected negative"),
-                        }
-                        assert_eq!(v, -5)
-                    },
-                    _ => unreachable!("Expected subordinate nonterminal transition")
-                };
-                n
-            },
-            _ => unreachable!("Expected nonterminal transition")
-        };
-        let test_node_4 = match test_node_3.step(&-5) {
-            NodeResult::Nonterminal(r, n) => {
-                match r {
-                    NontermReturn::Terminal(s, v) => {
-                        let _: i64 = v;
-                        match s {
-                            PosNegEnum::Negative => (),
-                            _ => unreachable!("Expected negative"),
-                        }
-                        assert_eq!(v, 5)
-                    },
-                    _ => unreachable!("Expected subordinate nonterminal transition"),
-                };
-                n
-            },
-            _ => unreachable!("Expected nonterminal transition")
-        };
-        match test_node_4.step(&5) {
-            NodeResult::Nonterminal(r, n) => {
-                match r {
-                    NontermReturn::Nonterminal(s, v) => {
-                        let _: i64 = v;
-                        match s {
-                            PosNegEnum::Positive => (),
-                            _ => unreachable!("Expected positive")
-                        }
-                        assert_eq!(v, 5);
-                    },
-                    _ => unreachable!("Expected subordinate nonterminal transition")
-                };
-                n
-            },
-            _ => unreachable!("Expected nonterminal transition")
+        let node = node.transition_to(&0, Phase::Third);
+        assert_eq!(node.discriminant(), Phase::Third);
+        assert_steps!(node;
+            2 => Nonterminal(NontermReturn::Nonterminal(Phase::Third, 2)),
+            -1 => Terminal(())
+        );
+    }
+
+    // Retries a phase which fails a number of times, counted by the decider
+    // itself, before giving up
+    #[derive(Clone)]
+    struct Retrier {
+        retries: u32
+    }
+
+    impl SerialDecider for Retrier {
+        type Enum = Phase;
+        type Input = i64;
+        type Nonterm = i64;
+        type Term = i64;
+        type Exit = Result<i64, i64>;
+
+        fn on_nonterminal(&mut self, _i: &i64, _s: Phase, o: i64) -> NontermDecision<
+            Phase, i64, Result<i64, i64>> 
+        {
+            NontermDecision::Step(o)
+        }
+
+        fn on_terminal(&mut self, _i: &i64, state: Phase, o: i64) -> TermDecision<
+            Phase, i64, Result<i64, i64>> 
+        {
+            if o > -10 {
+                TermDecision::Exit(Result::Ok(o))
+            } else if self.retries == 0 {
+                TermDecision::Exit(Result::Err(o))
+            } else {
+                self.retries -= 1;
+                TermDecision::Trans(state, o)
+            }
+        }
+    }
+
+    #[test]
+    fn stateful_decider_test() {
+        let node = SerialBranchNode::<Phases, _>::new(Retrier { retries: 2 }, Phase::First);
+        assert_steps!(node;
+            -10 => Nonterminal(NontermReturn::Terminal(Phase::First, -10)),
+            -11 => Nonterminal(NontermReturn::Terminal(Phase::First, -11)),
+            -1 => Terminal(Result::Ok(-1))
+        );
+        let node = SerialBranchNode::<Phases, _>::new(Retrier { retries: 1 }, Phase::First);
+        assert_steps!(node;
+            3 => Nonterminal(NontermReturn::Nonterminal(Phase::First, 3)),
+            -10 => Nonterminal(NontermReturn::Terminal(Phase::First, -10)),
+            -12 => Terminal(Result::Err(-12))
+        );
+    }
+
+    // Fails on false inputs and succeeds on true ones
+    struct Attempt(Phase);
+
+    impl BehaviorTreeNode for Attempt {
+        type Input = bool;
+        type Nonterminal = ();
+        type Terminal = bool;
+
+        fn step(self, input: &bool) -> NodeResult<(), bool, Attempt> {
+            NodeResult::Terminal(*input)
+        }
+    }
+
+    impl EnumNode for Attempt {
+        type Discriminant = Phase;
+
+        fn new(phase: Phase) -> Attempt {
+            Attempt(phase)
+        }
+
+        fn discriminant_of(&self) -> Phase {
+            self.0
+        }
+    }
+
+    #[test]
+    fn initial_child_test() {
+        use node_compositions::{RoundRobinRecord, RoundRobinSelector, SelectionHistory, 
+            HistorySelector};
+        let record = RoundRobinRecord::new();
+        let node = SerialBranchNode::<Attempt, _>::new(
+            RoundRobinSelector::new(record.clone()), Phase::First);
+        assert_steps!(node;
+            true => Terminal(Result::Ok((Phase::First, true)))
+        );
+        let node = SerialBranchNode::<Attempt, _>::new(
+            RoundRobinSelector::new(record.clone()), Phase::First);
+        assert_eq!(node.discriminant(), Phase::First);
+        assert_steps!(node;
+            false => Nonterminal(NontermReturn::Terminal(Phase::Second, false)),
+            false => Nonterminal(NontermReturn::Terminal(Phase::Third, false)),
+            false => Terminal(Result::Err(()))
+        );
+        let history = SelectionHistory::new();
+        let node = SerialBranchNode::<Attempt, _>::new(
+            HistorySelector::new(|_: &bool| (), history.clone()), Phase::First);
+        assert_steps!(node;
+            false => Nonterminal(NontermReturn::Terminal(Phase::First, false)),
+            true => Terminal(Result::Ok((Phase::Second, true)))
+        );
+        let node = SerialBranchNode::<Attempt, _>::new(
+            HistorySelector::new(|_: &bool| (), history.clone()), Phase::First);
+        assert_steps!(node;
+            true => Terminal(Result::Ok((Phase::Second, true)))
+        );
+    }
+
+    impl Snapshot for Phases {
+        type State = Phase;
+
+        fn snapshot(&self) -> Phase {
+            self.0
+        }
+
+        fn restore(phase: Phase) -> Phases {
+            Phases::new(phase)
+        }
+    }
+
+    #[test]
+    fn snapshot_decider_test() {
+        let node = SerialBranchNode::<Phases, _>::new(Retrier { retries: 1 }, Phase::First);
+        let node = match node.step(&-10) {
+            NodeResult::Nonterminal(_, n) => n,
+            NodeResult::Terminal(_) => unreachable!("Expected nonterminal transition")
         };
+        let restored = SerialBranchNode::<Phases, Retrier>::restore(node.snapshot());
+        assert_steps!(restored;
+            -11 => Terminal(Result::Err(-11))
+        );
     }
 
-}
\ No newline at end of file
+    impl Snapshot for Attempt {
+        type State = Phase;
+
+        fn snapshot(&self) -> Phase {
+            self.0
+        }
+
+        fn restore(phase: Phase) -> Attempt {
+            Attempt(phase)
+        }
+    }
+
+    #[test]
+    fn snapshot_unentered_test() {
+        use node_compositions::{RoundRobinRecord, RoundRobinSelector};
+        let record = RoundRobinRecord::new();
+        let node = SerialBranchNode::<Attempt, _>::new(
+            RoundRobinSelector::new(record.clone()), Phase::First);
+        assert_steps!(node;
+            true => Terminal(Result::Ok((Phase::First, true)))
+        );
+        let node = SerialBranchNode::<Attempt, _>::new(
+            RoundRobinSelector::new(record.clone()), Phase::First);
+        let restored = SerialBranchNode::<Attempt, RoundRobinSelector<Phase, bool, (), 
+            bool>>::restore(node.snapshot());
+        assert_steps!(restored;
+            true => Terminal(Result::Ok((Phase::Second, true)))
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests_ordinal {
+    use serial_node::Ordinal;
+
+    #[test]
+    fn unsigned_ordinal_test() {
+        assert_eq!(u8::COUNT, 256);
+        assert_eq!(u16::last(), 65535);
+        assert_eq!(7u32.next(), Option::Some(8));
+        assert_eq!(u64::MAX.next(), Option::None);
+        assert_eq!(u64::COUNT, usize::MAX);
+        assert_eq!(u128::last().wrapping_next(), 0);
+        assert_eq!(42usize.index(), 42);
+        assert!(usize::MAX.is_last());
+    }
+
+    #[test]
+    #[should_panic(expected = "Discriminant does not fit in usize")]
+    fn unsigned_ordinal_index_test() {
+        u128::last().index();
+    }
+
+    #[cfg(feature = "derive")]
+    #[derive(Copy, Clone, PartialEq, Debug, Ordinal)]
+    enum Stance {
+        Crouch,
+        Stand,
+        Leap
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn derived_ordinal_test() {
+        assert_eq!(Stance::COUNT, 3);
+        assert_eq!(Stance::first(), Stance::Crouch);
+        assert_eq!(Stance::Crouch.next(), Option::Some(Stance::Stand));
+        assert_eq!(Stance::Leap.next(), Option::None);
+        assert_eq!(Stance::last(), Stance::Leap);
+        assert_eq!(Stance::Stand.index(), 1);
+    }
+}
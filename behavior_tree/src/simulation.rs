@@ -0,0 +1,210 @@
+//! Simulation of nodes and automata against scripted input. 
+//!
+//! For tests, and for tuning the parameters of behaviors, it is useful to 
+//! run a node or an automaton for a number of ticks with inputs computed 
+//! from the tick number, and look at everything that happened afterwards. 
+//! The functions here do so, collecting the inputs given and the actions or 
+//! statepoints returned into a trace, which can be checked against a golden 
+//! trace with the same harness as the testing module uses. 
+
+use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+use stackbt_automata_impl::automaton::Automaton;
+use std::fmt::Debug;
+use std::path::Path;
+use testing::{check_golden_lines, GoldenError};
+
+/// Trace of a simulation, holding the input given at each tick, and the 
+/// action returned for it. 
+#[derive(Clone, PartialEq, Debug)]
+pub struct SimulationTrace<I, A> {
+    /// Inputs given, in tick order. 
+    pub inputs: Vec<I>,
+    /// Actions returned, in tick order. 
+    pub actions: Vec<A>
+}
+
+impl<I, A> SimulationTrace<I, A> {
+    /// Create a new empty trace. 
+    pub fn new() -> SimulationTrace<I, A> {
+        SimulationTrace {
+            inputs: Vec::new(),
+            actions: Vec::new()
+        }
+    }
+
+    /// Return the number of ticks simulated. 
+    pub fn len(&self) -> usize {
+        self.actions.len()
+    }
+
+    /// Return whether no ticks were simulated. 
+    pub fn is_empty(&self) -> bool {
+        self.actions.is_empty()
+    }
+
+    /// Return the trace as lines, one for each tick, consisting of the tick, 
+    /// the input and the action, formatted with Debug. 
+    pub fn lines(&self) -> Vec<String> where 
+        I: Debug,
+        A: Debug
+    {
+        self.inputs.iter().zip(self.actions.iter()).enumerate()
+            .map(|(tick, (input, action))| format!("{}: {:?} => {:?}", tick, input, action))
+            .collect()
+    }
+
+    /// Compare the trace against the golden trace stored at the given path, 
+    /// as testing::check_golden does for statepoint traces. 
+    pub fn check_golden<P>(&self, path: P) -> Result<(), GoldenError> where 
+        I: Debug,
+        A: Debug,
+        P: AsRef<Path>
+    {
+        check_golden_lines(&self.lines(), path)
+    }
+}
+
+impl<I, A> Default for SimulationTrace<I, A> {
+    fn default() -> SimulationTrace<I, A> {
+        SimulationTrace::new()
+    }
+}
+
+impl<I, N, T> SimulationTrace<I, Statepoint<N, T>> {
+    /// Return the nonterminals reached, along with their ticks. 
+    pub fn nonterminals(&self) -> Vec<(usize, &N)> {
+        self.actions.iter().enumerate().filter_map(|(tick, point)| match point {
+            Statepoint::Nonterminal(n) => Option::Some((tick, n)),
+            Statepoint::Terminal(_) => Option::None
+        }).collect()
+    }
+
+    /// Return the terminals reached, along with their ticks. 
+    pub fn terminals(&self) -> Vec<(usize, &T)> {
+        self.actions.iter().enumerate().filter_map(|(tick, point)| match point {
+            Statepoint::Nonterminal(_) => Option::None,
+            Statepoint::Terminal(t) => Option::Some((tick, t))
+        }).collect()
+    }
+}
+
+/// Result of simulating a node, holding the trace, and the node itself if 
+/// it has not terminated by the end of the simulation. 
+#[derive(Clone, PartialEq, Debug)]
+pub struct NodeSimulation<N> where N: BehaviorTreeNode {
+    /// Trace of the simulation. 
+    pub trace: SimulationTrace<N::Input, Statepoint<N::Nonterminal, N::Terminal>>,
+    /// The node, if it is still running. 
+    pub node: Option<N>
+}
+
+/// Transition an automaton for the given number of ticks, with the input of 
+/// each tick given by the script, counting ticks from zero. 
+pub fn simulate_automaton<'k, M, S>(machine: &mut M, ticks: usize, script: S) ->
+    SimulationTrace<M::Input, M::Action> where
+    M: Automaton<'k>,
+    S: Fn(usize) -> M::Input
+{
+    let mut trace = SimulationTrace::new();
+    for tick in 0..ticks {
+        let input = script(tick);
+        let action = machine.transition(&input);
+        trace.inputs.push(input);
+        trace.actions.push(action);
+    }
+    trace
+}
+
+/// Step a node for up to the given number of ticks, with the input of each 
+/// tick given by the script, counting ticks from zero. The simulation stops 
+/// early if the node terminates. 
+pub fn simulate_node<N, S>(node: N, ticks: usize, script: S) -> NodeSimulation<N> where
+    N: BehaviorTreeNode,
+    S: Fn(usize) -> N::Input
+{
+    let mut trace = SimulationTrace::new();
+    let mut current = node;
+    for tick in 0..ticks {
+        let input = script(tick);
+        let result = current.step(&input);
+        trace.inputs.push(input);
+        match result {
+            NodeResult::Nonterminal(v, n) => {
+                trace.actions.push(Statepoint::Nonterminal(v));
+                current = n;
+            },
+            NodeResult::Terminal(t) => {
+                trace.actions.push(Statepoint::Terminal(t));
+                return NodeSimulation {
+                    trace: trace,
+                    node: Option::None
+                };
+            }
+        }
+    }
+    NodeSimulation {
+        trace: trace,
+        node: Option::Some(current)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base_nodes::PredicateWait;
+    use behavior_tree_node::Statepoint;
+
+    fn countdown(input: &i64) -> Statepoint<i64, i64> {
+        if *input <= 0 {
+            Statepoint::Terminal(*input)
+        } else {
+            Statepoint::Nonterminal(*input)
+        }
+    }
+
+    #[test]
+    fn simulate_node_test() {
+        use simulation::simulate_node;
+        let sim = simulate_node(PredicateWait::new(countdown), 10,
+            |tick| 3 - tick as i64);
+        assert_eq!(sim.trace.inputs, vec![3, 2, 1, 0]);
+        assert_eq!(sim.trace.terminals(), vec![(3, &0)]);
+        assert!(sim.node.is_none());
+        let sim = simulate_node(PredicateWait::new(countdown), 2, |_| 1);
+        assert_eq!(sim.trace.len(), 2);
+        assert!(sim.node.is_some());
+    }
+
+    #[test]
+    fn simulate_automaton_test() {
+        use simulation::simulate_automaton;
+        use node_runner::NodeRunner;
+        let mut runner = NodeRunner::new(|| PredicateWait::new(countdown));
+        let trace = simulate_automaton(&mut runner, 6, |tick| (tick as i64) % 3 - 1);
+        assert_eq!(trace.inputs, vec![-1, 0, 1, -1, 0, 1]);
+        assert_eq!(trace.terminals(), vec![(0, &-1), (1, &0), (3, &-1), (4, &0)]);
+        assert_eq!(trace.nonterminals(), vec![(2, &1), (5, &1)]);
+    }
+
+    #[test]
+    fn simulation_golden_test() {
+        use simulation::simulate_node;
+        use testing::GoldenError;
+        use std::env;
+        use std::fs;
+        let sim = simulate_node(PredicateWait::new(countdown), 10, |tick| 1 - tick as i64);
+        assert_eq!(sim.trace.lines(), vec![
+            "0: 1 => Nonterminal(1)".to_owned(), 
+            "1: 0 => Terminal(0)".to_owned()
+        ]);
+        let path = env::temp_dir().join(format!("stackbt_simulation_{}.txt", 
+            ::std::process::id()));
+        let _ = fs::remove_file(&path);
+        match sim.trace.check_golden(&path) {
+            Result::Err(GoldenError::Missing(_)) => (),
+            _ => unreachable!("Expected missing golden file")
+        };
+        fs::write(&path, "0: 1 => Nonterminal(1)\n1: 0 => Terminal(0)\n").unwrap();
+        sim.trace.check_golden(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+    }
+}
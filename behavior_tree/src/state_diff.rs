@@ -0,0 +1,371 @@
+//! Behavior tree nodes hold their entire state within themselves, so a 
+//! snapshot of a tree is just its Debug representation, as might be found 
+//! written into a save file or a bug report. Comparing two such snapshots 
+//! by eye quickly becomes impractical as trees grow, so the functions here 
+//! parse them back into a tree of states, and report the differences 
+//! between them path by path. 
+//!
+//! A change in the type or variant at some path, such as a serial branch 
+//! node switching to a different child, means the node at that path was 
+//! reconstructed in between the snapshots, either because its predecessor 
+//! terminated or because it was abandoned by its parent. Two snapshots 
+//! alone cannot tell these apart, nor detect a node which terminated and 
+//! was reconstructed into an identical state. 
+
+use std::fmt::Debug;
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// Parsed representation of the Debug output of a node. 
+#[derive(Clone, PartialEq, Debug)]
+pub enum StateTree {
+    /// A primitive value, such as a number or a string. 
+    Leaf(String),
+    /// A structure, enum variant, tuple or list, with its name (empty for 
+    /// tuples, and "[]" for lists), and its labelled members. Tuple and 
+    /// list members are labelled by their index. 
+    Branch(String, Vec<(String, StateTree)>)
+}
+
+/// A difference found between two snapshots. 
+#[derive(Clone, PartialEq, Debug)]
+pub enum StateChange {
+    /// The primitive value at the path changed from the first value to the 
+    /// second. 
+    Changed(String, String, String),
+    /// The node at the path was reconstructed, changing from the first 
+    /// type or variant to the second. 
+    Reconstructed(String, String, String),
+    /// The member at the path, with the given value, exists only in the 
+    /// second snapshot. 
+    Added(String, String),
+    /// The member at the path, with the given value, exists only in the 
+    /// first snapshot. 
+    Removed(String, String)
+}
+
+impl StateTree {
+    /// Render the state tree back into a compact Debug-like form. 
+    pub fn render(&self) -> String {
+        match self {
+            StateTree::Leaf(value) => value.clone(),
+            StateTree::Branch(name, members) => {
+                let is_list = name == "[]";
+                let is_struct = members.iter().enumerate()
+                    .any(|(i, m)| m.0 != i.to_string());
+                let inner = members.iter().map(|(label, member)| if is_struct {
+                    format!("{}: {}", label, member.render())
+                } else {
+                    member.render()
+                }).collect::<Vec<_>>().join(", ");
+                if is_list {
+                    format!("[{}]", inner)
+                } else if is_struct {
+                    format!("{} {{ {} }}", name, inner)
+                } else {
+                    format!("{}({})", name, inner)
+                }
+            }
+        }
+    }
+}
+
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>
+}
+
+impl<'a> Parser<'a> {
+    fn skip_whitespace(&mut self) {
+        while let Option::Some(c) = self.chars.peek() {
+            if c.is_whitespace() {
+                self.chars.next();
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn atom(&mut self) -> String {
+        let mut text = String::new();
+        let mut angle_depth = 0;
+        while let Option::Some(&c) = self.chars.peek() {
+            match c {
+                '"' | '\'' => {
+                    self.chars.next();
+                    text.push(c);
+                    while let Option::Some(inner) = self.chars.next() {
+                        text.push(inner);
+                        if inner == '\\' {
+                            if let Option::Some(escaped) = self.chars.next() {
+                                text.push(escaped);
+                            }
+                        } else if inner == c {
+                            break;
+                        }
+                    }
+                    continue;
+                },
+                '<' => angle_depth += 1,
+                '>' if angle_depth > 0 => angle_depth -= 1,
+                '{' | '}' | '(' | ')' | '[' | ']' | ',' | ':' if angle_depth == 0 => {
+                    // Paths such as std::time::Duration are part of the atom
+                    if c == ':' {
+                        let mut ahead = self.chars.clone();
+                        ahead.next();
+                        if ahead.peek() == Option::Some(&':') {
+                            self.chars.next();
+                            self.chars.next();
+                            text.push_str("::");
+                            continue;
+                        }
+                    }
+                    break;
+                },
+                c if c.is_whitespace() && angle_depth == 0 => break,
+                _ => ()
+            };
+            text.push(c);
+            self.chars.next();
+        }
+        text
+    }
+
+    fn members(&mut self, close: char, keyed: bool) -> Option<Vec<(String, StateTree)>> {
+        let mut members = Vec::new();
+        loop {
+            self.skip_whitespace();
+            match self.chars.peek() {
+                Option::Some(&c) if c == close => {
+                    self.chars.next();
+                    return Option::Some(members);
+                },
+                Option::None => return Option::None,
+                _ => ()
+            };
+            let first = self.value()?;
+            self.skip_whitespace();
+            if keyed && self.chars.peek() == Option::Some(&':') {
+                self.chars.next();
+                let second = self.value()?;
+                members.push((first.render(), second));
+            } else if keyed && first == StateTree::Leaf("..".to_owned()) {
+                // Non-exhaustive structure marker
+            } else {
+                members.push((members.len().to_string(), first));
+            }
+            self.skip_whitespace();
+            match self.chars.peek() {
+                Option::Some(',') => {
+                    self.chars.next();
+                },
+                Option::Some(&c) if c == close => (),
+                _ => return Option::None
+            }
+        }
+    }
+
+    fn value(&mut self) -> Option<StateTree> {
+        self.skip_whitespace();
+        match self.chars.peek() {
+            Option::Some('[') => {
+                self.chars.next();
+                let members = self.members(']', false)?;
+                return Option::Some(StateTree::Branch("[]".to_owned(), members));
+            },
+            Option::Some('(') => {
+                self.chars.next();
+                let members = self.members(')', false)?;
+                return Option::Some(StateTree::Branch(String::new(), members));
+            },
+            Option::Some('{') => {
+                self.chars.next();
+                let members = self.members('}', true)?;
+                return Option::Some(StateTree::Branch(String::new(), members));
+            },
+            _ => ()
+        };
+        let name = self.atom();
+        if name.is_empty() {
+            return Option::None;
+        }
+        self.skip_whitespace();
+        match self.chars.peek() {
+            Option::Some('(') => {
+                self.chars.next();
+                let members = self.members(')', false)?;
+                Option::Some(StateTree::Branch(name, members))
+            },
+            Option::Some('{') => {
+                self.chars.next();
+                let members = self.members('}', true)?;
+                Option::Some(StateTree::Branch(name, members))
+            },
+            _ => Option::Some(StateTree::Leaf(name))
+        }
+    }
+}
+
+/// Parse a snapshot, either in compact or in pretty Debug form, into a 
+/// state tree. Returns None if the snapshot is malformed. 
+pub fn parse_state(snapshot: &str) -> Option<StateTree> {
+    let mut parser = Parser {
+        chars: snapshot.chars().peekable()
+    };
+    let tree = parser.value()?;
+    parser.skip_whitespace();
+    match parser.chars.next() {
+        Option::None => Option::Some(tree),
+        Option::Some(_) => Option::None
+    }
+}
+
+fn join_path(path: &str, label: &str) -> String {
+    if path.is_empty() {
+        label.to_owned()
+    } else {
+        format!("{}.{}", path, label)
+    }
+}
+
+fn diff_trees(path: &str, before: &StateTree, after: &StateTree,
+    changes: &mut Vec<StateChange>)
+{
+    match (before, after) {
+        (StateTree::Leaf(b), StateTree::Leaf(a)) => if a != b {
+            changes.push(StateChange::Changed(path.to_owned(), b.clone(), a.clone()));
+        },
+        (StateTree::Branch(bn, bm), StateTree::Branch(an, am)) if bn == an => {
+            for (label, b) in bm.iter() {
+                let sub = join_path(path, label);
+                match am.iter().find(|m| &m.0 == label) {
+                    Option::Some((_, a)) => diff_trees(&sub, b, a, changes),
+                    Option::None => changes.push(StateChange::Removed(sub, b.render()))
+                }
+            }
+            for (label, a) in am.iter() {
+                if !bm.iter().any(|m| &m.0 == label) {
+                    changes.push(StateChange::Added(join_path(path, label),
+                        a.render()));
+                }
+            }
+        },
+        _ => changes.push(StateChange::Reconstructed(path.to_owned(), before.render(),
+            after.render()))
+    }
+}
+
+/// Compare two snapshots of the same tree type, returning the changes 
+/// between them in order of path, or None if either snapshot is malformed. 
+/// The root has the empty path, and deeper paths are members labels joined 
+/// by periods. 
+pub fn diff_states(before: &str, after: &str) -> Option<Vec<StateChange>> {
+    let before_tree = parse_state(before)?;
+    let after_tree = parse_state(after)?;
+    let mut changes = Vec::new();
+    diff_trees("", &before_tree, &after_tree, &mut changes);
+    Option::Some(changes)
+}
+
+/// Compare two nodes of the same type through their Debug representations. 
+/// Nodes with a hand-written Debug implementation may not produce output 
+/// which can be parsed, in which case the nodes are compared as a whole, 
+/// and any difference is reported as a reconstruction of the root. 
+pub fn diff_nodes<N>(before: &N, after: &N) -> Vec<StateChange> where N: Debug {
+    let before_str = format!("{:?}", before);
+    let after_str = format!("{:?}", after);
+    match diff_states(&before_str, &after_str) {
+        Option::Some(changes) => changes,
+        Option::None => if before_str == after_str {
+            Vec::new()
+        } else {
+            vec![StateChange::Reconstructed(String::new(), before_str, after_str)]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use state_diff::{parse_state, diff_states, diff_nodes, StateTree, StateChange};
+
+    #[allow(dead_code)]
+    #[derive(Debug)]
+    enum Child {
+        Walk { steps: u32 },
+        Wait(f32)
+    }
+
+    #[allow(dead_code)]
+    #[derive(Debug)]
+    struct Parent {
+        name: &'static str,
+        counts: Vec<i64>,
+        child: Option<Child>
+    }
+
+    #[test]
+    fn parse_test() {
+        let tree = parse_state("Parent { name: \"a, b\", counts: [1, -2], \
+            child: Some(Wait(0.5)) }").unwrap();
+        assert_eq!(tree, StateTree::Branch("Parent".to_owned(), vec![
+            ("name".to_owned(), StateTree::Leaf("\"a, b\"".to_owned())),
+            ("counts".to_owned(), StateTree::Branch("[]".to_owned(), vec![
+                ("0".to_owned(), StateTree::Leaf("1".to_owned())),
+                ("1".to_owned(), StateTree::Leaf("-2".to_owned()))
+            ])),
+            ("child".to_owned(), StateTree::Branch("Some".to_owned(), vec![
+                ("0".to_owned(), StateTree::Branch("Wait".to_owned(), vec![
+                    ("0".to_owned(), StateTree::Leaf("0.5".to_owned()))
+                ]))
+            ]))
+        ]));
+        let pretty = format!("{:#?}", Parent {
+            name: "x",
+            counts: vec![],
+            child: Option::Some(Child::Walk { steps: 3 })
+        });
+        assert_eq!(parse_state(&pretty).unwrap().render(),
+            "Parent { name: \"x\", counts: [], child: Some(Walk { steps: 3 }) }");
+        assert_eq!(parse_state("Parent { name: "), Option::None);
+    }
+
+    #[test]
+    fn diff_test() {
+        let before = Parent {
+            name: "x",
+            counts: vec![1, 2],
+            child: Option::Some(Child::Walk { steps: 3 })
+        };
+        let after = Parent {
+            name: "x",
+            counts: vec![1, 5, 6],
+            child: Option::Some(Child::Wait(1.0))
+        };
+        assert_eq!(diff_nodes(&before, &after), vec![
+            StateChange::Changed("counts.1".to_owned(), "2".to_owned(), "5".to_owned()),
+            StateChange::Added("counts.2".to_owned(), "6".to_owned()),
+            StateChange::Reconstructed("child.0".to_owned(),
+                "Walk { steps: 3 }".to_owned(), "Wait(1.0)".to_owned())
+        ]);
+        assert_eq!(diff_states("PhantomData<(i64, i64)>", "PhantomData<(i64, i64)>"),
+            Option::Some(vec![]));
+    }
+
+    #[test]
+    fn unparseable_diff_test() {
+        use std::fmt::{Debug, Formatter, Result};
+
+        struct Opaque(u32);
+
+        impl Debug for Opaque {
+            fn fmt(&self, f: &mut Formatter) -> Result {
+                write!(f, "Opaque {{ {}", self.0)
+            }
+        }
+
+        assert_eq!(diff_nodes(&Opaque(1), &Opaque(1)), vec![]);
+        assert_eq!(diff_nodes(&Opaque(1), &Opaque(2)), vec![
+            StateChange::Reconstructed("".to_owned(), "Opaque { 1".to_owned(),
+                "Opaque { 2".to_owned())
+        ]);
+    }
+}
@@ -0,0 +1,348 @@
+//! Mock nodes and automata, and a step harness, for testing trees. 
+//!
+//! Tests of branch nodes and wrappers mostly care about how the node under 
+//! test reacts to what its children do, not about the children themselves. 
+//! The scripted node stands in for a child, returning a predefined sequence 
+//! of nonterminals followed by a terminal, the halt counter stands in for a 
+//! child which should be halted, and the mock automaton stands in for an 
+//! automaton, checking that it is given the inputs it expects. The step 
+//! harness drives a node through a list of inputs, checking the 
+//! statepoint reached at each step, as a builder for cases where the 
+//! assert_steps macro of the testing module does not fit, such as when the 
+//! steps are generated. 
+
+use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+use stackbt_automata_impl::automaton::Automaton;
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+/// Node which ignores its input, and returns a predefined sequence of 
+/// nonterminals, one per step, followed by a terminal. 
+#[derive(Clone, PartialEq, Debug)]
+pub struct ScriptedNode<I, N, T> {
+    script: VecDeque<N>,
+    terminal: T,
+    _input: PhantomData<I>
+}
+
+impl<I, N, T> ScriptedNode<I, N, T> {
+    /// Create a new scripted node, which returns the nonterminals in order, 
+    /// and then the terminal. 
+    pub fn new(nonterminals: Vec<N>, terminal: T) -> ScriptedNode<I, N, T> {
+        ScriptedNode {
+            script: nonterminals.into_iter().collect(),
+            terminal: terminal,
+            _input: PhantomData
+        }
+    }
+
+    /// Return the number of nonterminals left before the node terminates. 
+    pub fn remaining(&self) -> usize {
+        self.script.len()
+    }
+}
+
+impl<I, N, T> BehaviorTreeNode for ScriptedNode<I, N, T> {
+    type Input = I;
+    type Nonterminal = N;
+    type Terminal = T;
+
+    #[inline]
+    fn step(self, _input: &I) -> NodeResult<N, T, Self> {
+        let mut mut_self = self;
+        match mut_self.script.pop_front() {
+            Option::Some(n) => NodeResult::Nonterminal(n, mut_self),
+            Option::None => NodeResult::Terminal(mut_self.terminal)
+        }
+    }
+}
+
+/// Node which runs until halted, returning its input as its nonterminal, 
+/// and counting how many times it was halted. Clones share the count, so 
+/// that a clone kept by the test can check the halts of the others. 
+#[derive(Debug)]
+pub struct HaltCounter<I, T> {
+    halts: Rc<Cell<u32>>,
+    _types: PhantomData<(I, T)>
+}
+
+impl<I, T> HaltCounter<I, T> {
+    /// Create a new halt counter, which has not been halted yet. 
+    pub fn new() -> HaltCounter<I, T> {
+        HaltCounter {
+            halts: Rc::new(Cell::new(0)),
+            _types: PhantomData
+        }
+    }
+
+    /// Return how many times this node and its clones were halted. 
+    pub fn halts(&self) -> u32 {
+        self.halts.get()
+    }
+}
+
+impl<I, T> Clone for HaltCounter<I, T> {
+    fn clone(&self) -> HaltCounter<I, T> {
+        HaltCounter {
+            halts: self.halts.clone(),
+            _types: PhantomData
+        }
+    }
+}
+
+impl<I, T> Default for HaltCounter<I, T> {
+    fn default() -> HaltCounter<I, T> {
+        HaltCounter::new()
+    }
+}
+
+impl<I, T> BehaviorTreeNode for HaltCounter<I, T> where
+    I: Clone
+{
+    type Input = I;
+    type Nonterminal = I;
+    type Terminal = T;
+
+    #[inline]
+    fn step(self, input: &I) -> NodeResult<I, T, Self> {
+        NodeResult::Nonterminal(input.clone(), self)
+    }
+
+    #[inline]
+    fn halt(self, _input: &I) {
+        self.halts.set(self.halts.get() + 1);
+    }
+}
+
+/// Automaton which expects a predefined sequence of inputs, returning the 
+/// action paired with each input as it is given. 
+///
+/// # Panics 
+///
+/// Transitioning the automaton panics if the input differs from the one 
+/// expected, or if it is given more inputs than expected. 
+#[derive(Clone, PartialEq, Debug)]
+pub struct MockAutomaton<I, A> {
+    script: VecDeque<(I, A)>,
+    transitions: usize
+}
+
+impl<I, A> MockAutomaton<I, A> {
+    /// Create a new mock automaton from the expected inputs, each paired 
+    /// with the action to return for it. 
+    pub fn new(script: Vec<(I, A)>) -> MockAutomaton<I, A> {
+        MockAutomaton {
+            script: script.into_iter().collect(),
+            transitions: 0
+        }
+    }
+
+    /// Return whether the automaton has been given every expected input. 
+    pub fn is_done(&self) -> bool {
+        self.script.is_empty()
+    }
+
+    /// Return the number of transitions made so far. 
+    pub fn transitions(&self) -> usize {
+        self.transitions
+    }
+}
+
+impl<'k, I, A> Automaton<'k> for MockAutomaton<I, A> where
+    I: PartialEq + Debug + 'k
+{
+    type Input = I;
+    type Action = A;
+
+    fn transition(&mut self, input: &I) -> A {
+        let (expected, action) = match self.script.pop_front() {
+            Option::Some(entry) => entry,
+            Option::None => panic!("Transition {}: unexpected input {:?} after \
+                the end of the script", self.transitions, input)
+        };
+        assert!(*input == expected, "Transition {}: expected input {:?}, got {:?}",
+            self.transitions, expected, input);
+        self.transitions += 1;
+        action
+    }
+}
+
+// Check on the statepoint reached by a step
+type Check<N, T> = Box<dyn Fn(&Statepoint<N, T>) -> bool>;
+
+// Input for a step, with a check on the statepoint reached and a
+// description of what the check is for
+struct Step<I, N, T> {
+    input: I,
+    check: Check<N, T>,
+    description: String
+}
+
+/// Builder driving a node through a list of inputs, checking the statepoint 
+/// reached at each step. 
+///
+/// # Example 
+/// ```
+/// use stackbt_behavior_tree::behavior_tree_node::Statepoint;
+/// use stackbt_behavior_tree::test_utils::{ScriptedNode, StepHarness};
+///
+/// let node: ScriptedNode<(), i64, bool> = ScriptedNode::new(vec![1, 2], true);
+/// let done = StepHarness::new(node)
+///     .step((), Statepoint::Nonterminal(1))
+///     .step_where((), "an even nonterminal", |point| match point {
+///         Statepoint::Nonterminal(n) => n % 2 == 0,
+///         Statepoint::Terminal(_) => false
+///     })
+///     .step((), Statepoint::Terminal(true))
+///     .run();
+/// assert!(done.is_none());
+/// ```
+pub struct StepHarness<N> where
+    N: BehaviorTreeNode
+{
+    node: N,
+    steps: Vec<Step<N::Input, N::Nonterminal, N::Terminal>>
+}
+
+impl<N> StepHarness<N> where
+    N: BehaviorTreeNode,
+    N::Nonterminal: PartialEq + Debug + 'static,
+    N::Terminal: PartialEq + Debug + 'static
+{
+    /// Create a new step harness for the node. 
+    pub fn new(node: N) -> StepHarness<N> {
+        StepHarness {
+            node: node,
+            steps: Vec::new()
+        }
+    }
+
+    /// Add a step, expecting the node to reach the given statepoint. 
+    pub fn step(self, input: N::Input, expected: Statepoint<N::Nonterminal,
+        N::Terminal>) -> StepHarness<N>
+    {
+        let description = format!("{:?}", expected);
+        self.step_where(input, description, move |point| *point == expected)
+    }
+
+    /// Add a step, expecting the node to reach a statepoint satisfying the 
+    /// predicate, which is described by the description. 
+    pub fn step_where<D, P>(self, input: N::Input, description: D, predicate: P) ->
+        StepHarness<N> where
+        D: Into<String>,
+        P: Fn(&Statepoint<N::Nonterminal, N::Terminal>) -> bool + 'static
+    {
+        let mut mut_self = self;
+        mut_self.steps.push(Step {
+            input: input,
+            check: Box::new(predicate),
+            description: description.into()
+        });
+        mut_self
+    }
+
+    /// Run the steps, returning the node if it is still running after the 
+    /// last one. 
+    ///
+    /// # Panics 
+    ///
+    /// Panics if a step reaches a statepoint other than the one expected, 
+    /// or if the node terminates before the last step. 
+    pub fn run(self) -> Option<N> {
+        let count = self.steps.len();
+        let mut current = self.node;
+        for (index, step) in self.steps.into_iter().enumerate() {
+            let (point, next) = match current.step(&step.input) {
+                NodeResult::Nonterminal(v, n) => (Statepoint::Nonterminal(v),
+                    Option::Some(n)),
+                NodeResult::Terminal(t) => (Statepoint::Terminal(t), Option::None)
+            };
+            assert!((step.check)(&point), "Step {}: expected {}, got {:?}", index,
+                step.description, point);
+            match next {
+                Option::Some(n) => current = n,
+                Option::None => {
+                    assert!(index + 1 == count, "Step {}: node terminated with \
+                        {} steps left", index, count - index - 1);
+                    return Option::None;
+                }
+            }
+        }
+        Option::Some(current)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base_nodes::MachineWrapper;
+    use behavior_tree_node::Statepoint;
+    use stackbt_automata_impl::automaton::Automaton;
+    use test_utils::{HaltCounter, MockAutomaton, ScriptedNode, StepHarness};
+
+    #[test]
+    fn halt_counter_test() {
+        use behavior_tree_node::BehaviorTreeNode;
+        let probe: HaltCounter<i64, ()> = HaltCounter::new();
+        let node = assert_steps!(probe.clone(); 3 => Nonterminal(3));
+        assert_eq!(probe.halts(), 0);
+        node.halt(&4);
+        probe.clone().halt(&5);
+        assert_eq!(probe.halts(), 2);
+    }
+
+    #[test]
+    fn scripted_node_test() {
+        let node: ScriptedNode<i64, &str, bool> = ScriptedNode::new(vec!["a", "b"],
+            false);
+        assert_eq!(node.remaining(), 2);
+        let node = StepHarness::new(node)
+            .step(0, Statepoint::Nonterminal("a"))
+            .run()
+            .unwrap();
+        assert_eq!(node.remaining(), 1);
+        assert!(StepHarness::new(node)
+            .step(0, Statepoint::Nonterminal("b"))
+            .step(0, Statepoint::Terminal(false))
+            .run()
+            .is_none());
+    }
+
+    #[test]
+    fn mock_automaton_test() {
+        let mut mock = MockAutomaton::new(vec![(1, 'a'), (2, 'b')]);
+        assert_eq!(mock.transition(&1), 'a');
+        assert!(!mock.is_done());
+        assert_eq!(mock.transition(&2), 'b');
+        assert!(mock.is_done());
+        assert_eq!(mock.transitions(), 2);
+        let mock = MockAutomaton::new(vec![
+            (3, Statepoint::Nonterminal(())),
+            (4, Statepoint::Terminal(7))
+        ]);
+        StepHarness::new(MachineWrapper::new(mock))
+            .step(3, Statepoint::Nonterminal(()))
+            .step(4, Statepoint::Terminal(7))
+            .run();
+    }
+
+    #[test]
+    #[should_panic(expected = "expected input 2, got 3")]
+    fn mock_automaton_mismatch_test() {
+        let mut mock = MockAutomaton::new(vec![(1, ()), (2, ())]);
+        mock.transition(&1);
+        mock.transition(&3);
+    }
+
+    #[test]
+    #[should_panic(expected = "Step 1: expected Nonterminal(2), got Terminal(true)")]
+    fn step_harness_mismatch_test() {
+        let node: ScriptedNode<(), i64, bool> = ScriptedNode::new(vec![1], true);
+        StepHarness::new(node)
+            .step((), Statepoint::Nonterminal(1))
+            .step((), Statepoint::Nonterminal(2))
+            .run();
+    }
+}
@@ -0,0 +1,545 @@
+//!
+//! Checking a behavior tree node step by step by hand involves a lot of 
+//! nested matching, which obscures what is actually being tested. The 
+//! macros here script such checks in a more compact form. 
+//! 
+//! For longer runs, where writing out every expected statepoint is 
+//! impractical, the golden trace functions here record the sequence of 
+//! statepoints a node reaches against a known-good trace stored in a file, 
+//! so that changes in behavior are caught when refactoring. 
+//! 
+//! Deciders can also be checked against their invariants over generated 
+//! inputs. For serial deciders, this is that they never transition to a 
+//! discriminant outside of the set of variants of the enum node, and for 
+//! parallel deciders, this is that they never leave a different number of 
+//! statepoints than they were given when continuing. Violating these 
+//! would otherwise only show up as a panic at runtime. 
+//!
+
+use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+#[cfg(feature = "serial")]
+use serial_node::{SerialDecider, NontermDecision, TermDecision};
+#[cfg(feature = "parallel")]
+use parallel_node::ParallelDecider;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Environment variable which, when set, causes golden trace checks to 
+/// write the traces produced to the golden files instead of comparing 
+/// against them. This is how golden files are created in the first place, 
+/// and how they are updated after intended changes in behavior. 
+pub const BLESS_VAR: &str = "STACKBT_BLESS";
+
+/// Declarative macro for stepping a behavior tree node through a scripted 
+/// sequence of inputs, and asserting that each step reaches a statepoint 
+/// matching the given pattern. 
+/// 
+/// Each step is written as `input => Nonterminal(pattern)` or 
+/// `input => Terminal(pattern)`, optionally followed by an `if` guard, 
+/// where the input is an expression evaluating to the input, to which a 
+/// reference is taken. Only the last step may be a terminal one. If the 
+/// last step is nonterminal, the macro evaluates to the node as of that 
+/// step, so that it may be stepped further. 
+/// 
+/// On a mismatch, the macro panics with the index of the offending step, 
+/// the expected pattern, and the statepoint actually reached, so the 
+/// statepoint values must implement Debug. 
+/// 
+/// # Example
+/// ```
+/// #[macro_use]
+/// extern crate stackbt_behavior_tree;
+/// use stackbt_behavior_tree::behavior_tree_node::Statepoint;
+/// use stackbt_behavior_tree::base_nodes::PredicateWait;
+/// 
+/// # fn main() {
+/// let node = PredicateWait::new(|input: &i64| {
+///     if *input > 0 {
+///         Statepoint::Nonterminal(*input)
+///     } else {
+///         Statepoint::Terminal(*input)
+///     }
+/// });
+/// let node_1 = assert_steps!(node;
+///     3 => Nonterminal(3),
+///     5 => Nonterminal(x) if x > 4
+/// );
+/// assert_steps!(node_1;
+///     -1 => Terminal(-1)
+/// );
+/// # }
+/// ```
+#[macro_export]
+macro_rules! assert_steps {
+    (@step $node:ident, $idx:expr; $(,)*) => {
+        $node
+    };
+
+    (@step $node:ident, $idx:expr; 
+        $input:expr => Nonterminal ( $pat:pat ) $( if $guard:expr )? , 
+        $( $rest:tt )*
+    ) => {{
+        let next = match $crate::behavior_tree_node::BehaviorTreeNode::step(
+            $node, &$input) 
+        {
+            $crate::behavior_tree_node::NodeResult::Nonterminal(v, n) => {
+                match v {
+                    $pat $( if $guard )? => n,
+                    #[allow(unreachable_patterns)]
+                    other => panic!(
+                        "Step {}: expected nonterminal matching `{}`, got \
+                        nonterminal {:?}", 
+                        $idx, 
+                        stringify!($pat $( if $guard )?), 
+                        other
+                    )
+                }
+            },
+            $crate::behavior_tree_node::NodeResult::Terminal(t) => panic!(
+                "Step {}: expected nonterminal matching `{}`, got terminal {:?}", 
+                $idx, 
+                stringify!($pat $( if $guard )?), 
+                t
+            )
+        };
+        assert_steps!(@step next, $idx + 1; $( $rest )*)
+    }};
+
+    (@step $node:ident, $idx:expr; 
+        $input:expr => Terminal ( $pat:pat ) $( if $guard:expr )? , $(,)*
+    ) => {
+        match $crate::behavior_tree_node::BehaviorTreeNode::step($node, &$input) {
+            $crate::behavior_tree_node::NodeResult::Terminal(t) => {
+                match t {
+                    $pat $( if $guard )? => (),
+                    #[allow(unreachable_patterns)]
+                    other => panic!(
+                        "Step {}: expected terminal matching `{}`, got \
+                        terminal {:?}", 
+                        $idx, 
+                        stringify!($pat $( if $guard )?), 
+                        other
+                    )
+                }
+            },
+            $crate::behavior_tree_node::NodeResult::Nonterminal(v, _) => panic!(
+                "Step {}: expected terminal matching `{}`, got nonterminal {:?}", 
+                $idx, 
+                stringify!($pat $( if $guard )?), 
+                v
+            )
+        }
+    };
+
+    ( $node:expr ; $( $body:tt )* ) => {{
+        let node = $node;
+        assert_steps!(@step node, 0_usize; $( $body )* ,)
+    }};
+}
+
+/// Run a node against a script of inputs, returning the trace of the steps 
+/// taken, one line for each step, consisting of the input and the 
+/// statepoint reached, formatted with Debug. The run stops once the node 
+/// reaches a terminal state. 
+pub fn statepoint_trace<N, K>(node: N, inputs: K) -> Vec<String> where 
+    N: BehaviorTreeNode,
+    N::Input: fmt::Debug,
+    N::Nonterminal: fmt::Debug,
+    N::Terminal: fmt::Debug,
+    K: IntoIterator<Item=N::Input>
+{
+    let mut trace = Vec::new();
+    let mut current = node;
+    for (index, input) in inputs.into_iter().enumerate() {
+        match current.step(&input) {
+            NodeResult::Nonterminal(v, n) => {
+                trace.push(format!("{}: {:?} => Nonterminal({:?})", index, input, v));
+                current = n;
+            },
+            NodeResult::Terminal(t) => {
+                trace.push(format!("{}: {:?} => Terminal({:?})", index, input, t));
+                break;
+            }
+        }
+    }
+    trace
+}
+
+/// Error returned when a golden trace check fails. 
+#[derive(Debug)]
+pub enum GoldenError {
+    /// The golden file could not be read or written. 
+    Io(io::Error),
+    /// The golden file does not exist, and STACKBT_BLESS is not set to 
+    /// create it. 
+    Missing(PathBuf),
+    /// The trace produced differs from the stored one. Contains the lines 
+    /// that differ, with their line number, the expected line (if any), and 
+    /// the actual line (if any). 
+    Mismatch(Vec<(usize, Option<String>, Option<String>)>)
+}
+
+impl From<io::Error> for GoldenError {
+    fn from(err: io::Error) -> GoldenError {
+        GoldenError::Io(err)
+    }
+}
+
+impl fmt::Display for GoldenError {
+    fn fmt(&self, fmter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GoldenError::Io(err) => write!(fmter, "Could not access golden file: {}", 
+                err),
+            GoldenError::Missing(path) => write!(fmter, 
+                "Golden file {} does not exist; set {} to create it", path.display(), 
+                BLESS_VAR),
+            GoldenError::Mismatch(lines) => {
+                writeln!(fmter, "Trace differs from golden trace at {} line(s):", 
+                    lines.len())?;
+                for (line, expected, actual) in lines.iter() {
+                    writeln!(fmter, "line {}:", line)?;
+                    if let Option::Some(e) = expected {
+                        writeln!(fmter, "-{}", e)?;
+                    }
+                    if let Option::Some(a) = actual {
+                        writeln!(fmter, "+{}", a)?;
+                    }
+                }
+                Result::Ok(())
+            }
+        }
+    }
+}
+
+/// Run a node against a script of inputs, and compare the resulting trace 
+/// against the golden trace stored at the given path. If the STACKBT_BLESS 
+/// environment variable is set, the trace is written to the file instead. 
+pub fn check_golden<N, K, P>(node: N, inputs: K, path: P) -> Result<(), GoldenError> 
+    where 
+    N: BehaviorTreeNode,
+    N::Input: fmt::Debug,
+    N::Nonterminal: fmt::Debug,
+    N::Terminal: fmt::Debug,
+    K: IntoIterator<Item=N::Input>,
+    P: AsRef<Path>
+{
+    check_golden_lines(&statepoint_trace(node, inputs), path)
+}
+
+/// Compare lines of a trace against the golden trace stored at the given 
+/// path, or write them to the file if the STACKBT_BLESS environment 
+/// variable is set. Fails if the golden file does not exist and is not 
+/// being written. 
+pub fn check_golden_lines<P>(actual: &[String], path: P) -> Result<(), GoldenError> where 
+    P: AsRef<Path>
+{
+    let path = path.as_ref();
+    if env::var_os(BLESS_VAR).is_some() {
+        let mut contents = actual.join("\n");
+        contents.push('\n');
+        fs::write(path, contents)?;
+        return Result::Ok(());
+    }
+    if !path.exists() {
+        return Result::Err(GoldenError::Missing(path.to_path_buf()));
+    }
+    let stored = fs::read_to_string(path)?;
+    let expected = stored.lines().collect::<Vec<_>>();
+    let mut diffs = Vec::new();
+    for line in 0..expected.len().max(actual.len()) {
+        let exp = expected.get(line).map(|l| l.to_string());
+        let act = actual.get(line).cloned();
+        if exp != act {
+            diffs.push((line, exp, act));
+        }
+    }
+    if diffs.is_empty() {
+        Result::Ok(())
+    } else {
+        Result::Err(GoldenError::Mismatch(diffs))
+    }
+}
+
+/// Like check_golden, but panics with a readable diff on failure. 
+pub fn assert_golden<N, K, P>(node: N, inputs: K, path: P) where 
+    N: BehaviorTreeNode,
+    N::Input: fmt::Debug,
+    N::Nonterminal: fmt::Debug,
+    N::Terminal: fmt::Debug,
+    K: IntoIterator<Item=N::Input>,
+    P: AsRef<Path>
+{
+    if let Result::Err(e) = check_golden(node, inputs, path) {
+        panic!("{}", e);
+    }
+}
+
+/// Generate all statepoints constructible from the given nonterminal and 
+/// terminal values. 
+pub fn statepoints<N, T>(nonterms: &[N], terms: &[T]) -> Vec<Statepoint<N, T>> where 
+    N: Clone,
+    T: Clone
+{
+    nonterms.iter().map(|n| Statepoint::Nonterminal(n.clone()))
+        .chain(terms.iter().map(|t| Statepoint::Terminal(t.clone())))
+        .collect()
+}
+
+/// Generate all boxed statepoint slices of the given length whose members 
+/// are constructed from the given nonterminal and terminal values. 
+/// 
+/// The number of slices generated grows exponentially with the length, so 
+/// keep the length and the number of values small. 
+pub fn statepoint_slices<N, T>(len: usize, nonterms: &[N], terms: &[T]) -> 
+    Vec<Box<[Statepoint<N, T>]>> where 
+    N: Clone,
+    T: Clone
+{
+    let points = statepoints(nonterms, terms);
+    let mut slices = vec![Vec::new()];
+    for _ in 0..len {
+        slices = slices.into_iter().flat_map(|prefix: Vec<Statepoint<N, T>>| {
+            points.iter().map(move |point| {
+                let mut extended = prefix.clone();
+                extended.push(point.clone());
+                extended
+            }).collect::<Vec<_>>()
+        }).collect();
+    }
+    slices.into_iter().map(|v| v.into_boxed_slice()).collect()
+}
+
+/// Violation of a decider invariant found when checking a decider. 
+#[derive(Clone, PartialEq, Debug)]
+pub enum DeciderViolation<E> {
+    /// A serial decider, for the case at the given index, decided to 
+    /// transition from the first discriminant to the second, which is not 
+    /// among the valid discriminants. 
+    OutOfRange(usize, E, E),
+    /// A parallel decider, for the case at the given index, was given the 
+    /// first number of statepoints, and continued leaving the second number. 
+    WrongLength(usize, usize, usize)
+}
+
+impl<E> fmt::Display for DeciderViolation<E> where E: fmt::Debug {
+    fn fmt(&self, fmter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DeciderViolation::OutOfRange(case, from, to) => write!(fmter, 
+                "Case {}: decider transitioned from {:?} to out-of-range {:?}", 
+                case, from, to),
+            DeciderViolation::WrongLength(case, given, returned) => write!(fmter, 
+                "Case {}: decider given {} statepoints returned {}", 
+                case, given, returned)
+        }
+    }
+}
+
+/// Check that a serial decider never transitions to a discriminant not in 
+/// the given set of valid discriminants, for each of the given cases of 
+/// input and statepoint, from each of the valid discriminants. Cases are 
+/// numbered in the order they are checked, which is also the order the 
+/// decider is given them in, should it keep state between them. 
+#[cfg(feature = "serial")]
+pub fn check_serial_decider<D, K>(decider: &mut D, discriminants: &[D::Enum], cases: K) -> 
+    Result<(), DeciderViolation<D::Enum>> where 
+    D: SerialDecider,
+    D::Enum: Copy + PartialEq,
+    D::Nonterm: Clone,
+    D::Term: Clone,
+    K: IntoIterator<Item=(D::Input, Statepoint<D::Nonterm, D::Term>)>
+{
+    let mut case = 0;
+    for (input, point) in cases {
+        for from in discriminants.iter() {
+            let dest = match point.clone() {
+                Statepoint::Nonterminal(n) => match decider.on_nonterminal(&input, 
+                    *from, n) 
+                {
+                    NontermDecision::Trans(e, _) => Option::Some(e),
+                    _ => Option::None
+                },
+                Statepoint::Terminal(t) => match decider.on_terminal(&input, 
+                    *from, t) 
+                {
+                    TermDecision::Trans(e, _) => Option::Some(e),
+                    _ => Option::None
+                }
+            };
+            if let Option::Some(to) = dest {
+                if !discriminants.contains(&to) {
+                    return Result::Err(DeciderViolation::OutOfRange(case, *from, to));
+                }
+            }
+            case += 1;
+        }
+    }
+    Result::Ok(())
+}
+
+/// Check that a parallel decider, when continuing, always leaves as many 
+/// statepoints as it was given, for each of the given cases of input and 
+/// statepoint slice. 
+#[cfg(feature = "parallel")]
+pub fn check_parallel_decider<D, K>(decider: &mut D, cases: K) -> 
+    Result<(), DeciderViolation<()>> where 
+    D: ParallelDecider,
+    K: IntoIterator<Item=(D::Input, Box<[Statepoint<D::Nonterm, D::Term>]>)>
+{
+    for (case, (input, slice)) in cases.into_iter().enumerate() {
+        let given = slice.len();
+        let mut states = slice.into_vec();
+        if decider.each_step(&input, &mut states).is_none() && states.len() != given {
+            return Result::Err(DeciderViolation::WrongLength(case, given, 
+                states.len()));
+        }
+    }
+    Result::Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use base_nodes::PredicateWait;
+    use behavior_tree_node::Statepoint;
+
+    type SignNode = PredicateWait<i64, i64, i64, fn(&i64) -> Statepoint<i64, i64>>;
+
+    fn sign_node() -> SignNode {
+        fn sign(input: &i64) -> Statepoint<i64, i64> {
+            if *input > 0 {
+                Statepoint::Nonterminal(*input)
+            } else {
+                Statepoint::Terminal(*input)
+            }
+        }
+        PredicateWait::new(sign)
+    }
+
+    #[test]
+    fn assert_steps_test() {
+        let node = assert_steps!(sign_node();
+            1 => Nonterminal(1),
+            2 => Nonterminal(x) if x % 2 == 0,
+        );
+        assert_steps!(node;
+            3 => Nonterminal(_),
+            0 => Terminal(0)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Step 1: expected nonterminal matching `3`")]
+    fn nonterminal_mismatch_test() {
+        assert_steps!(sign_node();
+            1 => Nonterminal(1),
+            2 => Nonterminal(3)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Step 2: expected nonterminal matching `_`, got terminal")]
+    fn early_terminal_test() {
+        assert_steps!(sign_node();
+            1 => Nonterminal(_),
+            2 => Nonterminal(_),
+            -2 => Nonterminal(_)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Step 0: expected terminal matching `x if x < -5`")]
+    fn terminal_guard_test() {
+        assert_steps!(sign_node();
+            -2 => Terminal(x) if x < -5
+        );
+    }
+
+    #[test]
+    fn golden_trace_test() {
+        use testing::{statepoint_trace, check_golden, GoldenError};
+        use std::env;
+        use std::fs;
+        let trace = statepoint_trace(sign_node(), vec![3, 1, -1, 4]);
+        assert_eq!(trace, vec![
+            "0: 3 => Nonterminal(3)".to_owned(), 
+            "1: 1 => Nonterminal(1)".to_owned(), 
+            "2: -1 => Terminal(-1)".to_owned()
+        ]);
+        let path = env::temp_dir().join(format!("stackbt_golden_{}.txt", 
+            ::std::process::id()));
+        let _ = fs::remove_file(&path);
+        match check_golden(sign_node(), vec![3, 1, -1], &path) {
+            Result::Err(GoldenError::Missing(missing)) => assert_eq!(missing, path),
+            _ => unreachable!("Expected missing golden file")
+        };
+        assert!(!path.exists());
+        fs::write(&path, "0: 3 => Nonterminal(3)\n1: 1 => Nonterminal(1)\n\
+            2: -1 => Terminal(-1)\n").unwrap();
+        check_golden(sign_node(), vec![3, 1, -1], &path).unwrap();
+        match check_golden(sign_node(), vec![3, 2, -1], &path) {
+            Result::Err(GoldenError::Mismatch(lines)) => {
+                assert_eq!(lines, vec![(1, 
+                    Option::Some("1: 1 => Nonterminal(1)".to_owned()), 
+                    Option::Some("1: 2 => Nonterminal(2)".to_owned()))]);
+            },
+            _ => unreachable!("Expected trace mismatch")
+        };
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn statepoint_slices_test() {
+        use testing::statepoint_slices;
+        let slices = statepoint_slices(3, &[1, 2], &['a']);
+        assert_eq!(slices.len(), 27);
+        assert!(slices.iter().all(|s| s.len() == 3));
+        assert_eq!(&*slices[0], &[Statepoint::Nonterminal(1), 
+            Statepoint::Nonterminal(1), Statepoint::Nonterminal(1)]);
+    }
+
+    #[cfg(feature = "compositions")]
+    #[test]
+    fn serial_decider_check_test() {
+        use testing::{statepoints, check_serial_decider, DeciderViolation};
+        use node_compositions::{SerialRunner, SerialSelector};
+        let points = statepoints(&[0], &[1, 2]);
+        let cases = points.iter().map(|p| (0, *p)).collect::<Vec<_>>();
+        let mut runner = SerialRunner::<u8, i64, i64, i64>::new();
+        assert_eq!(check_serial_decider(&mut runner, &[0, 1, 2], cases.clone()), 
+            Result::Err(DeciderViolation::OutOfRange(5, 2, 3)));
+        assert_eq!(check_serial_decider(&mut runner, &[0, 1, 2, 3], 
+            Vec::<(i64, Statepoint<i64, i64>)>::new()), Result::Ok(()));
+        let mut selector = SerialSelector::<u8, i64, i64, i64>::new();
+        let cases = statepoints(&[0], &[Option::None, Option::Some(5)]).into_iter()
+            .map(|p| (0, p));
+        assert_eq!(check_serial_decider(&mut selector, &[0, 1, 255], cases), 
+            Result::Err(DeciderViolation::OutOfRange(4, 1, 2)));
+    }
+
+    #[cfg(feature = "compositions")]
+    #[test]
+    fn parallel_decider_check_test() {
+        use testing::{statepoint_slices, check_parallel_decider};
+        use node_compositions::ParallelRacer;
+        use parallel_node::ParallelDecider;
+        struct Truncator;
+        impl ParallelDecider for Truncator {
+            type Input = ();
+            type Nonterm = i64;
+            type Term = i64;
+            type Exit = ();
+            fn each_step(&mut self, _i: &(), states: &mut Vec<Statepoint<i64, i64>>) -> 
+                Option<()> 
+            {
+                states.truncate(2);
+                Option::None
+            }
+        }
+        let cases = || (0..4).flat_map(|len| statepoint_slices(len, &[1], &[2]))
+            .map(|s| ((), s));
+        assert!(check_parallel_decider(&mut ParallelRacer::new(), cases()).is_ok());
+        assert!(check_parallel_decider(&mut Truncator, cases()).is_err());
+    }
+}
@@ -0,0 +1,127 @@
+//! Wall-clock timing of behavior tree nodes. 
+//!
+//! Tick counts do not always map cleanly onto time, such as when a tree is 
+//! stepped from a server loop whose rate varies with load. The wall clock of 
+//! this module measures the time its node has been running with the 
+//! standard library's monotonic clock, and the timed node is a clocked node 
+//! which uses it to abort its node once it has run past a deadline. The 
+//! clock can read its instants from another source, such as a simulated 
+//! clock in tests. 
+
+use behavior_tree_node::BehaviorTreeNode;
+use control_wrappers::{Clock, ClockedNode};
+use std::time::{Duration, Instant};
+
+/// Clock which reads the wall-clock time elapsed since the node was first 
+/// stepped, taking instants from a source, which is the standard library's 
+/// monotonic clock unless another is given. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct WallClock<S> {
+    source: S,
+    started: Option<Instant>
+}
+
+impl<S> WallClock<S> where S: Fn() -> Instant {
+    /// Create a new wall clock, which takes instants from the given source. 
+    pub fn new(source: S) -> WallClock<S> {
+        WallClock {
+            source: source,
+            started: Option::None
+        }
+    }
+
+    /// Return the time elapsed since the node was first stepped, or None 
+    /// if it has not been stepped yet. 
+    pub fn elapsed(&self) -> Option<Duration> {
+        self.started.map(|started| (self.source)().duration_since(started))
+    }
+}
+
+impl<I, S> Clock<I> for WallClock<S> where S: Fn() -> Instant {
+    type Time = Duration;
+
+    fn now(&mut self, _input: &I) -> Duration {
+        let now = (self.source)();
+        let started = *self.started.get_or_insert(now);
+        now.duration_since(started)
+    }
+}
+
+/// A timing wrapper for a node, which measures the wall-clock time elapsed 
+/// since the node was first stepped, and optionally aborts it once a 
+/// deadline has passed. 
+pub type TimedNode<N, S = fn() -> Instant> = ClockedNode<N, WallClock<S>>;
+
+impl<N> TimedNode<N> where N: BehaviorTreeNode {
+    /// Create a new timed node without a deadline. 
+    pub fn new(node: N) -> TimedNode<N> {
+        ClockedNode::unlimited(WallClock::new(Instant::now as fn() -> Instant), node)
+    }
+
+    /// Create a new timed node which aborts the node once it has run for 
+    /// longer than the deadline. 
+    pub fn with_deadline(deadline: Duration, node: N) -> TimedNode<N> {
+        ClockedNode::limited(WallClock::new(Instant::now as fn() -> Instant), deadline, node)
+    }
+}
+
+impl<N, S> TimedNode<N, S> where 
+    N: BehaviorTreeNode,
+    S: Fn() -> Instant
+{
+    /// Create a new timed node which takes instants from the given source, 
+    /// and aborts the node once it has run for longer than the deadline. 
+    pub fn with_source(source: S, deadline: Duration, node: N) -> TimedNode<N, S> {
+        ClockedNode::limited(WallClock::new(source), deadline, node)
+    }
+
+    /// Return the time elapsed since the node was first stepped, or None 
+    /// if it has not been stepped yet. 
+    pub fn elapsed(&self) -> Option<Duration> {
+        self.clock().elapsed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use behavior_tree_node::Statepoint;
+    use base_nodes::PredicateWait;
+    use control_wrappers::DeadlineExit;
+    use timing::TimedNode;
+    use std::cell::Cell;
+    use std::time::{Duration, Instant};
+
+    fn countdown(i: &i64) -> Statepoint<i64, i64> {
+        if *i > 0 {
+            Statepoint::Nonterminal(*i)
+        } else {
+            Statepoint::Terminal(*i)
+        }
+    }
+
+    #[test]
+    fn timed_test() {
+        let node = TimedNode::new(PredicateWait::new(countdown));
+        assert_eq!(node.elapsed(), Option::None);
+        let node = assert_steps!(node; 2 => Nonterminal(2));
+        assert!(node.elapsed().is_some());
+        assert_steps!(node; 0 => Terminal(DeadlineExit::Finished(0, _)));
+    }
+
+    #[test]
+    fn deadline_test() {
+        let base = Instant::now();
+        let offset = Cell::new(Duration::from_millis(0));
+        let source = || base + offset.get();
+        let node = TimedNode::with_source(&source, Duration::from_millis(10),
+            PredicateWait::new(countdown));
+        let node = assert_steps!(node; 2 => Nonterminal(2));
+        offset.set(Duration::from_millis(4));
+        assert_eq!(node.elapsed(), Option::Some(Duration::from_millis(4)));
+        let node = assert_steps!(node; 1 => Nonterminal(1));
+        offset.set(Duration::from_millis(15));
+        assert_steps!(node;
+            0 => Terminal(DeadlineExit::DeadlinePassed(d)) if d == Duration::from_millis(15)
+        );
+    }
+}
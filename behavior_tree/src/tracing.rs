@@ -0,0 +1,318 @@
+//! Tracing of behavior tree nodes through the log crate. 
+//!
+//! When an agent misbehaves, the first question is usually what its tree 
+//! was doing at the time. The traced node of this module logs every step of 
+//! the node it wraps, along with the nonterminal or terminal the step 
+//! produced, and logs when the node is halted. Wrapping the children of a 
+//! branch node in traced nodes as well traces the decisions of the branch 
+//! node, since the nonterminals of serial branch nodes record the child 
+//! which was stepped and whether it terminated. The nonterminals of parallel 
+//! branch nodes only count their running children, so parallel branch nodes 
+//! are traced with TracedNode::parallel, which logs the statepoints of every 
+//! child as well. 
+//!
+//! Records are logged with the module path of this module as their target, 
+//! so that they can be filtered separately from the rest of the logs of a 
+//! game. 
+
+use behavior_tree_node::{BehaviorTreeNode, NodeResult};
+#[cfg(feature = "parallel")]
+use behavior_tree_node::Statepoint;
+use log::Level;
+#[cfg(feature = "parallel")]
+use parallel_node::{ParallelBranchNode, ParallelDecider};
+#[cfg(feature = "parallel")]
+use stackbt_automata_impl::automaton::BufferedAutomaton;
+use std::fmt::{self, Debug};
+use tree_description::{DescribeTree, TreeDescription};
+
+// Function describing the detail of a node logged after each nonterminal 
+// step, which is a setting of the traced node and not part of its state
+struct Detail<N>(fn(&N) -> String);
+
+impl<N> Copy for Detail<N> {}
+
+impl<N> Clone for Detail<N> {
+    fn clone(&self) -> Detail<N> {
+        *self
+    }
+}
+
+impl<N> PartialEq for Detail<N> {
+    fn eq(&self, _other: &Detail<N>) -> bool {
+        true
+    }
+}
+
+impl<N> Debug for Detail<N> {
+    fn fmt(&self, fmter: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmter, "Detail")
+    }
+}
+
+/// A tracing wrapper for a node, which logs each step of the node under the 
+/// given name, along with the number of steps the node has taken, and 
+/// optionally a detail of the node after each nonterminal step. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct TracedNode<N> where
+    N: BehaviorTreeNode,
+    N::Nonterminal: Debug,
+    N::Terminal: Debug
+{
+    node: N,
+    name: &'static str,
+    level: Level,
+    detail: Option<Detail<N>>,
+    steps: u64
+}
+
+impl<N> TracedNode<N> where
+    N: BehaviorTreeNode,
+    N::Nonterminal: Debug,
+    N::Terminal: Debug
+{
+    /// Create a new traced node, which logs at the debug level. 
+    pub fn new(name: &'static str, node: N) -> TracedNode<N> {
+        TracedNode::with_level(Level::Debug, name, node)
+    }
+
+    /// Create a new traced node, which logs at the given level. 
+    pub fn with_level(level: Level, name: &'static str, node: N) -> TracedNode<N> {
+        TracedNode {
+            node: node,
+            name: name,
+            level: level,
+            detail: Option::None,
+            steps: 0
+        }
+    }
+
+    /// Create a new traced node, which logs at the given level, and also 
+    /// logs what the given function returns for the node after each 
+    /// nonterminal step. 
+    pub fn with_detail(level: Level, name: &'static str, detail: fn(&N) -> String, 
+        node: N) -> TracedNode<N> 
+    {
+        TracedNode {
+            node: node,
+            name: name,
+            level: level,
+            detail: Option::Some(Detail(detail)),
+            steps: 0
+        }
+    }
+
+    /// Return the name the node is traced under. 
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Return the number of steps the node has taken. 
+    pub fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    // Log the result of a step, rewrapping the node with the traced node's 
+    // settings if it did not terminate
+    fn log_step(name: &'static str, level: Level, detail: Option<Detail<N>>, 
+        steps: u64, result: NodeResult<N::Nonterminal, N::Terminal, N>) -> 
+        NodeResult<N::Nonterminal, N::Terminal, Self> 
+    {
+        match result {
+            NodeResult::Nonterminal(v, n) => {
+                match detail {
+                    Option::Some(describe) => log!(level, "{} step {}: nonterminal {:?} {}", 
+                        name, steps, v, (describe.0)(&n)),
+                    Option::None => log!(level, "{} step {}: nonterminal {:?}", name, steps, v)
+                }
+                NodeResult::Nonterminal(v, TracedNode {
+                    node: n,
+                    name: name,
+                    level: level,
+                    detail: detail,
+                    steps: steps
+                })
+            },
+            NodeResult::Terminal(t) => {
+                log!(level, "{} step {}: terminal {:?}", name, steps, t);
+                NodeResult::Terminal(t)
+            }
+        }
+    }
+}
+
+impl<N> BehaviorTreeNode for TracedNode<N> where
+    N: BehaviorTreeNode,
+    N::Nonterminal: Debug,
+    N::Terminal: Debug
+{
+    type Input = N::Input;
+    type Nonterminal = N::Nonterminal;
+    type Terminal = N::Terminal;
+
+    #[inline]
+    fn step(self, input: &N::Input) -> NodeResult<N::Nonterminal, N::Terminal, Self> {
+        let (name, level, detail, steps) = (self.name, self.level, self.detail, 
+            self.steps + 1);
+        TracedNode::log_step(name, level, detail, steps, self.node.step(input))
+    }
+
+    #[inline]
+    fn step_with(self, input: N::Input) -> NodeResult<N::Nonterminal, N::Terminal, Self> {
+        let (name, level, detail, steps) = (self.name, self.level, self.detail, 
+            self.steps + 1);
+        TracedNode::log_step(name, level, detail, steps, self.node.step_with(input))
+    }
+
+    #[inline]
+    fn halt(self, input: &N::Input) {
+        log!(self.level, "{} halted after {} steps", self.name, self.steps);
+        self.node.halt(input);
+    }
+}
+
+#[cfg(feature = "parallel")]
+impl<C, D> TracedNode<ParallelBranchNode<C, D>> where
+    C: BufferedAutomaton<'static, Input=D::Input, Action=Statepoint<D::Nonterm, 
+        D::Term>>,
+    D: ParallelDecider,
+    D::Nonterm: Debug,
+    D::Term: Debug,
+    D::Exit: Debug
+{
+    /// Create a new traced parallel branch node, which logs at the debug 
+    /// level, and logs the statepoints of all of the parallel nodes after 
+    /// each nonterminal step, as the nonterminals of the node only count 
+    /// the running ones. 
+    pub fn parallel(name: &'static str, node: ParallelBranchNode<C, D>) -> 
+        TracedNode<ParallelBranchNode<C, D>> 
+    {
+        TracedNode::with_detail(Level::Debug, name, |node| {
+            format!("{:?}", node.statepoints())
+        }, node)
+    }
+}
+
+impl<N> DescribeTree for TracedNode<N> where
+    N: DescribeTree,
+    N::Nonterminal: Debug,
+    N::Terminal: Debug
+{
+    fn describe_tree() -> TreeDescription {
+        TreeDescription::branch::<Self>("TracedNode", vec![
+            ("node", N::describe_tree())
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base_nodes::PredicateWait;
+    use behavior_tree_node::{BehaviorTreeNode, Statepoint};
+    use log::{self, Log, Metadata, Record, LevelFilter};
+    use std::sync::Mutex;
+    use tracing::TracedNode;
+
+    struct Collector {
+        lines: Mutex<Vec<String>>
+    }
+
+    impl Log for Collector {
+        fn enabled(&self, _metadata: &Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &Record) {
+            self.lines.lock().unwrap().push(format!("{}", record.args()));
+        }
+
+        fn flush(&self) {}
+    }
+
+    #[test]
+    fn traced_node_test() {
+        let collector = Box::new(Collector {
+            lines: Mutex::new(Vec::new())
+        });
+        let collector: &'static Collector = Box::leak(collector);
+        log::set_logger(collector).unwrap();
+        log::set_max_level(LevelFilter::Debug);
+
+        let wait = PredicateWait::new(|input: &i64| {
+            if *input > 0 {
+                Statepoint::Nonterminal(*input)
+            } else {
+                Statepoint::Terminal(*input)
+            }
+        });
+        let node = TracedNode::new("wait", wait);
+        let node = assert_steps!(node; 2 => Nonterminal(2), 1 => Nonterminal(1));
+        assert_eq!(node.steps(), 2);
+        node.halt(&0);
+        let node = TracedNode::new("wait", wait);
+        assert_steps!(node; 0 => Terminal(0));
+        assert_eq!(*collector.lines.lock().unwrap(), vec![
+            "wait step 1: nonterminal 2",
+            "wait step 2: nonterminal 1",
+            "wait halted after 2 steps",
+            "wait step 1: terminal 0"
+        ]);
+        parallel_trace(collector);
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    fn parallel_trace(_collector: &Collector) {}
+
+    // Shares the logger set up by the traced node test, as the logger can 
+    // only be set once
+    #[cfg(feature = "parallel")]
+    fn parallel_trace(collector: &Collector) {
+        use parallel_node::{ParallelBranchNode, ParallelDecider};
+        use stackbt_automata_impl::internal_state_machine::{InternalTransition, 
+            InternalStateMachine};
+
+        // Runs while the input has the sign of the internal factor
+        #[derive(Copy, Clone)]
+        struct Signed;
+
+        impl InternalTransition for Signed {
+            type Input = i64;
+            type Internal = i64;
+            type Action = Statepoint<i64, i64>;
+
+            fn step(&self, input: &i64, factor: &mut i64) -> Statepoint<i64, i64> {
+                if *input * *factor > 0 {
+                    Statepoint::Nonterminal(*input * *factor)
+                } else {
+                    Statepoint::Terminal(*input * *factor)
+                }
+            }
+        }
+
+        struct Forever;
+
+        impl ParallelDecider for Forever {
+            type Input = i64;
+            type Nonterm = i64;
+            type Term = i64;
+            type Exit = ();
+
+            fn each_step(&mut self, _input: &i64, _states: &mut Vec<Statepoint<i64, i64>>) -> 
+                Option<()> 
+            {
+                Option::None
+            }
+        }
+
+        collector.lines.lock().unwrap().clear();
+        let node = TracedNode::parallel("par", ParallelBranchNode::new(Forever, vec![
+            InternalStateMachine::new(Signed, 1),
+            InternalStateMachine::new(Signed, -1)
+        ]));
+        assert_steps!(node; 2 => Nonterminal(1), -3 => Nonterminal(1));
+        assert_eq!(*collector.lines.lock().unwrap(), vec![
+            "par step 1: nonterminal 1 [Nonterminal(2), Terminal(-2)]",
+            "par step 2: nonterminal 1 [Terminal(-3), Nonterminal(3)]"
+        ]);
+    }
+}
@@ -0,0 +1,669 @@
+//! Since behavior trees in this library are types rather than structures in 
+//! memory, their shape is known without ever constructing them. The 
+//! DescribeTree trait exposes this shape as a TreeDescription, listing the 
+//! kind of each node, the names of its children and the type of its 
+//! decider, which can be rendered as a static JSON document for generating 
+//! documentation or for labelling nodes in debugging tools. 
+//!
+//! For debugging on headless servers and in tests, a description can also be 
+//! rendered as an ASCII tree, with a status glyph for each node given by the 
+//! caller from whatever it has recorded about the last step of the tree. 
+//!
+//! The node types of this library implement DescribeTree whenever their 
+//! children do. User-defined nodes, and enum nodes in particular, can use 
+//! the describe_tree! macro to implement it. 
+//!
+//! ```
+//! #[macro_use]
+//! extern crate stackbt_behavior_tree;
+//!
+//! use stackbt_behavior_tree::base_nodes::PredicateWait;
+//! use stackbt_behavior_tree::behavior_tree_node::{BehaviorTreeNode, NodeResult,
+//!     Statepoint};
+//! use stackbt_behavior_tree::tree_description::DescribeTree;
+//!
+//! type Wait = PredicateWait<i64, (), (), fn(&i64) -> Statepoint<(), ()>>;
+//!
+//! struct Patrol(Wait);
+//!
+//! impl BehaviorTreeNode for Patrol {
+//!     type Input = i64;
+//!     type Nonterminal = ();
+//!     type Terminal = ();
+//!
+//!     fn step(self, input: &i64) -> NodeResult<(), (), Self> {
+//!         match self.0.step(input) {
+//!             NodeResult::Nonterminal(n, w) => NodeResult::Nonterminal(n, Patrol(w)),
+//!             NodeResult::Terminal(t) => NodeResult::Terminal(t)
+//!         }
+//!     }
+//! }
+//!
+//! describe_tree!(Patrol, "Patrol", { "wait" => Wait });
+//!
+//! # fn main() {
+//! let description = Patrol::describe_tree();
+//! assert_eq!(description.children[0].1.kind, "PredicateWait");
+//! assert!(description.to_json().starts_with("{\"kind\":\"Patrol\""));
+//! # }
+//! ```
+
+use base_nodes::{PredicateWait, Evaluation, CallLoop, MachineWrapper, MachineLoop,
+    PushdownWrapper};
+use behavior_tree_node::{BehaviorTreeNode, Statepoint};
+#[cfg(feature = "wrappers")]
+use control_wrappers::{GuardedNode, ContextGuardedNode, Invert, InverterNode, Force, 
+    AlwaysSucceed, AlwaysFail, StepControlledNode, StepDecision, 
+    PostResetNode, RestartingNode, RestartCause, RepeatNode, ClockedNode, 
+    Clock, ScheduledNode};
+#[cfg(all(feature = "wrappers", feature = "compositions"))]
+use control_wrappers::SoftFailNode;
+#[cfg(all(feature = "wrappers", feature = "compositions"))]
+use node_compositions::Classify;
+#[cfg(feature = "wrappers")]
+use map_wrappers::{InputMappedNode, OutputMappedNode, WindowedInputNode,
+    LazyConstructedNode, RelazyConstructedNode, CustomConstructedNode};
+#[cfg(feature = "serial")]
+use serial_node::{EnumNode, SerialDecider, SerialBranchNode, Ordinal};
+#[cfg(feature = "serial")]
+use utility_node::UtilityNode;
+#[cfg(feature = "parallel")]
+use parallel_node::{ParallelDecider, ParallelBranchNode};
+#[cfg(feature = "const_generics")]
+use fixed_parallel_node::FixedParallelNode;
+#[cfg(feature = "parallel")]
+use homogeneous_parallel_node::{HomogeneousParallelNode, NodeCollection};
+use boxed_node::BoxedNode;
+use call_node::{CallNonterm, CallStackNode, Resume, SubroutineNode};
+use stackbt_automata_impl::automaton::{Automaton, FiniteStateAutomaton};
+#[cfg(feature = "parallel")]
+use stackbt_automata_impl::automaton::BufferedAutomaton;
+use stackbt_automata_impl::pushdown_automaton::{PushdownTransition, TerminalTransition};
+use std::any::type_name;
+#[cfg(feature = "wrappers")]
+use std::collections::VecDeque;
+
+/// Description of the static structure of a behavior tree node. 
+#[derive(Clone, PartialEq, Debug)]
+pub struct TreeDescription {
+    /// The kind of the node, such as "GuardedNode". 
+    pub kind: &'static str,
+    /// The full type name of the node. 
+    pub type_name: &'static str,
+    /// The type name of the decider of the node, if it has one. 
+    pub decider: Option<&'static str>,
+    /// The children of the node, with their names. 
+    pub children: Vec<(&'static str, TreeDescription)>
+}
+
+/// Status of a node, as shown by the ASCII renderer. 
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum NodeStatus {
+    /// The node was not stepped. 
+    Idle,
+    /// The node stepped to a nonterminal. 
+    Running,
+    /// The node terminated successfully. 
+    Succeeded,
+    /// The node terminated unsuccessfully. 
+    Failed
+}
+
+impl NodeStatus {
+    /// Return the glyph shown for the status. 
+    pub fn glyph(&self) -> char {
+        match *self {
+            NodeStatus::Idle => ' ',
+            NodeStatus::Running => '*',
+            NodeStatus::Succeeded => '+',
+            NodeStatus::Failed => 'x'
+        }
+    }
+}
+
+// Shared with the schema module, which is not always compiled in
+pub(crate) fn write_json_str(out: &mut String, text: &str) {
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c)
+        }
+    }
+    out.push('"');
+}
+
+impl TreeDescription {
+    /// Create a description of a node without a decider or children. 
+    pub fn leaf<N>(kind: &'static str) -> TreeDescription {
+        TreeDescription {
+            kind: kind,
+            type_name: type_name::<N>(),
+            decider: Option::None,
+            children: Vec::new()
+        }
+    }
+
+    /// Create a description of a node with the given children. 
+    pub fn branch<N>(kind: &'static str, children: Vec<(&'static str,
+        TreeDescription)>) -> TreeDescription
+    {
+        TreeDescription {
+            kind: kind,
+            type_name: type_name::<N>(),
+            decider: Option::None,
+            children: children
+        }
+    }
+
+    /// Set the decider type of the description. 
+    pub fn with_decider<D>(self) -> TreeDescription {
+        TreeDescription {
+            decider: Option::Some(type_name::<D>()),
+            .. self
+        }
+    }
+
+    /// Render the description as a compact JSON document. 
+    pub fn to_json(&self) -> String {
+        let mut out = String::new();
+        self.write_json(&mut out);
+        out
+    }
+
+    /// Render the description as an ASCII tree, one node per line. The 
+    /// status of each node is looked up by its path, the names of the 
+    /// children leading to it from the root, which is empty for the root. 
+    pub fn to_ascii<F>(&self, status: F) -> String where 
+        F: Fn(&[&'static str]) -> NodeStatus
+    {
+        let mut out = String::new();
+        let mut path = Vec::new();
+        out.push_str(&format!("[{}] {}\n", status(&path).glyph(), self.kind));
+        self.write_ascii(&mut out, &mut path, "", &status);
+        out
+    }
+
+    fn write_ascii<F>(&self, out: &mut String, path: &mut Vec<&'static str>, 
+        prefix: &str, status: &F) where 
+        F: Fn(&[&'static str]) -> NodeStatus
+    {
+        for (index, &(name, ref child)) in self.children.iter().enumerate() {
+            let last = index + 1 == self.children.len();
+            path.push(name);
+            out.push_str(&format!("{}{} [{}] {}: {}\n", prefix, 
+                if last { "`-" } else { "+-" }, status(path).glyph(), name, 
+                child.kind));
+            let inner = format!("{}{}", prefix, if last { "   " } else { "|  " });
+            child.write_ascii(out, path, &inner, status);
+            path.pop();
+        }
+    }
+
+    fn write_json(&self, out: &mut String) {
+        out.push_str("{\"kind\":");
+        write_json_str(out, self.kind);
+        out.push_str(",\"type\":");
+        write_json_str(out, self.type_name);
+        out.push_str(",\"decider\":");
+        match self.decider {
+            Option::Some(d) => write_json_str(out, d),
+            Option::None => out.push_str("null")
+        };
+        out.push_str(",\"children\":[");
+        for (index, (name, child)) in self.children.iter().enumerate() {
+            if index > 0 {
+                out.push(',');
+            }
+            out.push_str("{\"name\":");
+            write_json_str(out, name);
+            out.push_str(",\"node\":");
+            child.write_json(out);
+            out.push('}');
+        }
+        out.push_str("]}");
+    }
+}
+
+/// Trait for behavior tree nodes which can describe their static structure. 
+pub trait DescribeTree: BehaviorTreeNode {
+    /// Return the description of the structure of this node type. 
+    fn describe_tree() -> TreeDescription;
+}
+
+/// Implement DescribeTree for a node type, given its kind, and optionally 
+/// its named children. 
+#[macro_export]
+macro_rules! describe_tree {
+    ($node:ty, $kind:expr) => {
+        impl $crate::tree_description::DescribeTree for $node {
+            fn describe_tree() -> $crate::tree_description::TreeDescription {
+                $crate::tree_description::TreeDescription::leaf::<$node>($kind)
+            }
+        }
+    };
+    ($node:ty, $kind:expr, { $( $name:expr => $child:ty ),* $(,)* }) => {
+        impl $crate::tree_description::DescribeTree for $node {
+            fn describe_tree() -> $crate::tree_description::TreeDescription {
+                $crate::tree_description::TreeDescription::branch::<$node>($kind, vec![
+                    $( ($name, <$child as
+                        $crate::tree_description::DescribeTree>::describe_tree()) ),*
+                ])
+            }
+        }
+    };
+}
+
+impl<I, N, T, C> DescribeTree for PredicateWait<I, N, T, C> where
+    C: Fn(&I) -> Statepoint<N, T>
+{
+    fn describe_tree() -> TreeDescription {
+        TreeDescription::leaf::<Self>("PredicateWait")
+    }
+}
+
+impl<I, O, C> DescribeTree for Evaluation<I, O, C> where
+    C: Fn(&I) -> O
+{
+    fn describe_tree() -> TreeDescription {
+        TreeDescription::leaf::<Self>("Evaluation")
+    }
+}
+
+impl<I, O, C> DescribeTree for CallLoop<I, O, C> where
+    C: Fn(&I) -> O
+{
+    fn describe_tree() -> TreeDescription {
+        TreeDescription::leaf::<Self>("CallLoop")
+    }
+}
+
+impl<M, N, T> DescribeTree for MachineWrapper<M, N, T> where
+    M: Automaton<'static, Action=Statepoint<N, T>> + 'static
+{
+    fn describe_tree() -> TreeDescription {
+        TreeDescription::leaf::<Self>("MachineWrapper")
+    }
+}
+
+impl<M> DescribeTree for MachineLoop<M> where
+    M: Automaton<'static> + 'static
+{
+    fn describe_tree() -> TreeDescription {
+        TreeDescription::leaf::<Self>("MachineLoop")
+    }
+}
+
+impl<I, A, N, T> DescribeTree for PushdownWrapper<I, A, N, T> where
+    I: 'static,
+    A: 'static,
+    N: FiniteStateAutomaton<'static, Input=I, Action=PushdownTransition<A, N>> + 'static,
+    T: FiniteStateAutomaton<'static, Input=I, Action=TerminalTransition<A, N>> + 'static
+{
+    fn describe_tree() -> TreeDescription {
+        TreeDescription::leaf::<Self>("PushdownWrapper")
+    }
+}
+
+#[cfg(feature = "wrappers")]
+impl<N, G> DescribeTree for GuardedNode<N, G> where
+    N: DescribeTree,
+    G: Fn(&N::Input, &N::Nonterminal) -> bool
+{
+    fn describe_tree() -> TreeDescription {
+        TreeDescription::branch::<Self>("GuardedNode", vec![
+            ("node", N::describe_tree())
+        ])
+    }
+}
+
+#[cfg(feature = "wrappers")]
+impl<N, G, P, C> DescribeTree for ContextGuardedNode<N, G, P, C> where
+    N: DescribeTree,
+    G: Fn(&N::Input, &N::Nonterminal) -> bool,
+    P: Fn(&N::Input) -> C
+{
+    fn describe_tree() -> TreeDescription {
+        TreeDescription::branch::<Self>("ContextGuardedNode", vec![
+            ("node", N::describe_tree())
+        ])
+    }
+}
+
+#[cfg(feature = "wrappers")]
+impl<N> DescribeTree for InverterNode<N> where
+    N: DescribeTree,
+    N::Terminal: Invert
+{
+    fn describe_tree() -> TreeDescription {
+        TreeDescription::branch::<Self>("InverterNode", vec![
+            ("node", N::describe_tree())
+        ])
+    }
+}
+
+#[cfg(feature = "wrappers")]
+impl<N> DescribeTree for AlwaysSucceed<N> where
+    N: DescribeTree,
+    N::Terminal: Force
+{
+    fn describe_tree() -> TreeDescription {
+        TreeDescription::branch::<Self>("AlwaysSucceed", vec![
+            ("node", N::describe_tree())
+        ])
+    }
+}
+
+#[cfg(feature = "wrappers")]
+impl<N> DescribeTree for AlwaysFail<N> where
+    N: DescribeTree,
+    N::Terminal: Force
+{
+    fn describe_tree() -> TreeDescription {
+        TreeDescription::branch::<Self>("AlwaysFail", vec![
+            ("node", N::describe_tree())
+        ])
+    }
+}
+
+#[cfg(feature = "wrappers")]
+impl<N, S> DescribeTree for StepControlledNode<N, S> where
+    N: DescribeTree,
+    S: Fn(&N::Input) -> StepDecision<N>
+{
+    fn describe_tree() -> TreeDescription {
+        TreeDescription::branch::<Self>("StepControlledNode", vec![
+            ("node", N::describe_tree())
+        ])
+    }
+}
+
+#[cfg(feature = "wrappers")]
+impl<N, P> DescribeTree for PostResetNode<N, P> where
+    N: DescribeTree,
+    P: Fn(&N::Input, Statepoint<&N::Nonterminal, &N::Terminal>) -> Option<N>
+{
+    fn describe_tree() -> TreeDescription {
+        TreeDescription::branch::<Self>("PostResetNode", vec![
+            ("node", N::describe_tree())
+        ])
+    }
+}
+
+#[cfg(feature = "wrappers")]
+impl<N, F, R> DescribeTree for RestartingNode<N, F, R> where
+    N: DescribeTree,
+    F: Fn(RestartCause<&N::Terminal>) -> N,
+    R: Fn(&N::Input) -> bool
+{
+    fn describe_tree() -> TreeDescription {
+        TreeDescription::branch::<Self>("RestartingNode", vec![
+            ("node", N::describe_tree())
+        ])
+    }
+}
+
+#[cfg(feature = "wrappers")]
+impl<N, F> DescribeTree for RepeatNode<N, F> where
+    N: DescribeTree,
+    F: Fn(&N::Terminal) -> N
+{
+    fn describe_tree() -> TreeDescription {
+        TreeDescription::branch::<Self>("RepeatNode", vec![
+            ("node", N::describe_tree())
+        ])
+    }
+}
+
+#[cfg(feature = "wrappers")]
+impl<N, K> DescribeTree for ClockedNode<N, K> where
+    N: DescribeTree,
+    K: Clock<N::Input>
+{
+    fn describe_tree() -> TreeDescription {
+        TreeDescription::branch::<Self>("ClockedNode", vec![
+            ("node", N::describe_tree())
+        ])
+    }
+}
+
+#[cfg(feature = "wrappers")]
+impl<N, S> DescribeTree for ScheduledNode<N, S> where
+    N: DescribeTree,
+    S: Fn(&N::Input) -> bool
+{
+    fn describe_tree() -> TreeDescription {
+        TreeDescription::branch::<Self>("ScheduledNode", vec![
+            ("node", N::describe_tree())
+        ])
+    }
+}
+
+#[cfg(all(feature = "wrappers", feature = "compositions"))]
+impl<N, F> DescribeTree for SoftFailNode<N, F> where
+    N: DescribeTree,
+    N::Terminal: Classify,
+    F: Fn(&N::Terminal) -> N
+{
+    fn describe_tree() -> TreeDescription {
+        TreeDescription::branch::<Self>("SoftFailNode", vec![
+            ("node", N::describe_tree())
+        ])
+    }
+}
+
+#[cfg(feature = "wrappers")]
+impl<N, M, I> DescribeTree for InputMappedNode<N, M, I> where
+    N: DescribeTree,
+    M: Fn(&I) -> N::Input
+{
+    fn describe_tree() -> TreeDescription {
+        TreeDescription::branch::<Self>("InputMappedNode", vec![
+            ("node", N::describe_tree())
+        ])
+    }
+}
+
+#[cfg(feature = "wrappers")]
+impl<N, M, O, S, T> DescribeTree for OutputMappedNode<N, M, O, S, T> where
+    N: DescribeTree,
+    M: Fn(N::Nonterminal) -> S,
+    O: Fn(N::Terminal) -> T
+{
+    fn describe_tree() -> TreeDescription {
+        TreeDescription::branch::<Self>("OutputMappedNode", vec![
+            ("node", N::describe_tree())
+        ])
+    }
+}
+
+#[cfg(feature = "wrappers")]
+impl<N, R, I> DescribeTree for WindowedInputNode<N, R, I> where
+    N: DescribeTree,
+    R: Fn(&VecDeque<I>) -> N::Input,
+    I: Clone
+{
+    fn describe_tree() -> TreeDescription {
+        TreeDescription::branch::<Self>("WindowedInputNode", vec![
+            ("node", N::describe_tree())
+        ])
+    }
+}
+
+#[cfg(feature = "wrappers")]
+impl<N, M> DescribeTree for LazyConstructedNode<N, M> where
+    N: DescribeTree,
+    M: Fn(&N::Input) -> N
+{
+    fn describe_tree() -> TreeDescription {
+        TreeDescription::branch::<Self>("LazyConstructedNode", vec![
+            ("node", N::describe_tree())
+        ])
+    }
+}
+
+#[cfg(feature = "wrappers")]
+impl<N, M> DescribeTree for RelazyConstructedNode<N, M> where
+    N: DescribeTree,
+    M: Fn(&N::Input) -> N
+{
+    fn describe_tree() -> TreeDescription {
+        TreeDescription::branch::<Self>("RelazyConstructedNode", vec![
+            ("node", N::describe_tree())
+        ])
+    }
+}
+
+#[cfg(feature = "wrappers")]
+impl<N, C> DescribeTree for CustomConstructedNode<N, C> where
+    N: DescribeTree,
+    C: Fn() -> N
+{
+    fn describe_tree() -> TreeDescription {
+        TreeDescription::branch::<Self>("CustomConstructedNode", vec![
+            ("node", N::describe_tree())
+        ])
+    }
+}
+
+#[cfg(feature = "serial")]
+impl<E, D> DescribeTree for SerialBranchNode<E, D> where
+    E: EnumNode + DescribeTree,
+    D: SerialDecider<Enum=E::Discriminant, Input=E::Input, Nonterm=E::Nonterminal,
+        Term=E::Terminal>
+{
+    fn describe_tree() -> TreeDescription {
+        TreeDescription::branch::<Self>("SerialBranchNode", vec![
+            ("nodes", E::describe_tree())
+        ]).with_decider::<D>()
+    }
+}
+
+#[cfg(feature = "serial")]
+impl<E, S> DescribeTree for UtilityNode<E, S> where
+    E: EnumNode + DescribeTree,
+    E::Discriminant: Ordinal + PartialEq,
+    S: Fn(&E::Input, E::Discriminant) -> f64
+{
+    fn describe_tree() -> TreeDescription {
+        TreeDescription::branch::<Self>("UtilityNode", vec![
+            ("nodes", E::describe_tree())
+        ])
+    }
+}
+
+#[cfg(feature = "parallel")]
+impl<C, D> DescribeTree for ParallelBranchNode<C, D> where
+    C: BufferedAutomaton<'static, Input=D::Input, Action=Statepoint<D::Nonterm,
+        D::Term>>,
+    D: ParallelDecider
+{
+    fn describe_tree() -> TreeDescription {
+        TreeDescription::leaf::<Self>("ParallelBranchNode").with_decider::<D>()
+    }
+}
+
+#[cfg(feature = "const_generics")]
+impl<N, const K: usize> DescribeTree for FixedParallelNode<N, K> where
+    N: BehaviorTreeNode + DescribeTree,
+    N::Terminal: Clone
+{
+    fn describe_tree() -> TreeDescription {
+        TreeDescription::branch::<Self>("FixedParallelNode", vec![
+            ("children", N::describe_tree())
+        ])
+    }
+}
+
+#[cfg(feature = "parallel")]
+impl<C> DescribeTree for HomogeneousParallelNode<C> where
+    C: NodeCollection,
+    C::Node: DescribeTree,
+    <C::Node as BehaviorTreeNode>::Terminal: Clone
+{
+    fn describe_tree() -> TreeDescription {
+        TreeDescription::branch::<Self>("HomogeneousParallelNode", vec![
+            ("children", C::Node::describe_tree())
+        ])
+    }
+}
+
+impl<I, N, T> DescribeTree for BoxedNode<I, N, T> {
+    fn describe_tree() -> TreeDescription {
+        TreeDescription::leaf::<Self>("BoxedNode")
+    }
+}
+
+impl<N, V> DescribeTree for CallStackNode<N, V> where
+    N: DescribeTree + BehaviorTreeNode<Nonterminal=CallNonterm<N, V>> + Copy + 'static,
+    N::Input: 'static,
+    N::Terminal: Copy + 'static,
+    V: 'static
+{
+    fn describe_tree() -> TreeDescription {
+        TreeDescription::branch::<Self>("CallStackNode", vec![
+            ("node", N::describe_tree())
+        ])
+    }
+}
+
+impl<N, V> DescribeTree for SubroutineNode<N, V> where
+    N: DescribeTree + Resume<V> + Copy + 'static,
+    N::Input: 'static,
+    N::Terminal: Copy + 'static,
+    V: 'static
+{
+    fn describe_tree() -> TreeDescription {
+        TreeDescription::branch::<Self>("SubroutineNode", vec![
+            ("node", N::describe_tree())
+        ])
+    }
+}
+
+#[cfg(all(test, feature = "wrappers"))]
+mod tests {
+    use base_nodes::PredicateWait;
+    use behavior_tree_node::Statepoint;
+    use control_wrappers::GuardedNode;
+    use tree_description::DescribeTree;
+
+    type Wait = PredicateWait<i64, i64, i64, fn(&i64) -> Statepoint<i64, i64>>;
+
+    #[test]
+    fn guarded_description_test() {
+        let description = GuardedNode::<Wait, fn(&i64, &i64) -> bool>::describe_tree();
+        assert_eq!(description.kind, "GuardedNode");
+        assert_eq!(description.decider, Option::None);
+        assert_eq!(description.children.len(), 1);
+        assert_eq!(description.children[0].0, "node");
+        assert_eq!(description.children[0].1.kind, "PredicateWait");
+        let json = description.to_json();
+        assert!(json.starts_with("{\"kind\":\"GuardedNode\",\"type\":\""));
+        assert!(json.ends_with("\"decider\":null,\"children\":[]}}]}"));
+    }
+
+    #[test]
+    fn json_escape_test() {
+        use tree_description::write_json_str;
+        let mut out = String::new();
+        write_json_str(&mut out, "say \"hi\"\n\\\t");
+        assert_eq!(out, "\"say \\\"hi\\\"\\n\\\\\\u0009\"");
+    }
+
+    #[test]
+    fn ascii_render_test() {
+        use tree_description::NodeStatus;
+        let description = GuardedNode::<GuardedNode<Wait, fn(&i64, &i64) -> bool>,
+            fn(&i64, &i64) -> bool>::describe_tree();
+        let rendered = description.to_ascii(|path| match path.len() {
+            0 | 1 => NodeStatus::Running,
+            _ => NodeStatus::Failed
+        });
+        assert_eq!(rendered, "[*] GuardedNode\n\
+            `- [*] node: GuardedNode\n   \
+               `- [x] node: PredicateWait\n");
+    }
+}
@@ -0,0 +1,257 @@
+//! Utility-based selection among the variants of an enumerable node. 
+//!
+//! Rather than running children in a fixed order, a utility node scores 
+//! every child against the input each step, and runs whichever scores the 
+//! highest, switching children whenever another one overtakes the running 
+//! one. To keep noise in the scores from flipping between children that 
+//! score about the same, the running child can be given an inertia bonus, 
+//! and a minimum number of steps to run before it may be switched away 
+//! from. The scores, with the inertia included, are reported in the 
+//! nonterminals of the node, for tuning. 
+
+use behavior_tree_node::{BehaviorTreeNode, NodeResult};
+use serial_node::{EnumNode, Ordinal};
+
+/// Nonterminal of a utility node. 
+#[derive(Clone, PartialEq, Debug)]
+pub struct UtilityNonterm<D, N> {
+    /// The child which was stepped. 
+    pub child: D,
+    /// The nonterminal the child stepped to. 
+    pub nonterminal: N,
+    /// The score of each child, including the inertia bonus given to the 
+    /// child which was running. 
+    pub scores: Box<[(D, f64)]>
+}
+
+/// A utility node, which each step scores all of the variants of an 
+/// enumerable node with the scorer, and steps the highest scoring one, 
+/// switching to it if it is not already running. The node terminates when 
+/// the running child terminates, with the child and its terminal. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct UtilityNode<E, S> where
+    E: EnumNode,
+    E::Discriminant: Ordinal + PartialEq,
+    S: Fn(&E::Input, E::Discriminant) -> f64
+{
+    node: Option<E>,
+    scorer: S,
+    inertia: f64,
+    min_run: u64,
+    run: u64
+}
+
+impl<E, S> UtilityNode<E, S> where
+    E: EnumNode,
+    E::Discriminant: Ordinal + PartialEq,
+    S: Fn(&E::Input, E::Discriminant) -> f64
+{
+    /// Create a new utility node, which picks its first child on its first 
+    /// step. 
+    pub fn new(scorer: S) -> UtilityNode<E, S> {
+        UtilityNode {
+            node: Option::None,
+            scorer: scorer,
+            inertia: 0.0,
+            min_run: 0,
+            run: 0
+        }
+    }
+
+    /// Add the inertia to the score of the running child. 
+    pub fn with_inertia(mut self, inertia: f64) -> UtilityNode<E, S> {
+        self.inertia = inertia;
+        self
+    }
+
+    /// Keep running a child for at least the given number of steps before 
+    /// switching away from it. 
+    pub fn with_min_run(mut self, min_run: u64) -> UtilityNode<E, S> {
+        self.min_run = min_run;
+        self
+    }
+
+    fn scores(&self, input: &E::Input, running: Option<E::Discriminant>) ->
+        Box<[(E::Discriminant, f64)]>
+    {
+        let mut scores = Vec::new();
+        let mut current = Option::Some(E::Discriminant::first());
+        while let Option::Some(child) = current {
+            let mut score = (self.scorer)(input, child);
+            if running == Option::Some(child) {
+                score += self.inertia;
+            }
+            scores.push((child, score));
+            current = child.next();
+        }
+        scores.into_boxed_slice()
+    }
+}
+
+impl<E, S> BehaviorTreeNode for UtilityNode<E, S> where
+    E: EnumNode,
+    E::Discriminant: Ordinal + PartialEq,
+    S: Fn(&E::Input, E::Discriminant) -> f64
+{
+    type Input = E::Input;
+    type Nonterminal = UtilityNonterm<E::Discriminant, E::Nonterminal>;
+    type Terminal = (E::Discriminant, E::Terminal);
+
+    #[inline]
+    fn step(self, input: &E::Input) -> NodeResult<Self::Nonterminal,
+        (E::Discriminant, E::Terminal), Self>
+    {
+        let running = self.node.as_ref().map(|node| node.discriminant_of());
+        let scores = self.scores(input, running);
+        let mut best = scores[0];
+        for entry in scores.iter() {
+            if entry.1 > best.1 {
+                best = *entry;
+            }
+        }
+        let (node, run) = match self.node {
+            Option::Some(node) => {
+                let running = node.discriminant_of();
+                let kept = scores.iter().any(|entry| entry.0 == running &&
+                    entry.1 >= best.1);
+                if kept || self.run < self.min_run {
+                    (node, self.run)
+                } else {
+                    node.halt(input);
+                    (E::new(best.0), 0)
+                }
+            },
+            Option::None => (E::new(best.0), 0)
+        };
+        let child = node.discriminant_of();
+        match node.step(input) {
+            NodeResult::Nonterminal(v, n) => NodeResult::Nonterminal(
+                UtilityNonterm {
+                    child: child,
+                    nonterminal: v,
+                    scores: scores
+                },
+                UtilityNode {
+                    node: Option::Some(n),
+                    scorer: self.scorer,
+                    inertia: self.inertia,
+                    min_run: self.min_run,
+                    run: run + 1
+                }
+            ),
+            NodeResult::Terminal(t) => NodeResult::Terminal((child, t))
+        }
+    }
+
+    #[inline]
+    fn halt(self, input: &E::Input) {
+        if let Option::Some(node) = self.node {
+            node.halt(input);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base_nodes::PredicateWait;
+    use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+    use serial_node::EnumNode;
+    use utility_node::UtilityNode;
+
+    // The input holds the scores of the two activities, and a negative
+    // score for the running activity makes it terminate
+    fn keep_busy(input: &[f64; 2]) -> Statepoint<(), ()> {
+        if input[0] < 0.0 || input[1] < 0.0 {
+            Statepoint::Terminal(())
+        } else {
+            Statepoint::Nonterminal(())
+        }
+    }
+
+    type Waiter = PredicateWait<[f64; 2], (), (), fn(&[f64; 2]) -> Statepoint<(), ()>>;
+
+    #[derive(Copy, Clone, PartialEq, Eq, Debug)]
+    enum Activity {
+        Eat,
+        Sleep
+    }
+
+    impl_ordinal!(Activity { Eat, Sleep });
+
+    struct Activities(Activity, Waiter);
+
+    impl BehaviorTreeNode for Activities {
+        type Input = [f64; 2];
+        type Nonterminal = ();
+        type Terminal = ();
+
+        fn step(self, input: &[f64; 2]) -> NodeResult<(), (), Activities> {
+            let activity = self.0;
+            match self.1.step(input) {
+                NodeResult::Nonterminal(v, n) => {
+                    NodeResult::Nonterminal(v, Activities(activity, n))
+                },
+                NodeResult::Terminal(t) => NodeResult::Terminal(t)
+            }
+        }
+    }
+
+    impl EnumNode for Activities {
+        type Discriminant = Activity;
+
+        fn new(activity: Activity) -> Activities {
+            Activities(activity, PredicateWait::new(keep_busy))
+        }
+
+        fn discriminant_of(&self) -> Activity {
+            self.0
+        }
+    }
+
+    fn score(input: &[f64; 2], activity: Activity) -> f64 {
+        match activity {
+            Activity::Eat => input[0],
+            Activity::Sleep => input[1]
+        }
+    }
+
+    type ActivityNode = UtilityNode<Activities, fn(&[f64; 2], Activity) -> f64>;
+
+    fn child_of(node: ActivityNode, input: [f64; 2]) -> (Activity, ActivityNode) {
+        match node.step(&input) {
+            NodeResult::Nonterminal(v, n) => (v.child, n),
+            NodeResult::Terminal(_) => unreachable!("Expected nonterminal state")
+        }
+    }
+
+    #[test]
+    fn utility_node_test() {
+        let scorer: fn(&[f64; 2], Activity) -> f64 = score;
+        let node = UtilityNode::<Activities, _>::new(scorer);
+        let (child, node) = child_of(node, [1.0, 2.0]);
+        assert_eq!(child, Activity::Sleep);
+        let (child, node) = child_of(node, [2.5, 2.0]);
+        assert_eq!(child, Activity::Eat);
+        assert_steps!(node; [3.0, -1.0] => Terminal((Activity::Eat, ())));
+    }
+
+    #[test]
+    fn utility_inertia_test() {
+        let scorer: fn(&[f64; 2], Activity) -> f64 = score;
+        let node = UtilityNode::<Activities, _>::new(scorer).with_inertia(1.0);
+        let node = assert_steps!(node;
+            [1.0, 2.0] => Nonterminal(v)
+                if *v.scores == [(Activity::Eat, 1.0), (Activity::Sleep, 2.0)],
+            [2.5, 2.0] => Nonterminal(v) if v.child == Activity::Sleep
+                && *v.scores == [(Activity::Eat, 2.5), (Activity::Sleep, 3.0)]
+        );
+        let (child, _) = child_of(node, [3.5, 2.0]);
+        assert_eq!(child, Activity::Eat);
+        let node = UtilityNode::<Activities, _>::new(scorer).with_min_run(2);
+        let (_, node) = child_of(node, [1.0, 2.0]);
+        let (child, node) = child_of(node, [3.0, 2.0]);
+        assert_eq!(child, Activity::Sleep);
+        let (child, _) = child_of(node, [3.0, 2.0]);
+        assert_eq!(child, Activity::Eat);
+    }
+}
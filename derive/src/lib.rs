@@ -0,0 +1,106 @@
+//! Derive macros for StackBT. 
+//!
+//! Deriving Ordinal for a fieldless enum steps through its variants in the 
+//! order they are declared, so that the enum can be used as the 
+//! discriminant of a serial branch node with the built-in serial deciders, 
+//! without listing the variants again as impl_ordinal! does. 
+//!
+//! The implementation refers to the trait as 
+//! `::stackbt_behavior_tree::serial_node::Ordinal`. Crates which only depend 
+//! on the trait through another path, such as the stackbt crate, can give 
+//! the path of the behavior tree crate with an `ordinal` attribute: 
+//!
+//! ```ignore
+//! #[derive(Copy, Clone, PartialEq, Debug, Ordinal)]
+//! #[ordinal(crate = "stackbt::behavior_tree")]
+//! enum Phase { Approach, Strike, Retreat }
+//! ```
+
+extern crate proc_macro;
+extern crate proc_macro2;
+#[macro_use]
+extern crate quote;
+extern crate syn;
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use syn::{Data, DeriveInput, Error, Fields, LitStr, Path};
+
+/// Derive Ordinal for a fieldless enum, with the variants in the order they 
+/// are declared. 
+#[proc_macro_derive(Ordinal, attributes(ordinal))]
+pub fn derive_ordinal(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    match expand_ordinal(&input) {
+        Result::Ok(tokens) => tokens.into(),
+        Result::Err(error) => error.to_compile_error().into()
+    }
+}
+
+// Find the path of the behavior tree crate, which can be given with an
+// ordinal attribute
+fn crate_path(input: &DeriveInput) -> Result<Path, Error> {
+    let mut path = Option::None;
+    for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("ordinal")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("crate") {
+                let lit: LitStr = meta.value()?.parse()?;
+                path = Option::Some(lit.parse::<Path>()?);
+                Result::Ok(())
+            } else {
+                Result::Err(meta.error("Expected `crate = \"...\"`"))
+            }
+        })?;
+    }
+    match path {
+        Option::Some(path) => Result::Ok(path),
+        Option::None => syn::parse_str("::stackbt_behavior_tree")
+    }
+}
+
+fn expand_ordinal(input: &DeriveInput) -> Result<proc_macro2::TokenStream, Error> {
+    let name = &input.ident;
+    let data = match input.data {
+        Data::Enum(ref data) => data,
+        _ => return Result::Err(Error::new(Span::call_site(),
+            "Ordinal can only be derived for enums"))
+    };
+    if data.variants.is_empty() {
+        return Result::Err(Error::new(Span::call_site(),
+            "Ordinal cannot be derived for an enum without variants"));
+    }
+    for variant in data.variants.iter() {
+        match variant.fields {
+            Fields::Unit => (),
+            _ => return Result::Err(Error::new_spanned(variant,
+                "Ordinal can only be derived for enums whose variants have no fields"))
+        }
+    }
+    let path = crate_path(input)?;
+    let variants = data.variants.iter().map(|variant| &variant.ident).collect::<Vec<_>>();
+    let count = variants.len();
+    let first = variants[0];
+    let arms = variants.iter().zip(variants.iter().skip(1)).map(|(current, next)| {
+        quote!(#name :: #current => ::std::option::Option::Some(#name :: #next),)
+    });
+    let last = variants[count - 1];
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Result::Ok(quote! {
+        impl #impl_generics #path::serial_node::Ordinal for #name #ty_generics
+            #where_clause
+        {
+            const COUNT: usize = #count;
+
+            fn first() -> Self {
+                #name :: #first
+            }
+
+            fn next(self) -> ::std::option::Option<Self> {
+                match self {
+                    #( #arms )*
+                    #name :: #last => ::std::option::Option::None
+                }
+            }
+        }
+    })
+}
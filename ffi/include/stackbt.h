@@ -0,0 +1,48 @@
+/*
+ * C interface to StackBT node runners. 
+ *
+ * Runners are created by constructor functions exported from Rust with the 
+ * export_runner! macro, which are declared by the user as 
+ * 
+ *     StackbtRunner *my_constructor(void);
+ * 
+ * A constructor returns NULL if building the runner panicked. 
+ * 
+ * Inputs and outputs are exchanged as byte blobs, whose encoding is given 
+ * by the BlobCodec implementations of the node's payload types. Each output 
+ * blob starts with a tag byte, STACKBT_NONTERMINAL or STACKBT_TERMINAL. 
+ */
+
+#ifndef STACKBT_H
+#define STACKBT_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define STACKBT_NONTERMINAL 0
+#define STACKBT_TERMINAL 1
+#define STACKBT_ERR_NULL (-1)
+#define STACKBT_ERR_DECODE (-2)
+#define STACKBT_ERR_BUFFER (-3)
+#define STACKBT_ERR_PANIC (-4)
+#define STACKBT_ERR_NO_OUTPUT (-5)
+
+typedef struct StackbtRunner StackbtRunner;
+
+int32_t stackbt_runner_tick(StackbtRunner *runner, const uint8_t *input, 
+    size_t input_len, uint8_t *output, size_t output_cap, size_t *output_len);
+
+int32_t stackbt_runner_last_output(StackbtRunner *runner, uint8_t *output, 
+    size_t output_cap, size_t *output_len);
+
+void stackbt_runner_destroy(StackbtRunner *runner);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
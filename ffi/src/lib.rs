@@ -0,0 +1,371 @@
+//! C-compatible interface to StackBT node runners. 
+//!
+//! Behavior trees in StackBT are statically typed, so they cannot be handed 
+//! directly to code that cannot link against Rust generics, such as C and 
+//! C++ game engines. This crate erases the type of a node runner behind an 
+//! opaque handle, and exchanges inputs and statepoints with the host as 
+//! byte blobs, which are encoded and decoded with the BlobCodec trait. 
+//!
+//! The host creates a runner through a constructor function exported with 
+//! the export_runner! macro, steps it with stackbt_runner_tick, and frees it 
+//! with stackbt_runner_destroy. The corresponding C declarations are in 
+//! include/stackbt.h. 
+//!
+//! Each output blob starts with a tag byte, which is STACKBT_NONTERMINAL if 
+//! the rest of the blob is an encoded nonterminal, or STACKBT_TERMINAL if 
+//! it is an encoded terminal. 
+//! 
+//! With the wasm feature enabled, the same runners can also be exported to 
+//! JavaScript through wasm-bindgen, and with the python feature enabled, 
+//! to Python through PyO3, along with leaf nodes implemented in Python. 
+
+pub extern crate stackbt_automata_impl;
+pub extern crate stackbt_behavior_tree;
+#[cfg(feature = "wasm")]
+extern crate wasm_bindgen;
+#[cfg(feature = "python")]
+extern crate pyo3;
+// The code generated by the pyo3 macros refers to core by absolute path. 
+#[cfg(feature = "python")]
+extern crate core;
+
+use stackbt_automata_impl::automaton::Automaton;
+use stackbt_behavior_tree::behavior_tree_node::{BehaviorTreeNode, Statepoint};
+use stackbt_behavior_tree::node_runner::NodeRunner;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr;
+use std::slice;
+
+/// wasm-bindgen bindings for type-erased node runners. 
+#[cfg(feature = "wasm")]
+pub mod wasm;
+/// Python bindings for prototyping behavior trees. 
+#[cfg(feature = "python")]
+pub mod python;
+
+/// Tag byte and status code for a node runner reaching a nonterminal state. 
+pub const STACKBT_NONTERMINAL: i32 = 0;
+/// Tag byte and status code for a node runner reaching a terminal state. 
+pub const STACKBT_TERMINAL: i32 = 1;
+/// Status code for a null pointer passed where one is not allowed. 
+pub const STACKBT_ERR_NULL: i32 = -1;
+/// Status code for an input blob which could not be decoded. 
+pub const STACKBT_ERR_DECODE: i32 = -2;
+/// Status code for an output buffer too small to hold the output blob. 
+pub const STACKBT_ERR_BUFFER: i32 = -3;
+/// Status code for a panic within the node runner, after which the runner 
+/// should be destroyed. 
+pub const STACKBT_ERR_PANIC: i32 = -4;
+/// Status code for a runner with no output blob to retrieve, because it 
+/// has not been stepped yet or its last step failed. 
+pub const STACKBT_ERR_NO_OUTPUT: i32 = -5;
+
+/// Trait for types which can be passed across the C interface as byte 
+/// blobs. 
+pub trait BlobCodec: Sized {
+    /// Decode a value from the given bytes, returning None if the bytes 
+    /// do not encode a valid value. 
+    fn decode(bytes: &[u8]) -> Option<Self>;
+    /// Encode the value, appending the bytes to the given buffer. 
+    fn encode(&self, out: &mut Vec<u8>);
+}
+
+impl BlobCodec for () {
+    fn decode(bytes: &[u8]) -> Option<()> {
+        if bytes.is_empty() {
+            Option::Some(())
+        } else {
+            Option::None
+        }
+    }
+
+    fn encode(&self, _out: &mut Vec<u8>) {}
+}
+
+impl BlobCodec for bool {
+    fn decode(bytes: &[u8]) -> Option<bool> {
+        match bytes {
+            [0] => Option::Some(false),
+            [1] => Option::Some(true),
+            _ => Option::None
+        }
+    }
+
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(*self as u8);
+    }
+}
+
+macro_rules! blob_codec_impl {
+    ($($t:ty),*) => {
+        $(
+            impl BlobCodec for $t {
+                fn decode(bytes: &[u8]) -> Option<$t> {
+                    let mut le = <$t>::default().to_le_bytes();
+                    if bytes.len() != le.len() {
+                        return Option::None;
+                    }
+                    le.copy_from_slice(bytes);
+                    Option::Some(<$t>::from_le_bytes(le))
+                }
+
+                fn encode(&self, out: &mut Vec<u8>) {
+                    out.extend_from_slice(&self.to_le_bytes());
+                }
+            }
+        )*
+    }
+}
+
+blob_codec_impl!(i8, i16, i32, i64, u8, u16, u32, u64, f32, f64);
+
+/// Type-erased node runner, taking and returning byte blobs. 
+pub trait ErasedRunner {
+    /// Decode the input blob, step the runner with it, and encode the 
+    /// statepoint reached into the output buffer, prefixed with its tag 
+    /// byte. Returns the status code of the step. 
+    fn tick_blob(&mut self, input: &[u8], output: &mut Vec<u8>) -> i32;
+}
+
+impl<N, C> ErasedRunner for NodeRunner<N, C> where
+    N: BehaviorTreeNode + 'static,
+    N::Input: BlobCodec,
+    N::Nonterminal: BlobCodec,
+    N::Terminal: BlobCodec,
+    C: Fn() -> N
+{
+    fn tick_blob(&mut self, input: &[u8], output: &mut Vec<u8>) -> i32 {
+        let decoded = match N::Input::decode(input) {
+            Option::Some(i) => i,
+            Option::None => return STACKBT_ERR_DECODE
+        };
+        match self.transition(&decoded) {
+            Statepoint::Nonterminal(n) => {
+                output.push(STACKBT_NONTERMINAL as u8);
+                n.encode(output);
+                STACKBT_NONTERMINAL
+            },
+            Statepoint::Terminal(t) => {
+                output.push(STACKBT_TERMINAL as u8);
+                t.encode(output);
+                STACKBT_TERMINAL
+            }
+        }
+    }
+}
+
+/// Opaque handle to a type-erased node runner, as seen by C code. 
+pub struct StackbtRunner {
+    runner: Box<dyn ErasedRunner>,
+    buffer: Vec<u8>
+}
+
+impl StackbtRunner {
+    /// Box up a runner, returning a raw handle to pass to C code. The 
+    /// handle must eventually be freed with stackbt_runner_destroy. 
+    pub fn into_raw<R>(runner: R) -> *mut StackbtRunner where
+        R: ErasedRunner + 'static
+    {
+        Box::into_raw(Box::new(StackbtRunner {
+            runner: Box::new(runner),
+            buffer: Vec::new()
+        }))
+    }
+}
+
+/// Export an extern "C" constructor function with the given name, which 
+/// creates a node runner from the given node constructor and returns a 
+/// handle to it, or a null pointer if building the runner panicked. 
+#[macro_export]
+macro_rules! export_runner {
+    ($name:ident, $constructor:expr) => {
+        #[no_mangle]
+        pub extern "C" fn $name() -> *mut $crate::StackbtRunner {
+            // Unwinding into the host is undefined behavior
+            let built = ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(||
+                $crate::StackbtRunner::into_raw(
+                    $crate::stackbt_behavior_tree::node_runner::NodeRunner::new($constructor)
+                )));
+            built.unwrap_or(::std::ptr::null_mut())
+        }
+    }
+}
+
+/// Step the runner with the input blob, writing the output blob into the 
+/// output buffer and its length into output_len, and return the status 
+/// code. If the output buffer is too small, STACKBT_ERR_BUFFER is returned 
+/// and the needed length is written into output_len, but the step is still 
+/// taken; call stackbt_runner_last_output with a larger buffer to retrieve 
+/// the output blob. 
+/// 
+/// # Safety
+/// 
+/// runner must be a live handle from an exported constructor, input must 
+/// point to input_len readable bytes, output must point to output_cap 
+/// writable bytes, and output_len must point to a writable usize. 
+#[no_mangle]
+pub unsafe extern "C" fn stackbt_runner_tick(runner: *mut StackbtRunner,
+    input: *const u8, input_len: usize, output: *mut u8, output_cap: usize,
+    output_len: *mut usize) -> i32
+{
+    if runner.is_null() || (input.is_null() && input_len != 0) {
+        return STACKBT_ERR_NULL;
+    }
+    let handle = &mut *runner;
+    let input_slice = if input_len == 0 {
+        &[]
+    } else {
+        slice::from_raw_parts(input, input_len)
+    };
+    handle.buffer.clear();
+    let StackbtRunner { ref mut runner, ref mut buffer } = *handle;
+    let status = match catch_unwind(AssertUnwindSafe(||
+        runner.tick_blob(input_slice, buffer)))
+    {
+        Result::Ok(s) => s,
+        Result::Err(_) => {
+            handle.buffer.clear();
+            return STACKBT_ERR_PANIC;
+        }
+    };
+    if status < 0 {
+        return status;
+    }
+    copy_output(handle, output, output_cap, output_len)
+}
+
+/// Copy the output blob of the last step into the output buffer, writing 
+/// its length into output_len. Returns the status code of that step, 
+/// STACKBT_ERR_NO_OUTPUT if the runner has not been stepped or its last 
+/// step failed, or another error code otherwise. 
+/// 
+/// # Safety
+/// 
+/// The same requirements as for stackbt_runner_tick apply. 
+#[no_mangle]
+pub unsafe extern "C" fn stackbt_runner_last_output(runner: *mut StackbtRunner,
+    output: *mut u8, output_cap: usize, output_len: *mut usize) -> i32
+{
+    if runner.is_null() {
+        return STACKBT_ERR_NULL;
+    }
+    copy_output(&*runner, output, output_cap, output_len)
+}
+
+unsafe fn copy_output(handle: &StackbtRunner, output: *mut u8, output_cap: usize,
+    output_len: *mut usize) -> i32
+{
+    if output_len.is_null() {
+        return STACKBT_ERR_NULL;
+    }
+    // The tag byte of the blob is the status code of the step
+    let status = match handle.buffer.first() {
+        Option::Some(&tag) => i32::from(tag),
+        Option::None => return STACKBT_ERR_NO_OUTPUT
+    };
+    *output_len = handle.buffer.len();
+    if handle.buffer.len() > output_cap {
+        return STACKBT_ERR_BUFFER;
+    }
+    if output.is_null() {
+        return STACKBT_ERR_NULL;
+    }
+    ptr::copy_nonoverlapping(handle.buffer.as_ptr(), output, handle.buffer.len());
+    status
+}
+
+/// Free a runner created by an exported constructor. Passing null is a 
+/// no-op. 
+/// 
+/// # Safety
+/// 
+/// runner must be null or a live handle from an exported constructor, and 
+/// must not be used again afterwards. 
+#[no_mangle]
+pub unsafe extern "C" fn stackbt_runner_destroy(runner: *mut StackbtRunner) {
+    if !runner.is_null() {
+        drop(Box::from_raw(runner));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use stackbt_behavior_tree::base_nodes::PredicateWait;
+    use stackbt_behavior_tree::behavior_tree_node::Statepoint;
+    use std::ptr;
+    use {stackbt_runner_tick, stackbt_runner_last_output, stackbt_runner_destroy,
+        BlobCodec, STACKBT_NONTERMINAL, STACKBT_TERMINAL, STACKBT_ERR_BUFFER,
+        STACKBT_ERR_DECODE, STACKBT_ERR_NO_OUTPUT};
+
+    fn sign(input: &i32) -> Statepoint<i32, u8> {
+        if *input > 0 {
+            Statepoint::Nonterminal(*input * 2)
+        } else {
+            Statepoint::Terminal(7)
+        }
+    }
+
+    type SignNode = PredicateWait<i32, i32, u8, fn(&i32) -> Statepoint<i32, u8>>;
+
+    fn make_sign() -> SignNode {
+        PredicateWait::new(sign)
+    }
+
+    fn make_broken() -> SignNode {
+        panic!("Sign node could not be built")
+    }
+
+    export_runner!(stackbt_test_create_sign, make_sign);
+    export_runner!(stackbt_test_create_broken, make_broken);
+
+    #[test]
+    fn codec_test() {
+        let mut out = Vec::new();
+        (-3i32).encode(&mut out);
+        2.5f32.encode(&mut out);
+        assert_eq!(i32::decode(&out[..4]), Option::Some(-3));
+        assert_eq!(f32::decode(&out[4..]), Option::Some(2.5));
+        assert_eq!(i32::decode(&out[..3]), Option::None);
+        assert_eq!(bool::decode(&[2]), Option::None);
+    }
+
+    #[test]
+    fn tick_test() {
+        unsafe {
+            let runner = stackbt_test_create_sign();
+            let mut buf = [0u8; 8];
+            let mut len = 0;
+            assert_eq!(stackbt_runner_last_output(runner, buf.as_mut_ptr(), 8, &mut len),
+                STACKBT_ERR_NO_OUTPUT);
+            let input = 5i32.to_le_bytes();
+            assert_eq!(stackbt_runner_tick(runner, input.as_ptr(), 4, buf.as_mut_ptr(),
+                8, &mut len), STACKBT_NONTERMINAL);
+            assert_eq!(&buf[..len], &[0, 10, 0, 0, 0]);
+            let input = 0i32.to_le_bytes();
+            assert_eq!(stackbt_runner_tick(runner, input.as_ptr(), 4, buf.as_mut_ptr(),
+                8, &mut len), STACKBT_TERMINAL);
+            assert_eq!(&buf[..len], &[1, 7]);
+            assert_eq!(stackbt_runner_last_output(runner, buf.as_mut_ptr(), 8, &mut len),
+                STACKBT_TERMINAL);
+            assert_eq!(&buf[..len], &[1, 7]);
+            assert_eq!(stackbt_runner_tick(runner, input.as_ptr(), 3, buf.as_mut_ptr(),
+                8, &mut len), STACKBT_ERR_DECODE);
+            assert_eq!(stackbt_runner_last_output(runner, buf.as_mut_ptr(), 8, &mut len),
+                STACKBT_ERR_NO_OUTPUT);
+            let input = 1i32.to_le_bytes();
+            assert_eq!(stackbt_runner_tick(runner, input.as_ptr(), 4, buf.as_mut_ptr(),
+                2, &mut len), STACKBT_ERR_BUFFER);
+            assert_eq!(len, 5);
+            assert_eq!(stackbt_runner_last_output(runner, buf.as_mut_ptr(), 8, &mut len),
+                STACKBT_NONTERMINAL);
+            assert_eq!(&buf[..len], &[0, 2, 0, 0, 0]);
+            stackbt_runner_destroy(runner);
+            stackbt_runner_destroy(ptr::null_mut());
+        }
+    }
+
+    #[test]
+    fn constructor_panic_test() {
+        assert!(stackbt_test_create_broken().is_null());
+    }
+}
@@ -0,0 +1,128 @@
+//! Python bindings for prototyping behavior trees. 
+//!
+//! Leaf behavior is often easier to iterate on in Python before it is 
+//! ported to Rust. PythonLeaf is a behavior tree node which delegates its 
+//! steps to a Python callable, so that it can stand in for a leaf of a 
+//! static tree during prototyping, and the classes registered by register 
+//! let Python code run such leaves, and type-erased runners of whole trees 
+//! exported from Rust, directly. 
+//!
+//! A Python leaf callable is called with the input, and returns a tuple of 
+//! a boolean, which is true if the leaf has terminated, and the statepoint 
+//! value. If it raises an exception or returns anything else, the leaf 
+//! terminates with the error, which LeafRunner raises again from tick. 
+
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use pyo3::types::PyBytes;
+use stackbt_automata_impl::automaton::Automaton;
+use stackbt_behavior_tree::behavior_tree_node::{BehaviorTreeNode, NodeResult,
+    Statepoint};
+use stackbt_behavior_tree::node_runner::NodeRunner;
+use {ErasedRunner, STACKBT_ERR_DECODE};
+
+/// Behavior tree leaf node which delegates its steps to a Python callable. 
+///
+/// Exceptions raised by the callable, and return values of the wrong shape, 
+/// terminate the node with the Python error, so that a runner can rebuild 
+/// the node and carry on. 
+pub struct PythonLeaf {
+    callable: Py<PyAny>
+}
+
+impl PythonLeaf {
+    /// Create a new Python leaf from a callable. 
+    pub fn new(callable: Py<PyAny>) -> PythonLeaf {
+        PythonLeaf {
+            callable: callable
+        }
+    }
+}
+
+impl BehaviorTreeNode for PythonLeaf {
+    type Input = Py<PyAny>;
+    type Nonterminal = Py<PyAny>;
+    type Terminal = PyResult<Py<PyAny>>;
+
+    #[inline]
+    fn step(self, input: &Py<PyAny>) -> NodeResult<Py<PyAny>, PyResult<Py<PyAny>>, Self> {
+        let returned = Python::attach(|py| {
+            self.callable.call1(py, (input.clone_ref(py),))?
+                .extract::<(bool, Py<PyAny>)>(py)
+        });
+        match returned {
+            Result::Ok((false, value)) => NodeResult::Nonterminal(value, self),
+            Result::Ok((true, value)) => NodeResult::Terminal(Result::Ok(value)),
+            Result::Err(e) => NodeResult::Terminal(Result::Err(e))
+        }
+    }
+}
+
+/// Runner for a Python leaf, which restarts the leaf when it terminates. 
+#[pyclass(unsendable)]
+pub struct LeafRunner {
+    runner: NodeRunner<PythonLeaf, Box<dyn Fn() -> PythonLeaf>>
+}
+
+#[pymethods]
+impl LeafRunner {
+    /// Create a new runner for a leaf with the given callable. 
+    #[new]
+    fn py_new(callable: Py<PyAny>) -> LeafRunner {
+        let constructor: Box<dyn Fn() -> PythonLeaf> = Box::new(move ||
+            Python::attach(|py| PythonLeaf::new(callable.clone_ref(py))));
+        LeafRunner {
+            runner: NodeRunner::new(constructor)
+        }
+    }
+
+    /// Step the leaf with the input, returning a tuple of whether it 
+    /// terminated and the statepoint value, or raising the error the leaf 
+    /// terminated with. The leaf is restarted on the next tick either way. 
+    fn tick(&mut self, input: Py<PyAny>) -> PyResult<(bool, Py<PyAny>)> {
+        match self.runner.transition(&input) {
+            Statepoint::Nonterminal(n) => Result::Ok((false, n)),
+            Statepoint::Terminal(t) => t.map(|t| (true, t))
+        }
+    }
+}
+
+/// Type-erased runner of a tree exported from Rust, exchanging inputs and 
+/// outputs as bytes with the same blob convention as the C interface. 
+#[pyclass(unsendable)]
+pub struct BlobRunner {
+    runner: Box<dyn ErasedRunner>
+}
+
+impl BlobRunner {
+    /// Wrap a runner for use from Python. 
+    pub fn new<R>(runner: R) -> BlobRunner where R: ErasedRunner + 'static {
+        BlobRunner {
+            runner: Box::new(runner)
+        }
+    }
+}
+
+#[pymethods]
+impl BlobRunner {
+    /// Step the runner with the input blob, returning the output blob, 
+    /// which starts with its tag byte. 
+    fn tick<'py>(&mut self, py: Python<'py>, input: &[u8]) ->
+        PyResult<Bound<'py, PyBytes>>
+    {
+        let mut buffer = Vec::new();
+        match self.runner.tick_blob(input, &mut buffer) {
+            STACKBT_ERR_DECODE => Result::Err(PyValueError::new_err(
+                "Could not decode input blob")),
+            _ => Result::Ok(PyBytes::new(py, &buffer))
+        }
+    }
+}
+
+/// Register the classes of this module with a Python module. Call this 
+/// from the pymodule function of the extension module exporting the trees. 
+pub fn register(module: &Bound<PyModule>) -> PyResult<()> {
+    module.add_class::<LeafRunner>()?;
+    module.add_class::<BlobRunner>()?;
+    Result::Ok(())
+}
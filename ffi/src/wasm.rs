@@ -0,0 +1,50 @@
+//! wasm-bindgen bindings for type-erased node runners. 
+//! 
+//! Since wasm-bindgen cannot export generic types, trees are exported by 
+//! wrapping their node runners in a WasmRunner from a constructor function 
+//! annotated with wasm_bindgen, which JavaScript code then calls: 
+//! 
+//! ```ignore
+//! #[wasm_bindgen]
+//! pub fn create_guard_tree() -> WasmRunner {
+//!     WasmRunner::new(NodeRunner::new(make_guard_tree))
+//! }
+//! ```
+//! 
+//! Inputs and outputs are exchanged as byte arrays, using the same blob 
+//! convention as the C interface. 
+
+use wasm_bindgen::prelude::*;
+use {ErasedRunner, STACKBT_ERR_DECODE};
+
+/// Type-erased node runner exposed to JavaScript. 
+#[wasm_bindgen]
+pub struct WasmRunner {
+    runner: Box<dyn ErasedRunner>,
+    buffer: Vec<u8>
+}
+
+impl WasmRunner {
+    /// Wrap a runner for export to JavaScript. 
+    pub fn new<R>(runner: R) -> WasmRunner where R: ErasedRunner + 'static {
+        WasmRunner {
+            runner: Box::new(runner),
+            buffer: Vec::new()
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl WasmRunner {
+    /// Step the runner with the input blob, returning the output blob, 
+    /// which starts with its tag byte. Throws if the input blob could not 
+    /// be decoded. 
+    pub fn tick(&mut self, input: &[u8]) -> Result<Vec<u8>, JsValue> {
+        self.buffer.clear();
+        match self.runner.tick_blob(input, &mut self.buffer) {
+            STACKBT_ERR_DECODE => Result::Err(JsValue::from_str(
+                "Could not decode input blob")),
+            _ => Result::Ok(self.buffer.clone())
+        }
+    }
+}
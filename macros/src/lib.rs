@@ -1,4 +1,7 @@
+pub extern crate stackbt_automata_impl;
 extern crate stackbt_behavior_tree;
-extern crate num_derive;
-extern crate num_traits;
 
+#[doc(hidden)]
+pub use stackbt_automata_impl as automata_impl;
+
+pub mod state_machine_macro;
@@ -0,0 +1,120 @@
+//! Transition table macro for reference state machines. 
+
+/// Define an enum of states along with a ReferenceTransition implementation 
+/// for it, from a table of transitions. Each row of the table reads 
+/// `State + pattern => NextState / action`, where the pattern may be 
+/// followed by a guard as in a match arm, and a row may begin with several 
+/// states separated by `|` to share the row between them. When the 
+/// enum is stepped, the first row whose state is the current state and 
+/// whose pattern matches the input is taken, so more specific rows should 
+/// come first. The rows are expanded into a single match over the state and 
+/// input, so a table which leaves out some combination of state and input 
+/// fails to compile, and rows which can never be taken are warned about. 
+///
+/// # Example
+/// ```
+/// #[macro_use]
+/// extern crate stackbt_macros;
+/// extern crate stackbt_automata_impl;
+///
+/// use stackbt_automata_impl::automaton::Automaton;
+/// use stackbt_automata_impl::ref_state_machine::RefStateMachine;
+///
+/// state_machine!(
+///     enum SRLatch { Low, High }
+///     transitions (bool, bool) => bool {
+///         Low + (_, true) => High / false,
+///         Low + _ => Low / false,
+///         High + (true, _) => Low / true,
+///         High + _ => High / true
+///     }
+/// );
+///
+/// fn main() {
+///     let mut latch = RefStateMachine::new(SRLatch::Low);
+///     assert!(!latch.transition(&(true, false)));
+///     assert!(!latch.transition(&(false, true)));
+///     assert!(latch.transition(&(false, false)));
+///     assert!(latch.transition(&(true, true)));
+/// }
+/// ```
+#[macro_export]
+macro_rules! state_machine {
+    (
+        $( #[ $mval:meta ] )*
+        $visibility:vis enum $name:ident {
+            $( $state:ident ),*
+        }
+        transitions $input:ty => $action:ty {
+            $(
+                $( $from:ident )|+ + $pattern:pat $( if $guard:expr )* =>
+                    $to:ident / $result:expr
+            ),*
+        }
+    ) => {
+        $( #[ $mval ] )*
+        $visibility enum $name {
+            $( $state ),*
+        }
+
+        impl $crate::automata_impl::ref_state_machine::ReferenceTransition for $name {
+            type Input = $input;
+            type Action = $action;
+
+            fn step(self, input: &$input) -> ($action, $name) {
+                match (self, input) {
+                    $(
+                        ( $( $name :: $from )|+ , $pattern ) $( if $guard )* =>
+                            ($result, $name :: $to),
+                    )*
+                }
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use automata_impl::automaton::Automaton;
+    use automata_impl::ref_state_machine::{ReferenceTransition, RefStateMachine};
+
+    state_machine!(
+        #[derive(Copy, Clone, PartialEq, Debug)]
+        pub enum Ratchet { Zero, One, Two, Three }
+        transitions i64 => Option<i64> {
+            Zero | One | Two + 3 => Three / Option::Some(3),
+            Zero | One + 2 => Two / Option::Some(2),
+            Zero + 1 => One / Option::Some(1),
+            Zero + _ => Zero / Option::Some(0),
+            One + _ => One / Option::Some(1),
+            Two + _ => Two / Option::Some(2),
+            Three + _ => Three / Option::None
+        }
+    );
+
+    state_machine!(
+        enum Tally { Counting }
+        transitions i64 => i64 {
+            Counting + &value if value < 0 => Counting / -value,
+            Counting + value => Counting / *value
+        }
+    );
+
+    #[test]
+    fn ratchet_test() {
+        assert_eq!(Ratchet::Zero.step(&2), (Option::Some(2), Ratchet::Two));
+        assert_eq!(Ratchet::Two.step(&1), (Option::Some(2), Ratchet::Two));
+        let mut machine = RefStateMachine::new(Ratchet::Zero);
+        assert_eq!(machine.transition(&1), Option::Some(1));
+        assert_eq!(machine.transition(&0), Option::Some(1));
+        assert_eq!(machine.transition(&3), Option::Some(3));
+        assert_eq!(machine.transition(&1), Option::None);
+    }
+
+    #[test]
+    fn binding_test() {
+        let mut machine = RefStateMachine::new(Tally::Counting);
+        assert_eq!(machine.transition(&4), 4);
+        assert_eq!(machine.transition(&-5), 5);
+    }
+}
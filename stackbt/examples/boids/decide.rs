@@ -1,334 +1,338 @@
-/*use {FIELD_HEIGHT, FIELD_WIDTH};
-use stackbt::automata_impl::{
-    automaton::Automaton,
-    internal_state_machine::{InternalTransition, InternalStateMachine},
-    map_wrappers::{CustomConstructor, CustomConstructedMachine, InputMachineMap, 
-        InputMappedMachine},
-    dual_state_machine::{DualTransition, DualStateMachine}
-};
-use stackbt::behavior_tree::{
-    behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint},
-    base_nodes::{PredicateWait, WaitCondition, MachineWrapper},
-    node_compositions::SerialRepeater,
-    control_wrappers::{GuardFailure, GuardedNode, NodeGuard},
-    serial_node::{EnumNode, NontermReturn, SerialBranchNode, SerialDecider, 
-        NontermDecision, TermDecision},
-    map_wrappers::{InputMappedNode, InputNodeMap, OutputMappedNode, OutputNodeMap},
-    node_runner::NodeRunner
-};
-use rand::Rng;
-use rand::distributions::{Pareto, Uniform};
-
-// The basic structure of the boid AI is this: 
-//     In panic? 
+use {FIELD_HEIGHT, FIELD_WIDTH};
+use stackbt::automata_impl::automaton::Automaton;
+use stackbt::automata_impl::steering::{Alignment, Cohesion, Flee, Kinematic,
+    NeighborInput, Seek, Separation, TargetInput, Vector2, Wander};
+use stackbt::behavior_tree::behavior_tree_node::{BehaviorTreeNode, NodeResult};
+use stackbt::behavior_tree::base_nodes::MachineLoop;
+use stackbt::behavior_tree::control_wrappers::{GuardFailure, GuardedNode};
+use stackbt::behavior_tree::node_compositions::ReactiveSelector;
+use stackbt::behavior_tree::serial_node::{EnumNode, NontermReturn, Ordinal,
+    SerialBranchNode};
+
+// The basic structure of the boid AI is this:
+//     In panic?
 //         Yes: act erratically, avoid other boids
-//         No: Impending obstacle? 
+//         No: Impending obstacle?
 //             Yes: avoid
-//             No: Boids nearby? 
+//             No: Boids nearby?
 //                 Yes: Fly in a flock, may panic based on proximity
-//                 No: Conduct levy flight
+//                 No: Wander about
 // (There is no actual obstacle collision, it's mostly so boids stay
 // on screen rather than flying off)
+//
+// Each behavior is a steering automaton looped over in a leaf node, which
+// gives way once the condition it was picked for stops holding. A reactive
+// selector picks the behavior to enter, and interrupts the running one
+// whenever one higher up becomes eligible.
 
-//On initialization, a timer is set to tick down per frame, 
-//and when it fires off, the engine gets started. 
-
-const LEVY_FLIGHT_SHAPE: f64 = 1.5;
-const LEVY_FLIGHT_SCALE: f64 = 1.0/60.0;
-const APPROACH_DISTANCE: f32 = 40.0;
+const MAX_SPEED: f32 = 2.0;
+const WANDER_DISTANCE: f32 = 4.0;
+const WANDER_RADIUS: f32 = 2.0;
+const WANDER_JITTER: f32 = 0.3;
+const FIELD_MARGIN: f32 = 40.0;
 const SEPARATE_DISTANCE: f32 = 20.0;
 const PANIC_THRESHOLD: f32 = 120.0;
 const PANIC_SEEKING_DECAY: f32 = 0.004;
 const PANIC_FLOCKING_DECAY: f32 = 0.01;
 const PANIC_RETURNING_DECAY: f32 = 0.005;
 
-lazy_static! {
-    static ref LEVY_FLIGHT_DISTRIBUTION: Pareto = {
-        Pareto::new(LEVY_FLIGHT_SCALE, LEVY_FLIGHT_SHAPE)
-    };
-    static ref TURN_DISTRIBUTION: Uniform<f32> = {
-        Uniform::new(-1.0_f32, 1.0_f32)
-    };
+/// What a boid knows about itself and the boids it can see. 
+#[derive(Clone, PartialEq, Debug)]
+pub struct BoidInfo {
+    pub panic_level: f32,
+    pub about_self: Kinematic,
+    pub about_crowd: Vec<Kinematic>
 }
 
-fn relative_facing(self_sin_cos: (f32, f32), other_sin_cos: (f32, f32)) -> f32 {
-    let crossprod = self_sin_cos.0*other_sin_cos.1 - self_sin_cos.1*other_sin_cos.0;
-    crossprod.asin().to_degrees()
-}
+impl BoidInfo {
+    fn neighbors(&self) -> NeighborInput {
+        NeighborInput {
+            agent: self.about_self,
+            neighbors: self.about_crowd.clone()
+        }
+    }
 
-fn boid_info_gather(input: &RawInfo) -> BoidInfo {
-    assert!(input.about_crowd.len() > 0, "Expected nonempty slice");
-    let mut running_x_sum = 0.0_f32;
-    let mut running_y_sum = 0.0_f32;
-    let mut running_sqrdist_sum = 0.0_f32;
-    let mut running_sin_sum = 0.0_f32;
-    let mut running_cos_sum = 0.0_f32;
-    for entry in input.about_crowd.iter() {
-        let xoffset = entry.xpos - input.about_self.xpos;
-        let yoffset = entry.ypos - input.about_self.ypos;
-        running_x_sum += xoffset;
-        running_y_sum += yoffset;
-        running_sqrdist_sum += xoffset*xoffset + yoffset*yoffset;
-        let face_sin_cos = entry.facing.to_radians().sin_cos();
-        running_sin_sum += face_sin_cos.0;
-        running_cos_sum += face_sin_cos.1;
+    fn crowd_center(&self) -> Option<Vector2> {
+        if self.about_crowd.is_empty() {
+            return Option::None;
+        }
+        let sum = self.about_crowd.iter().fold(Vector2::default(), |acc, other| {
+            acc + other.position
+        });
+        Option::Some(sum * (1.0 / self.about_crowd.len() as f32))
     }
-    BoidInfo {
-        panic_level: input.panic_level,
-        facing: input.about_self.facing,
-        dx: running_x_sum / (input.about_crowd.len() as f32),
-        dy: running_y_sum / (input.about_crowd.len() as f32),
-        average_face_cos: running_cos_sum / (input.about_crowd.len() as f32),
-        average_face_sin: running_sin_sum / (input.about_crowd.len() as f32),
-        stdev: (running_sqrdist_sum / (input.about_crowd.len() as f32)).sqrt()
+
+    fn off_field(&self) -> bool {
+        let position = self.about_self.position;
+        position.x.abs() > FIELD_WIDTH / 2.0 - FIELD_MARGIN ||
+            position.y.abs() > FIELD_HEIGHT / 2.0 - FIELD_MARGIN
     }
 }
 
+/// Steering decided for a boid, along with its panic level for the next 
+/// frame. 
 #[derive(Copy, Clone, PartialEq, Debug)]
-pub struct BoidStatus {
-    xpos: f32,
-    ypos: f32,
-    facing: f32,
+pub struct Steer {
+    pub steering: Vector2,
+    pub panic_level: f32
 }
 
-#[derive(Clone, PartialEq, Debug)]
-pub struct RawInfo {
-    panic_level: f32,
-    about_self: BoidStatus,
-    about_crowd: Box<[BoidStatus]>
+// Turns erratically while fleeing the center of the crowd, getting more
+// panicked while any boids are in sight
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Panicking {
+    flee: Flee,
+    wander: Wander
 }
 
-#[derive(Copy, Clone, PartialEq, Debug)]
-pub struct BoidInfo {
-    panic_level: f32,
-    facing: f32,
-    dx: f32,
-    dy: f32,
-    average_face_cos: f32, 
-    average_face_sin: f32,
-    stdev: f32
+impl Automaton<'static> for Panicking {
+    type Input = BoidInfo;
+    type Action = Steer;
+
+    fn transition(&mut self, input: &BoidInfo) -> Steer {
+        let jitter = self.wander.transition(&input.about_self);
+        let (away, increment) = match input.crowd_center() {
+            Option::Some(center) => (self.flee.transition(&TargetInput {
+                agent: input.about_self,
+                target: center
+            }), 1.0),
+            Option::None => (Vector2::default(), 0.0)
+        };
+        Steer {
+            steering: (away + jitter).truncate(MAX_SPEED),
+            panic_level: input.panic_level * (1.0 - PANIC_SEEKING_DECAY) + increment
+        }
+    }
 }
 
-pub enum LevyFlight {
-    Straight,
-    Turning
+// Heads back towards the middle of the field
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Recentering {
+    seek: Seek
 }
 
-impl DualTransition for LevyFlight {
-    type Input = RawInfo;
-    type Internal = (i32, f32);
-    type Action = Statepoint<(f32, f32), ()>;
-
-    fn step(self, input: &RawInfo, internal: &mut (i32, f32)) -> (Self::Action, Self) {
-        if internal.0 <= 0 {
-            let mut rng = rand::thread_rng();
-            match self {
-                LevyFlight::Straight => {
-                    *internal = (179, rng.sample(*TURN_DISTRIBUTION))
-                },
-                LevyFlight::Turning => {
-                    *internal = (
-                        1 + (rng.sample(*LEVY_FLIGHT_DISTRIBUTION).ceil() as i32),
-                        0.0_f32
-                    )
-                }
-            }
-            (
-                Statepoint::Nonterminal((
-                    internal.1, 
-                    input.panic_level*(1.0_f32 - PANIC_SEEKING_DECAY)
-                )), 
-                self
-            )
-        } else {
-            internal.0 -= 1;
-            (
-                Statepoint::Nonterminal((
-                    internal.1, 
-                    input.panic_level*(1.0_f32 - PANIC_SEEKING_DECAY)
-                )),
-                self
-            )
+impl Automaton<'static> for Recentering {
+    type Input = BoidInfo;
+    type Action = Steer;
+
+    fn transition(&mut self, input: &BoidInfo) -> Steer {
+        Steer {
+            steering: self.seek.transition(&TargetInput {
+                agent: input.about_self,
+                target: Vector2::default()
+            }),
+            panic_level: input.panic_level * (1.0 - PANIC_RETURNING_DECAY)
         }
     }
 }
 
-#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
-pub struct BoidNavigate;
-
-impl WaitCondition for BoidNavigate {
-    type Input = RawInfo;
-    type Nonterminal = (f32, f32);
-    type Terminal = ();
-    fn do_end(&self, info: &RawInfo) -> Statepoint<(f32, f32), ()> {
-        let input = boid_info_gather(info);
-        if input.stdev > APPROACH_DISTANCE {
-            if input.dx == 0.0_f32 && input.dy == 0.0_f32 {
-                Statepoint::Nonterminal((
-                    0.0_f32, 
-                    input.panic_level*(1.0_f32-PANIC_SEEKING_DECAY)
-                ))
-            } else {
-                let hypdist = input.dx.hypot(input.dy);
-                let canonicalized_diff = relative_facing(
-                    input.facing.to_radians().sin_cos(), 
-                    (input.dx/hypdist, input.dy/hypdist)
-                );
-                if canonicalized_diff.abs() < 1.0_f32 {
-                    Statepoint::Nonterminal((
-                        canonicalized_diff,
-                        input.panic_level*(1.0_f32-PANIC_SEEKING_DECAY)
-                    ))
-                } else {
-                    Statepoint::Nonterminal((
-                        canonicalized_diff.signum(),
-                        input.panic_level*(1.0_f32-PANIC_SEEKING_DECAY)
-                    ))
-                }
-            }
-        } else if input.stdev > SEPARATE_DISTANCE {
-            if input.average_face_sin == 0.0 && input.average_face_cos == 0.0 {
-                let mut rng = rand::thread_rng();
-                if rng.gen_bool(0.5) {
-                    Statepoint::Nonterminal((
-                        -1.0_f32,
-                        input.panic_level*(1.0_f32-PANIC_FLOCKING_DECAY)
-                    ))
-                } else {
-                    Statepoint::Nonterminal((
-                        1.0_f32,
-                        input.panic_level*(1.0_f32-PANIC_FLOCKING_DECAY)
-                    ))
-                }
-            } else {
-                let iface_mag = input.average_face_cos.hypot(
-                    input.average_face_sin
-                );
-                let canonicalized_diff = relative_facing(
-                    input.facing.to_radians().sin_cos(), 
-                    (input.average_face_cos/iface_mag, input.average_face_sin/iface_mag)
-                );
-                if canonicalized_diff.abs() < 1.0_f32 {
-                    Statepoint::Nonterminal((
-                        canonicalized_diff,
-                        input.panic_level*(1.0_f32-PANIC_FLOCKING_DECAY)
-                    ))
-                } else {
-                    Statepoint::Nonterminal((
-                        canonicalized_diff.signum(),
-                        input.panic_level*(1.0_f32-PANIC_FLOCKING_DECAY)
-                    ))
-                }
+// Flies in a flock with the boids in sight, getting more panicked when they
+// crowd in too close
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Flocking {
+    separation: Separation,
+    cohesion: Cohesion,
+    alignment: Alignment
+}
 
-            }
-        } else if input.dx == 0.0_f32 && input.dy == 0.0_f32 {
-            Statepoint::Nonterminal((
-                0.0_f32,
-                input.panic_level+1.0_f32
-            ))
-        } else {
-            let hypdist = input.dx.hypot(input.dy);
-            let canonicalized_diff = relative_facing(
-                input.facing.to_radians().sin_cos(), 
-                (input.dx/hypdist, input.dy/hypdist)
-            );
-            if canonicalized_diff.abs() < 1.0_f32 {
-                Statepoint::Nonterminal((
-                    -canonicalized_diff,
-                    input.panic_level+1.0_f32
-                ))
+impl Automaton<'static> for Flocking {
+    type Input = BoidInfo;
+    type Action = Steer;
+
+    fn transition(&mut self, input: &BoidInfo) -> Steer {
+        let neighbors = input.neighbors();
+        let steering = self.separation.transition(&neighbors) * MAX_SPEED
+            + self.cohesion.transition(&neighbors)
+            + self.alignment.transition(&neighbors);
+        let crowded = input.crowd_center().map_or(false, |center| {
+            (center - input.about_self.position).length() < SEPARATE_DISTANCE
+        });
+        Steer {
+            steering: steering.truncate(MAX_SPEED),
+            panic_level: if crowded {
+                input.panic_level + 1.0
             } else {
-                Statepoint::Nonterminal((
-                    -canonicalized_diff.signum(),
-                    input.panic_level+1.0_f32
-                ))
+                input.panic_level * (1.0 - PANIC_FLOCKING_DECAY)
             }
         }
     }
 }
 
+// Meanders about while no boids are in sight
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Wandering {
+    wander: Wander
+}
+
+impl Automaton<'static> for Wandering {
+    type Input = BoidInfo;
+    type Action = Steer;
+
+    fn transition(&mut self, input: &BoidInfo) -> Steer {
+        Steer {
+            steering: self.wander.transition(&input.about_self).truncate(MAX_SPEED),
+            panic_level: input.panic_level * (1.0 - PANIC_SEEKING_DECAY)
+        }
+    }
+}
+
+fn new_wander() -> Wander {
+    Wander::new(WANDER_DISTANCE, WANDER_RADIUS, WANDER_JITTER, rand::random())
+}
+
+/// Behaviors of a boid, from the most urgent to the least. 
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Behavior {
+    Panic,
+    Recenter,
+    Flock,
+    Wander
+}
+
+impl Ordinal for Behavior {
+    const COUNT: usize = 4;
+
+    fn first() -> Behavior {
+        Behavior::Panic
+    }
 
-#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
-struct BoidRecenter;
-
-impl WaitCondition for BoidRecenter {
-    type Input = RawInfo;
-    type Nonterminal = (f32, f32);
-    type Terminal = ();
-
-    fn do_end(&self, info: &RawInfo) -> Statepoint<(f32, f32), ()> {
-        assert!(info.about_self.xpos != 0.0 || info.about_self.ypos != 0.0);
-        //The probability of turning a particular direction is 
-        //guided by the angle between current facing and 
-        let diff_dist = info.about_self.xpos.hypot(info.about_self.ypos);
-        let offset = relative_facing(
-            info.about_self.facing.to_radians().sin_cos(),
-            (-info.about_self.xpos/diff_dist, -info.about_self.ypos/diff_dist)
-        );
-        if offset.abs() < 60.0 {
-            Statepoint::Nonterminal((
-                0.0_f32,
-                info.panic_level*(1.0_f32-PANIC_RETURNING_DECAY)
-            ))
-        } else {
-            Statepoint::Nonterminal((
-                offset.signum(),
-                info.panic_level*(1.0_f32-PANIC_RETURNING_DECAY)
-            ))
+    fn next(self) -> Option<Behavior> {
+        match self {
+            Behavior::Panic => Option::Some(Behavior::Recenter),
+            Behavior::Recenter => Option::Some(Behavior::Flock),
+            Behavior::Flock => Option::Some(Behavior::Wander),
+            Behavior::Wander => Option::None
         }
     }
 }
 
-#[derive(Copy, Clone, Debug)]
-struct BoidPanic;
-
-impl WaitCondition for BoidPanic {
-    type Input = RawInfo;
-    type Nonterminal = (f32, f32);
-    type Terminal = ();
-
-    // Calm down once no boids are in sight, otherwise turn erratically and 
-    // avoid facing the same way as other boids
-    fn do_end(&self, input: &RawInfo) -> Statepoint<(f32, f32), ()> {
-        let (prob_left, increment) = match input.about_crowd.len() {
-            0 ... 1 => (0.5_f32, 0.0_f32),
-            _ => {
-                let mut running_x_sum = 0.0_f32;
-                let mut running_y_sum = 0.0_f32;
-                for entry in input.about_crowd.iter() {
-                    running_x_sum += entry.xpos - input.about_self.xpos;
-                    running_y_sum += entry.ypos - input.about_self.ypos;
-                }
-                let dx = running_x_sum;
-                let dy = running_y_sum;
-                if dx == 0.0_f32 && dy == 0.0_f32 {
-                    (0.5_f32, 1.0_f32)
-                } else {
-                    let facing_sin_cos = input.about_self.facing.to_radians().sin_cos();
-                    //The probability of turning a particular direction is 
-                    //guided by the angle between current facing and 
-                    let diff_dist = dx.hypot(dy);
-                    let norm_dx = dx/diff_dist;
-                    let norm_dy = dy/diff_dist;
-                    (
-                        (facing_sin_cos.0*norm_dy-facing_sin_cos.1*norm_dx+1.5_f32)/3.0_f32,
-                        1.0_f32
-                    )
-                }
+fn eligible(behavior: Behavior, input: &BoidInfo) -> bool {
+    match behavior {
+        Behavior::Panic => input.panic_level > PANIC_THRESHOLD,
+        Behavior::Recenter => input.off_field(),
+        Behavior::Flock => !input.about_crowd.is_empty(),
+        Behavior::Wander => true
+    }
+}
+
+type Guard = fn(&BoidInfo, &Steer) -> bool;
+
+type BehaviorNode<M> = GuardedNode<MachineLoop<M>, Guard>;
+
+fn keep_panicking(input: &BoidInfo, _steer: &Steer) -> bool {
+    eligible(Behavior::Panic, input)
+}
+
+fn keep_recentering(input: &BoidInfo, _steer: &Steer) -> bool {
+    eligible(Behavior::Recenter, input)
+}
+
+fn keep_flocking(input: &BoidInfo, _steer: &Steer) -> bool {
+    eligible(Behavior::Flock, input)
+}
+
+fn keep_wandering(input: &BoidInfo, _steer: &Steer) -> bool {
+    eligible(Behavior::Wander, input)
+}
+
+fn behavior_node<M>(machine: M, guard: Guard) -> BehaviorNode<M> where
+    M: Automaton<'static, Input=BoidInfo, Action=Steer> + 'static
+{
+    GuardedNode::new(guard, MachineLoop::new(machine))
+}
+
+/// The running behavior of a boid. 
+pub enum BoidNode {
+    Panic(BehaviorNode<Panicking>),
+    Recenter(BehaviorNode<Recentering>),
+    Flock(BehaviorNode<Flocking>),
+    Wander(BehaviorNode<Wandering>)
+}
+
+type BehaviorExit = Result<(), GuardFailure<Steer>>;
+
+impl BehaviorTreeNode for BoidNode {
+    type Input = BoidInfo;
+    type Nonterminal = Steer;
+    type Terminal = BehaviorExit;
+
+    fn step(self, input: &BoidInfo) -> NodeResult<Steer, BehaviorExit, BoidNode> {
+        match self {
+            BoidNode::Panic(n) => match n.step(input) {
+                NodeResult::Nonterminal(s, n) => NodeResult::Nonterminal(s, BoidNode::Panic(n)),
+                NodeResult::Terminal(t) => NodeResult::Terminal(t)
+            },
+            BoidNode::Recenter(n) => match n.step(input) {
+                NodeResult::Nonterminal(s, n) => NodeResult::Nonterminal(s, BoidNode::Recenter(n)),
+                NodeResult::Terminal(t) => NodeResult::Terminal(t)
+            },
+            BoidNode::Flock(n) => match n.step(input) {
+                NodeResult::Nonterminal(s, n) => NodeResult::Nonterminal(s, BoidNode::Flock(n)),
+                NodeResult::Terminal(t) => NodeResult::Terminal(t)
+            },
+            BoidNode::Wander(n) => match n.step(input) {
+                NodeResult::Nonterminal(s, n) => NodeResult::Nonterminal(s, BoidNode::Wander(n)),
+                NodeResult::Terminal(t) => NodeResult::Terminal(t)
             }
-        };
-        let mut rng = rand::thread_rng();
-        if rng.gen_bool(prob_left as f64) {
-            Statepoint::Nonterminal((
-                -1.0_f32,
-                input.panic_level*(1.0_f32-PANIC_SEEKING_DECAY) + increment
-            ))
-        } else {
-            Statepoint::Nonterminal((
-                1.0_f32,
-                input.panic_level*(1.0_f32-PANIC_SEEKING_DECAY) + increment
-            ))
         }
     }
 }
-*/
\ No newline at end of file
+
+impl EnumNode for BoidNode {
+    type Discriminant = Behavior;
+
+    fn new(behavior: Behavior) -> BoidNode {
+        match behavior {
+            Behavior::Panic => BoidNode::Panic(behavior_node(Panicking {
+                flee: Flee::new(MAX_SPEED),
+                wander: new_wander()
+            }, keep_panicking)),
+            Behavior::Recenter => BoidNode::Recenter(behavior_node(Recentering {
+                seek: Seek::new(MAX_SPEED)
+            }, keep_recentering)),
+            Behavior::Flock => BoidNode::Flock(behavior_node(Flocking {
+                separation: Separation::new(SEPARATE_DISTANCE),
+                cohesion: Cohesion::new(MAX_SPEED),
+                alignment: Alignment::new()
+            }, keep_flocking)),
+            Behavior::Wander => BoidNode::Wander(behavior_node(Wandering {
+                wander: new_wander()
+            }, keep_wandering))
+        }
+    }
+
+    fn discriminant_of(&self) -> Behavior {
+        match *self {
+            BoidNode::Panic(_) => Behavior::Panic,
+            BoidNode::Recenter(_) => Behavior::Recenter,
+            BoidNode::Flock(_) => Behavior::Flock,
+            BoidNode::Wander(_) => Behavior::Wander
+        }
+    }
+}
+
+type BoidSelector = ReactiveSelector<Behavior, BoidInfo, Steer, BehaviorExit,
+    fn(Behavior, &BoidInfo) -> bool>;
+
+/// Decision making of a boid. 
+pub type BoidBrain = SerialBranchNode<BoidNode, BoidSelector>;
+
+/// Create the decision making of a boid, which enters the most urgent 
+/// behavior eligible on its first step. 
+pub fn new_brain() -> BoidBrain {
+    SerialBranchNode::new(ReactiveSelector::new(eligible), Behavior::Wander)
+}
+
+/// Step the decision making of a boid, returning how it steers and the 
+/// decision making to step on the next frame. 
+pub fn decide(brain: BoidBrain, input: &BoidInfo) -> (Steer, BoidBrain) {
+    match brain.step(input) {
+        NodeResult::Nonterminal(NontermReturn::Nonterminal(_, steer), next) => (steer, next),
+        // The behavior gave way, but still steered on its last step
+        NodeResult::Nonterminal(NontermReturn::Terminal(_, exit), next) => match exit {
+            Result::Err(GuardFailure(steer)) => (steer, next),
+            Result::Ok(()) => unreachable!("Steering behaviors loop until they give way")
+        },
+        NodeResult::Terminal(_) => unreachable!("Boids can always wander")
+    }
+}
@@ -8,9 +8,6 @@ extern crate rand;
 extern crate stackbt;
 #[macro_use]
 extern crate lazy_static;
-#[macro_use]
-extern crate num_derive;
-extern crate num_traits;
 
 mod decide;
 mod components;
@@ -2,9 +2,6 @@ pub extern crate stackbt_automata_impl;
 pub extern crate stackbt_behavior_tree;
 pub extern crate stackbt_macros;
 
-extern crate num_derive;
-extern crate num_traits;
-
 pub use stackbt_automata_impl as automata_impl;
 pub use stackbt_behavior_tree as behavior_tree;
 pub mod macros;
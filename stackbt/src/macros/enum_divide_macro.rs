@@ -72,7 +72,7 @@ macro_rules! enum_divide_from_body {
                     $firstvariant :: $firstoldvariant
                 ) ) => ( $oldname :: $firstoldvariant ) 
             ),*
-        );
+        )
     };
 
     (@munch 
@@ -90,7 +90,7 @@ macro_rules! enum_divide_from_body {
                     $firstvariant :: $firstoldvariant
                 ) ) => ( $oldname :: $firstoldvariant ) 
             ),*
-        );
+        )
     };
 
     (
@@ -101,7 +101,7 @@ macro_rules! enum_divide_from_body {
         enum_divide_from_body!(@munch
             $var ; $name : $oldname ;
             $( $( $oldvariant )|* => $variant ),* ;
-        );
+        )
     }
 }
 
@@ -262,6 +262,7 @@ macro_rules! enum_divide {
     };
 }
 
+#[cfg(test)]
 mod tests {
 
     enum Foo {
@@ -15,6 +15,43 @@ macro_rules! first {
     };
 }
 
+#[doc(hidden)]
+#[macro_export]
+macro_rules! enum_iter_ordinal {
+    (@one $variant:ident) => {
+        1
+    };
+
+    (@munch $name:ident ; $count:tt ; $first:ident ; $last:ident ; $( $arms:tt )* ) => {
+        impl $crate::behavior_tree::serial_node::Ordinal for $name {
+            const COUNT: usize = $count;
+
+            fn first() -> $name {
+                $name :: $first
+            }
+
+            fn next(self) -> Option<$name> {
+                match self {
+                    $( $arms )*
+                    $name :: $last => Option::None
+                }
+            }
+        }
+    };
+
+    (@munch $name:ident ; $count:tt ; $first:ident ; $variant:ident , $nextvariant:ident 
+        $( , $othervariants:ident )* ; $( $arms:tt )* ) => 
+    {
+        enum_iter_ordinal!(@munch $name ; $count ; $first ; $nextvariant $( , $othervariants )* ; 
+            $( $arms )* $name :: $variant => Option::Some($name :: $nextvariant), );
+    };
+
+    ( $name:ident { $first:ident $( , $othervariants:ident )* } ) => {
+        enum_iter_ordinal!(@munch $name ; ( 1 $( + enum_iter_ordinal!(@one $othervariants) )* ) ; 
+            $first ; $first $( , $othervariants )* ; );
+    };
+}
+
 #[doc(hidden)]
 #[macro_export]
 macro_rules! enum_iter_from {
@@ -24,10 +61,11 @@ macro_rules! enum_iter_from {
         }
     ) => {
         #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
-        #[derive(::num_derive::ToPrimitive, ::num_derive::FromPrimitive)]
         enum $itername {
             $( $variant ),*
         }
+
+        enum_iter_ordinal!( $itername { $( $variant ),* } );
     };
 
     (
@@ -36,10 +74,11 @@ macro_rules! enum_iter_from {
         }
     ) => {
         #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
-        #[derive(::num_derive::ToPrimitive, ::num_derive::FromPrimitive)]
         $visibility enum $itername {
             $( $variant ),*
         }
+
+        enum_iter_ordinal!( $itername { $( $variant ),* } );
     }
 }
 
@@ -164,7 +203,7 @@ macro_rules! enum_iter {
 #[cfg(test)]
 mod tests {
 
-    use num_traits::FromPrimitive;
+    use behavior_tree::serial_node::Ordinal;
 
     enum_iter!(
         pub enum Foo: Bar {
@@ -177,9 +216,11 @@ mod tests {
     fn bar_iter_test() {
         let a = Foo::Baz(0);
         let b = Foo::Quux(1);
-        assert_eq!(Bar::from_u64(0), Option::Some(Bar::Baz));
-        assert_eq!(Bar::from_u64(1), Option::Some(Bar::Quux));
-        assert_eq!(Bar::from_u64(2), Option::None);
+        assert_eq!(Bar::COUNT, 2);
+        assert_eq!(Bar::first(), Bar::Baz);
+        assert_eq!(Bar::Baz.next(), Option::Some(Bar::Quux));
+        assert_eq!(Bar::Quux.next(), Option::None);
+        assert_eq!(Bar::Quux.wrapping_next(), Bar::Baz);
         assert_eq!(a.discriminant_of(), Bar::Baz);
         assert_eq!(b.discriminant_of(), Bar::Quux);
     }
@@ -1,2 +1,3 @@
 pub mod enum_divide_macro;
-pub mod enum_iter_macro;
\ No newline at end of file
+pub mod enum_iter_macro;
+pub mod state_machine_macro;
\ No newline at end of file